@@ -1,6 +1,7 @@
 use std::{borrow::Cow, cell::RefCell, cmp::Ordering, fmt::Display, iter::FromIterator, ops::{DerefMut, Deref}, rc::Rc};
-use crate::{InternalString, RantValue, util};
-use fnv::FnvHashMap;
+use crate::{InternalString, RantValue, ValueError, ValueResult, util};
+use fnv::{FnvHashMap, FnvHashSet};
+use num_bigint::BigInt;
 
 const LIST_INLINE_SIZE: usize = 2;
 
@@ -10,19 +11,24 @@ pub type RantMapRef = Rc<RefCell<RantMap>>;
 /// Type alias for `Rc<RefCell<RantList>>`
 pub type RantListRef = Rc<RefCell<RantList>>;
 
+/// Type alias for `Rc<RefCell<RantSet>>`
+pub type RantSetRef = Rc<RefCell<RantSet>>;
+
 /// Represents Rant's `list` type, which stores an ordered collection of values.
+///
+/// Cloning a list is cheap: the copies share their contents until one of them is modified.
 #[derive(Debug, Clone, PartialEq)]
-pub struct RantList(Vec<RantValue>);
+pub struct RantList(Rc<Vec<RantValue>>);
 
 impl RantList {
   /// Creates an empty RantList.
   pub fn new() -> Self {
-    Self(vec![])
+    Self(Default::default())
   }
 
   /// Creates an empty RantList with the specified initial capacity.
   pub fn with_capacity(capacity: usize) -> Self {
-    Self(Vec::with_capacity(capacity))
+    Self(Rc::new(Vec::with_capacity(capacity)))
   }
 
   #[inline(always)]
@@ -38,7 +44,7 @@ impl RantList {
 
 impl From<Vec<RantValue>> for RantList {
   fn from(list: Vec<RantValue>) -> Self {
-    Self(list)
+    Self(Rc::new(list))
   }
 }
 
@@ -57,7 +63,7 @@ impl Deref for RantList {
 
 impl DerefMut for RantList {
   fn deref_mut(&mut self) -> &mut Self::Target {
-    &mut self.0
+    Rc::make_mut(&mut self.0)
   }
 }
 
@@ -76,16 +82,18 @@ impl IntoIterator for RantList {
   type IntoIter = std::vec::IntoIter<Self::Item>;
 
   fn into_iter(self) -> Self::IntoIter {
-    self.0.into_iter()
+    Rc::try_unwrap(self.0).unwrap_or_else(|list| Vec::clone(&list)).into_iter()
   }
 }
 
 /// Represents Rant's `map` type, which stores a collection of key-value pairs.
 /// Map keys are always strings.
+///
+/// Cloning a map is cheap: the copies share their contents until one of them is modified.
 #[derive(Debug, Clone)]
 pub struct RantMap {
   /// The physical contents of the map
-  map: FnvHashMap<InternalString, RantValue>,
+  map: Rc<FnvHashMap<InternalString, RantValue>>,
   /// The prototype of the map
   proto: Option<RantMapRef>
 }
@@ -100,7 +108,7 @@ impl RantMap {
 
   #[inline]
   pub fn clear(&mut self) {
-    self.map.clear();
+    Rc::make_mut(&mut self.map).clear();
   }
 
   #[inline]
@@ -121,8 +129,9 @@ impl RantMap {
   #[inline]
   pub fn extend<M: Deref<Target = RantMap>>(&mut self, other: M)
   {
+    let map = Rc::make_mut(&mut self.map);
     for (k, v) in other.map.iter() {
-      self.map.insert(k.clone(), v.clone());
+      map.insert(k.clone(), v.clone());
     }
   }
 
@@ -133,17 +142,17 @@ impl RantMap {
 
   #[inline]
   pub fn raw_set(&mut self, key: &str, val: RantValue) {
-    self.map.insert(InternalString::from(key), val);
+    Rc::make_mut(&mut self.map).insert(InternalString::from(key), val);
   }
 
   #[inline]
   pub fn raw_remove(&mut self, key: &str) {
-    self.map.remove(key);
+    Rc::make_mut(&mut self.map).remove(key);
   }
 
   #[inline]
   pub fn raw_take(&mut self, key: &str) -> Option<RantValue> {
-    self.map.remove(key)
+    Rc::make_mut(&mut self.map).remove(key)
   }
 
   #[inline]
@@ -197,6 +206,159 @@ impl Default for RantMap {
   }
 }
 
+/// Represents Rant's `set` type, which stores a collection of unique values.
+/// Sets can hold strings, numbers, and booleans, and remember the order their items were added in.
+///
+/// Cloning a set is cheap: the copies share their contents until one of them is modified.
+#[derive(Debug, Clone, Default)]
+pub struct RantSet {
+  /// The items of the set, in insertion order
+  items: Rc<Vec<RantValue>>,
+  /// Hashable keys of the items, for membership checks
+  keys: Rc<FnvHashSet<SetKey>>,
+}
+
+/// Identifies a set item by value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SetKey {
+  String(InternalString),
+  Int(i64),
+  BigInt(BigInt),
+  Float(u64),
+  Boolean(bool),
+}
+
+impl SetKey {
+  fn from_value(value: &RantValue) -> Option<Self> {
+    Some(match value {
+      RantValue::String(s) => Self::String(InternalString::from(s.as_str())),
+      RantValue::Int(n) => Self::Int(*n),
+      RantValue::BigInt(n) => Self::BigInt(BigInt::clone(n)),
+      // Whole floats are equal to the matching ints, so they need to share a key
+      RantValue::Float(n) if n.fract() == 0.0 && *n >= i64::MIN as f64 && *n < i64::MAX as f64 => Self::Int(*n as i64),
+      RantValue::Float(n) => Self::Float(n.to_bits()),
+      RantValue::Boolean(b) => Self::Boolean(*b),
+      _ => return None,
+    })
+  }
+}
+
+impl RantSet {
+  /// Creates an empty RantSet.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates a set from a sequence of values, skipping duplicates.
+  ///
+  /// Fails if any of the values can't be stored in a set.
+  pub fn from_values<I: IntoIterator<Item = RantValue>>(values: I) -> ValueResult<Self> {
+    let mut set = Self::new();
+    for value in values {
+      set.insert(value)?;
+    }
+    Ok(set)
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  #[inline]
+  pub fn clear(&mut self) {
+    Rc::make_mut(&mut self.items).clear();
+    Rc::make_mut(&mut self.keys).clear();
+  }
+
+  /// Returns true if the set contains the specified value.
+  #[inline]
+  pub fn contains(&self, value: &RantValue) -> bool {
+    SetKey::from_value(value).is_some_and(|key| self.keys.contains(&key))
+  }
+
+  /// Adds a value to the end of the set and returns true if it wasn't already there.
+  ///
+  /// Fails if the value's type can't be stored in a set.
+  pub fn insert(&mut self, value: RantValue) -> ValueResult<bool> {
+    let key = SetKey::from_value(&value).ok_or_else(|| ValueError::InvalidSetItem(value.get_type()))?;
+    if !Rc::make_mut(&mut self.keys).insert(key) {
+      return Ok(false)
+    }
+    Rc::make_mut(&mut self.items).push(value);
+    Ok(true)
+  }
+
+  /// Removes a value from the set and returns true if it was there.
+  pub fn remove(&mut self, value: &RantValue) -> bool {
+    let key = match SetKey::from_value(value) {
+      Some(key) if Rc::make_mut(&mut self.keys).remove(&key) => key,
+      _ => return false,
+    };
+    Rc::make_mut(&mut self.items).retain(|item| SetKey::from_value(item).as_ref() != Some(&key));
+    true
+  }
+
+  /// Iterates over the items of the set in the order they were added.
+  #[inline]
+  pub fn iter(&self) -> impl Iterator<Item = &RantValue> {
+    self.items.iter()
+  }
+
+  /// Creates a set with the items of `self` followed by the items of `other` that aren't in `self`.
+  pub fn union(&self, other: &Self) -> Self {
+    let mut set = self.clone();
+    for item in other.iter() {
+      if let Some(key) = SetKey::from_value(item) {
+        if Rc::make_mut(&mut set.keys).insert(key) {
+          Rc::make_mut(&mut set.items).push(item.clone());
+        }
+      }
+    }
+    set
+  }
+
+  /// Creates a set with the items of `self` that are also in `other`.
+  pub fn intersection(&self, other: &Self) -> Self {
+    self.filtered(|item| other.contains(item))
+  }
+
+  /// Creates a set with the items of `self` that aren't in `other`.
+  pub fn difference(&self, other: &Self) -> Self {
+    self.filtered(|item| !other.contains(item))
+  }
+
+  #[inline]
+  fn filtered(&self, mut predicate: impl FnMut(&RantValue) -> bool) -> Self {
+    let mut set = Self::new();
+    for item in self.items.iter().filter(|item| predicate(item)) {
+      if let Some(key) = SetKey::from_value(item) {
+        Rc::make_mut(&mut set.keys).insert(key);
+        Rc::make_mut(&mut set.items).push(item.clone());
+      }
+    }
+    set
+  }
+
+  /// Copies the items of the set into a Rant `list` object, in insertion order.
+  #[inline]
+  pub fn to_list(&self) -> RantList {
+    self.items.iter().cloned().collect()
+  }
+}
+
+// Sets are equal if they have the same items, regardless of order
+impl PartialEq for RantSet {
+  fn eq(&self, other: &Self) -> bool {
+    self.keys == other.keys
+  }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct RantRange {
   start: i64,
@@ -311,6 +473,17 @@ impl RantRange {
     self.start == self.end || self.abs_step() as usize > self.abs_size()
   }
 
+  /// Indicates whether the range produces the specified value.
+  #[inline]
+  pub fn contains(&self, value: i64) -> bool {
+    let is_in_bounds = if self.start <= self.end {
+      value >= self.start && value < self.end
+    } else {
+      value <= self.start && value > self.end
+    };
+    is_in_bounds && (value - self.start) % self.step == 0
+  }
+
   /// Gets the nth value in the range.
   #[inline]
   pub fn get(&self, index: usize) -> Option<i64> {
@@ -0,0 +1,278 @@
+use super::lexer::RantToken;
+use logos::Logos;
+use std::ops::Range;
+
+/// The string used for each level of indentation.
+const INDENT: &str = "  ";
+
+/// The whitespace the formatter puts between two tokens where the parser ignores it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Spacing {
+  /// No whitespace.
+  None,
+  /// A single space.
+  Space,
+}
+
+/// Whitespace layout collected by the parser for the formatter.
+#[derive(Debug, Default)]
+pub(crate) struct LayoutHints {
+  /// Spans of whitespace tokens that the program prints.
+  pub printed_whitespace: Vec<Range<usize>>,
+  /// Token boundaries where the parser skips whitespace, paired with the spacing to use there.
+  pub spacing: Vec<(usize, Spacing)>,
+}
+
+/// Classifies a piece of source text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum PieceKind {
+  /// A token seen by the parser.
+  Token,
+  /// A `#` comment, excluding the line break that ends it.
+  LineComment,
+  /// A `## ... ##` comment.
+  BlockComment,
+  /// Whitespace skipped by the lexer.
+  Space,
+}
+
+/// A span of source text in a lossless token stream.
+#[derive(Debug)]
+struct Piece {
+  kind: PieceKind,
+  span: Range<usize>,
+}
+
+impl Piece {
+  /// Determines whether the piece only contains whitespace, whether or not the lexer skips it.
+  fn is_whitespace(&self, source: &str) -> bool {
+    match self.kind {
+      PieceKind::Space => true,
+      PieceKind::Token => source[self.span.clone()].trim().is_empty(),
+      PieceKind::LineComment | PieceKind::BlockComment => false,
+    }
+  }
+}
+
+/// Splits source code into tokens, comments, and skipped whitespace, covering every byte of the source.
+fn lossless_pieces(source: &str) -> Vec<Piece> {
+  let mut pieces = vec![];
+  let mut lexer = RantToken::lexer(source);
+  let mut last_end = 0;
+  while lexer.next().is_some() {
+    let span = lexer.span();
+    split_skipped(source, last_end..span.start, &mut pieces);
+    last_end = span.end;
+    pieces.push(Piece {
+      kind: PieceKind::Token,
+      span,
+    });
+  }
+  split_skipped(source, last_end..source.len(), &mut pieces);
+  pieces
+}
+
+/// Splits text skipped by the lexer into comments and whitespace.
+fn split_skipped(source: &str, span: Range<usize>, pieces: &mut Vec<Piece>) {
+  let mut pos = span.start;
+  while pos < span.end {
+    let rest = &source[pos..span.end];
+    let (kind, len) = if let Some(comment) = rest.strip_prefix("##") {
+      (PieceKind::BlockComment, comment.find("##").map_or(rest.len(), |end| end + 4))
+    } else if rest.starts_with('#') {
+      (PieceKind::LineComment, rest.find(&['\r', '\n'][..]).unwrap_or(rest.len()))
+    } else {
+      (PieceKind::Space, rest.find('#').unwrap_or(rest.len()))
+    };
+    pieces.push(Piece {
+      kind,
+      span: pos .. pos + len,
+    });
+    pos += len;
+  }
+}
+
+/// Gets the spans of the text between solid tokens, which only contain whitespace and comments.
+fn layout_regions(source: &str, pieces: &[Piece]) -> Vec<Range<usize>> {
+  let mut regions = vec![];
+  let mut region_start = 0;
+  for piece in pieces.iter().filter(|piece| piece.kind == PieceKind::Token && !piece.is_whitespace(source)) {
+    regions.push(region_start .. piece.span.start);
+    region_start = piece.span.end;
+  }
+  regions.push(region_start .. source.len());
+  regions
+}
+
+/// Gets the whitespace tokens that the lexer produces for the text of a region between solid tokens, with spans relative to the region.
+fn region_whitespace_tokens(text: &str, region_start: usize) -> Vec<(Range<usize>, &str)> {
+  // Whitespace at the very start of the source is skipped, so elsewhere the lexer needs something in front of the region
+  let prefix = if region_start == 0 { "" } else { "_" };
+  let source = format!("{}{}", prefix, text);
+  let mut lexer = RantToken::lexer(&source);
+  let mut tokens = vec![];
+  while let Some(token) = lexer.next() {
+    if token == RantToken::Whitespace {
+      let span = lexer.span().start - prefix.len() .. lexer.span().end - prefix.len();
+      tokens.push((span.clone(), &text[span]));
+    }
+  }
+  tokens
+}
+
+/// A replacement of a span of source text.
+#[derive(Debug)]
+struct Edit {
+  span: Range<usize>,
+  text: String,
+}
+
+/// Replaces the spans of `edits`, which must not overlap, in `source`.
+fn apply_edits(source: &str, mut edits: Vec<Edit>) -> String {
+  edits.sort_by_key(|edit| edit.span.start);
+  let mut output = String::with_capacity(source.len());
+  let mut pos = 0;
+  for edit in edits {
+    output.push_str(&source[pos..edit.span.start]);
+    output.push_str(&edit.text);
+    pos = edit.span.end;
+  }
+  output.push_str(&source[pos..]);
+  output
+}
+
+/// Re-indents source code by nesting depth, removes trailing whitespace, and normalizes the spacing in function signatures and collections.
+///
+/// Text inside string literals and comments is left untouched.
+/// Only whitespace that the lexer skips or that the parser ignores according to `layout` is changed,
+/// so the formatted program always behaves the same as the original.
+pub(crate) fn format(source: &str, layout: &LayoutHints) -> String {
+  let pieces = lossless_pieces(source);
+  let regions = layout_regions(source, &pieces);
+  let region_index = |offset: usize| regions.partition_point(|region| region.end < offset);
+
+  let mut edits = vec![];
+  let mut line_edits: Vec<(usize, Edit)> = vec![];
+
+  // A range can only be changed if it doesn't cut into a solid token or comment
+  let is_layout_range = |range: &Range<usize>| {
+    let first = pieces.partition_point(|piece| piece.span.end <= range.start);
+    pieces[first..].iter()
+      .take_while(|piece| piece.span.start < range.end)
+      .all(|piece| piece.is_whitespace(source))
+  };
+
+  // Open brackets, paired with the index of the line they appear on
+  let mut open_brackets: Vec<usize> = vec![];
+  let mut tokens = pieces.iter().filter(|piece| piece.kind == PieceKind::Token).peekable();
+  let mut line_start = 0;
+  for (line_index, text) in source.split_inclusive('\n').enumerate() {
+    let line_end = line_start + text.len();
+    let content = text.trim_end_matches(&['\r', '\n'][..]);
+    let body_start = line_start + content.len() - content.trim_start_matches(&[' ', '\t'][..]).len();
+    let body_end = (line_start + content.trim_end_matches(&[' ', '\t'][..]).len()).max(body_start);
+    let content_end = line_start + content.len();
+
+    // Collect the tokens that start on this line
+    let mut line_tokens = vec![];
+    while let Some(token) = tokens.next_if(|token| token.span.start < line_end) {
+      line_tokens.push(&source[token.span.clone()]);
+    }
+
+    // Closing brackets at the start of the line are dedented along with the line
+    let mut line_tokens = line_tokens.into_iter().filter(|token| !token.trim().is_empty()).peekable();
+    while line_tokens.next_if(|token| is_closing_bracket(token)).is_some() {
+      open_brackets.pop();
+    }
+
+    // Each line with unclosed brackets adds one level of indentation, no matter how many brackets it opens
+    let mut depth = open_brackets.iter().fold((0, None), |(depth, prev_line), line| {
+      if prev_line == Some(*line) { (depth, prev_line) } else { (depth + 1, Some(*line)) }
+    }).0;
+
+    for token in line_tokens {
+      if is_opening_bracket(token) {
+        open_brackets.push(line_index);
+      } else if is_closing_bracket(token) {
+        open_brackets.pop();
+      }
+    }
+
+    if body_start == body_end {
+      depth = 0;
+    }
+
+    let indent_span = line_start .. body_start;
+    let indent = INDENT.repeat(depth);
+    if source[indent_span.clone()] != indent && is_layout_range(&indent_span) {
+      line_edits.push((region_index(indent_span.start), Edit { span: indent_span, text: indent }));
+    }
+    let trailing_span = body_end .. content_end;
+    if !trailing_span.is_empty() && body_end > body_start && is_layout_range(&trailing_span) {
+      line_edits.push((region_index(trailing_span.start), Edit { span: trailing_span, text: String::new() }));
+    }
+
+    line_start = line_end;
+  }
+
+  // Line edits are kept if they don't change any whitespace that the program prints;
+  // whitespace tokens that aren't printed may change or go away, but none can be added where the lexer skipped everything
+  let mut line_edits = line_edits.into_iter().peekable();
+  while let Some((index, first_edit)) = line_edits.next() {
+    let mut region_edits = vec![first_edit];
+    while let Some((_, edit)) = line_edits.next_if(|(next_index, _)| *next_index == index) {
+      region_edits.push(edit);
+    }
+
+    let region = &regions[index];
+    let shifted_edits = region_edits.iter()
+      .map(|edit| Edit { span: edit.span.start - region.start .. edit.span.end - region.start, text: edit.text.clone() })
+      .collect();
+    let original_text = &source[region.clone()];
+    let edited_text = apply_edits(original_text, shifted_edits);
+    let original_tokens = region_whitespace_tokens(original_text, region.start);
+    let edited_tokens = region_whitespace_tokens(&edited_text, region.start);
+    let is_printed = |(span, _): &(Range<usize>, &str)| layout.printed_whitespace.contains(&(span.start + region.start .. span.end + region.start));
+    let is_print_unchanged = if original_tokens.iter().any(is_printed) {
+      original_tokens.iter().map(|(_, text)| text).eq(edited_tokens.iter().map(|(_, text)| text))
+    } else {
+      !original_tokens.is_empty() || edited_tokens.is_empty()
+    };
+    if is_print_unchanged {
+      edits.extend(region_edits);
+    }
+  }
+
+  // Normalize the spacing inside lines where the parser skips whitespace; where hints disagree, no space wins
+  let mut spacing = layout.spacing.iter()
+    .map(|(offset, spacing)| (region_index(*offset), *spacing))
+    .collect::<Vec<_>>();
+  spacing.sort_by_key(|(index, spacing)| (*index, *spacing == Spacing::Space));
+  spacing.dedup_by_key(|(index, _)| *index);
+  for (index, spacing) in spacing {
+    let region = regions[index].clone();
+    let text = &source[region.clone()];
+    if text.contains(&['\r', '\n'][..]) || !is_layout_range(&region) || edits.iter().any(|edit| edit.span.start < region.end && region.start < edit.span.end) {
+      continue
+    }
+    let spaced = match spacing {
+      Spacing::None => "",
+      Spacing::Space => " ",
+    };
+    if text != spaced {
+      edits.push(Edit { span: region, text: spaced.to_owned() });
+    }
+  }
+
+  apply_edits(source, edits)
+}
+
+#[inline]
+fn is_opening_bracket(token: &str) -> bool {
+  matches!(token, "{" | "[" | "(" | "<")
+}
+
+#[inline]
+fn is_closing_bracket(token: &str) -> bool {
+  matches!(token, "}" | "]" | ")" | ">")
+}
@@ -1,7 +1,8 @@
 use logos::*;
 use crate::InternalString;
 
-#[derive(Logos, Debug, PartialEq)]
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(extras = Vec<AllowDirective>)]
 pub enum RantToken {
   /// Sequence of printable non-whitespace characters
   #[error]
@@ -146,7 +147,7 @@ pub enum RantToken {
   
   /// Represents inline and multi-line comments
   #[regex(r"\s*##([^#]|#[^#])*(##\s*)?", logos::skip, priority = 6)]
-  #[regex(r"\s*#([^#][^\r\n]*)?\n?", logos::skip, priority = 5)]
+  #[regex(r"\s*#([^#][^\r\n]*)?\n?", lex_line_comment, priority = 5)]
   Comment,
   
   /// Represents any escape sequence
@@ -157,12 +158,92 @@ pub enum RantToken {
   /// Represents a verbatim string literal, e.g. `"hello world"`
   #[regex(r#""(""|[^"])*""#, parse_string_literal)]
   StringLiteral(InternalString),
+
+  /// Represents an interpolated string literal, e.g. `$"hello <name>"`
+  ///
+  /// The contents are parsed separately, since they can contain accessors and function calls.
+  /// Quotes inside embedded items start nested string literals, e.g. `$"[cat: "a"; "b"]"`.
+  #[regex(r#"\$"(""|[^"])*""#, lex_interpolated_string)]
+  InterpolatedStringLiteral,
   
   /// Error token indicating an unterminated string literal, e.g. `"foo`
   #[regex(r#""(""|[^"])*"#)]
+  #[regex(r#"\$"(""|[^"])*"#)]
   UnterminatedStringLiteral,
 }
 
+/// A `#@allow(...)` comment, which suppresses warnings on the line it applies to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllowDirective {
+  /// The byte offset of the comment's `#`.
+  pub offset: usize,
+  /// The codes or names of the problems to allow, e.g. `R1002` or `unused-function`.
+  pub problems: Vec<String>,
+}
+
+/// Skips a line comment, recording it first if it's an `#@allow(...)` directive.
+fn lex_line_comment(lex: &mut Lexer<RantToken>) -> Skip {
+  let slice = lex.slice();
+  if let Some(comment_start) = slice.find('#') {
+    let comment = slice[comment_start..].trim_end();
+    if let Some(args) = comment.strip_prefix("#@allow(").and_then(|args| args.strip_suffix(')')) {
+      let problems = args.split(',').map(|problem| problem.trim().to_owned()).filter(|problem| !problem.is_empty()).collect();
+      let offset = lex.span().start + comment_start;
+      lex.extras.push(AllowDirective {
+        offset,
+        problems,
+      });
+    }
+  }
+  Skip
+}
+
+/// Extends an interpolated string literal past any quotes inside its embedded items.
+///
+/// If the items leave the literal unclosed, it ends at its first quote so that the parser reports the unclosed item.
+fn lex_interpolated_string(lex: &mut Lexer<RantToken>) {
+  if let Some(len) = interpolated_string_len(&lex.source()[lex.span().start..]) {
+    lex.bump(len - lex.slice().len());
+  }
+}
+
+/// Gets the length of the interpolated string literal at the start of `source`, or `None` if it isn't closed.
+///
+/// Outside of embedded items, `""` is an escaped quote; inside them, quotes start nested string literals.
+fn interpolated_string_len(source: &str) -> Option<usize> {
+  let bytes = source.as_bytes();
+  let mut pos = 2;
+  let mut depth = 0usize;
+  while pos < bytes.len() {
+    match (bytes[pos], bytes.get(pos + 1)) {
+      (b'"', Some(b'"')) if depth == 0 => pos += 1,
+      (b'"', _) if depth == 0 => return Some(pos + 1),
+      (b'"', _) => {
+        // Nested verbatim string literal
+        pos += 1;
+        loop {
+          match (bytes.get(pos)?, bytes.get(pos + 1)) {
+            (b'"', Some(b'"')) => pos += 2,
+            (b'"', _) => break,
+            _ => pos += 1,
+          }
+        }
+      },
+      (b'$', Some(b'"')) if depth > 0 => {
+        pos += interpolated_string_len(&source[pos..])?;
+        continue
+      },
+      (b'\\', _) | (b'|', Some(b'>')) if depth > 0 => pos += 1,
+      (b'[' | b'<', _) => depth += 1,
+      (b'{' | b'(', _) if depth > 0 => depth += 1,
+      (b']' | b'>' | b'}' | b')', _) if depth > 0 => depth -= 1,
+      _ => {},
+    }
+    pos += 1;
+  }
+  None
+}
+
 fn parse_temporal_spread_label(lex: &mut Lexer<RantToken>) -> InternalString {
   let slice = lex.slice();
   InternalString::from(&slice[1 .. slice.len() - 1])
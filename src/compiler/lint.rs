@@ -0,0 +1,110 @@
+use super::{CompilerMessage, Problem, Reporter, Severity};
+use std::collections::HashMap;
+
+/// The name that refers to every warning in a `WarningConfig` or `#@allow(...)` comment.
+pub const ALL_WARNINGS: &str = "warnings";
+
+/// Describes how a warning is reported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WarningLevel {
+  /// The warning is silenced.
+  Allow,
+  /// The warning is reported as a warning.
+  Warn,
+  /// The warning is reported as an error, causing compilation to fail.
+  Deny,
+}
+
+/// Configures the level of compiler warnings by problem code (e.g. `R1002`) or name (e.g. `unused-function`).
+///
+/// Levels set for a specific problem take precedence over the level set for all warnings with the name `warnings`.
+/// Errors can't be configured and are always reported as errors.
+///
+/// ```
+/// use rant::Rant;
+/// use rant::compiler::{CompilerMessage, WarningConfig, WarningLevel};
+///
+/// let mut config = WarningConfig::new();
+/// config.set("unused-function", WarningLevel::Deny);
+///
+/// let mut messages: Vec<CompilerMessage> = vec![];
+/// let result = Rant::new().compile("[$helper] {}", &mut config.reporter(&mut messages));
+/// assert!(result.is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WarningConfig {
+  levels: HashMap<String, WarningLevel>,
+  all_level: Option<WarningLevel>,
+}
+
+impl WarningConfig {
+  /// Creates a configuration that reports every warning as a warning.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Sets the level of a warning by code or name, or of all warnings if `problem` is `warnings`.
+  ///
+  /// Returns `false` without changing the configuration if `problem` doesn't refer to any problem.
+  pub fn set(&mut self, problem: &str, level: WarningLevel) -> bool {
+    if problem == ALL_WARNINGS {
+      self.all_level = Some(level);
+    } else if is_known_problem(problem) {
+      self.levels.insert(problem.to_owned(), level);
+    } else {
+      return false
+    }
+    true
+  }
+
+  /// Gets the configured level of a warning.
+  pub fn level_of(&self, problem: &Problem) -> WarningLevel {
+    self.levels.get(problem.code())
+      .or_else(|| self.levels.get(problem.name()))
+      .copied()
+      .or(self.all_level)
+      .unwrap_or(WarningLevel::Warn)
+  }
+
+  /// Wraps a reporter so that warnings passed to it are reported according to this configuration.
+  pub fn reporter<'a, R: Reporter>(&'a self, inner: &'a mut R) -> ConfiguredReporter<'a, R> {
+    ConfiguredReporter {
+      config: self,
+      inner,
+    }
+  }
+}
+
+/// A reporter that applies a `WarningConfig` to the warnings passed to another reporter.
+///
+/// Created with `WarningConfig::reporter()`.
+pub struct ConfiguredReporter<'a, R: Reporter> {
+  config: &'a WarningConfig,
+  inner: &'a mut R,
+}
+
+impl<'a, R: Reporter> Reporter for ConfiguredReporter<'a, R> {
+  fn report(&mut self, msg: CompilerMessage) {
+    self.inner.report(msg)
+  }
+
+  fn warning_severity(&self, problem: &Problem) -> Option<Severity> {
+    match self.config.level_of(problem) {
+      WarningLevel::Allow => None,
+      WarningLevel::Warn => self.inner.warning_severity(problem),
+      WarningLevel::Deny => Some(Severity::Error),
+    }
+  }
+}
+
+/// Checks if a code or name can be used in a warning configuration or `#@allow(...)` comment.
+#[inline]
+pub(crate) fn is_known_problem(code_or_name: &str) -> bool {
+  code_or_name == ALL_WARNINGS || Problem::is_known(code_or_name)
+}
+
+/// Checks if a problem is referred to by a code or name in a warning configuration or `#@allow(...)` comment.
+#[inline]
+pub(crate) fn problem_matches(problem: &Problem, code_or_name: &str) -> bool {
+  code_or_name == ALL_WARNINGS || code_or_name == problem.code() || code_or_name == problem.name()
+}
@@ -58,7 +58,7 @@ impl Display for Severity {
 }
 
 /// Describes the location and nature of a compiler message.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CompilerMessage {
   pos: Option<Position>,
   severity: Severity,
@@ -122,7 +122,7 @@ impl CompilerMessage {
 }
 
 /// Describes a problem (warning/error) encountered when building a source.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Problem {
   UnexpectedToken(String),
   ExpectedToken(String),
@@ -135,6 +135,7 @@ pub enum Problem {
   UnclosedVariableAccess,
   UnclosedList,
   UnclosedMap,
+  UnclosedSet,
   AnonValueAssignment,
   MultipleVariadicParams,
   MissingFunctionBody,
@@ -166,9 +167,93 @@ pub enum Problem {
   DynamicDepth,
   InvalidDepthUsage,
   DepthAssignment,
+  UnclosedMatch,
+  InvalidPattern(String),
+  MissingMatchFallback,
+  DuplicateBinding(String),
   FallibleOptionalArgAccess(String),
+  UndefinedVariable(String),
+  ArgumentCountMismatch(String, String, usize),
+  UnreachableMatchArm,
+  DuplicateNamedArgument(String),
+  PositionalArgumentAfterNamed,
+  UnknownNamedArgument(String, String),
+  VariadicNamedArgument(String),
+  NamedArgumentAlreadyPassed(String),
+  MissingArgument(String, String),
+  UnknownType(String),
+  UnclosedTypeAnnotation,
+  ArgumentTypeMismatch(String, String, String),
+  DefinitionInInterpolatedString(String),
+  UnknownProblem(String),
 }
 
+/// An instance of every kind of problem, used to check whether a code or name refers to a problem.
+const ALL_PROBLEMS: &[Problem] = &[
+  Problem::UnexpectedToken(String::new()),
+  Problem::ExpectedToken(String::new()),
+  Problem::UnclosedBlock,
+  Problem::DynamicKeyBlockMultiElement,
+  Problem::FunctionBodyBlockMultiElement,
+  Problem::UnclosedFunctionCall,
+  Problem::UnclosedFunctionSignature,
+  Problem::UnclosedStringLiteral,
+  Problem::UnclosedVariableAccess,
+  Problem::UnclosedList,
+  Problem::UnclosedMap,
+  Problem::UnclosedSet,
+  Problem::AnonValueAssignment,
+  Problem::MultipleVariadicParams,
+  Problem::MissingFunctionBody,
+  Problem::UnclosedFunctionBody,
+  Problem::InvalidParamOrder(String::new(), String::new()),
+  Problem::InvalidParameter(String::new()),
+  Problem::MissingIdentifier,
+  Problem::InvalidIdentifier(String::new()),
+  Problem::DuplicateParameter(String::new()),
+  Problem::AccessPathStartsWithIndex,
+  Problem::AccessPathStartsWithSlice,
+  Problem::InvalidSliceBound(String::new()),
+  Problem::InvalidSinkOn(""),
+  Problem::InvalidHintOn(""),
+  Problem::InvalidSink,
+  Problem::InvalidHint,
+  Problem::NothingToPipe,
+  Problem::UnusedVariable(String::new()),
+  Problem::UnusedParameter(String::new()),
+  Problem::UnusedFunction(String::new()),
+  Problem::EmptyFunctionBody(String::new()),
+  Problem::NestedFunctionDefMarkedConstant,
+  Problem::ConstantReassignment(String::new()),
+  Problem::ConstantRedefinition(String::new()),
+  Problem::InvalidKeyword(String::new()),
+  Problem::WeightNotAllowed,
+  Problem::FileNotFound(String::new()),
+  Problem::FileSystemError(String::new()),
+  Problem::DynamicDepth,
+  Problem::InvalidDepthUsage,
+  Problem::DepthAssignment,
+  Problem::UnclosedMatch,
+  Problem::InvalidPattern(String::new()),
+  Problem::MissingMatchFallback,
+  Problem::DuplicateBinding(String::new()),
+  Problem::FallibleOptionalArgAccess(String::new()),
+  Problem::UndefinedVariable(String::new()),
+  Problem::ArgumentCountMismatch(String::new(), String::new(), 0),
+  Problem::UnreachableMatchArm,
+  Problem::DuplicateNamedArgument(String::new()),
+  Problem::PositionalArgumentAfterNamed,
+  Problem::UnknownNamedArgument(String::new(), String::new()),
+  Problem::VariadicNamedArgument(String::new()),
+  Problem::NamedArgumentAlreadyPassed(String::new()),
+  Problem::MissingArgument(String::new(), String::new()),
+  Problem::UnknownType(String::new()),
+  Problem::UnclosedTypeAnnotation,
+  Problem::ArgumentTypeMismatch(String::new(), String::new(), String::new()),
+  Problem::DefinitionInInterpolatedString(String::new()),
+  Problem::UnknownProblem(String::new()),
+];
+
 macro_rules! rmsg {
   ($msg:literal) => {
     $msg.to_string()
@@ -179,7 +264,8 @@ macro_rules! rmsg {
 }
 
 impl Problem {
-  fn code(&self) -> &'static str {
+  /// Gets the code of the problem, e.g. `R1002`.
+  pub fn code(&self) -> &'static str {
     /// Formats Rant error code constants.
     macro_rules! rcode {
       ($code:literal) => {
@@ -225,6 +311,26 @@ impl Problem {
       Self::DynamicDepth =>                                     rcode!(0025),
       Self::DepthAssignment =>                                  rcode!(0026),
       Self::InvalidDepthUsage =>                                rcode!(0027),
+
+      // Match expressions
+      Self::UnclosedMatch =>                                    rcode!(0028),
+      Self::InvalidPattern(_) =>                                rcode!(0029),
+      Self::MissingMatchFallback =>                             rcode!(0030),
+      Self::DuplicateBinding(_) =>                              rcode!(0031),
+
+      // Named arguments
+      Self::DuplicateNamedArgument(_) =>                        rcode!(0032),
+      Self::PositionalArgumentAfterNamed =>                     rcode!(0033),
+
+      // Type annotations
+      Self::UnknownType(_) =>                                   rcode!(0035),
+      Self::UnclosedTypeAnnotation =>                           rcode!(0036),
+
+      // Sets
+      Self::UnclosedSet =>                                      rcode!(0037),
+
+      // Interpolated strings
+      Self::DefinitionInInterpolatedString(_) =>                rcode!(0038),
       
       // Static analysis errors (0100 - 0199)
       Self::ConstantReassignment(_) =>                          rcode!(0100),
@@ -245,6 +351,15 @@ impl Problem {
       Self::EmptyFunctionBody(_) =>                             rcode!(1003),
       Self::NestedFunctionDefMarkedConstant =>                  rcode!(1004),
       Self::FallibleOptionalArgAccess(_) =>                     rcode!(1005),
+      Self::UndefinedVariable(_) =>                             rcode!(1006),
+      Self::ArgumentCountMismatch(..) =>                        rcode!(1007),
+      Self::UnreachableMatchArm =>                              rcode!(1008),
+      Self::UnknownNamedArgument(..) =>                         rcode!(1009),
+      Self::VariadicNamedArgument(_) =>                         rcode!(1010),
+      Self::NamedArgumentAlreadyPassed(_) =>                    rcode!(1011),
+      Self::MissingArgument(..) =>                              rcode!(1012),
+      Self::ArgumentTypeMismatch(..) =>                         rcode!(1013),
+      Self::UnknownProblem(_) =>                                rcode!(1014),
       
       // File access errors (0100 - 0109)
       Self::FileNotFound(_) =>                                  rcode!(2100),
@@ -252,6 +367,79 @@ impl Problem {
     }
   }
 
+  /// Gets the name of the problem in kebab case, e.g. `unused-function`.
+  ///
+  /// Names can be used in place of codes to allow warnings with `#@allow(...)` comments or to configure them with `WarningConfig`.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::UnexpectedToken(_) =>              "unexpected-token",
+      Self::ExpectedToken(_) =>                "expected-token",
+      Self::UnclosedBlock =>                   "unclosed-block",
+      Self::DynamicKeyBlockMultiElement =>     "dynamic-key-block-multi-element",
+      Self::FunctionBodyBlockMultiElement =>   "function-body-block-multi-element",
+      Self::UnclosedFunctionCall =>            "unclosed-function-call",
+      Self::UnclosedFunctionSignature =>       "unclosed-function-signature",
+      Self::UnclosedStringLiteral =>           "unclosed-string-literal",
+      Self::UnclosedVariableAccess =>          "unclosed-variable-access",
+      Self::UnclosedList =>                    "unclosed-list",
+      Self::UnclosedMap =>                     "unclosed-map",
+      Self::UnclosedSet =>                     "unclosed-set",
+      Self::AnonValueAssignment =>             "anon-value-assignment",
+      Self::MultipleVariadicParams =>          "multiple-variadic-params",
+      Self::MissingFunctionBody =>             "missing-function-body",
+      Self::UnclosedFunctionBody =>            "unclosed-function-body",
+      Self::InvalidParamOrder(..) =>           "invalid-param-order",
+      Self::InvalidParameter(_) =>             "invalid-parameter",
+      Self::MissingIdentifier =>               "missing-identifier",
+      Self::InvalidIdentifier(_) =>            "invalid-identifier",
+      Self::DuplicateParameter(_) =>           "duplicate-parameter",
+      Self::AccessPathStartsWithIndex =>       "access-path-starts-with-index",
+      Self::AccessPathStartsWithSlice =>       "access-path-starts-with-slice",
+      Self::InvalidSliceBound(_) =>            "invalid-slice-bound",
+      Self::InvalidSink | Self::InvalidSinkOn(_) => "invalid-sink",
+      Self::InvalidHint | Self::InvalidHintOn(_) => "invalid-hint",
+      Self::NothingToPipe =>                   "nothing-to-pipe",
+      Self::UnusedVariable(_) =>               "unused-variable",
+      Self::UnusedParameter(_) =>              "unused-parameter",
+      Self::UnusedFunction(_) =>               "unused-function",
+      Self::EmptyFunctionBody(_) =>            "empty-function-body",
+      Self::NestedFunctionDefMarkedConstant => "nested-function-def-marked-constant",
+      Self::ConstantReassignment(_) =>         "constant-reassignment",
+      Self::ConstantRedefinition(_) =>         "constant-redefinition",
+      Self::InvalidKeyword(_) =>               "invalid-keyword",
+      Self::WeightNotAllowed =>                "weight-not-allowed",
+      Self::FileNotFound(_) =>                 "file-not-found",
+      Self::FileSystemError(_) =>              "file-system-error",
+      Self::DynamicDepth =>                    "dynamic-depth",
+      Self::InvalidDepthUsage =>               "invalid-depth-usage",
+      Self::DepthAssignment =>                 "depth-assignment",
+      Self::FallibleOptionalArgAccess(_) =>    "fallible-optional-arg-access",
+      Self::UndefinedVariable(_) =>            "undefined-variable",
+      Self::ArgumentCountMismatch(..) =>       "argument-count-mismatch",
+      Self::UnclosedMatch =>                   "unclosed-match",
+      Self::InvalidPattern(_) =>               "invalid-pattern",
+      Self::MissingMatchFallback =>            "missing-match-fallback",
+      Self::DuplicateBinding(_) =>             "duplicate-binding",
+      Self::UnreachableMatchArm =>             "unreachable-match-arm",
+      Self::DuplicateNamedArgument(_) =>       "duplicate-named-argument",
+      Self::PositionalArgumentAfterNamed =>    "positional-argument-after-named",
+      Self::UnknownNamedArgument(..) =>        "unknown-named-argument",
+      Self::VariadicNamedArgument(_) =>        "variadic-named-argument",
+      Self::NamedArgumentAlreadyPassed(_) =>   "named-argument-already-passed",
+      Self::MissingArgument(..) =>             "missing-argument",
+      Self::UnknownType(_) =>                  "unknown-type",
+      Self::UnclosedTypeAnnotation =>          "unclosed-type-annotation",
+      Self::ArgumentTypeMismatch(..) =>        "argument-type-mismatch",
+      Self::DefinitionInInterpolatedString(_) => "definition-in-interpolated-string",
+      Self::UnknownProblem(_) =>               "unknown-problem",
+    }
+  }
+
+  /// Determines whether `code_or_name` is the code or name of any problem, e.g. `R1002` or `unused-function`.
+  pub fn is_known(code_or_name: &str) -> bool {
+    ALL_PROBLEMS.iter().any(|problem| problem.code() == code_or_name || problem.name() == code_or_name)
+  }
+
   fn message(&self) -> String {
     match self {
       Self::UnclosedBlock => rmsg!("unclosed block; expected '}'"),
@@ -271,7 +459,7 @@ impl Problem {
       Self::UnclosedFunctionSignature => rmsg!("unclosed function signature; expected ']' followed by body block"),
       Self::InvalidParamOrder(first, second) => rmsg!("{} is not allowed after {}", second, first),
       Self::MissingFunctionBody => rmsg!("missing body in function definition"),
-      Self::UnclosedFunctionBody => rmsg!("unclosed function body; expected '}}'"),
+      Self::UnclosedFunctionBody => rmsg!("unclosed function body; expected '}'"),
       Self::InvalidParameter(pname) => rmsg!("invalid parameter '{}'; must be a valid identifier or '*'", pname),
       Self::DuplicateParameter(pname) => rmsg!("duplicate parameter '{}' in function signature", pname),
       Self::MultipleVariadicParams => rmsg!("multiple variadic parameters are not allowed"),
@@ -285,6 +473,7 @@ impl Problem {
       Self::UnclosedVariableAccess => rmsg!("unclosed accessor; expected '>'"),
       Self::UnclosedList => rmsg!("unclosed list initializer; expected ')'"),
       Self::UnclosedMap => rmsg!("unclosed map initializer; expected ')'"),
+      Self::UnclosedSet => rmsg!("unclosed set initializer; expected '}'"),
       Self::DynamicKeyBlockMultiElement => rmsg!("dynamic key blocks can't have more than one element; if branching is desired, create an inner block"),
       Self::FunctionBodyBlockMultiElement => rmsg!("function body blocks can't have more than one element; if branching is desired, create an inner block"),
       Self::AnonValueAssignment => rmsg!("can't assign directly to anonymous value; try assigning to a key or index instead"),
@@ -297,12 +486,30 @@ impl Problem {
       Self::DepthAssignment => rmsg!("variable depth cannot be assigned to"),
       Self::InvalidDepthUsage => rmsg!("depth operator is not valid in this context"),
       Self::FallibleOptionalArgAccess(argname) => rmsg!("access to optional argument '{}' can fail; consider adding a fallback to the accessor or specifying a default argument", argname),
+      Self::UndefinedVariable(vname) => rmsg!("variable '{}' is not defined in any enclosing scope", vname),
+      Self::ArgumentCountMismatch(fname, expected, found) => rmsg!("wrong number of arguments to '{}'; expected {}, found {}", fname, expected, found),
+      Self::UnclosedMatch => rmsg!("unclosed match expression; expected '}'"),
+      Self::InvalidPattern(token) => rmsg!("invalid pattern: '{}'", token),
+      Self::MissingMatchFallback => rmsg!("match expression has no fallback arm; the last arm must match any value, e.g. '_' or '$name'"),
+      Self::DuplicateBinding(vname) => rmsg!("variable '{}' is bound more than once in the same pattern", vname),
+      Self::UnreachableMatchArm => rmsg!("match arm is unreachable because an earlier arm matches every value it does"),
+      Self::DuplicateNamedArgument(argname) => rmsg!("argument '{}' is passed by name more than once", argname),
+      Self::PositionalArgumentAfterNamed => rmsg!("positional arguments can't follow named arguments"),
+      Self::UnknownNamedArgument(fname, argname) => rmsg!("function '{}' has no parameter named '{}'", fname, argname),
+      Self::VariadicNamedArgument(argname) => rmsg!("variadic parameter '{}' can't be passed by name; pass its values as positional arguments instead", argname),
+      Self::NamedArgumentAlreadyPassed(argname) => rmsg!("argument '{}' is already passed by position", argname),
+      Self::MissingArgument(fname, pname) => rmsg!("call to '{}' is missing required argument '{}'", fname, pname),
+      Self::UnknownType(tname) => rmsg!("unknown type '{}' in type annotation; expected a value type such as 'int' or 'string', or 'any'", tname),
+      Self::UnclosedTypeAnnotation => rmsg!("unclosed type annotation; expected ')'"),
+      Self::ArgumentTypeMismatch(pname, expected, found) => rmsg!("argument '{}' should be {}, but found {}", pname, expected, found),
+      Self::DefinitionInInterpolatedString(name) => rmsg!("'{}' can't be defined inside an interpolated string; define it before the string instead", name),
+      Self::UnknownProblem(problem) => rmsg!("'{}' is not the code or name of any compiler problem", problem),
     }
   }
   
   fn inline_message(&self) -> Option<String> {
     Some(match self {
-      Self::UnclosedBlock => rmsg!("no matching '}}' found"),
+      Self::UnclosedBlock => rmsg!("no matching '}' found"),
       Self::UnclosedStringLiteral => rmsg!("string literal needs closing delimiter"),
       Self::ExpectedToken(token) => rmsg!("expected '{}'", token),
       Self::MissingIdentifier => rmsg!("missing identifier"),
@@ -315,18 +522,37 @@ impl Problem {
       Self::UnclosedFunctionSignature => rmsg!("no matching ']' found"),
       Self::InvalidParamOrder(_, second) => rmsg!("{} is not valid in this position", second),
       Self::MissingFunctionBody => rmsg!("function body should follow"),
-      Self::UnclosedFunctionBody => rmsg!("no matching '}}' found"),
+      Self::UnclosedFunctionBody => rmsg!("no matching '}' found"),
       Self::InvalidParameter(_) => rmsg!("invalid parameter"),
       Self::DuplicateParameter(_) => rmsg!("rename parameter to something unique"),
       Self::MultipleVariadicParams => rmsg!("remove extra variadic parameter"),
       Self::UnclosedVariableAccess => rmsg!("no matching '>' found"),
       Self::UnclosedList => rmsg!("no matching ')' found"),
       Self::UnclosedMap => rmsg!("no matching ')' found"),
+      Self::UnclosedSet => rmsg!("no matching '}' found"),
       Self::FunctionBodyBlockMultiElement => rmsg!("multiple elements not allowed here"),
       Self::DynamicKeyBlockMultiElement => rmsg!("multiple elements not allowed here"),
       Self::AnonValueAssignment => rmsg!("direct assignment impossible"),
       Self::NothingToPipe => rmsg!("no previous output to consume"),
       Self::NestedFunctionDefMarkedConstant => rmsg!("use '$' here instead"),
+      Self::UndefinedVariable(_) => rmsg!("undefined variable"),
+      Self::ArgumentCountMismatch(_, expected, _) => rmsg!("expected {}", expected),
+      Self::UnclosedMatch => rmsg!("no matching '}' found"),
+      Self::InvalidPattern(_) => rmsg!("invalid pattern"),
+      Self::MissingMatchFallback => rmsg!("add a '_' arm at the end"),
+      Self::DuplicateBinding(_) => rmsg!("rename binding to something unique"),
+      Self::UnreachableMatchArm => rmsg!("unreachable arm"),
+      Self::DuplicateNamedArgument(_) => rmsg!("remove duplicate argument"),
+      Self::PositionalArgumentAfterNamed => rmsg!("move before named arguments"),
+      Self::UnknownNamedArgument(..) => rmsg!("unknown parameter"),
+      Self::VariadicNamedArgument(_) => rmsg!("can't be passed by name"),
+      Self::NamedArgumentAlreadyPassed(_) => rmsg!("already passed"),
+      Self::MissingArgument(_, pname) => rmsg!("missing '{}'", pname),
+      Self::UnknownType(_) => rmsg!("unknown type"),
+      Self::UnclosedTypeAnnotation => rmsg!("no matching ')' found"),
+      Self::ArgumentTypeMismatch(_, expected, _) => rmsg!("expected {}", expected),
+      Self::DefinitionInInterpolatedString(_) => rmsg!("definition not allowed here"),
+      Self::UnknownProblem(_) => rmsg!("unknown problem"),
       _ => return None
     })
   }
@@ -1,6 +1,7 @@
-use crate::{RantProgram, RantProgramInfo};
+use crate::{InternalString, RantProgram, RantProgramInfo, RantVar};
 use self::parser::RantParser;
-use std::{error::Error, fs};
+use fnv::FnvBuildHasher;
+use std::{collections::HashMap, error::Error, fs};
 use std::{fmt::Display, path::Path, rc::Rc};
 use std::io::ErrorKind as IOErrorKind;
 
@@ -8,8 +9,15 @@ pub(crate) mod lexer;
 pub(crate) mod reader;
 pub(crate) mod parser;
 pub(crate) mod message;
+pub(crate) mod symbols;
+pub(crate) mod formatter;
+pub(crate) mod lint;
+pub(crate) mod optimizer;
 
+pub use lint::*;
 pub use message::*;
+pub use optimizer::OptimizationLevel;
+pub use symbols::*;
 
 /// Type alias for `Result<RantProgram, CompilerErrorKind>`
 pub type CompileResult = Result<RantProgram, CompilerErrorKind>;
@@ -45,6 +53,14 @@ impl Error for CompilerErrorKind {
 pub trait Reporter {
   /// Passes a compiler message to the implementor for processing.
   fn report(&mut self, msg: CompilerMessage);
+
+  /// Gets the severity to report a warning with, or `None` to silence it.
+  ///
+  /// The compiler calls this before reporting each warning; returning `Severity::Error` causes compilation to fail.
+  /// The default implementation reports every warning as a warning.
+  fn warning_severity(&self, _problem: &Problem) -> Option<Severity> {
+    Some(Severity::Warning)
+  }
 }
 
 impl Reporter for () {
@@ -57,27 +73,68 @@ impl Reporter for Vec<CompilerMessage> {
   }
 }
 
-pub(crate) fn compile_string<R: Reporter>(source: &str, reporter: &mut R, debug_enabled: bool, info: RantProgramInfo) -> CompileResult {
+/// Globals available to a program when it runs, which the compiler checks variable accesses and function calls against.
+pub(crate) type KnownGlobals = HashMap<InternalString, RantVar, FnvBuildHasher>;
+
+pub(crate) fn compile_string<R: Reporter>(source: &str, reporter: &mut R, debug_enabled: bool, opt_level: OptimizationLevel, info: RantProgramInfo, globals: Option<&KnownGlobals>) -> CompileResult {
   let info = Rc::new(info);
 
   let mut parser = RantParser::new(source, reporter, debug_enabled, &info);
+  if let Some(globals) = globals {
+    parser = parser.with_globals(globals);
+  }
 
   // Return compilation result
   match parser.parse() {
-    Ok(seq) => Ok(RantProgram::new(seq, info)),
+    Ok(mut seq) => {
+      optimizer::optimize(&mut seq, opt_level, globals);
+      Ok(RantProgram::new(seq, info))
+    },
     Err(()) => Err(CompilerErrorKind::SyntaxError),
   }
 }
 
-pub(crate) fn compile_file<P: AsRef<Path>, R: Reporter>(path: P, reporter: &mut R, debug_enabled: bool) -> CompileResult {
+/// Parses source code without producing a program and returns the symbols it defines and references.
+///
+/// Compiler messages are passed to `reporter` as usual. If the source has syntax errors,
+/// the returned table contains whatever was collected before parsing stopped.
+pub fn analyze<R: Reporter>(source: &str, reporter: &mut R) -> SymbolTable {
+  let info = Rc::new(RantProgramInfo {
+    name: None,
+    path: None,
+  });
+
+  let mut parser = RantParser::new(source, reporter, false, &info).with_symbols();
+  let _ = parser.parse();
+  parser.take_symbols().unwrap_or_default()
+}
+
+/// Formats source code with consistent indentation of blocks, function signatures, and collections,
+/// and consistent spacing between the parameters of function signatures and the items of collections.
+///
+/// Only whitespace that the compiler ignores is changed, so the formatted code always behaves the same as the original.
+/// Comments are preserved. Fails if the source has syntax errors, which are passed to `reporter`.
+pub fn format_source<R: Reporter>(source: &str, reporter: &mut R) -> Result<String, CompilerErrorKind> {
+  let info = Rc::new(RantProgramInfo {
+    name: None,
+    path: None,
+  });
+
+  let mut parser = RantParser::new(source, reporter, false, &info).with_layout();
+  parser.parse().map_err(|_| CompilerErrorKind::SyntaxError)?;
+  let layout = parser.take_layout().unwrap_or_default();
+  Ok(formatter::format(source, &layout))
+}
+
+pub(crate) fn compile_file<P: AsRef<Path>, R: Reporter>(path: P, reporter: &mut R, debug_enabled: bool, opt_level: OptimizationLevel, globals: Option<&KnownGlobals>) -> CompileResult {
   let source_name = path.as_ref().canonicalize().unwrap_or_else(|_| path.as_ref().to_path_buf()).to_string_lossy().to_string();
   let file_read_result = fs::read_to_string(path);
   match file_read_result {
     Ok(source) => {
-      compile_string(&source, reporter, debug_enabled, RantProgramInfo {
+      compile_string(&source, reporter, debug_enabled, opt_level, RantProgramInfo {
         name: None,
         path: Some(source_name)
-      })
+      }, globals)
     },
     // Something went wrong with reading the file
     Err(err) => {
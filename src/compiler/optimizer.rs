@@ -0,0 +1,495 @@
+//! Simplifies parsed Rant programs so that they do less work at runtime.
+
+use super::KnownGlobals;
+use crate::{InternalString, RantList, RantMap, RantSet, RantValue};
+use crate::lang::*;
+use fnv::FnvHashSet;
+use std::{cell::RefCell, mem, rc::Rc};
+
+/// Standard library functions that can be folded when called with constant numeric arguments, and their argument counts.
+const FOLDABLE_FUNCTIONS: &[(&str, usize)] = &[
+  ("add", 2), ("sub", 2), ("mul", 2), ("div", 2), ("mod", 2), ("neg", 1),
+];
+
+/// Describes how much the compiler optimizes a program.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OptimizationLevel {
+  /// Programs are run exactly as they were parsed.
+  None,
+  /// Merges adjacent text fragments and builds lists and maps that only contain constant values at compile time.
+  ///
+  /// Each evaluation of a prebuilt collection gets its own handle, but its contents are only copied if the program modifies them.
+  ///
+  /// These optimizations never change the behavior of a program.
+  #[default]
+  Basic,
+  /// Applies the basic optimizations, folds calls to `add`, `sub`, `mul`, `div`, `mod`, and `neg` with constant numeric arguments,
+  /// removes block elements with a constant weight of zero, and inlines blocks with a single non-printing element.
+  ///
+  /// Calls are only folded if the program doesn't define a variable with the same name,
+  /// so hosts that replace these functions with globals of their own should use `Basic` instead.
+  /// Blocks are only changed where no block attributes can apply to them.
+  ///
+  /// Removed elements still appear in coverage reports as never selected, but inlined blocks don't appear at all.
+  Full,
+}
+
+/// Optimizes a parsed program in place.
+///
+/// Nodes that are shared with other parts of the tree are left unchanged.
+pub(crate) fn optimize(root: &mut Rc<Sequence>, level: OptimizationLevel, globals: Option<&KnownGlobals>) {
+  if level == OptimizationLevel::None {
+    return
+  }
+
+  let mut defined_names = NameCollector::default();
+  if level == OptimizationLevel::Full {
+    defined_names.visit_sequence(root);
+  }
+
+  let optimizer = Optimizer {
+    level,
+    globals,
+    defined_names: defined_names.0,
+  };
+
+  // Block attributes are always unset when a program starts
+  optimizer.optimize_sequence(root, true);
+}
+
+struct Optimizer<'a> {
+  level: OptimizationLevel,
+  globals: Option<&'a KnownGlobals>,
+  /// Names that the program defines anywhere, which can't be assumed to refer to standard library functions.
+  defined_names: FnvHashSet<InternalString>,
+}
+
+impl<'a> Optimizer<'a> {
+  #[inline]
+  fn is_full(&self) -> bool {
+    self.level == OptimizationLevel::Full
+  }
+
+  /// Optimizes a sequence and its children.
+  ///
+  /// `attrs_unset` indicates that the sequence always starts running with no block attributes set,
+  /// so its blocks can be simplified until an element of the sequence is able to set attributes.
+  fn optimize_sequence(&self, seq: &mut Rc<Sequence>, mut attrs_unset: bool) {
+    let seq = match Rc::get_mut(seq) {
+      Some(seq) => seq,
+      None => return,
+    };
+
+    let elements = mem::take(&mut **seq);
+    let mut optimized: Vec<Rc<Rst>> = Vec::with_capacity(elements.len());
+
+    for mut rst in elements {
+      if let Some(rst) = Rc::get_mut(&mut rst) {
+        if let Some(replacement) = self.optimize_rst(rst, attrs_unset) {
+          *rst = replacement;
+        }
+      }
+
+      if let Rst::Block(block) = rst.as_ref() {
+        if self.is_full() && attrs_unset && can_inline_block(block) {
+          // Inlined elements can't call functions, so attributes stay unset
+          optimized.extend(block.elements[0].main.iter().cloned());
+          continue
+        }
+        // Blocks consume the attributes set before them
+        attrs_unset = !can_set_attrs(&rst);
+      } else {
+        attrs_unset = attrs_unset && !can_set_attrs(&rst);
+      }
+
+      // Merge adjacent fragments
+      if let (Some(Rst::Fragment(prev)), Rst::Fragment(next)) = (optimized.last().map(Rc::as_ref), rst.as_ref()) {
+        let mut merged = prev.clone();
+        merged.push_str(next.as_str());
+        *optimized.last_mut().unwrap() = Rc::new(Rst::Fragment(merged));
+        continue
+      }
+
+      optimized.push(rst);
+    }
+
+    **seq = optimized;
+  }
+
+  /// Optimizes the children of a node and returns a simpler node to replace it with, if any.
+  fn optimize_rst(&self, rst: &mut Rst, attrs_unset: bool) -> Option<Rst> {
+    match rst {
+      Rst::Sequence(seq) => self.optimize_sequence(seq, false),
+      Rst::Block(block) => {
+        if let Some(block) = Rc::get_mut(block) {
+          self.optimize_block(block, attrs_unset);
+        }
+      },
+      Rst::ListInit(items) => {
+        if let Some(items) = Rc::get_mut(items) {
+          for item in items.iter_mut() {
+            self.optimize_sequence(item, false);
+          }
+        }
+
+        let list = items.iter().map(|item| constant_value(item)).collect::<Option<RantList>>()?;
+        return Some(Rst::ConstCollection(RantValue::List(Rc::new(RefCell::new(list)))))
+      },
+      Rst::SetInit(items) => {
+        if let Some(items) = Rc::get_mut(items) {
+          for item in items.iter_mut() {
+            self.optimize_sequence(item, false);
+          }
+        }
+
+        // Sets with items that can't be stored are left for the runtime to report
+        let values = items.iter().map(|item| constant_value(item)).collect::<Option<Vec<_>>>()?;
+        let set = RantSet::from_values(values).ok()?;
+        return Some(Rst::ConstCollection(RantValue::Set(Rc::new(RefCell::new(set)))))
+      },
+      Rst::InterpolatedString(parts) => {
+        if let Some(parts) = Rc::get_mut(parts) {
+          for part in parts.iter_mut() {
+            if let StringPart::Expr(expr) = part {
+              self.optimize_sequence(expr, false);
+            }
+          }
+        }
+      },
+      Rst::MapInit(entries) => {
+        if let Some(entries) = Rc::get_mut(entries) {
+          for (key, value) in entries.iter_mut() {
+            if let MapKeyExpr::Dynamic(key_expr) = key {
+              self.optimize_sequence(key_expr, false);
+            }
+            self.optimize_sequence(value, false);
+          }
+        }
+
+        let mut map = RantMap::new();
+        for (key, value) in entries.iter() {
+          match key {
+            MapKeyExpr::Static(key) => map.raw_set(key.as_str(), constant_value(value)?),
+            MapKeyExpr::Dynamic(_) => return None,
+          }
+        }
+        return Some(Rst::ConstCollection(RantValue::Map(Rc::new(RefCell::new(map)))))
+      },
+      Rst::Match(expr) => {
+        if let Some(expr) = Rc::get_mut(expr) {
+          self.optimize_sequence(&mut expr.value, false);
+          if let Some(arms) = Rc::get_mut(&mut expr.arms) {
+            for arm in arms.iter_mut() {
+              self.optimize_sequence(&mut arm.body, false);
+            }
+          }
+        }
+      },
+      Rst::Lambda(lambda) => {
+        self.optimize_params(&mut lambda.params);
+        self.optimize_sequence(&mut lambda.body, false);
+      },
+      Rst::FuncCall(call) => {
+        self.optimize_call(call);
+        if self.is_full() {
+          return self.fold_call(call)
+        }
+      },
+      Rst::PipedCall(piped) => {
+        if let Some(steps) = Rc::get_mut(&mut piped.steps) {
+          for step in steps.iter_mut() {
+            self.optimize_call(step);
+          }
+        }
+      },
+      Rst::FuncDef(def) => {
+        self.optimize_path(&mut def.path);
+        self.optimize_params(&mut def.params);
+        self.optimize_sequence(&mut def.body, false);
+      },
+      Rst::Get(path, fallback) => {
+        self.optimize_path(path);
+        if let Some(fallback) = fallback {
+          self.optimize_sequence(fallback, false);
+        }
+      },
+      Rst::Set(path, value) => {
+        self.optimize_path(path);
+        self.optimize_sequence(value, false);
+      },
+      Rst::DefVar(.., Some(expr))
+      | Rst::DefConst(.., Some(expr))
+      | Rst::Depth(.., Some(expr))
+      | Rst::Return(Some(expr))
+      | Rst::Continue(Some(expr))
+      | Rst::Break(Some(expr))
+      | Rst::Yield(Some(expr)) => self.optimize_sequence(expr, false),
+      _ => {},
+    }
+
+    None
+  }
+
+  fn optimize_block(&self, block: &mut Block, attrs_unset: bool) {
+    let elements = match Rc::get_mut(&mut block.elements) {
+      Some(elements) => elements,
+      None => return,
+    };
+
+    for elem in elements.iter_mut() {
+      if let Some(BlockWeight::Dynamic(weight)) = &mut elem.weight {
+        self.optimize_sequence(weight, false);
+      }
+      // Elements of a block that takes no attributes start with no attributes set either
+      self.optimize_sequence(&mut elem.main, attrs_unset);
+    }
+
+    // Zero-weight elements can be selected by selectors and are counted by `[rep: all]`, so they can only be removed when there are no attributes
+    if self.is_full() && attrs_unset && block.is_weighted {
+      let is_zero = |elem: &BlockElement| matches!(elem.weight, Some(BlockWeight::Constant(weight)) if weight == 0.0);
+      // A block with only zero-weight elements prints nothing, which removing all of them would change
+      if elements.iter().any(is_zero) && !elements.iter().all(is_zero) {
+        block.removed_elements.extend(elements.iter().filter(|elem| is_zero(elem)).map(|elem| elem.pos.clone()));
+        elements.retain(|elem| !is_zero(elem));
+      }
+    }
+  }
+
+  fn optimize_call(&self, call: &mut FunctionCall) {
+    match &mut call.target {
+      FunctionCallTarget::Path(path) => self.optimize_path(path),
+      FunctionCallTarget::Expression(expr) => self.optimize_sequence(expr, false),
+    }
+
+    if let Some(args) = Rc::get_mut(&mut call.arguments) {
+      for arg in args.iter_mut() {
+        self.optimize_sequence(&mut arg.expr, false);
+      }
+    }
+  }
+
+  fn optimize_params(&self, params: &mut Rc<Vec<Parameter>>) {
+    if let Some(params) = Rc::get_mut(params) {
+      for param in params.iter_mut() {
+        if let Some(default_value) = &mut param.default_value_expr {
+          self.optimize_sequence(default_value, false);
+        }
+      }
+    }
+  }
+
+  fn optimize_path(&self, path: &mut Rc<AccessPath>) {
+    let path = match Rc::get_mut(path) {
+      Some(path) => path,
+      None => return,
+    };
+
+    for component in path.iter_mut() {
+      match component {
+        AccessPathComponent::DynamicKey(expr) | AccessPathComponent::AnonymousValue(expr) => self.optimize_sequence(expr, false),
+        AccessPathComponent::Slice(SliceExpr::From(from)) => self.optimize_slice_index(from),
+        AccessPathComponent::Slice(SliceExpr::To(to)) => self.optimize_slice_index(to),
+        AccessPathComponent::Slice(SliceExpr::Between(from, to)) => {
+          self.optimize_slice_index(from);
+          self.optimize_slice_index(to);
+        },
+        _ => {},
+      }
+    }
+  }
+
+  fn optimize_slice_index(&self, index: &mut SliceIndex) {
+    if let SliceIndex::Dynamic(expr) = index {
+      self.optimize_sequence(expr, false);
+    }
+  }
+
+  /// Evaluates a call to a foldable standard library function with constant numeric arguments.
+  fn fold_call(&self, call: &FunctionCall) -> Option<Rst> {
+    if call.is_temporal || !matches!(call.flag, PrintFlag::None) {
+      return None
+    }
+
+    let name = match &call.target {
+      FunctionCallTarget::Path(path) if path.kind().is_local() => path.static_variable()?,
+      _ => return None,
+    };
+
+    let (_, arg_count) = FOLDABLE_FUNCTIONS.iter().find(|(func_name, _)| *func_name == name)?;
+    if call.arguments.len() != *arg_count
+    || self.defined_names.contains(name)
+    || !self.globals.is_some_and(|globals| globals.contains_key(name)) {
+      return None
+    }
+
+    let mut args = call.arguments.iter().map(|arg| match (&arg.spread_mode, &arg.name, arg.expr.as_slice()) {
+      (ArgumentSpreadMode::NoSpread, None, [rst]) => match rst.as_ref() {
+        Rst::Integer(n) => Some(RantValue::Int(*n)),
+        Rst::Float(n) => Some(RantValue::Float(*n)),
+        _ => None,
+      },
+      _ => None,
+    }).collect::<Option<Vec<RantValue>>>()?.into_iter();
+
+    let lhs = args.next()?;
+    let result = match (name, args.next()) {
+      // Errors such as overflow and division by zero are left for the runtime to report
+      ("add", Some(rhs)) => (lhs + rhs).ok()?,
+      ("sub", Some(rhs)) => (lhs - rhs).ok()?,
+      ("mul", Some(rhs)) => (lhs * rhs).ok()?,
+      ("div", Some(rhs)) => (lhs / rhs).ok()?,
+      ("mod", Some(rhs)) => (lhs % rhs).ok()?,
+      ("neg", None) => (-lhs).ok()?,
+      _ => return None,
+    };
+
+    match result {
+      RantValue::Int(n) => Some(Rst::Integer(n)),
+      RantValue::Float(n) => Some(Rst::Float(n)),
+      _ => None,
+    }
+  }
+}
+
+/// Gets the value that a sequence always evaluates to, if it only contains a constant.
+fn constant_value(seq: &Sequence) -> Option<RantValue> {
+  match seq.as_slice() {
+    [] => Some(RantValue::Empty),
+    [rst] => match rst.as_ref() {
+      Rst::Fragment(s) => Some(RantValue::String(s.as_str().into())),
+      Rst::Integer(n) => Some(RantValue::Int(*n)),
+      Rst::Float(n) => Some(RantValue::Float(*n)),
+      Rst::Boolean(b) => Some(RantValue::Boolean(*b)),
+      Rst::EmptyValue => Some(RantValue::Empty),
+      Rst::ConstCollection(value) => Some(value.clone()),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// Checks if a block has a single element that prints nothing and behaves the same when run outside of the block.
+fn can_inline_block(block: &Block) -> bool {
+  // Print flags only decide whether the element's output is printed, so they don't matter here
+  if block.elements.len() != 1 {
+    return false
+  }
+
+  let elem = &block.elements[0];
+  if !elem.weight.as_ref().is_none_or(|weight| matches!(weight, BlockWeight::Constant(weight) if *weight > 0.0)) {
+    return false
+  }
+
+  // Local definitions would leak out of the block's scope
+  let is_non_printing = elem.main.iter().all(|rst| match rst.as_ref() {
+    Rst::Nop | Rst::DebugCursor(_) | Rst::Set(..) => true,
+    Rst::DefVar(_, kind, _) | Rst::DefConst(_, kind, _) => *kind == AccessPathKind::ExplicitGlobal,
+    Rst::FuncDef(def) => def.path.is_explicit_global(),
+    _ => false,
+  });
+
+  if !is_non_printing {
+    return false
+  }
+
+  let mut checker = InlineChecker { can_inline: true };
+  checker.visit_sequence(&elem.main);
+  checker.can_inline
+}
+
+/// Checks if running a node can set block attributes.
+fn can_set_attrs(rst: &Rst) -> bool {
+  let mut finder = CallFinder { found: false };
+  finder.visit_rst(rst);
+  finder.found
+}
+
+/// Looks for function calls that run when a node is evaluated.
+struct CallFinder {
+  found: bool,
+}
+
+impl RstVisitor for CallFinder {
+  fn visit_func_call(&mut self, _call: &FunctionCall) {
+    self.found = true;
+  }
+
+  // Function bodies don't run where they're defined
+  fn visit_func_def(&mut self, def: &FunctionDef) {
+    self.visit_access_path(&def.path);
+  }
+
+  fn visit_lambda(&mut self, _lambda: &LambdaExpr) {}
+}
+
+/// Looks for anything in a block element that depends on the element running in its own scope or block.
+struct InlineChecker {
+  can_inline: bool,
+}
+
+impl RstVisitor for InlineChecker {
+  fn visit_rst(&mut self, rst: &Rst) {
+    match rst {
+      Rst::Break(_) | Rst::Continue(_) | Rst::Depth(..) => self.can_inline = false,
+      _ => walk_rst(self, rst),
+    }
+  }
+
+  // Calls can inspect the block they run in, such as with `[step]`
+  fn visit_func_call(&mut self, _call: &FunctionCall) {
+    self.can_inline = false;
+  }
+
+  fn visit_func_def(&mut self, def: &FunctionDef) {
+    self.can_inline &= def.path.is_explicit_global();
+    self.visit_access_path(&def.path);
+  }
+
+  fn visit_lambda(&mut self, _lambda: &LambdaExpr) {}
+
+  fn visit_access_path(&mut self, path: &AccessPath) {
+    self.can_inline &= !matches!(path.kind(), AccessPathKind::Descope(_));
+    walk_access_path(self, path);
+  }
+
+  fn visit_var_def(&mut self, name: &Identifier, kind: AccessPathKind, is_const: bool, value: Option<&Sequence>) {
+    self.can_inline &= kind == AccessPathKind::ExplicitGlobal;
+    walk_var_def(self, name, kind, is_const, value);
+  }
+}
+
+/// Collects the names of all variables, functions, and parameters defined or assigned in a program.
+#[derive(Default)]
+struct NameCollector(FnvHashSet<InternalString>);
+
+impl NameCollector {
+  fn add_path(&mut self, path: &AccessPath) {
+    if let Some(name) = path.var_name() {
+      self.0.insert(InternalString::from(name.as_str()));
+    }
+  }
+}
+
+impl RstVisitor for NameCollector {
+  fn visit_rst(&mut self, rst: &Rst) {
+    if let Rst::Set(path, _) = rst {
+      self.add_path(path);
+    }
+    walk_rst(self, rst);
+  }
+
+  fn visit_func_def(&mut self, def: &FunctionDef) {
+    self.add_path(&def.path);
+    walk_func_def(self, def);
+  }
+
+  fn visit_parameter(&mut self, param: &Parameter) {
+    self.0.insert(InternalString::from(param.name.as_str()));
+    walk_parameter(self, param);
+  }
+
+  fn visit_var_def(&mut self, name: &Identifier, kind: AccessPathKind, is_const: bool, value: Option<&Sequence>) {
+    self.0.insert(InternalString::from(name.as_str()));
+    walk_var_def(self, name, kind, is_const, value);
+  }
+}
@@ -1,12 +1,12 @@
 #![allow(dead_code)]
 #![allow(clippy::ptr_arg)]
 
-use super::{reader::RantTokenReader, lexer::RantToken, message::*, Problem, Reporter};
-use crate::{InternalString, RantProgramInfo, lang::*};
+use super::{reader::RantTokenReader, lexer::RantToken, formatter::{LayoutHints, Spacing}, lint::{is_known_problem, problem_matches}, message::*, symbols::*, KnownGlobals, Problem, Reporter};
+use crate::{InternalString, RantFunction, RantProgramInfo, RantRange, RantValue, RantValueType, lang::*};
 use fnv::FnvBuildHasher;
 use line_col::LineColLookup;
 use quickscope::ScopeMap;
-use std::{collections::{HashMap, HashSet}, ops::Range, rc::Rc};
+use std::{collections::{HashMap, HashSet}, ops::Range, path::Path, rc::Rc};
 use RantToken::*;
 
 type ParseResult<T> = Result<T, ()>;
@@ -17,6 +17,7 @@ const MAIN_PROGRAM_SCOPE_NAME: &str = "main scope";
 const KW_RETURN: &str = "return";
 const KW_BREAK: &str = "break";
 const KW_CONTINUE: &str = "continue";
+const KW_YIELD: &str = "yield";
 const KW_WEIGHT: &str = "weight";
 const KW_TRUE: &str = "true";
 const KW_FALSE: &str = "false";
@@ -36,6 +37,26 @@ const KW_GREATER: &str = "gt";
 const KW_GREATER_OR_EQUAL: &str = "ge";
 const KW_LESS: &str = "lt";
 const KW_LESS_OR_EQUAL: &str = "le";
+const KW_MATCH: &str = "match";
+
+/// Keywords that have a meaning of their own in a sequence, so `@keyword = value` can't be a named argument.
+const SEQUENCE_KEYWORDS: &[&str] = &[KW_RETURN, KW_BREAK, KW_CONTINUE, KW_YIELD, KW_WEIGHT, KW_TRUE, KW_FALSE, KW_MATCH];
+
+/// The types that can be matched with a type pattern, e.g. `@int`, or named in a type annotation.
+const PATTERN_TYPES: &[RantValueType] = &[
+  RantValueType::String,
+  RantValueType::Float,
+  RantValueType::Int,
+  RantValueType::Boolean,
+  RantValueType::Function,
+  RantValueType::List,
+  RantValueType::Map,
+  RantValueType::Set,
+  RantValueType::Special,
+  RantValueType::Range,
+  RantValueType::Iterator,
+  RantValueType::Empty,
+];
 
 /// Provides context to the sequence parser; determines valid terminating tokens among other context-sensitive features.
 #[derive(Copy, Clone, PartialEq)]
@@ -80,10 +101,18 @@ enum SequenceParseMode {
   ///
   /// Breaks on `Semi` and `RightParen`.
   CollectionInit,
+  /// Parse a sequence like a set initializer element.
+  ///
+  /// Breaks on `Semi` and `RightBrace`.
+  SetInit,
   /// Parses a single item only.
   ///
   /// Breaks automatically or on EOF.
   SingleItem,
+  /// Parse a sequence like a match arm body.
+  ///
+  /// Breaks on `Pipe` and `RightBrace`.
+  MatchArm,
 }
 
 /// What type of collection initializer to parse?
@@ -91,7 +120,9 @@ enum CollectionInitKind {
   /// Parse a list
   List,
   /// Parse a map
-  Map
+  Map,
+  /// Parse a set
+  Set,
 }
 
 /// Indicates what kind of token terminated a sequence read.
@@ -128,7 +159,7 @@ enum SequenceEndType {
   AccessorFallbackValueToEnd,
   /// Accessor fallback value was terminated by `Semi`.
   AccessorFallbackValueToDelim,
-  /// Collection initializer was terminated by `RightParen`.
+  /// Collection initializer was terminated by `RightParen` (or `RightBrace` for sets).
   CollectionInitEnd,
   /// Collection initializer was termianted by `Semi`.
   CollectionInitDelim,
@@ -138,6 +169,10 @@ enum SequenceEndType {
   ParamDefaultValueSeparator,
   /// Parameter default value was terminated by `RightBracket`, indicating the end of the signature was reached..
   ParamDefaultValueSignatureEnd,
+  /// Match arm body was terminated by `Pipe`.
+  MatchArmDelim,
+  /// Match arm body was terminated by `RightBrace`.
+  MatchArmEnd,
 }
 
 /// Used to track variable usages during compilation.
@@ -151,6 +186,10 @@ struct VarStats {
   has_fallible_read: bool,
   is_const: bool,
   role: VarRole,
+  /// Index of the variable's definition in the symbol table, if symbols are being collected.
+  symbol: Option<usize>,
+  /// The parameters of the function stored in the variable, if known.
+  params: Option<Rc<Vec<Parameter>>>,
 }
 
 impl VarStats {
@@ -175,6 +214,80 @@ enum VarRole {
   Argument,
   FallibleOptionalArgument,
   PipeValue,
+  Module,
+}
+
+/// The range of argument counts accepted by a function.
+#[derive(Copy, Clone)]
+struct Arity {
+  min: usize,
+  /// The maximum argument count, or `None` if the function is variadic.
+  max: Option<usize>,
+}
+
+impl Arity {
+  /// Gets the arity of a function with the specified parameters, following the same rules as the runtime.
+  fn of_params(params: &[Parameter]) -> Self {
+    let is_variadic = params.iter().any(|p| p.varity.is_variadic());
+    Self {
+      min: params.iter().take_while(|p| p.is_required()).count(),
+      max: if is_variadic { None } else { Some(params.len()) },
+    }
+  }
+
+  fn of_function(func: &RantFunction) -> Self {
+    Self {
+      min: func.min_arg_count,
+      max: if func.is_variadic() { None } else { Some(func.params.len()) },
+    }
+  }
+
+  #[inline]
+  fn accepts(&self, argc: usize) -> bool {
+    argc >= self.min && self.max.is_none_or(|max| argc <= max)
+  }
+
+  /// Describes the accepted argument counts for error messages.
+  fn describe(&self) -> String {
+    match self.max {
+      Some(max) if max == self.min => max.to_string(),
+      Some(max) => format!("{} to {}", self.min, max),
+      None => format!("at least {}", self.min),
+    }
+  }
+}
+
+/// An access to a variable that wasn't defined at the time it was parsed.
+struct UndefinedAccess {
+  name: Identifier,
+  span: Range<usize>,
+  /// IDs of the scopes in which a later definition of the variable would be visible to the access.
+  scope_ids: Vec<usize>,
+}
+
+#[inline]
+fn is_opening_bracket(token: &RantToken) -> bool {
+  matches!(token, LeftBrace | LeftBracket | LeftParen | LeftAngle)
+}
+
+#[inline]
+fn is_closing_bracket(token: &RantToken) -> bool {
+  matches!(token, RightBrace | RightBracket | RightParen | RightAngle)
+}
+
+#[inline]
+fn bracket_str(token: &RantToken) -> &'static str {
+  match token {
+    LeftBrace => "{",
+    RightBrace => "}",
+    LeftBracket => "[",
+    RightBracket => "]",
+    LeftParen => "(",
+    RightParen => ")",
+    LeftAngle => "<",
+    RightAngle => ">",
+    _ => "",
+  }
 }
 
 /// Returns a range that encompasses both input ranges.
@@ -183,6 +296,30 @@ fn super_range(a: &Range<usize>, b: &Range<usize>) -> Range<usize> {
   a.start.min(b.start)..a.end.max(b.end)
 }
 
+/// Gets the type of the value a sequence evaluates to, if it's a literal.
+fn literal_type(seq: &Sequence) -> Option<RantValueType> {
+  let mut elements = seq.iter().filter(|rst| !matches!(rst.as_ref(), Rst::DebugCursor(_))).peekable();
+  let first = elements.peek()?;
+  let value_type = match first.as_ref() {
+    Rst::Integer(_) => RantValueType::Int,
+    Rst::Float(_) => RantValueType::Float,
+    Rst::Boolean(_) => RantValueType::Boolean,
+    Rst::EmptyValue => RantValueType::Empty,
+    Rst::ListInit(_) => RantValueType::List,
+    Rst::MapInit(_) => RantValueType::Map,
+    Rst::SetInit(_) => RantValueType::Set,
+    Rst::Lambda(_) => RantValueType::Function,
+    Rst::InterpolatedString(_) => RantValueType::String,
+    // Text is only a string if nothing else is printed with it
+    Rst::Fragment(_) | Rst::Whitespace(_) => {
+      return elements.all(|rst| matches!(rst.as_ref(), Rst::Fragment(_) | Rst::Whitespace(_))).then_some(RantValueType::String)
+    },
+    _ => return None,
+  };
+  elements.next();
+  elements.next().is_none().then_some(value_type)
+}
+
 #[derive(Debug)]
 enum ParsedSequenceExtras {
   WeightedBlockElement {
@@ -190,6 +327,26 @@ enum ParsedSequenceExtras {
   }
 }
 
+/// A step from a destructured value into one of its parts.
+enum DestructureStep {
+  /// Reads a list item.
+  Index(i64),
+  /// Reads a map value.
+  Key(Identifier),
+  /// Slices the list items starting from an index.
+  Rest(i64),
+}
+
+impl DestructureStep {
+  fn to_component(&self) -> AccessPathComponent {
+    match self {
+      Self::Index(index) => AccessPathComponent::Index(*index),
+      Self::Key(key) => AccessPathComponent::Name(key.clone()),
+      Self::Rest(from) => AccessPathComponent::Slice(SliceExpr::From(SliceIndex::Static(*from))),
+    }
+  }
+}
+
 /// Contains information about a successfully parsed sequence and its context.
 struct ParsedSequence {
   sequence: Sequence,
@@ -218,6 +375,33 @@ pub struct RantParser<'source, 'report, R: Reporter> {
   var_stack: ScopeMap<Identifier, VarStats>,
   /// Keeps track of active variable capture frames.
   capture_stack: Vec<(usize, HashSet<Identifier, FnvBuildHasher>)>,
+  /// Collects symbol definitions and references, if enabled.
+  symbols: Option<SymbolTable>,
+  /// Collects whitespace layout for the formatter, if enabled.
+  layout: Option<LayoutHints>,
+  /// The number of sequences currently being parsed.
+  sequence_depth: usize,
+  /// Closing tokens of constructs that error recovery gave up on at a line break, paired with the sequence depth they belong to.
+  ///
+  /// A stray closing token matching the top entry is skipped without an error.
+  abandoned_closers: Vec<(RantToken, usize)>,
+  /// Globals that will be available when the program runs, such as the standard library.
+  ///
+  /// Undefined variables and argument count mismatches are only checked if this is set.
+  globals: Option<&'report KnownGlobals>,
+  /// Unique IDs of the layers of `var_stack`, from outermost to innermost.
+  scope_ids: Vec<usize>,
+  /// The ID to assign to the next layer pushed to `var_stack`.
+  next_scope_id: usize,
+  /// Accesses to variables that haven't been defined yet.
+  /// Any left over at the end of parsing are reported as undefined.
+  undefined_accesses: Vec<UndefinedAccess>,
+  /// Flag set if the program calls `[require]`.
+  /// Required modules can define globals that the compiler can't see, so undefined variables aren't reported.
+  requires_modules: bool,
+  /// Messages produced while parsing.
+  /// They are passed to the reporter at the end of parsing, once every `#@allow(...)` comment has been read.
+  messages: Vec<CompilerMessage>,
 }
 
 impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
@@ -232,14 +416,59 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       info: Rc::clone(info),
       var_stack: Default::default(),
       capture_stack: Default::default(),
+      symbols: None,
+      layout: None,
+      sequence_depth: 0,
+      abandoned_closers: vec![],
+      globals: None,
+      scope_ids: vec![],
+      next_scope_id: 0,
+      undefined_accesses: vec![],
+      requires_modules: false,
+      messages: vec![],
     }
   }
+
+  /// Enables checks for undefined variables and argument count mismatches against the specified globals.
+  pub fn with_globals(mut self, globals: &'report KnownGlobals) -> Self {
+    self.globals = Some(globals);
+    self
+  }
+
+  /// Enables collection of symbol definitions and references while parsing.
+  pub fn with_symbols(mut self) -> Self {
+    self.symbols = Some(Default::default());
+    self
+  }
+
+  /// Takes the collected symbols, if symbol collection was enabled.
+  pub fn take_symbols(&mut self) -> Option<SymbolTable> {
+    self.symbols.take().map(|mut symbols| {
+      symbols.sort();
+      symbols
+    })
+  }
+
+  /// Enables collection of whitespace layout for the formatter while parsing.
+  pub(crate) fn with_layout(mut self) -> Self {
+    self.layout = Some(Default::default());
+    self
+  }
+
+  /// Takes the collected whitespace layout, if layout collection was enabled.
+  pub(crate) fn take_layout(&mut self) -> Option<LayoutHints> {
+    self.layout.take()
+  }
 }
 
 impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
   /// Top-level parsing function invoked by the compiler.
   pub fn parse(&mut self) -> Result<Rc<Sequence>, ()> {
     let result = self.parse_sequence(SequenceParseMode::TopLevel);
+    if !self.has_errors {
+      self.report_undefined_accesses();
+    }
+    self.flush_messages();
     match result {
       // Err if parsing "succeeded" but there are soft syntax errors
       Ok(..) if self.has_errors => Err(()),
@@ -254,13 +483,106 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
   fn report_error(&mut self, problem: Problem, span: &Range<usize>) {
     let (line, col) = self.lookup.get(span.start);
     self.has_errors = true;
-    self.reporter.report(CompilerMessage::new(problem, Severity::Error, Some(Position::new(line, col, span.clone()))));
+    self.messages.push(CompilerMessage::new(problem, Severity::Error, Some(Position::new(line, col, span.clone()))));
+  }
+
+  /// Records the spacing the formatter should use in the ignored whitespace at a token boundary.
+  #[inline]
+  fn hint_spacing(&mut self, offset: usize, spacing: Spacing) {
+    if let Some(layout) = self.layout.as_mut() {
+      layout.spacing.push((offset, spacing));
+    }
+  }
+
+  /// Records the spacing around the delimiter that ended a collection item.
+  fn hint_item_end(&mut self, end_type: &SequenceEndType) {
+    let delim_span = self.reader.last_consumed_span();
+    match end_type {
+      SequenceEndType::CollectionInitDelim => {
+        self.hint_spacing(delim_span.start, Spacing::None);
+        self.hint_spacing(delim_span.end, Spacing::Space);
+      },
+      SequenceEndType::CollectionInitEnd => self.hint_spacing(delim_span.start, Spacing::None),
+      _ => {},
+    }
+  }
+
+  /// Gets the number of errors reported so far.
+  fn error_count(&self) -> usize {
+    self.messages.iter().filter(|msg| msg.is_error()).count()
   }
 
   /// Reports a warning, but allows compiling to succeed.
   fn report_warning(&mut self, problem: Problem, span: &Range<usize>) {
     let (line, col) = self.lookup.get(span.start);
-    self.reporter.report(CompilerMessage::new(problem, Severity::Warning, Some(Position::new(line, col, span.clone()))));
+    self.messages.push(CompilerMessage::new(problem, Severity::Warning, Some(Position::new(line, col, span.clone()))));
+  }
+
+  /// Passes the parser's messages to the reporter, leaving out warnings allowed by `#@allow(...)` comments
+  /// and applying the severity the reporter requests for the rest.
+  fn flush_messages(&mut self) {
+    // Directives that name unknown problems are probably misspelled
+    let unknown_problems: Vec<(String, Range<usize>)> = self.reader.allow_directives().iter()
+      .flat_map(|directive| {
+        let span = directive.offset .. self.source[directive.offset..].find(')').map_or(self.source.len(), |end| directive.offset + end + 1);
+        directive.problems.iter()
+          .filter(|problem| !is_known_problem(problem))
+          .map(move |problem| (problem.clone(), span.clone()))
+      })
+      .collect();
+    for (problem, span) in unknown_problems {
+      self.report_warning(Problem::UnknownProblem(problem), &span);
+    }
+
+    let allowed_lines = self.allowed_lines();
+    for msg in std::mem::take(&mut self.messages) {
+      let (pos, severity, problem) = msg.consume();
+      let severity = match severity {
+        Severity::Error => Severity::Error,
+        Severity::Warning => {
+          let line = pos.as_ref().map(|pos| pos.line());
+          let is_allowed = allowed_lines.iter().any(|(allowed_line, problems)| {
+            Some(*allowed_line) == line && problems.iter().any(|allowed| problem_matches(&problem, allowed))
+          });
+          if is_allowed {
+            continue
+          }
+          match self.reporter.warning_severity(&problem) {
+            Some(severity) => severity,
+            None => continue,
+          }
+        },
+      };
+
+      if matches!(severity, Severity::Error) {
+        self.has_errors = true;
+      }
+      self.reporter.report(CompilerMessage::new(problem, severity, pos));
+    }
+  }
+
+  /// Gets the lines that `#@allow(...)` comments apply to, paired with the problems they allow.
+  ///
+  /// A comment following code applies to its own line; a comment on a line by itself applies to the next line with code.
+  fn allowed_lines(&self) -> Vec<(usize, Vec<String>)> {
+    let lines: Vec<&str> = self.source.lines().collect();
+    self.reader.allow_directives().iter().filter_map(|directive| {
+      let (line, _) = self.lookup.get(directive.offset);
+      let line_start = self.source[..directive.offset].rfind('\n').map_or(0, |i| i + 1);
+      let target_line = if self.source[line_start..directive.offset].trim().is_empty() {
+        lines.iter()
+          .enumerate()
+          .skip(line)
+          .find(|(_, text)| {
+            let text = text.trim();
+            !text.is_empty() && !text.starts_with('#')
+          })
+          .map(|(index, _)| index + 1)?
+      } else {
+        line
+      };
+      Some((target_line, directive.problems.clone()))
+    }).collect()
   }
   
   /// Emits an "unexpected token" error for the most recently read token.
@@ -269,19 +591,91 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     self.report_error(Problem::UnexpectedToken(self.reader.last_token_string().to_string()), &self.reader.last_token_span())
   }
 
+  /// Skips the rest of a construct after a syntax error so that parsing can resume after it.
+  ///
+  /// Tokens are skipped until `closer` is consumed, skipping over nested brackets as a whole.
+  /// Stops early before an unmatched closing bracket of another kind, a line break, or EOF.
+  /// When stopped at a line break, the next stray `closer` in the current sequence is skipped instead of reported,
+  /// so the rest of a construct spanning multiple lines doesn't cause more errors.
+  ///
+  /// Returns `true` if `closer` was consumed.
+  fn synchronize(&mut self, closer: RantToken) -> bool {
+    // Account for the token that caused the error
+    let last_token = &self.source[self.reader.last_consumed_span()];
+    if last_token == bracket_str(&closer) {
+      return true
+    }
+    let mut nesting = if matches!(last_token, "{" | "[" | "(" | "<") { 1usize } else { 0 };
+    loop {
+      let is_line_break = self.reader.is_next_on_new_line();
+      let (is_opener, is_closer, is_target) = match self.reader.peek() {
+        Some((token, _)) => (is_opening_bracket(token), is_closing_bracket(token), *token == closer),
+        None => return false,
+      };
+
+      if nesting == 0 {
+        if is_target {
+          self.reader.skip_one();
+          return true
+        }
+        if is_closer {
+          return false
+        }
+        if is_line_break {
+          self.abandoned_closers.push((closer, self.sequence_depth));
+          return false
+        }
+      }
+
+      if is_opener {
+        nesting += 1;
+      } else if is_closer {
+        nesting -= 1;
+      }
+      self.reader.skip_one();
+    }
+  }
+
   /// Parses a sequence of items. Items are individual elements of a Rant program (fragments, blocks, function calls, etc.)
   #[inline]
   fn parse_sequence(&mut self, mode: SequenceParseMode) -> ParseResult<ParsedSequence> {
-    self.var_stack.push_layer();
+    self.push_scope();
     let parse_result = self.parse_sequence_inner(mode);
     self.analyze_top_vars();
-    self.var_stack.pop_layer();
+    self.pop_scope();
     parse_result
   }
+
+  /// Pushes a new layer to the variable stack.
+  #[inline]
+  fn push_scope(&mut self) {
+    self.var_stack.push_layer();
+    self.scope_ids.push(self.next_scope_id);
+    self.next_scope_id += 1;
+  }
+
+  /// Pops the top layer of the variable stack.
+  #[inline]
+  fn pop_scope(&mut self) {
+    self.var_stack.pop_layer();
+    self.scope_ids.pop();
+  }
   
   /// Inner logic of `parse_sequence()`. Intended to be wrapped in other specialized sequence-parsing functions.
   #[inline(always)]
-  fn parse_sequence_inner(&mut self, mode: SequenceParseMode) -> ParseResult<ParsedSequence> {    
+  fn parse_sequence_inner(&mut self, mode: SequenceParseMode) -> ParseResult<ParsedSequence> {
+    self.sequence_depth += 1;
+    let parse_result = self.parse_sequence_items(mode);
+    // Closers abandoned in this sequence can't appear after it ends
+    let depth = self.sequence_depth;
+    self.abandoned_closers.retain(|(_, closer_depth)| *closer_depth < depth);
+    self.sequence_depth -= 1;
+    parse_result
+  }
+
+  /// Parses the items of a sequence until a terminating token for `mode` is reached.
+  #[inline(always)]
+  fn parse_sequence_items(&mut self, mode: SequenceParseMode) -> ParseResult<ParsedSequence> {
     let mut sequence = Sequence::empty(&self.info);
     let mut next_print_flag = PrintFlag::None;
     let mut last_print_flag_span: Option<Range<usize>> = None;
@@ -311,6 +705,12 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     while let Some((token, span)) = self.reader.next() {
       let _debug_inject_toggle = true;
 
+      // Skip the leftover closer of a construct that error recovery gave up on
+      if matches!(self.abandoned_closers.last(), Some((closer, depth)) if *closer == token && *depth == self.sequence_depth) {
+        self.abandoned_closers.pop();
+        continue
+      }
+
       macro_rules! no_debug {
         ($e:expr) => {{
           let _debug_inject_toggle = false;
@@ -380,7 +780,10 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       macro_rules! whitespace {
         (allow) => {
           if is_seq_printing {
-            if let Some(ws) = pending_whitespace.take() {
+            if let Some((ws, ws_span)) = pending_whitespace.take() {
+              if let Some(layout) = self.layout.as_mut() {
+                layout.printed_whitespace.push(ws_span);
+              }
               emit!(Rst::Whitespace(ws));
             }
           } else {
@@ -388,8 +791,8 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
           }
         };
         (queue next) => {{
-          if let Some((Whitespace, ..)) = self.reader.take_where(|tt| matches!(tt, Some((Whitespace, ..)))) {
-            pending_whitespace = Some(self.reader.last_token_string());
+          if let Some((Whitespace, ws_span)) = self.reader.take_where(|tt| matches!(tt, Some((Whitespace, ..)))) {
+            pending_whitespace = Some((self.reader.last_token_string(), ws_span));
           }
         }};
         (queue $ws:expr) => {
@@ -493,6 +896,20 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 extras: charm_extras,
               })
             },
+            // Yield
+            KW_YIELD => no_flags!(on {
+              whitespace!(ignore both);
+              let yield_value = self.parse_yield_value()?;
+              whitespace!(ignore next);
+              Rst::Yield(yield_value)
+            }),
+            // Match expression
+            KW_MATCH => no_flags!(on {
+              let match_expr = self.parse_match(span.start)?;
+              whitespace!(allow);
+              is_seq_printing = true;
+              Rst::Match(Rc::new(match_expr))
+            }),
             other => self.report_error(Problem::InvalidKeyword(other.to_string()), &span),
           }          
         },
@@ -581,6 +998,14 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 extras: None,
               })
             },
+            SequenceParseMode::MatchArm => {
+              return Ok(ParsedSequence {
+                sequence: sequence.with_name_str("match arm"),
+                end_type: SequenceEndType::MatchArmDelim,
+                is_text: is_seq_printing,
+                extras: None,
+              })
+            },
             SequenceParseMode::DynamicKey => {
               self.report_error(Problem::DynamicKeyBlockMultiElement, &span);
             },
@@ -619,17 +1044,36 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 is_text: true,
                 extras: None,
               })
+            },
+            SequenceParseMode::SetInit => {
+              return Ok(ParsedSequence {
+                sequence,
+                end_type: SequenceEndType::CollectionInitEnd,
+                is_text: true,
+                extras: None,
+              })
+            },
+            SequenceParseMode::MatchArm => {
+              return Ok(ParsedSequence {
+                sequence: sequence.with_name_str("match arm"),
+                end_type: SequenceEndType::MatchArmEnd,
+                is_text: true,
+                extras: None,
+              })
             }
             _ => unexpected_token_error!()
           }
         }),
         
-        // Map initializer
+        // Map/set initializer
         At => no_flags!(on {
           match self.reader.next_solid() {
             Some((LeftParen, _)) => {
               self.parse_collection_initializer(CollectionInitKind::Map, &span)?
             },
+            Some((LeftBrace, _)) => {
+              self.parse_collection_initializer(CollectionInitKind::Set, &span)?
+            },
             _ => {
               self.report_error(Problem::ExpectedToken("(".to_owned()), &self.reader.last_token_span());
               Rst::EmptyValue
@@ -769,7 +1213,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
           // Don't set is_printing here; whitespace tokens always appear with other printing tokens
           if is_seq_printing {
             let ws = self.reader.last_token_string();
-            whitespace!(queue ws);
+            whitespace!(queue (ws, span.clone()));
           }
         }),
         
@@ -809,6 +1253,13 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
           Rst::Fragment(s)
         }),
         
+        // Interpolated string literals
+        InterpolatedStringLiteral => no_flags!(on {
+          whitespace!(allow);
+          is_seq_printing = true;
+          self.parse_interpolated_string(&span)?
+        }),
+        
         // Colon can be either fragment or argument separator.
         Colon => no_flags!({
           match mode {
@@ -831,7 +1282,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
               is_text: true,
               extras: None,
             }),
-            SequenceParseMode::CollectionInit => return Ok(ParsedSequence {
+            SequenceParseMode::CollectionInit | SequenceParseMode::SetInit => return Ok(ParsedSequence {
               sequence: sequence.with_name_str("collection item"),
               end_type: SequenceEndType::CollectionInitDelim,
               is_text: true,
@@ -896,15 +1347,28 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     })
   }
   
-  /// Parses a list/map initializer.
+  /// Parses a list/map/set initializer.
   fn parse_collection_initializer(&mut self, kind: CollectionInitKind, start_span: &Range<usize>) -> ParseResult<Rst> {
     match kind {
-      CollectionInitKind::List => {
+      CollectionInitKind::List | CollectionInitKind::Set => {
+        let is_set = matches!(kind, CollectionInitKind::Set);
+        let (item_mode, unclosed_problem) = if is_set {
+          (SequenceParseMode::SetInit, Problem::UnclosedSet)
+        } else {
+          (SequenceParseMode::CollectionInit, Problem::UnclosedList)
+        };
+        let make_rst = |sequences| if is_set { Rst::SetInit(Rc::new(sequences)) } else { Rst::ListInit(Rc::new(sequences)) };
+
+        self.hint_spacing(self.reader.last_consumed_span().end, Spacing::None);
         self.reader.skip_ws();
         
-        // Exit early on empty list
-        if self.reader.eat_where(|token| matches!(token, Some((RightParen, ..)))) {
-          return Ok(Rst::ListInit(Rc::new(vec![])))
+        // Exit early on empty collection
+        if self.reader.eat_where(|token| match token {
+          Some((RightParen, ..)) => !is_set,
+          Some((RightBrace, ..)) => is_set,
+          _ => false,
+        }) {
+          return Ok(make_rst(vec![]))
         }
         
         let mut sequences = vec![];
@@ -912,7 +1376,8 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
         loop {
           self.reader.skip_ws();
           
-          let ParsedSequence { sequence, end_type: seq_end, .. } = self.parse_sequence(SequenceParseMode::CollectionInit)?;
+          let ParsedSequence { sequence, end_type: seq_end, .. } = self.parse_sequence(item_mode)?;
+          self.hint_item_end(&seq_end);
           
           match seq_end {
             SequenceEndType::CollectionInitDelim => {
@@ -923,7 +1388,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
               break
             },
             SequenceEndType::ProgramEnd => {
-              self.report_error(Problem::UnclosedList, &super_range(start_span, &self.reader.last_token_span()));
+              self.report_error(unclosed_problem, &super_range(start_span, &self.reader.last_token_span()));
               return Err(())
             },
             _ => unreachable!()
@@ -937,10 +1402,11 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
           }
         }
 
-        Ok(Rst::ListInit(Rc::new(sequences)))
+        Ok(make_rst(sequences))
       },
       CollectionInitKind::Map => {
         let mut pairs = vec![];
+        self.hint_spacing(self.reader.last_consumed_span().end, Spacing::None);
         
         loop {
           let key_expr = match self.reader.next_solid() {
@@ -961,7 +1427,10 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
               MapKeyExpr::Static(s)
             },
             // End of map
-            Some((RightParen, _)) => break,
+            Some((RightParen, span)) => {
+              self.hint_spacing(span.start, Spacing::None);
+              break
+            },
             // Soft error on anything weird
             Some(_) => {
               self.unexpected_last_token_error();
@@ -974,17 +1443,24 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
             }
           };
           
+          self.hint_spacing(self.reader.last_consumed_span().end, Spacing::Space);
           self.reader.skip_ws();
           if !self.reader.eat_where(|tok| matches!(tok, Some((Equals, ..)))) {
             self.report_error(Problem::ExpectedToken("=".to_owned()), &self.reader.last_token_span());
-            return Err(())
+            if self.reader.peek().is_none() {
+              return Err(())
+            }
+            self.synchronize(RightParen);
+            break
           }
+          self.hint_spacing(self.reader.last_consumed_span().end, Spacing::Space);
           self.reader.skip_ws();
           let ParsedSequence { 
             sequence: value_expr, 
             end_type: value_expr_end, 
             .. 
           } = self.parse_sequence(SequenceParseMode::CollectionInit)?;
+          self.hint_item_end(&value_expr_end);
           
           match value_expr_end {
             SequenceEndType::CollectionInitDelim => {
@@ -1024,6 +1500,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       Some((Colon, _)) => {
         // Read the params
         'read_params: loop {
+          self.hint_spacing(self.reader.last_consumed_span().end, Spacing::Space);
           match self.reader.next_solid() {
             // Regular parameter
             Some((Fragment, span)) => {              
@@ -1039,6 +1516,10 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 self.report_error(Problem::DuplicateParameter(param_name.to_string()), &span);
               }                
               
+              // Get type annotation of parameter
+              self.reader.skip_ws();
+              let type_annotation = self.parse_type_annotation(start_span)?;
+
               // Get varity of parameter
               self.reader.skip_ws();
               let (varity, full_param_span) = 
@@ -1073,11 +1554,17 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
 
               // Read default value expr on optional params
               if matches!(varity, Varity::Optional) {
+                let default_value_start = self.reader.last_token_span().end;
                 let ParsedSequence {
                   sequence: default_value_seq,
                   end_type: default_value_end_type,
                   ..
                 } = self.parse_sequence(SequenceParseMode::ParamDefaultValue)?;
+                let default_value_span = self.trimmed_position(default_value_start .. self.reader.last_token_span().start).span();
+                if !default_value_seq.is_empty() {
+                  self.hint_spacing(full_param_span.end, Spacing::Space);
+                }
+                self.hint_spacing(self.reader.last_consumed_span().start, Spacing::None);
 
                 let should_continue = match default_value_end_type {
                   SequenceEndType::ParamDefaultValueSeparator => true,
@@ -1092,11 +1579,18 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 let opt_param = Parameter {
                   name: param_name,
                   varity: Varity::Optional,
-                  default_value_expr: (!default_value_seq.is_empty()).then(|| Rc::new(default_value_seq))
+                  default_value_expr: (!default_value_seq.is_empty()).then(|| Rc::new(default_value_seq)),
+                  pattern: None,
+                  type_annotation,
                 };
 
+                // Literal default values must fit the type annotation
+                if let Some(default_value_expr) = &opt_param.default_value_expr {
+                  self.check_literal_type(&opt_param, default_value_expr, &default_value_span);
+                }
+
                 // Add parameter to list
-                params.push((opt_param, full_param_span.end .. self.reader.last_token_span().start));
+                params.push((opt_param, full_param_span.start .. self.reader.last_token_span().start));
 
                 // Keep reading other params if needed
                 if should_continue {
@@ -1112,6 +1606,8 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 name: param_name,
                 varity,
                 default_value_expr: None,
+                pattern: None,
+                type_annotation,
               };
               
               // Add parameter to list
@@ -1120,17 +1616,20 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
               // Check if there are more params or if the signature is done
               match self.reader.next_solid() {
                 // ';' means there are more params
-                Some((Semicolon, ..)) => {
+                Some((Semicolon, span)) => {
+                  self.hint_spacing(span.start, Spacing::None);
                   continue 'read_params
                 },
                 // ']' means end of signature
-                Some((RightBracket, ..)) => {
+                Some((RightBracket, span)) => {
+                  self.hint_spacing(span.start, Spacing::None);
                   break 'read_params
                 },
-                // Emit a hard error on anything else
+                // Skip the rest of the signature on anything else
                 Some((_, span)) => {
                   self.report_error(Problem::UnexpectedToken(self.reader.last_token_string().to_string()), &span);
-                  return Err(())
+                  self.synchronize(RightBracket);
+                  break 'read_params
                 },
                 None => {
                   self.report_error(Problem::UnclosedFunctionSignature, &start_span);
@@ -1138,6 +1637,61 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 },
               }
             },
+            // Destructured parameter
+            Some((token, span)) if matches!(token, LeftParen | At) => {
+              let mut bindings = vec![];
+              let pattern = match self.parse_destructure_pattern(token, &mut bindings) {
+                Ok(pattern) => pattern,
+                Err(()) if self.reader.peek().is_none() => return Err(()),
+                Err(()) => {
+                  self.synchronize(RightBracket);
+                  break 'read_params
+                },
+              };
+              let full_param_span = span.start .. self.reader.last_token_span().end;
+
+              // Check for duplicates against the other parameters
+              for (name, binding_span) in &bindings {
+                if !params_set.insert(name.clone()) {
+                  self.report_error(Problem::DuplicateParameter(name.to_string()), binding_span);
+                }
+              }
+
+              // Destructured parameters are always required
+              if !Varity::is_valid_order(last_varity, Varity::Required) {
+                self.report_error(Problem::InvalidParamOrder(last_varity.to_string(), Varity::Required.to_string()), &full_param_span);
+              }
+              last_varity = Varity::Required;
+
+              let param = Parameter {
+                name: Identifier::new(InternalString::from(format!("{}{}", DESTRUCTURE_PARAM_PREFIX, params.len()))),
+                varity: Varity::Required,
+                default_value_expr: None,
+                pattern: Some(Rc::new(pattern)),
+                type_annotation: None,
+              };
+
+              params.push((param, full_param_span));
+
+              // Check if there are more params or if the signature is done
+              match self.reader.next_solid() {
+                Some((Semicolon, ..)) => {
+                  continue 'read_params
+                },
+                Some((RightBracket, ..)) => {
+                  break 'read_params
+                },
+                Some((_, span)) => {
+                  self.report_error(Problem::UnexpectedToken(self.reader.last_token_string().to_string()), &span);
+                  self.synchronize(RightBracket);
+                  break 'read_params
+                },
+                None => {
+                  self.report_error(Problem::UnclosedFunctionSignature, start_span);
+                  return Err(())
+                },
+              }
+            },
             // Error on early close
             Some((RightBracket, span)) => {
               self.report_error(Problem::MissingIdentifier, &span);
@@ -1156,10 +1710,10 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       },
       // ']' means there are no params-- fall through to the next step
       Some((RightBracket, _)) => {},
-      // Something weird is here, emit a hard error
+      // Something weird is here, skip the rest of the signature
       Some((.., span)) => {
         self.report_error(Problem::UnexpectedToken(self.reader.last_token_string().to_string()), &span);
-        return Err(())
+        self.synchronize(RightBracket);
       },
       // Nothing is here, emit a hard error
       None => {
@@ -1171,6 +1725,77 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     Ok(params)
   }
     
+  /// Parses a type annotation if the next token opens one, e.g. `(int | float)`.
+  fn parse_type_annotation(&mut self, start_span: &Range<usize>) -> ParseResult<Option<TypeAnnotation>> {
+    let open_span = match self.reader.take_where(|t| matches!(t, Some((LeftParen, _)))) {
+      Some((_, span)) => span,
+      None => return Ok(None),
+    };
+
+    let mut types = vec![];
+    let mut is_any = false;
+    let mut is_valid = true;
+    loop {
+      match self.reader.next_solid() {
+        Some((Fragment, span)) => {
+          let type_name = &self.source[span.clone()];
+          if type_name == "any" {
+            is_any = true;
+          } else if let Some(value_type) = PATTERN_TYPES.iter().find(|t| t.name() == type_name) {
+            if !types.contains(value_type) {
+              types.push(*value_type);
+            }
+          } else {
+            self.report_error(Problem::UnknownType(type_name.to_owned()), &span);
+            is_valid = false;
+          }
+        },
+        Some((_, span)) => {
+          self.report_error(Problem::UnexpectedToken(self.reader.last_token_string().to_string()), &span);
+          self.synchronize(RightParen);
+          break
+        },
+        None => {
+          self.report_error(Problem::UnclosedTypeAnnotation, &open_span);
+          self.report_error(Problem::UnclosedFunctionSignature, start_span);
+          return Err(())
+        },
+      }
+
+      // Types in a union are separated by '|'
+      self.reader.skip_ws();
+      if matches!(self.reader.peek(), Some((RightBracket | RightBrace | RightAngle, _))) {
+        // Leave the closing bracket for the enclosing element
+        self.report_error(Problem::UnclosedTypeAnnotation, &open_span);
+        return Ok(None)
+      }
+      match self.reader.next_solid() {
+        Some((VertBar, _)) => continue,
+        Some((RightParen, _)) => break,
+        Some((_, span)) => {
+          self.report_error(Problem::UnexpectedToken(self.reader.last_token_string().to_string()), &span);
+          self.synchronize(RightParen);
+          break
+        },
+        None => {
+          self.report_error(Problem::UnclosedTypeAnnotation, &open_span);
+          self.report_error(Problem::UnclosedFunctionSignature, start_span);
+          return Err(())
+        },
+      }
+    }
+
+    if !is_valid {
+      return Ok(None)
+    }
+
+    Ok(Some(if is_any {
+      TypeAnnotation::Any
+    } else {
+      TypeAnnotation::Union(types)
+    }))
+  }
+
   /// Parses a function definition, anonymous function, or function call.
   fn parse_func_access(&mut self, flag: PrintFlag) -> ParseResult<Rst> {
     let start_span = self.reader.last_token_span();
@@ -1184,44 +1809,63 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
           let is_const = matches!(tt, Percent);
 
           // Name of variable function will be stored in
+          let sig_error_count = self.error_count();
           let (func_path, _func_path_span) = self.parse_access_path(false)?;
 
           // Warn user if non-variable function definition is marked as a constant
-          if is_const && !func_path.is_variable() {
+          if is_const && !func_path.is_variable() && !func_path.is_empty() {
             self.report_warning(Problem::NestedFunctionDefMarkedConstant, &func_access_type_span);
           }
           
           self.reader.skip_ws();
 
+          // Return type
+          let return_type = self.parse_type_annotation(&start_span)?.map(Rc::new);
+          self.reader.skip_ws();
+
           let ((body, params, end_func_sig_span), captures) = self.capture_pass(|self_| {
             // Function params
             let params = self_.parse_func_params(&start_span)?;
             let end_func_sig_span = self_.reader.last_token_span();
             
-            // Read function body
-            let body = self_.parse_func_body(&params, false)?;
+            // Read function body; a missing body isn't reported after a signature that already failed
+            self_.reader.skip_ws();
+            let body = if self_.error_count() > sig_error_count && !matches!(self_.reader.peek(), Some((LeftBrace, _))) {
+              Sequence::empty(&self_.info)
+            } else {
+              self_.parse_func_body(&params, false)?
+            };
             
             Ok((body, params, end_func_sig_span))
           })?;
 
+          let params: Rc<Vec<Parameter>> = Rc::new(params.into_iter().map(|(p, _)| p).collect());
+
           // Track variable
           if func_path.is_variable() {
             if let Some(id) = &func_path.var_name() {
               let func_def_span = super_range(&start_span, &end_func_sig_span);
               self.track_variable(id, &func_path.kind(), is_const, VarRole::Function, &func_def_span);
+              if let Some(tracker) = self.get_var_tracker_mut(id, func_path.kind()) {
+                tracker.params = Some(Rc::clone(&params));
+              }
             }
           }
           
           Ok(Rst::FuncDef(FunctionDef {
             body: Rc::new(body.with_name_str(format!("[{}]", func_path).as_str())),
             path: Rc::new(func_path),
-            params: Rc::new(params.into_iter().map(|(p, _)| p).collect()),
+            params,
             capture_vars: Rc::new(captures),
             is_const,
+            return_type,
           }))
         },
         // Lambda
         Question => {
+          // Return type
+          self.reader.skip_ws();
+          let return_type = self.parse_type_annotation(&start_span)?.map(Rc::new);
           // Lambda params
           let params = self.parse_func_params(&start_span)?;
           self.reader.skip_ws();
@@ -1232,6 +1876,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
             capture_vars: Rc::new(captures),
             body: Rc::new(body.with_name_str("lambda")),
             params: Rc::new(params.into_iter().map(|(p, _)| p).collect()),
+            return_type,
           }))
         },
         _ => unreachable!()
@@ -1263,6 +1908,10 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
         let mut is_temporal = false;
         // Do the user-supplied args use the pipe value?
         let mut is_pipeval_used = false;
+        // Name spans of the named arguments, in order
+        let mut named_arg_spans: Vec<Range<usize>> = vec![];
+        // Spans of the argument values, in order
+        let mut arg_spans: Vec<Range<usize>> = vec![];
         
         /// Reads arguments until a terminating / delimiting token is reached.
         macro_rules! parse_args {
@@ -1304,13 +1953,33 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 None => {},
               }
 
+              // Check for argument name (`@name = value`)
+              let mut arg_name = None;
+              let is_name_next = matches!(spread_mode, ArgumentSpreadMode::NoSpread)
+                && matches!(self.reader.peek(), Some((Keyword(name), _)) if is_valid_ident(name) && !SEQUENCE_KEYWORDS.contains(&name.as_str()))
+                && matches!(self.reader.peek_solid_after_next(), Some(Equals));
+              if is_name_next {
+                if let Some((Keyword(name), name_span)) = self.reader.next() {
+                  arg_name = Some(Identifier::new(name));
+                  named_arg_spans.push(name_span);
+                }
+                self.reader.skip_ws();
+                self.reader.skip_one();
+              }
+
+              self.reader.skip_ws();
+              let value_start = match self.reader.peek() {
+                Some((_, span)) => span.start,
+                None => self.reader.last_token_span().end,
+              };
+
               // Parse argument
               let ParsedSequence {
                 sequence: arg_seq,
                 end_type: arg_end,
                 ..
               } = if is_piped {
-                self.var_stack.push_layer();
+                self.push_scope();
                 // Track pipe value inside arguement scope
                 let pipeval_stats = VarStats {
                   writes: 1,
@@ -1319,20 +1988,26 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                   is_const: true,
                   has_fallible_read: false,
                   role: VarRole::PipeValue,
+                  symbol: None,
+                  params: None,
                 };
                 self.var_stack.define(Identifier::from(PIPE_VALUE_NAME), pipeval_stats);
                 let parsed_arg_expr = self.parse_sequence_inner(SequenceParseMode::FunctionArg)?;
                 is_pipeval_used |= self.var_stack.get(PIPE_VALUE_NAME).unwrap().reads > 0;
                 self.analyze_top_vars();
-                self.var_stack.pop_layer();
+                self.pop_scope();
                 parsed_arg_expr
               } else {
                 self.parse_sequence(SequenceParseMode::FunctionArg)?
               };
 
+              let arg_end_pos = self.reader.last_consumed_span().start.max(value_start);
+              arg_spans.push(value_start .. value_start + self.source[value_start .. arg_end_pos].trim_end().len());
+
               let arg = ArgumentExpr {
                 expr: Rc::new(arg_seq),
                 spread_mode,
+                name: arg_name,
               };
               func_args.push(arg);
               match arg_end {
@@ -1362,8 +2037,10 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
               let arg = ArgumentExpr {
                 expr: Rc::new(Sequence::one(Rst::PipeValue, &self.info)),
                 spread_mode: ArgumentSpreadMode::NoSpread,
+                name: None,
               };
               func_args.insert(0, arg);
+              arg_spans.insert(0, Default::default());
             }
           }
         }
@@ -1378,7 +2055,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
             end_type: func_expr_end,
             ..
           } = if is_piped {
-            self.var_stack.push_layer();
+            self.push_scope();
             // Track pipe value inside anonymous function access scope
             let pipeval_stats = VarStats {
               writes: 1,
@@ -1387,12 +2064,14 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
               is_const: true,
               has_fallible_read: false,
               role: VarRole::PipeValue,
+              symbol: None,
+              params: None,
             };
             self.var_stack.define(Identifier::from(PIPE_VALUE_NAME), pipeval_stats);
             let seq = self.parse_sequence_inner(SequenceParseMode::AnonFunctionExpr)?;
             is_pipeval_used |= self.var_stack.get(PIPE_VALUE_NAME).unwrap().reads > 0;
             self.analyze_top_vars();
-            self.var_stack.pop_layer();
+            self.pop_scope();
             seq
           } else {
             self.parse_sequence(SequenceParseMode::AnonFunctionExpr)?
@@ -1427,6 +2106,14 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
         } else {
           // Named function call
           let (func_path, func_path_span) = self.parse_access_path(false)?;
+
+          // Skip the rest of the call if its name is invalid
+          if func_path.is_empty() {
+            if !self.reader.eat_where(|t| matches!(t, Some((RightBracket, ..)))) {
+              self.synchronize(RightBracket);
+            }
+            return Ok(Rst::Nop)
+          }
           if let Some((token, _)) = self.reader.next_solid() {
             match token {
               // No args, fall through
@@ -1439,9 +2126,12 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
               PipeOp => {
                 is_piped = true;
               }
+              // Skip the rest of the call on anything else
               _ => {
                 self.unexpected_last_token_error();
-                return Err(())
+                self.track_variable_access(&func_path, false, false, &func_path_span);
+                self.synchronize(RightBracket);
+                return Ok(Rst::Nop)
               }
             }
 
@@ -1449,6 +2139,8 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
             
             // Record access to function
             self.track_variable_access(&func_path, false, false, &func_path_span);
+            self.check_call_args(&func_path, &func_args, &arg_spans, &named_arg_spans, &func_path_span);
+            self.track_require(&func_path, &func_args, start_span.start .. self.reader.last_token_span().end);
             
             // Create final node for function call
             let fcall = FunctionCall {
@@ -1537,6 +2229,14 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     } else {
       // Check for global/descope specifiers
       access_kind = self.parse_access_path_kind();
+
+      // Leave a closing bracket for the caller so that it can still end the construct
+      self.reader.skip_ws();
+      if let Some((token, span)) = self.reader.peek().filter(|(token, _)| is_closing_bracket(token)) {
+        let (token, span) = (bracket_str(token), span.clone());
+        self.report_error(Problem::InvalidIdentifier(token.to_owned()), &span);
+        return Ok((AccessPath::new(idparts, access_kind), start_span.start .. span.start))
+      }
       
       let first_part = self.reader.next_solid();
       
@@ -1757,7 +2457,11 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     } else {
       if !self.reader.eat_where(|t| matches!(t, Some((LeftBrace, _)))) {
         self.report_error(Problem::ExpectedToken("{".to_owned()), &self.reader.last_token_span());
-        return Err(())
+        // Without a body, resume parsing from the token after the signature
+        if self.reader.peek().is_none() {
+          return Err(())
+        }
+        return Ok(Sequence::empty(&self.info))
       }
       true
     };
@@ -1766,22 +2470,32 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
 
     // Define each parameter as a variable in the current var_stack frame so they are not accidentally captured
     for (param, span) in params {
-      self.var_stack.define(param.name.clone(), VarStats {
-        reads: 0,
-        writes: 1,
-        def_span: span.clone(),
-        is_const: true,
-        has_fallible_read: false,
-        role: if param.is_optional() && param.default_value_expr.is_none() {
-          VarRole::FallibleOptionalArgument
-        } else { 
-          VarRole::Argument 
-        }
-      });
+      // Destructured parameters define the variables in their pattern instead
+      let names = match &param.pattern {
+        Some(pattern) => pattern.var_names(),
+        None => vec![&param.name],
+      };
+      for name in names {
+        let symbol = self.add_symbol_definition(name, SymbolKind::Parameter, span);
+        self.var_stack.define(name.clone(), VarStats {
+          reads: 0,
+          writes: 1,
+          def_span: span.clone(),
+          is_const: true,
+          has_fallible_read: false,
+          role: if param.is_optional() && param.default_value_expr.is_none() {
+            VarRole::FallibleOptionalArgument
+          } else { 
+            VarRole::Argument 
+          },
+          symbol,
+          params: None,
+        });
+      }
     }
 
     // parse_sequence_inner() is used here so that the new stack frame can be customized before use
-    let ParsedSequence { sequence, end_type, .. } = self.parse_sequence_inner(if is_block_body {
+    let ParsedSequence { mut sequence, end_type, .. } = self.parse_sequence_inner(if is_block_body {
       SequenceParseMode::FunctionBodyBlock
     } else {
       SequenceParseMode::SingleItem
@@ -1801,8 +2515,17 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       _ => unreachable!()
     }
 
-    Ok(sequence)
-  }
+    // Unpack destructured parameters before the rest of the body runs
+    let mut prologue = vec![];
+    for (param, _) in params {
+      if let Some(pattern) = &param.pattern {
+        self.lower_destructure(pattern, &param.name, &mut vec![], AccessPathKind::Local, true, &mut prologue);
+      }
+    }
+    sequence.splice(0..0, prologue.into_iter().map(Rc::new));
+
+    Ok(sequence)
+  }
   
   fn capture_pass<T>(&mut self, parse_func: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<(T, Vec<Identifier>)> {
     // Since we're about to push another var_stack frame, we can use the current depth of var_stack as the index
@@ -1812,7 +2535,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     self.capture_stack.push((capture_height, Default::default()));
 
     // Push a new variable frame
-    self.var_stack.push_layer();
+    self.push_scope();
 
     // Call parse_func
     let parse_out = parse_func(self)?;
@@ -1820,7 +2543,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     // Run static analysis on variable/param usage
     self.analyze_top_vars();
 
-    self.var_stack.pop_layer();
+    self.pop_scope();
 
     // Pop the topmost capture frame and grab the set of captures
     let (_, mut capture_set) = self.capture_stack.pop().unwrap();
@@ -1845,6 +2568,9 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     let mut elements = vec![];
     
     loop {
+      // Elements start right after the opening brace or previous delimiter
+      let elem_start = self.reader.last_token_span().end;
+
       let ParsedSequence { 
         sequence, 
         end_type, 
@@ -1854,6 +2580,8 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       
       auto_hint |= is_text;
 
+      let elem_end = self.reader.last_token_pos().max(elem_start);
+
       let element = BlockElement {
         main: Rc::new(sequence),
         weight: if let Some(ParsedSequenceExtras::WeightedBlockElement { weight_expr }) = extras {
@@ -1867,6 +2595,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
         } else {
           None
         },
+        pos: self.trimmed_position(elem_start .. elem_end),
       };
       
       match end_type {
@@ -1887,13 +2616,573 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       }
     }
     
+    let (line, col) = self.lookup.get(start_pos);
+    let pos = Position::new(line, col, start_pos .. self.reader.last_token_span().end);
+    
     // Figure out the printflag before returning the block
     if auto_hint && flag != PrintFlag::Sink {
-      Ok(Block::new(PrintFlag::Hint, is_weighted, elements))
+      Ok(Block::new(PrintFlag::Hint, is_weighted, elements, pos))
+    } else {
+      Ok(Block::new(flag, is_weighted, elements, pos))
+    }
+  }
+
+  /// Parses a match expression following the `@match` keyword.
+  /// Parses the item after a `@yield` keyword, if there is one.
+  ///
+  /// Unlike `@return`, `@yield` only takes a single item, since the rest of the sequence runs when the generator is resumed.
+  fn parse_yield_value(&mut self) -> ParseResult<Option<Rc<Sequence>>> {
+    // A bare `@yield` yields nothing
+    if matches!(self.reader.peek(), None | Some((RightBrace | RightBracket | RightParen | RightAngle | Semicolon | VertBar | PipeOp, _))) {
+      return Ok(None)
+    }
+
+    let ParsedSequence {
+      sequence: value,
+      ..
+    } = self.parse_sequence(SequenceParseMode::SingleItem)?;
+    Ok(Some(Rc::new(value.with_name_str("yield value"))))
+  }
+
+  fn parse_match(&mut self, start_pos: usize) -> ParseResult<MatchExpr> {
+    // Read in the value to match
+    self.reader.skip_ws();
+    let ParsedSequence {
+      sequence: value,
+      end_type: value_end,
+      ..
+    } = self.parse_sequence(SequenceParseMode::SingleItem)?;
+
+    if !matches!(value_end, SequenceEndType::SingleItemEnd) {
+      let err_span = start_pos .. self.source.len();
+      self.report_error(Problem::UnclosedMatch, &err_span);
+      return Err(())
+    }
+
+    self.reader.skip_ws();
+    if !self.reader.eat_where(|t| matches!(t, Some((LeftBrace, _)))) {
+      self.report_error(Problem::ExpectedToken("{".to_owned()), &self.reader.last_token_span());
+      return Err(())
+    }
+
+    let mut arms: Vec<MatchArm> = vec![];
+
+    loop {
+      let mut bindings = vec![];
+      let (pattern, pattern_span) = self.parse_pattern(&mut bindings)?;
+
+      self.reader.skip_ws();
+      if !self.reader.eat_where(|t| matches!(t, Some((Colon, _)))) {
+        self.report_error(Problem::ExpectedToken(":".to_owned()), &self.reader.last_token_span());
+        return Err(())
+      }
+
+      // Warn about arms that can't be reached because an earlier arm matches everything they do
+      if arms.iter().any(|arm| arm.pattern.covers(&pattern)) {
+        self.report_warning(Problem::UnreachableMatchArm, &pattern_span);
+      }
+
+      // Bound values are locals in the scope of the arm body
+      self.push_scope();
+      for (id, span) in bindings.iter() {
+        self.track_variable(id, &AccessPathKind::Local, false, VarRole::Normal, span);
+      }
+      let body_result = self.parse_sequence_inner(SequenceParseMode::MatchArm);
+      self.analyze_top_vars();
+      self.pop_scope();
+
+      let ParsedSequence {
+        sequence: body,
+        end_type: body_end,
+        ..
+      } = body_result?;
+
+      let body_end_pos = self.reader.last_token_pos().max(pattern_span.end);
+
+      arms.push(MatchArm {
+        pattern,
+        body: Rc::new(body),
+        pos: self.trimmed_position(pattern_span.start .. body_end_pos),
+      });
+
+      match body_end {
+        SequenceEndType::MatchArmDelim => continue,
+        SequenceEndType::MatchArmEnd => break,
+        SequenceEndType::ProgramEnd => {
+          // Hard error if match isn't closed
+          let err_span = start_pos .. self.source.len();
+          self.report_error(Problem::UnclosedMatch, &err_span);
+          return Err(())
+        },
+        _ => unreachable!()
+      }
+    }
+
+    let end_pos = self.reader.last_token_span().end;
+
+    // The last arm must match anything the other arms don't
+    if !arms.last().is_some_and(|arm| arm.pattern.is_irrefutable()) {
+      self.report_error(Problem::MissingMatchFallback, &(start_pos .. end_pos));
+    }
+
+    let (line, col) = self.lookup.get(start_pos);
+    Ok(MatchExpr {
+      value: Rc::new(value),
+      arms: Rc::new(arms),
+      pos: Position::new(line, col, start_pos .. end_pos),
+    })
+  }
+
+  /// Parses a match pattern, adding the names it binds to `bindings`.
+  ///
+  /// Returns the pattern and its span.
+  fn parse_pattern(&mut self, bindings: &mut Vec<(Identifier, Range<usize>)>) -> ParseResult<(Pattern, Range<usize>)> {
+    let (token, span) = match self.reader.next_solid() {
+      Some(next) => next,
+      None => {
+        self.report_error(Problem::InvalidPattern(String::new()), &self.reader.last_token_span());
+        return Err(())
+      }
+    };
+
+    macro_rules! invalid_pattern {
+      () => {{
+        self.report_error(Problem::InvalidPattern(self.reader.last_token_string().to_string()), &self.reader.last_token_span());
+        return Err(())
+      }}
+    }
+
+    let is_bare_binding = matches!(token, Dollar);
+
+    let pattern = match token {
+      Fragment => {
+        let fragment = self.reader.last_token_string();
+        if fragment.as_str() == "_" {
+          Pattern::Wildcard
+        } else {
+          Pattern::Literal(RantValue::String(fragment.as_str().into()))
+        }
+      },
+      StringLiteral(s) => Pattern::Literal(RantValue::String(s.as_str().into())),
+      Float(n) => Pattern::Literal(RantValue::Float(n)),
+      EmptyValue => Pattern::Literal(RantValue::Empty),
+      Integer(n) => {
+        // Check for range pattern, e.g. `1..10`
+        if self.eat_range_dots() {
+          match self.reader.next() {
+            Some((Integer(end), _)) => Pattern::Range(RantRange::new(n, end, 1)),
+            _ => invalid_pattern!(),
+          }
+        } else {
+          Pattern::Literal(RantValue::Int(n))
+        }
+      },
+      Keyword(kw) => match kw.as_str() {
+        KW_TRUE => Pattern::Literal(RantValue::Boolean(true)),
+        KW_FALSE => Pattern::Literal(RantValue::Boolean(false)),
+        type_name => match PATTERN_TYPES.iter().find(|t| t.name() == type_name) {
+          Some(t) => Pattern::Type(*t),
+          None => invalid_pattern!(),
+        }
+      },
+      Dollar => Pattern::Wildcard,
+      // List pattern
+      LeftParen => {
+        let mut items = vec![];
+        let mut rest = None;
+        self.reader.skip_ws();
+        if !self.reader.eat_where(|t| matches!(t, Some((RightParen, _)))) {
+          loop {
+            self.reader.skip_ws();
+            if self.reader.eat_where(|t| matches!(t, Some((Star, _)))) {
+              // Rest pattern matches a list of the remaining items
+              self.reader.skip_ws();
+              rest = Some(Box::new(if self.reader.eat_where(|t| matches!(t, Some((RightParen, _)))) {
+                Pattern::Wildcard
+              } else {
+                let (rest_pattern, _) = self.parse_pattern(bindings)?;
+                self.reader.skip_ws();
+                if !self.reader.eat_where(|t| matches!(t, Some((RightParen, _)))) {
+                  self.report_error(Problem::ExpectedToken(")".to_owned()), &self.reader.last_token_span());
+                  return Err(())
+                }
+                rest_pattern
+              }));
+              break
+            }
+
+            let (item, _) = self.parse_pattern(bindings)?;
+            items.push(item);
+
+            match self.reader.next_solid() {
+              Some((Semicolon, _)) => continue,
+              Some((RightParen, _)) => break,
+              _ => invalid_pattern!(),
+            }
+          }
+        }
+        Pattern::List { items, rest }
+      },
+      // Map pattern
+      At => {
+        if !self.reader.eat_where(|t| matches!(t, Some((LeftParen, _)))) {
+          invalid_pattern!()
+        }
+        let mut entries = vec![];
+        self.reader.skip_ws();
+        if !self.reader.eat_where(|t| matches!(t, Some((RightParen, _)))) {
+          loop {
+            let key = match self.reader.next_solid() {
+              Some((Fragment, _)) => self.reader.last_token_string(),
+              Some((StringLiteral(key), _)) => key,
+              _ => invalid_pattern!(),
+            };
+
+            // A key without a pattern only needs to exist
+            self.reader.skip_ws();
+            let entry_pattern = if self.reader.eat_where(|t| matches!(t, Some((Equals, _)))) {
+              self.parse_pattern(bindings)?.0
+            } else {
+              Pattern::Wildcard
+            };
+            entries.push((key, entry_pattern));
+
+            match self.reader.next_solid() {
+              Some((Semicolon, _)) => continue,
+              Some((RightParen, _)) => break,
+              _ => invalid_pattern!(),
+            }
+          }
+        }
+        Pattern::Map(entries)
+      },
+      _ => invalid_pattern!(),
+    };
+
+    // A pattern can be followed by `$name` to bind the value it matches; `$name` alone matches anything
+    let has_binding = is_bare_binding || {
+      self.reader.skip_ws();
+      self.reader.eat_where(|t| matches!(t, Some((Dollar, _))))
+    };
+
+    let pattern = if has_binding {
+      let binding_start = self.reader.last_token_span().start;
+      let id = self.parse_ident()?;
+      let binding_span = binding_start .. self.reader.last_token_span().end;
+      if bindings.iter().any(|(bound_id, _)| bound_id == &id) {
+        self.report_error(Problem::DuplicateBinding(id.to_string()), &binding_span);
+      }
+      bindings.push((id.clone(), binding_span));
+      Pattern::Bind(Box::new(pattern), id)
+    } else {
+      pattern
+    };
+
+    Ok((pattern, span.start .. self.reader.last_consumed_span().end))
+  }
+
+  /// Parses the contents of an interpolated string literal, e.g. `$"Hello, <name>!"`.
+  ///
+  /// Accessors and function calls in the string are parsed as single items; `""` and escape sequences produce literal characters.
+  fn parse_interpolated_string(&mut self, span: &Range<usize>) -> ParseResult<Rst> {
+    let source = self.source;
+    // Skip the leading `$"` and trailing `"`
+    let content_end = span.end - 1;
+    let mut pos = span.start + 2;
+    let mut parts = vec![];
+    let mut text = InternalString::new();
+
+    while pos < content_end {
+      let mut chars = source[pos..content_end].chars();
+      match chars.next() {
+        // The lexer only allows quotes in pairs
+        Some('"') => {
+          text.push('"');
+          pos += 2;
+        },
+        Some('\\') => match chars.next() {
+          Some(escaped) => {
+            text.push(match escaped {
+              'r' => '\r',
+              'n' => '\n',
+              't' => '\t',
+              '0' => '\0',
+              's' => ' ',
+              other => other,
+            });
+            pos += 1 + escaped.len_utf8();
+          },
+          None => {
+            text.push('\\');
+            pos += 1;
+          }
+        },
+        Some('<') | Some('[') => {
+          if !text.is_empty() {
+            parts.push(StringPart::Text(std::mem::replace(&mut text, InternalString::new())));
+          }
+
+          // Parse the embedded item with a reader that can't go past the end of the string
+          let outer_reader = std::mem::replace(&mut self.reader, RantTokenReader::with_bounds(source, pos .. content_end));
+          self.push_scope();
+          let parsed = self.parse_sequence_inner(SequenceParseMode::SingleItem);
+          self.reject_top_definitions();
+          self.pop_scope();
+          let last_span = self.reader.last_consumed_span();
+          self.reader = outer_reader;
+          let ParsedSequence { sequence, .. } = parsed?;
+          parts.push(StringPart::Expr(Rc::new(sequence)));
+
+          // Whitespace consumed after the item is still part of the text
+          pos = if source[last_span.clone()].trim().is_empty() {
+            last_span.start
+          } else {
+            last_span.end
+          };
+        },
+        Some(c) => {
+          text.push(c);
+          pos += c.len_utf8();
+        },
+        None => break,
+      }
+    }
+
+    // Strings without any embedded items are just fragments
+    if parts.is_empty() {
+      return Ok(Rst::Fragment(text))
+    }
+
+    if !text.is_empty() {
+      parts.push(StringPart::Text(text));
+    }
+
+    Ok(Rst::InterpolatedString(Rc::new(parts)))
+  }
+
+  /// Parses a destructuring pattern after its opening `(` or `@`, adding the names it binds to `bindings`.
+  fn parse_destructure_pattern(&mut self, open_token: RantToken, bindings: &mut Vec<(Identifier, Range<usize>)>) -> ParseResult<DestructurePattern> {
+    let start_span = self.reader.last_token_span();
+    let is_map = matches!(open_token, At);
+    if is_map && !self.reader.eat_where(|t| matches!(t, Some((LeftParen, _)))) {
+      self.report_error(Problem::ExpectedToken("(".to_owned()), &self.reader.last_token_span());
+      return Err(())
+    }
+
+    macro_rules! unclosed_error {
+      () => {{
+        let problem = if is_map { Problem::UnclosedMap } else { Problem::UnclosedList };
+        self.report_error(problem, &super_range(&start_span, &self.reader.last_token_span()));
+        return Err(())
+      }}
+    }
+
+    macro_rules! unexpected_error {
+      () => {{
+        self.unexpected_last_token_error();
+        return Err(())
+      }}
+    }
+
+    /// Reads the last token as a variable or key name.
+    macro_rules! last_token_ident {
+      () => {{
+        let id = Identifier::new(self.reader.last_token_string());
+        if !is_valid_ident(id.as_str()) {
+          self.report_error(Problem::InvalidIdentifier(id.to_string()), &self.reader.last_token_span());
+        }
+        id
+      }}
+    }
+
+    macro_rules! bind {
+      ($id:expr, $span:expr) => {{
+        let (id, span) = ($id, $span);
+        if bindings.iter().any(|(bound_id, _)| bound_id == &id) {
+          self.report_error(Problem::DuplicateBinding(id.to_string()), &span);
+        }
+        bindings.push((id.clone(), span));
+        id
+      }}
+    }
+
+    /// Reads the `;` or `)` after an item and evaluates to `true` if it ends the pattern.
+    macro_rules! item_delim {
+      () => {{
+        match self.reader.next_solid() {
+          Some((Semicolon, _)) => false,
+          Some((RightParen, _)) => true,
+          Some(_) => unexpected_error!(),
+          None => unclosed_error!(),
+        }
+      }}
+    }
+
+    let mut items = vec![];
+    let mut rest = None;
+    let mut entries = vec![];
+
+    loop {
+      let (token, span) = match self.reader.next_solid() {
+        // Allows empty patterns and trailing semicolons
+        Some((RightParen, _)) => break,
+        Some(next) => next,
+        None => unclosed_error!(),
+      };
+
+      // A list pattern can end with `*name` to capture the remaining items.
+      // Since it looks like the start of a labeled temporal spread, `*name` is usually lexed as a single fragment.
+      let rest_binding = match token {
+        _ if is_map => None,
+        Star => match self.reader.next_solid() {
+          Some((Fragment, rest_span)) => Some((last_token_ident!(), rest_span)),
+          Some(_) => unexpected_error!(),
+          None => unclosed_error!(),
+        },
+        Fragment if self.reader.last_token_string().starts_with('*') => {
+          let rest_str = self.reader.last_token_string();
+          let rest_id = Identifier::new(InternalString::from(&rest_str[1..]));
+          let rest_span = span.start + 1 .. span.end;
+          if !is_valid_ident(rest_id.as_str()) {
+            self.report_error(Problem::InvalidIdentifier(rest_id.to_string()), &rest_span);
+          }
+          Some((rest_id, rest_span))
+        },
+        _ => None,
+      };
+
+      if let Some((rest_id, rest_span)) = rest_binding {
+        rest = Some(bind!(rest_id, rest_span));
+        match self.reader.next_solid() {
+          Some((RightParen, _)) => break,
+          Some(_) => unexpected_error!(),
+          None => unclosed_error!(),
+        }
+      }
+
+      // Map entries are `key` or `key = item`; list entries are just the item
+      let (key, item_token, item_span, item_name) = if is_map {
+        let key = match token {
+          Fragment => last_token_ident!(),
+          _ => unexpected_error!(),
+        };
+        self.reader.skip_ws();
+        if self.reader.eat_where(|t| matches!(t, Some((Equals, _)))) {
+          match self.reader.next_solid() {
+            Some((Fragment, item_span)) => (Some(key), Fragment, item_span, Some(last_token_ident!())),
+            Some((item_token, item_span)) => (Some(key), item_token, item_span, None),
+            None => unclosed_error!(),
+          }
+        } else {
+          (Some(key.clone()), Fragment, span, Some(key))
+        }
+      } else {
+        let name = if matches!(token, Fragment) { Some(last_token_ident!()) } else { None };
+        (None, token, span, name)
+      };
+
+      let (item, is_end) = match (item_token, item_name) {
+        // Variable with optional fallback
+        (Fragment, Some(name)) => {
+          let name = bind!(name, item_span);
+          self.reader.skip_ws();
+          if self.reader.eat_where(|t| matches!(t, Some((Question, _)))) {
+            self.reader.skip_ws();
+            let ParsedSequence { sequence: fallback, end_type, .. } = self.parse_sequence(SequenceParseMode::CollectionInit)?;
+            let is_end = match end_type {
+              SequenceEndType::CollectionInitDelim => false,
+              SequenceEndType::CollectionInitEnd => true,
+              SequenceEndType::ProgramEnd => unclosed_error!(),
+              _ => unreachable!()
+            };
+            (DestructurePattern::Var { name, fallback: Some(Rc::new(fallback)) }, is_end)
+          } else {
+            (DestructurePattern::Var { name, fallback: None }, item_delim!())
+          }
+        },
+        // Nested pattern
+        (nested_token @ LeftParen, _) | (nested_token @ At, _) => {
+          let nested = self.parse_destructure_pattern(nested_token, bindings)?;
+          (nested, item_delim!())
+        },
+        _ => unexpected_error!(),
+      };
+
+      match key {
+        Some(key) => entries.push((key, item)),
+        None => items.push(item),
+      }
+
+      if is_end {
+        break
+      }
+    }
+
+    Ok(if is_map {
+      DestructurePattern::Map(entries)
     } else {
-      Ok(Block::new(flag, is_weighted, elements))
+      DestructurePattern::List { items, rest }
+    })
+  }
+
+  /// Generates the definitions for the variables bound by a destructuring pattern.
+  ///
+  /// Each variable is read from the local variable `source` through `path`, which holds the steps into the value that the pattern applies to.
+  fn lower_destructure(&self, pattern: &DestructurePattern, source: &Identifier, path: &mut Vec<DestructureStep>, access_kind: AccessPathKind, is_const: bool, defs: &mut Vec<Rst>) {
+    let def = |name: &Identifier, path: &[DestructureStep], fallback: Option<Rc<Sequence>>| {
+      let components = std::iter::once(AccessPathComponent::Name(source.clone()))
+        .chain(path.iter().map(DestructureStep::to_component))
+        .collect();
+      let getter = Rst::Get(Rc::new(AccessPath::new(components, AccessPathKind::Local)), fallback);
+      let value = Some(Rc::new(Sequence::one(getter, &self.info)));
+      if is_const {
+        Rst::DefConst(name.clone(), access_kind, value)
+      } else {
+        Rst::DefVar(name.clone(), access_kind, value)
+      }
+    };
+
+    match pattern {
+      DestructurePattern::Var { name, fallback } => {
+        defs.push(def(name, path, fallback.as_ref().map(Rc::clone)));
+      },
+      DestructurePattern::List { items, rest } => {
+        for (index, item) in items.iter().enumerate() {
+          path.push(DestructureStep::Index(index as i64));
+          self.lower_destructure(item, source, path, access_kind, is_const, defs);
+          path.pop();
+        }
+        if let Some(rest) = rest {
+          path.push(DestructureStep::Rest(items.len() as i64));
+          defs.push(def(rest, path, None));
+          path.pop();
+        }
+      },
+      DestructurePattern::Map(entries) => {
+        for (key, item) in entries {
+          path.push(DestructureStep::Key(key.clone()));
+          self.lower_destructure(item, source, path, access_kind, is_const, defs);
+          path.pop();
+        }
+      },
     }
   }
+
+  /// Consumes the `..` between the bounds of a range pattern, if present.
+  fn eat_range_dots(&mut self) -> bool {
+    let source = self.source;
+    let is_dot = |t: Option<&(RantToken, Range<usize>)>| matches!(t, Some((Fragment, span)) if &source[span.clone()] == ".");
+    self.reader.eat_where(is_dot) && self.reader.eat_where(is_dot)
+  }
+
+  /// Gets the position of a source span with its leading and trailing whitespace removed.
+  fn trimmed_position(&self, span: Range<usize>) -> Position {
+    let text = &self.source[span.clone()];
+    let start = span.start + (text.len() - text.trim_start().len());
+    let end = (span.end - (text.len() - text.trim_end().len())).max(start);
+    let (line, col) = self.lookup.get(start);
+    Position::new(line, col, start .. end)
+  }
   
   /// Parses an identifier.
   fn parse_ident(&mut self) -> ParseResult<Identifier> {
@@ -1948,6 +3237,15 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       }
     }
 
+    // Record symbol definition
+    let symbol = match role {
+      VarRole::Function => self.add_symbol_definition(id, SymbolKind::Function, def_span),
+      VarRole::Normal if is_const => self.add_symbol_definition(id, SymbolKind::Constant, def_span),
+      VarRole::Normal => self.add_symbol_definition(id, SymbolKind::Variable, def_span),
+      VarRole::Argument | VarRole::FallibleOptionalArgument => self.add_symbol_definition(id, SymbolKind::Parameter, def_span),
+      VarRole::PipeValue | VarRole::Module => None,
+    };
+
     // Create variable tracking info
     let v = VarStats {
       writes: 0,
@@ -1956,19 +3254,47 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
       has_fallible_read: false,
       is_const,
       role,
+      symbol,
+      params: None,
     };
 
     // Add to stack
-    match access_kind {
+    let scope_depth = match access_kind {
       AccessPathKind::Local => {
         self.var_stack.define(id.clone(), v);
+        0
       },
       AccessPathKind::Descope(n) => {
         self.var_stack.define_parent(id.clone(), v, *n);
+        *n
       },
       AccessPathKind::ExplicitGlobal => {
         self.var_stack.define_parent(id.clone(), v, self.var_stack.depth());
+        self.var_stack.depth()
       },
+    };
+
+    // Resolve earlier accesses that can see the new variable (e.g. from inside a function body)
+    let scope_index = self.scope_ids.len().saturating_sub(1).saturating_sub(scope_depth);
+    if let Some(scope_id) = self.scope_ids.get(scope_index).copied() {
+      self.undefined_accesses.retain(|access| access.name != *id || !access.scope_ids.contains(&scope_id));
+    }
+  }
+
+  /// Gets the tracker of the variable that an access path of the specified kind refers to.
+  #[inline]
+  fn get_var_tracker_mut(&mut self, id: &Identifier, kind: AccessPathKind) -> Option<&mut VarStats> {
+    match kind {
+      AccessPathKind::Local => {
+        self.var_stack.get_mut(id)
+      },
+      AccessPathKind::Descope(n) => {
+        self.var_stack.get_parent_mut(id, n)
+      },
+      // Globals are tracked in the bottom layer, so skip every layer above it
+      AccessPathKind::ExplicitGlobal => {
+        self.var_stack.get_parent_mut(id, self.var_stack.depth().saturating_sub(1))
+      }
     }
   }
 
@@ -1976,17 +3302,9 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
   fn track_variable_access(&mut self, path: &AccessPath, is_write: bool, fallback_hint: bool, span: &Range<usize>) {
     // Handle access stats
     if let Some(id) = &path.var_name() {
-      let tracker = match path.kind() {
-        AccessPathKind::Local => {
-          self.var_stack.get_mut(id)
-        },
-        AccessPathKind::Descope(n) => {
-          self.var_stack.get_parent_mut(id, n)
-        },
-        AccessPathKind::ExplicitGlobal => {
-          self.var_stack.get_parent_mut(id, self.var_stack.depth())
-        }
-      };
+      let tracker = self.get_var_tracker_mut(id, path.kind());
+      let symbol = tracker.as_ref().and_then(|tracker| tracker.symbol);
+      let is_defined = tracker.is_some();
 
       // Update tracker
       if let Some(tracker) = tracker {
@@ -2005,6 +3323,32 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
           }
         }
       }
+
+      // Record access to undefined variable; accessors with a fallback expect that the variable might not exist
+      if !is_defined && !fallback_hint && self.globals.is_some() && id.as_str() != PIPE_VALUE_NAME {
+        let visible_scope_count = match path.kind() {
+          AccessPathKind::Local => self.scope_ids.len(),
+          AccessPathKind::Descope(n) => self.scope_ids.len().saturating_sub(n).max(1),
+          AccessPathKind::ExplicitGlobal => 1,
+        };
+        self.undefined_accesses.push(UndefinedAccess {
+          name: id.clone(),
+          span: self.symbol_name_span(id, span),
+          scope_ids: self.scope_ids[..visible_scope_count.min(self.scope_ids.len())].to_vec(),
+        });
+      }
+
+      // Record symbol reference
+      if id.as_str() != PIPE_VALUE_NAME {
+        let name_span = self.symbol_name_span(id, span);
+        if let Some(symbols) = self.symbols.as_mut() {
+          symbols.add_reference(SymbolReference {
+            name: id.to_string(),
+            span: name_span,
+            definition: symbol,
+          });
+        }
+      }
     }
     
     // Handle captures
@@ -2022,6 +3366,213 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     }
   }
 
+  /// Records a symbol definition if symbols are being collected, returning its index.
+  fn add_symbol_definition(&mut self, id: &Identifier, kind: SymbolKind, def_span: &Range<usize>) -> Option<usize> {
+    let name_span = self.symbol_name_span(id, def_span);
+    self.symbols.as_mut().map(|symbols| symbols.add_definition(SymbolDefinition {
+      name: id.to_string(),
+      kind,
+      name_span,
+      span: def_span.clone(),
+    }))
+  }
+
+  /// Finds the span of an identifier within a larger span, falling back to the larger span if it can't be found.
+  fn symbol_name_span(&self, id: &Identifier, span: &Range<usize>) -> Range<usize> {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let name = id.as_str();
+    if let Some(text) = self.source.get(span.clone()) {
+      for (offset, _) in text.match_indices(name) {
+        let is_bounded_before = !text[..offset].ends_with(is_ident_char);
+        let is_bounded_after = !text[offset + name.len()..].starts_with(is_ident_char);
+        if is_bounded_before && is_bounded_after {
+          let start = span.start + offset;
+          return start .. start + name.len()
+        }
+      }
+    }
+    span.clone()
+  }
+
+  /// Checks a call to a function with a known signature for arguments that don't fit its parameters.
+  ///
+  /// `arg_spans` holds the value spans of `args`; `named_arg_spans` holds the name spans of the named arguments in `args`, in order.
+  /// Argument counts aren't checked for calls that spread arguments from a list, since they aren't known until runtime.
+  fn check_call_args(&mut self, func_path: &AccessPath, args: &[ArgumentExpr], arg_spans: &[Range<usize>], named_arg_spans: &[Range<usize>], span: &Range<usize>) {
+    let globals = match self.globals {
+      Some(globals) => globals,
+      None => return,
+    };
+
+    let id = match func_path.static_variable() {
+      Some(name) if func_path.kind().is_local() => Identifier::new(InternalString::from(name)),
+      _ => return,
+    };
+
+    // Local definitions shadow globals; only check them if they haven't been reassigned
+    let (arity, params) = match self.var_stack.get(&id) {
+      Some(VarStats { writes: 0, params: Some(params), .. }) => (Arity::of_params(params), Rc::clone(params)),
+      Some(_) => return,
+      None => match globals.get(id.as_str()).map(|var| var.value_cloned()) {
+        Some(RantValue::Function(func)) => (Arity::of_function(&func), Rc::clone(&func.params)),
+        _ => return,
+      },
+    };
+
+    let vararg_index = params.iter().position(|param| param.varity.is_variadic());
+    let mut named_arg_spans = named_arg_spans.iter();
+    let mut bound_names = Vec::with_capacity(args.len());
+    for arg in args {
+      let name = arg.name.as_ref().map(|name| (name, named_arg_spans.next().unwrap_or(span)));
+      let bound_name = match name {
+        Some((name, name_span)) if params.iter().any(|param| &param.name == name) => Some((name, name_span)),
+        Some((name, name_span)) => {
+          self.report_warning(Problem::UnknownNamedArgument(id.to_string(), name.to_string()), name_span);
+          None
+        },
+        None => None,
+      };
+      bound_names.push(bound_name);
+    }
+
+    let is_spread = args.iter().any(|arg| matches!(arg.spread_mode, ArgumentSpreadMode::Parametric));
+    let is_count_accepted = is_spread || arity.accepts(args.len());
+    if !is_count_accepted {
+      self.report_warning(Problem::ArgumentCountMismatch(id.to_string(), arity.describe(), args.len()), span);
+    }
+
+    // Spread arguments can only add to the number of positional arguments
+    let min_positional_count = args.iter()
+      .take_while(|arg| arg.name.is_none())
+      .filter(|arg| !matches!(arg.spread_mode, ArgumentSpreadMode::Parametric))
+      .count();
+    let mut named_indices = vec![];
+    let mut has_named_arg = false;
+    for ((arg, bound_name), arg_span) in args.iter().zip(bound_names.iter()).zip(arg_spans.iter()) {
+      if arg.name.is_none() {
+        // Named arguments must come last
+        if has_named_arg {
+          self.report_error(Problem::PositionalArgumentAfterNamed, arg_span);
+        }
+        continue
+      }
+      has_named_arg = true;
+      let (name, name_span) = match bound_name {
+        Some(bound_name) => *bound_name,
+        None => continue,
+      };
+      let index = params.iter().position(|param| &param.name == name).unwrap();
+      if named_indices.contains(&index) {
+        self.report_error(Problem::DuplicateNamedArgument(name.to_string()), name_span);
+      } else if params[index].varity.is_variadic() {
+        self.report_warning(Problem::VariadicNamedArgument(name.to_string()), name_span);
+      } else if index < min_positional_count {
+        self.report_warning(Problem::NamedArgumentAlreadyPassed(name.to_string()), name_span);
+      } else {
+        named_indices.push(index);
+      }
+    }
+
+    // Literal arguments must fit the type annotations of their parameters
+    let positional_args = args.iter().zip(arg_spans.iter()).enumerate()
+      .take_while(|(_, (arg, _))| arg.name.is_none() && !matches!(arg.spread_mode, ArgumentSpreadMode::Parametric));
+    for (index, (arg, arg_span)) in positional_args {
+      let param_index = vararg_index.map_or(index, |vararg_index| index.min(vararg_index));
+      if let (Some(param), ArgumentSpreadMode::NoSpread) = (params.get(param_index), &arg.spread_mode) {
+        self.check_literal_arg_type(param, arg, arg_span);
+      }
+    }
+    for ((arg, bound_name), arg_span) in args.iter().zip(bound_names.iter()).zip(arg_spans.iter()) {
+      if let Some(param) = bound_name.and_then(|(name, _)| params.iter().find(|param| &param.name == name)) {
+        self.check_literal_arg_type(param, arg, arg_span);
+      }
+    }
+
+    // Required parameters after the positional arguments must be passed by name
+    if !is_spread && is_count_accepted && has_named_arg {
+      for (index, param) in params.iter().enumerate().skip(min_positional_count) {
+        if param.is_required() && !named_indices.contains(&index) {
+          self.report_warning(Problem::MissingArgument(id.to_string(), param.name.to_string()), span);
+        }
+      }
+    }
+  }
+
+  /// Warns if a literal argument doesn't fit the type annotation of the parameter it's passed to.
+  #[inline]
+  fn check_literal_arg_type(&mut self, param: &Parameter, arg: &ArgumentExpr, span: &Range<usize>) {
+    self.check_literal_type(param, &arg.expr, span);
+  }
+
+  /// Warns if a literal argument or default value doesn't fit the type annotation of its parameter.
+  fn check_literal_type(&mut self, param: &Parameter, value: &Sequence, span: &Range<usize>) {
+    let (type_annotation, found) = match (&param.type_annotation, literal_type(value)) {
+      (Some(type_annotation), Some(found)) => (type_annotation, found),
+      _ => return,
+    };
+
+    if !type_annotation.accepts(found) {
+      self.report_warning(Problem::ArgumentTypeMismatch(param.name.to_string(), type_annotation.to_string(), found.name().to_owned()), span);
+    }
+  }
+
+  /// Records a `[require]` call if its first argument is static text.
+  ///
+  /// When checking for undefined variables, also defines the variable the module is imported into.
+  fn track_require(&mut self, func_path: &AccessPath, args: &[ArgumentExpr], span: Range<usize>) {
+    if (self.symbols.is_none() && self.globals.is_none()) || func_path.static_variable() != Some("require") {
+      return
+    }
+
+    self.requires_modules = true;
+
+    let mut path = String::new();
+    if let Some(arg) = args.first() {
+      for rst in arg.expr.iter() {
+        match rst.as_ref() {
+          Rst::Fragment(text) | Rst::Whitespace(text) => path.push_str(text),
+          Rst::DebugCursor(_) => {},
+          _ => return,
+        }
+      }
+    }
+
+    let path = path.trim();
+
+    // Module name is taken from the file name, the same way as the runtime does it
+    if self.globals.is_some() {
+      let module_name = Path::new(path).with_extension("");
+      if let Some(module_name) = module_name.file_name().and_then(|name| name.to_str()).filter(|name| is_valid_ident(name)) {
+        self.track_variable(&Identifier::new(InternalString::from(module_name)), &AccessPathKind::Local, false, VarRole::Module, &span);
+      }
+    }
+
+    if let Some(symbols) = self.symbols.as_mut() {
+      symbols.add_module(ModuleReference {
+        path: path.to_owned(),
+        span,
+      });
+    }
+  }
+
+  /// Reports the variable accesses that were never resolved to a definition or a known global.
+  fn report_undefined_accesses(&mut self) {
+    let globals = match self.globals {
+      Some(globals) => globals,
+      None => return,
+    };
+
+    let undefined_accesses = std::mem::take(&mut self.undefined_accesses);
+    if self.requires_modules {
+      return
+    }
+    for access in undefined_accesses {
+      if !globals.contains_key(access.name.as_str()) {
+        self.report_warning(Problem::UndefinedVariable(access.name.to_string()), &access.span);
+      }
+    }
+  }
+
   #[inline]
   fn analyze_top_vars(&mut self) {
     let mut unused_vars: Vec<(String, VarRole, Range<usize>)> = vec![];
@@ -2047,6 +3598,20 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
     }
   }
     
+  /// Reports an error for each variable and function defined in the top scope.
+  ///
+  /// Used for embedded items in interpolated strings, whose scope ends before anything else could use the definitions.
+  fn reject_top_definitions(&mut self) {
+    let mut defs: Vec<(String, Range<usize>)> = self.var_stack.iter_top()
+      .filter(|(_, tracker)| matches!(tracker.role, VarRole::Normal | VarRole::Function))
+      .map(|(id, tracker)| (id.to_string(), tracker.def_span.clone()))
+      .collect();
+    defs.sort_by_key(|(_, span)| span.start);
+    for (name, span) in defs {
+      self.report_error(Problem::DefinitionInInterpolatedString(name), &span);
+    }
+  }
+
   /// Parses one or more accessors (getter/setter/definition).
   #[inline(always)]
   fn parse_accessor(&mut self) -> ParseResult<Vec<Rst>> {
@@ -2089,8 +3654,70 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
         // Check for accessor modifiers
         let access_kind = self.parse_access_path_kind();
         self.reader.skip_ws();
+
+        // Destructuring definition
+        if let Some((pattern_token, _)) = self.reader.take_where(|t| matches!(t, Some((LeftParen | At, _)))) {
+          let mut bindings = vec![];
+          let pattern = match self.parse_destructure_pattern(pattern_token, &mut bindings) {
+            Ok(pattern) => pattern,
+            Err(()) if self.reader.peek().is_none() => return Err(()),
+            Err(()) => {
+              self.synchronize(RightAngle);
+              break 'read
+            }
+          };
+
+          self.reader.skip_ws();
+          if !self.reader.eat_where(|t| matches!(t, Some((Equals, _)))) {
+            self.report_error(Problem::ExpectedToken("=".to_owned()), &self.reader.last_token_span());
+            if self.reader.peek().is_none() {
+              return Err(())
+            }
+            self.synchronize(RightAngle);
+            break 'read
+          }
+
+          self.reader.skip_ws();
+          let ParsedSequence {
+            sequence: setter_expr,
+            end_type: setter_end_type,
+            ..
+          } = self.parse_sequence(SequenceParseMode::VariableAssignment)?;
+
+          for (id, span) in &bindings {
+            self.track_variable(id, &access_kind, is_const_def, VarRole::Normal, span);
+          }
+
+          // Store the value in a hidden variable and define each binding from a getter into it
+          let source = Identifier::from(DESTRUCTURE_VALUE_NAME);
+          add_accessor!(Rst::DefVar(source.clone(), AccessPathKind::Local, Some(Rc::new(setter_expr))));
+          self.lower_destructure(&pattern, &source, &mut vec![], access_kind, is_const_def, &mut accessors);
+
+          match setter_end_type {
+            SequenceEndType::VariableAssignDelim => {
+              continue 'read
+            },
+            SequenceEndType::VariableAccessEnd => {
+              break 'read
+            },
+            SequenceEndType::ProgramEnd => {
+              self.report_error(Problem::UnclosedVariableAccess, &self.reader.last_token_span());
+              return Err(())
+            },
+            _ => unreachable!()
+          }
+        }
+
         // Read name of variable we're defining
-        let var_name = self.parse_ident()?;
+        let var_name = match self.parse_ident() {
+          Ok(var_name) => var_name,
+          Err(()) if self.reader.peek().is_none() => return Err(()),
+          // Skip the rest of the accessor if the name is invalid
+          Err(()) => {
+            self.synchronize(RightAngle);
+            break 'read
+          }
+        };
 
         let def_span = access_start_span.start .. self.reader.last_token_span().end;
         
@@ -2150,10 +3777,11 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 _ => unreachable!()
               }
             },
-            // Ran into something we don't support
+            // Skip the rest of the accessor if we ran into something we don't support
             _ => {
               self.unexpected_last_token_error();
-              return Err(())
+              self.synchronize(RightAngle);
+              break 'read
             }
           }
         } else {
@@ -2268,10 +3896,12 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 _ => unreachable!()
               }
             },
-            // Anything else is an error
+            // Skip the rest of the accessor on anything else
             _ => {
               self.unexpected_last_token_error();
-              return Err(())
+              self.track_variable_access(&var_path, false, false, &var_path_span);
+              self.synchronize(RightAngle);
+              break 'read
             }
           }
         } else {
@@ -1,11 +1,13 @@
 use logos::*;
-use super::lexer::RantToken;
+use super::lexer::{AllowDirective, RantToken};
 use std::ops::Range;
 use crate::InternalString;
 
 pub struct RantTokenReader<'source> {
   lexer: Lexer<'source, RantToken>,
   peeked: Option<(RantToken, Range<usize>)>,
+  /// The span of the most recently consumed token.
+  consumed_span: Range<usize>,
 }
 
 impl<'source> RantTokenReader<'source> {
@@ -13,12 +15,30 @@ impl<'source> RantTokenReader<'source> {
     Self {
       lexer: RantToken::lexer(src),
       peeked: None,
+      consumed_span: 0..0,
+    }
+  }
+
+  /// Creates a reader for the tokens in a span of `src`.
+  ///
+  /// Token spans are still relative to the start of `src`.
+  pub fn with_bounds(src: &'source str, span: Range<usize>) -> Self {
+    let mut lexer = RantToken::lexer(&src[..span.end]);
+    lexer.bump(span.start);
+    Self {
+      lexer,
+      peeked: None,
+      consumed_span: span.start .. span.start,
     }
   }
   
   pub fn next(&mut self) -> Option<(RantToken, Range<usize>)> {
     // Consume any peeked token before iterating lexer again
-    self.peeked.take().or_else(|| self.lexer.next().map(|token| (token, self.lexer.span())))
+    let token = self.peeked.take().or_else(|| self.lexer.next().map(|token| (token, self.lexer.span())));
+    if let Some((_, span)) = &token {
+      self.consumed_span = span.clone();
+    }
+    token
   }
   
   pub fn skip_one(&mut self) {
@@ -43,6 +63,11 @@ impl<'source> RantTokenReader<'source> {
     }
   }
   
+  /// Gets the `#@allow(...)` directives that the lexer has passed so far.
+  pub fn allow_directives(&self) -> &[AllowDirective] {
+    &self.lexer.extras
+  }
+
   /// Gets the last token string that was read.
   pub fn last_token_string(&self) -> InternalString {
     InternalString::from(self.lexer.slice())
@@ -84,8 +109,37 @@ impl<'source> RantTokenReader<'source> {
     }
     
     // If no previous peek was performed for the current iteration, iterate, store, and return reference to token
-    let token = self.next();
+    let token = self.lexer.next().map(|token| (token, self.lexer.span()));
     self.peeked = token;
     self.peeked.as_ref()
   }
+
+  /// Gets the first non-whitespace token after the next token without consuming either.
+  pub fn peek_solid_after_next(&mut self) -> Option<RantToken> {
+    self.peek();
+    let mut lexer = self.lexer.clone();
+    loop {
+      match lexer.next() {
+        Some(RantToken::Whitespace) => continue,
+        token => return token,
+      }
+    }
+  }
+
+  /// Gets the span of the most recently consumed token, ignoring any token that was only peeked.
+  pub fn last_consumed_span(&self) -> Range<usize> {
+    self.consumed_span.clone()
+  }
+
+  /// Determines whether a line break separates the most recently consumed token from the next token.
+  pub fn is_next_on_new_line(&mut self) -> bool {
+    let prev_end = self.consumed_span.end;
+    let source = self.lexer.source();
+    match self.peek() {
+      // Printable whitespace can contain line breaks too
+      Some((RantToken::Whitespace, span)) => source[prev_end..span.end].contains('\n'),
+      Some((_, span)) => source[prev_end..span.start].contains('\n'),
+      None => false,
+    }
+  }
 }
\ No newline at end of file
@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+/// Describes what kind of value a symbol was defined as.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SymbolKind {
+  /// A variable defined with `<$name>`.
+  Variable,
+  /// A constant defined with `<%name>`.
+  Constant,
+  /// A function defined with `[$name]` or `[%name]`.
+  Function,
+  /// A function parameter.
+  Parameter,
+}
+
+/// A variable, constant, function, or parameter defined in the source.
+#[derive(Debug, Clone)]
+pub struct SymbolDefinition {
+  /// The name of the symbol.
+  pub name: String,
+  /// The kind of symbol.
+  pub kind: SymbolKind,
+  /// The span of the symbol's name in the source.
+  pub name_span: Range<usize>,
+  /// The span of the whole definition in the source.
+  pub span: Range<usize>,
+}
+
+/// An access to a variable in the source.
+#[derive(Debug, Clone)]
+pub struct SymbolReference {
+  /// The name of the accessed variable.
+  pub name: String,
+  /// The span of the variable name in the source.
+  pub span: Range<usize>,
+  /// The index of the definition the access resolves to, if it was defined in the same source.
+  pub definition: Option<usize>,
+}
+
+/// A module loaded by a `[require]` call with a static path argument.
+#[derive(Debug, Clone)]
+pub struct ModuleReference {
+  /// The module path passed to `[require]`.
+  pub path: String,
+  /// The span of the `[require]` call in the source.
+  pub span: Range<usize>,
+}
+
+/// Definitions and references collected from a single source by `compiler::analyze()`.
+///
+/// All spans are byte offsets into the analyzed source.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+  definitions: Vec<SymbolDefinition>,
+  references: Vec<SymbolReference>,
+  modules: Vec<ModuleReference>,
+}
+
+impl SymbolTable {
+  /// Gets all definitions in source order.
+  #[inline]
+  pub fn definitions(&self) -> &[SymbolDefinition] {
+    &self.definitions
+  }
+
+  /// Gets all variable accesses.
+  #[inline]
+  pub fn references(&self) -> &[SymbolReference] {
+    &self.references
+  }
+
+  /// Gets all statically resolvable `[require]` calls.
+  #[inline]
+  pub fn modules(&self) -> &[ModuleReference] {
+    &self.modules
+  }
+
+  /// Gets the definition a reference resolves to.
+  #[inline]
+  pub fn definition_of(&self, reference: &SymbolReference) -> Option<&SymbolDefinition> {
+    reference.definition.and_then(|index| self.definitions.get(index))
+  }
+
+  /// Gets the innermost reference whose span contains the specified offset.
+  pub fn reference_at(&self, offset: usize) -> Option<&SymbolReference> {
+    innermost(self.references.iter(), offset, |r| &r.span)
+  }
+
+  /// Gets the innermost definition whose name span contains the specified offset.
+  pub fn definition_at(&self, offset: usize) -> Option<&SymbolDefinition> {
+    innermost(self.definitions.iter(), offset, |d| &d.name_span)
+  }
+
+  /// Gets the innermost `[require]` call whose span contains the specified offset.
+  pub fn module_at(&self, offset: usize) -> Option<&ModuleReference> {
+    innermost(self.modules.iter(), offset, |m| &m.span)
+  }
+
+  pub(crate) fn add_definition(&mut self, def: SymbolDefinition) -> usize {
+    self.definitions.push(def);
+    self.definitions.len() - 1
+  }
+
+  pub(crate) fn add_reference(&mut self, reference: SymbolReference) {
+    self.references.push(reference);
+  }
+
+  pub(crate) fn add_module(&mut self, module: ModuleReference) {
+    self.modules.push(module);
+  }
+
+  /// Sorts the definitions by position, remapping reference indices to match.
+  pub(crate) fn sort(&mut self) {
+    let mut order: Vec<usize> = (0..self.definitions.len()).collect();
+    order.sort_by_key(|i| self.definitions[*i].span.start);
+    let mut new_indices = vec![0; order.len()];
+    for (new_index, old_index) in order.iter().enumerate() {
+      new_indices[*old_index] = new_index;
+    }
+
+    let mut definitions: Vec<Option<SymbolDefinition>> = self.definitions.drain(..).map(Some).collect();
+    self.definitions = order.iter().filter_map(|i| definitions[*i].take()).collect();
+    for reference in self.references.iter_mut() {
+      reference.definition = reference.definition.map(|i| new_indices[i]);
+    }
+    self.references.sort_by_key(|r| r.span.start);
+    self.modules.sort_by_key(|m| m.span.start);
+  }
+}
+
+fn innermost<'a, T, I, F>(items: I, offset: usize, span_of: F) -> Option<&'a T>
+where
+  I: Iterator<Item = &'a T>,
+  F: Fn(&T) -> &Range<usize>,
+{
+  items
+    .filter(|item| {
+      let span = span_of(item);
+      span.start <= offset && offset <= span.end
+    })
+    .min_by_key(|item| {
+      let span = span_of(item);
+      span.end - span.start
+    })
+}
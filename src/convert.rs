@@ -10,7 +10,9 @@ use crate::runtime::*;
 use crate::{lang::{Varity, Parameter, Identifier}, stdlib::RantStdResult};
 use cast::*;
 use cast::Error as CastError;
-use std::{rc::Rc, ops::{DerefMut, Deref}, cell::RefCell, convert::TryInto};
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive};
+use std::{rc::Rc, ops::{DerefMut, Deref}, cell::RefCell, collections::HashSet, convert::TryInto, hash::Hash};
 
 /// Enables conversion from a native type to a `RantValue`.
 pub trait IntoRant {
@@ -110,6 +112,11 @@ macro_rules! rant_int_conversions {
               ))
             }
           },
+          RantValue::BigInt(ref n) => Err(rant_cast_error(
+            val.type_name(),
+            stringify!($int_type),
+            if n.is_negative() { CastError::Underflow } else { CastError::Overflow }
+          )),
           RantValue::Float(f) => {
             let result: Result<$int_type, CastError> = cast_float_to_int!($int_type, f);
             match result {
@@ -167,6 +174,30 @@ impl FromRant for RantEmpty {
   }
 }
 
+impl IntoRant for BigInt {
+  fn into_rant(self) -> ValueResult<RantValue> {
+    Ok(RantValue::from_big_int(self))
+  }
+}
+
+impl FromRant for BigInt {
+  fn from_rant(val: RantValue) -> ValueResult<Self> {
+    match val {
+      RantValue::Int(i) => Ok(BigInt::from(i)),
+      RantValue::BigInt(i) => Ok(BigInt::clone(&i)),
+      _ => Err(ValueError::InvalidConversion {
+        from: val.type_name(),
+        to: "big int",
+        message: None,
+      })
+    }
+  }
+
+  fn is_rant_optional() -> bool {
+    false
+  }
+}
+
 impl FromRant for bool {
   fn from_rant(val: RantValue) -> Result<Self, ValueError> {
     match val {
@@ -195,6 +226,7 @@ impl FromRant for f32 {
   fn from_rant(val: RantValue) -> ValueResult<Self> {
     match val {
       RantValue::Int(i) => Ok(f32(i)),
+      RantValue::BigInt(i) => Ok(i.to_f32().unwrap_or(f32::NAN)),
       RantValue::Float(f) => match f32(f) {
         Ok(f) => Ok(f),
         Err(err) => Err(rant_cast_error(val.type_name(), "f32", err))
@@ -216,6 +248,7 @@ impl FromRant for f64 {
   fn from_rant(val: RantValue) -> ValueResult<Self> {
     match val {
       RantValue::Int(i) => Ok(f64(i)),
+      RantValue::BigInt(i) => Ok(i.to_f64().unwrap_or(f64::NAN)),
       RantValue::Float(f) => Ok(f),
       _ => Err(ValueError::InvalidConversion {
         from: val.type_name(),
@@ -261,6 +294,12 @@ impl IntoRant for RantList {
   }
 }
 
+impl IntoRant for RantSet {
+  fn into_rant(self) -> Result<RantValue, ValueError> {
+    Ok(RantValue::Set(RantSetRef::new(RefCell::new(self))))
+  }
+}
+
 impl IntoRant for InternalString {
   fn into_rant(self) -> Result<RantValue, ValueError> {
     RantString::from(self.as_str()).into_rant()
@@ -332,6 +371,19 @@ impl FromRant for RantMapRef {
   }
 }
 
+impl FromRant for RantSetRef {
+  fn from_rant(val: RantValue) -> ValueResult<Self> {
+    if let RantValue::Set(set_ref) = val {
+      Ok(set_ref)
+    } else {
+      Err(ValueError::InvalidConversion { from: val.type_name(), to: "set", message: None })
+    }
+  }
+  fn is_rant_optional() -> bool {
+    false
+  }
+}
+
 impl FromRant for RantFunctionRef {
   fn from_rant(val: RantValue) -> Result<Self, ValueError> {
     if let RantValue::Function(func_ref) = val {
@@ -370,10 +422,35 @@ impl<T: FromRant> FromRant for Vec<T> {
   fn from_rant(val: RantValue) -> ValueResult<Self> {
     match val {
       RantValue::List(vec) => Ok(vec.borrow().iter().cloned().map(T::from_rant).collect::<ValueResult<Vec<T>>>()?),
+      RantValue::Set(set) => Ok(set.borrow().iter().cloned().map(T::from_rant).collect::<ValueResult<Vec<T>>>()?),
       other => Err(ValueError::InvalidConversion {
         from: other.type_name(),
         to: stringify!(Vec<T>),
-        message: Some("only lists can be turned into vectors".to_owned())
+        message: Some("only lists and sets can be turned into vectors".to_owned())
+      })
+    }
+  }
+
+  fn is_rant_optional() -> bool {
+    false
+  }
+}
+
+impl<T: IntoRant> IntoRant for HashSet<T> {
+  fn into_rant(self) -> Result<RantValue, ValueError> {
+    let set = RantSet::from_values(self.into_iter().map(T::into_rant).collect::<ValueResult<Vec<_>>>()?)?;
+    Ok(RantValue::Set(Rc::new(RefCell::new(set))))
+  }
+}
+
+impl<T: FromRant + Eq + Hash> FromRant for HashSet<T> {
+  fn from_rant(val: RantValue) -> ValueResult<Self> {
+    match val {
+      RantValue::Set(set) => Ok(set.borrow().iter().cloned().map(T::from_rant).collect::<ValueResult<HashSet<T>>>()?),
+      other => Err(ValueError::InvalidConversion {
+        from: other.type_name(),
+        to: stringify!(HashSet<T>),
+        message: Some("only sets can be turned into hash sets".to_owned())
       })
     }
   }
@@ -422,6 +499,8 @@ impl<T: FromRant> FromRantArgs for T {
       name: Identifier::new(InternalString::from("arg0")),
       varity,
       default_value_expr: None,
+      pattern: None,
+      type_annotation: None,
     };
 
     vec![param]
@@ -494,6 +573,8 @@ macro_rules! impl_from_rant_args {
           name: Identifier::new(InternalString::from(format!("arg{}", inc(&mut i)))),
           varity: as_varity::<$generic_types>(),
           default_value_expr: None,
+          pattern: None,
+          type_annotation: None,
         },)*]
       }
     }
@@ -517,11 +598,15 @@ macro_rules! impl_from_rant_args {
           name: Identifier::new(InternalString::from(format!("arg{}", inc(&mut i)))),
           varity: as_varity::<$generic_types>(),
           default_value_expr: None,
+          pattern: None,
+          type_annotation: None,
         },)*
         Parameter {
           name: Identifier::new(InternalString::from(format!("arg{}", inc(&mut i)))),
           varity: Varity::VariadicStar,
           default_value_expr: None,
+          pattern: None,
+          type_annotation: None,
         }]
       }
     }
@@ -545,11 +630,15 @@ macro_rules! impl_from_rant_args {
           name: Identifier::new(InternalString::from(format!("arg{}", inc(&mut i)))),
           varity: as_varity::<$generic_types>(),
           default_value_expr: None,
+          pattern: None,
+          type_annotation: None,
         },)*
         Parameter {
           name: Identifier::new(InternalString::from(format!("arg{}", inc(&mut i)))),
           varity: Varity::VariadicPlus,
           default_value_expr: None,
+          pattern: None,
+          type_annotation: None,
         }]
       }
     }
@@ -594,6 +683,7 @@ impl<Params: FromRantArgs, Function: Fn(&mut VM, Params) -> RantStdResult> AsRan
       .unwrap_or_else(|| params.len()),
       params,
       flavor: None,
+      return_type: None,
     }
   }
 }
\ No newline at end of file
@@ -61,6 +61,7 @@ impl<T> IntoRuntimeResult<T> for DataSourceResult<T> {
       error_type: RuntimeErrorType::DataSourceError(err),
       description: None,
       stack_trace: None,
+      stack_frames: None,
     })
   }
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 use std::cmp::Ordering;
 
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, ToPrimitive, Zero};
+
 use crate::{FromRant, InternalString, IntoRant, RantValue, ValueError};
 
 const DEFAULT_SIGN_POSITIVE: &str = "+";
@@ -405,6 +408,21 @@ impl NumberFormat {
     }
   }
 
+  /// Formats an integer that doesn't fit in an `i64`.
+  ///
+  /// Roman numerals aren't supported for numbers this large, so they fall back to decimal.
+  pub fn format_big_integer(&self, n: &BigInt) -> InternalString {
+    use NumeralSystem::*;
+    match self.system {
+      WestArabic | EastArabic | Persian | Roman => self.format_decimal_big_integer(n),
+      Babylonian => self.format_babylonian_big_integer(n),
+      Hex => self.format_bitwise_big_integer(n, 16),
+      Octal => self.format_bitwise_big_integer(n, 8),
+      Binary => self.format_bitwise_big_integer(n, 2),
+      Alpha => self.format_alpha_big_integer(n),
+    }
+  }
+
   #[inline]
   fn get_float_sign(&self, n: f64) -> &'static str {
     if n.abs() < f64::EPSILON {
@@ -437,6 +455,15 @@ impl NumberFormat {
     }
   }
 
+  #[inline]
+  fn get_big_integer_sign(&self, n: &BigInt) -> &'static str {
+    self.get_integer_sign(match n.sign() {
+      Sign::Minus => -1,
+      Sign::NoSign => 0,
+      Sign::Plus => 1,
+    })
+  }
+
   #[inline]
   fn get_needed_padding<N: Into<usize>>(&self, num_significant_digits: N) -> usize {
     (self.padding as usize).saturating_sub(num_significant_digits.into())
@@ -780,4 +807,129 @@ impl NumberFormat {
 
     buf
   }
-}
\ No newline at end of file
+
+  fn format_alpha_big_integer(&self, input: &BigInt) -> InternalString {
+    let mut buf = InternalString::new();
+    let mut n = input.magnitude().clone();
+
+    if n.is_zero() { return " ".into() }
+
+    let mut digit_stack: Vec<char> = vec![];
+
+    buf.push_str(self.get_big_integer_sign(input));
+
+    let radix = BigUint::from(26u8);
+    while !n.is_zero() {
+      n -= BigUint::one();
+      let alpha_index = (&n % &radix).to_u8().unwrap();
+      digit_stack.push((b'a' + alpha_index) as char);
+      n /= &radix;
+    }
+
+    for d in digit_stack.drain(..).rev() {
+      buf.push(d);
+    }
+
+    if self.uppercase {
+      buf.make_ascii_uppercase();
+    }
+
+    buf
+  }
+
+  fn format_bitwise_big_integer(&self, input: &BigInt, radix: usize) -> InternalString {
+    let sign_padding_index = if input.sign() == Sign::Minus { radix - 1 } else { 0 };
+    let n = BigUint::from_bytes_be(&match self.endianness {
+      Endianness::Big => input.to_signed_bytes_be(),
+      Endianness::Little => input.to_signed_bytes_le(),
+    });
+
+    let mut digits = n.to_str_radix(radix as u32);
+    if self.uppercase {
+      digits.make_ascii_uppercase();
+    }
+
+    let mut buf = InternalString::new();
+    let needed_padding = self.get_needed_padding(digits.len());
+
+    // Add prefix
+    if self.alternate {
+      buf.push_str(self.get_radix_prefix(radix));
+    }
+
+    // Add big-endian padding
+    if self.endianness == Endianness::Big {
+      for _ in 0..needed_padding {
+        buf.push(DIGITS_DEFAULT[sign_padding_index]);
+      }
+    }
+
+    // Add digits
+    buf.push_str(&digits);
+
+    // Add little-endian padding
+    if self.endianness == Endianness::Little {
+      for _ in 0..needed_padding {
+        buf.push(DIGITS_DEFAULT[sign_padding_index]);
+      }
+    }
+
+    buf
+  }
+
+  fn format_babylonian_big_integer(&self, input: &BigInt) -> InternalString {
+    let mut buf = InternalString::new();
+
+    buf.push_str(self.get_big_integer_sign(input));
+
+    for (power, bab_digit) in input.magnitude().to_radix_be(60).into_iter().enumerate() {
+      // Add spaces between digits
+      if power > 0 {
+        buf.push(' ');
+      }
+
+      let bab_digit = bab_digit as usize;
+      if bab_digit > 0 {
+        let ones_index = bab_digit % 10;
+        let tens_index = bab_digit / 10;
+        buf.push_str(if self.alternate { BABYLONIAN_TENS_ALT[tens_index] } else { BABYLONIAN_TENS[tens_index] });
+        buf.push_str(BABYLONIAN_ONES[ones_index]);
+      } else {
+        buf.push_str(BABYLONIAN_ZERO);
+      }
+    }
+
+    buf
+  }
+
+  fn format_decimal_big_integer(&self, input: &BigInt) -> InternalString {
+    let mut buf = InternalString::new();
+
+    // Add sign
+    buf.push_str(self.get_big_integer_sign(input));
+
+    let raw_digits = input.magnitude().to_str_radix(10);
+    let num_digits = raw_digits.len();
+
+    // Add padding
+    for _ in 0..self.get_needed_padding(num_digits) {
+      buf.push(self.system.get_decimal_digit(0).unwrap());
+    }
+
+    // Add digits
+    for (i, d) in raw_digits.chars().enumerate() {
+      // Roman numerals fall back to Western Arabic digits
+      buf.push(self.system.transliterate_decimal_digit(d).unwrap_or(d));
+
+      // Add group separator
+      let place = num_digits - i - 1;
+      if let Some(group_sep) = self.group_sep.as_deref() {
+        if place > 0 && place.is_multiple_of(3) {
+          buf.push_str(group_sep);
+        }
+      }
+    }
+
+    buf
+  }
+}
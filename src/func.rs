@@ -18,6 +18,8 @@ pub struct RantFunction {
   pub(crate) captured_vars: Vec<(Identifier, RantVar)>,
   /// The body of the function.
   pub(crate) body: RantFunctionInterface,
+  /// The type the function's return value is checked against, if annotated.
+  pub(crate) return_type: Option<Rc<TypeAnnotation>>,
   /// Assigns a custom flavor to the stack frame created by the function call.
   /// If not set, the default function call flavor will be used.
   pub(crate) flavor: Option<StackFrameFlavor>,
@@ -35,6 +37,43 @@ impl RantFunction {
   pub fn is_native(&self) -> bool {
     matches!(self.body, RantFunctionInterface::Foreign(_))
   }
+
+  /// Renames the function's parameters in order so that they can be passed as named arguments.
+  ///
+  /// Only intended for newly created native functions, whose parameters are otherwise named `arg0`, `arg1`, and so on.
+  pub(crate) fn with_param_names(mut self, names: &[&str]) -> Self {
+    debug_assert_eq!(names.len(), self.params.len(), "parameter name count doesn't match function signature");
+    if let Some(params) = Rc::get_mut(&mut self.params) {
+      for (param, name) in params.iter_mut().zip(names) {
+        param.name = Identifier::new(InternalString::from(*name));
+      }
+    }
+    self
+  }
+
+  /// Formats the function's signature in definition syntax under the specified name, e.g. `[$add: lhs; rhs]`.
+  pub fn signature(&self, name: &str) -> String {
+    let params = self.params
+      .iter()
+      .map(|param| format!("{}{}{}",
+        param.pattern.as_ref().map_or_else(|| param.name.to_string(), |pattern| pattern.to_string()),
+        param.type_annotation.as_ref().map_or_else(String::new, |t| format!(" ({})", t)),
+        match param.varity {
+          Varity::Required => "",
+          Varity::Optional => "?",
+          Varity::VariadicStar => "*",
+          Varity::VariadicPlus => "+",
+        }
+      ))
+      .collect::<Vec<_>>();
+
+    let return_type = self.return_type.as_ref().map_or_else(String::new, |t| format!(" ({})", t));
+    if params.is_empty() {
+      format!("[${}{}]", name, return_type)
+    } else {
+      format!("[${}{}: {}]", name, return_type, params.join("; "))
+    }
+  }
 }
 
 /// Defines endpoint variants for Rant functions.
@@ -0,0 +1,305 @@
+use std::{cell::RefCell, collections::VecDeque, fmt::Debug, rc::Rc};
+use crate::*;
+use crate::lang::PrintFlag;
+use crate::runtime::*;
+
+/// Type alias for `Rc<RefCell<RantIterator>>`
+pub type RantIteratorRef = Rc<RefCell<RantIterator>>;
+
+/// Callback that receives the next item of an iterator, or `None` if the iterator is exhausted.
+pub(crate) type IterNextCallback = Box<dyn FnOnce(&mut VM, Option<RantValue>) -> RuntimeResult<()>>;
+
+/// Callback that receives the items read from an iterator.
+pub(crate) type IterCollectCallback = Box<dyn FnOnce(&mut VM, RantList) -> RuntimeResult<()>>;
+
+/// Callback that receives the return value of a function called by an iterator.
+type CallCallback = Box<dyn FnOnce(&mut VM, RantValue) -> RuntimeResult<()>>;
+
+/// Represents Rant's `iterator` type, which lazily produces a sequence of values.
+///
+/// Iterators are consumed as they are read; adapters such as `[map]` and `[take]` read from the iterator they wrap.
+pub struct RantIterator {
+  source: IterSource,
+  is_done: bool,
+}
+
+/// Where the items of an iterator come from.
+enum IterSource {
+  /// Items of a list.
+  List { list: RantListRef, index: usize },
+  /// Values of a range.
+  Range { range: RantRange, index: usize },
+  /// Return values of a function, until it returns `empty`.
+  Func { func: RantFunctionRef },
+  /// Items of another iterator passed through a function.
+  Map { source: RantIteratorRef, func: RantFunctionRef },
+  /// Items of another iterator that satisfy a predicate.
+  Filter { source: RantIteratorRef, predicate: RantFunctionRef },
+  /// The first `remaining` items of another iterator.
+  Take { source: RantIteratorRef, remaining: usize },
+  /// Pairs of items from two iterators combined by a function.
+  Zip { source_a: RantIteratorRef, source_b: RantIteratorRef, func: RantFunctionRef },
+  /// Items of several iterators, one after another.
+  Chain { sources: VecDeque<RantIteratorRef> },
+  /// Values yielded by a generator function.
+  Generator { state: GeneratorStateRef },
+}
+
+impl RantIterator {
+  #[inline]
+  fn new(source: IterSource) -> Self {
+    Self {
+      source,
+      is_done: false,
+    }
+  }
+
+  /// Creates an iterator over the items of a list.
+  pub fn from_list(list: RantListRef) -> Self {
+    Self::new(IterSource::List { list, index: 0 })
+  }
+
+  /// Creates an iterator over the values of a range.
+  pub fn from_range(range: RantRange) -> Self {
+    Self::new(IterSource::Range { range, index: 0 })
+  }
+
+  /// Creates an iterator that calls a function for each item and ends when the function returns `empty`.
+  pub fn from_func(func: RantFunctionRef) -> Self {
+    Self::new(IterSource::Func { func })
+  }
+
+  /// Creates an iterator that passes the items of `source` through `func`.
+  pub fn map(source: RantIteratorRef, func: RantFunctionRef) -> Self {
+    Self::new(IterSource::Map { source, func })
+  }
+
+  /// Creates an iterator over the items of `source` that `predicate` returns `@true` for.
+  pub fn filter(source: RantIteratorRef, predicate: RantFunctionRef) -> Self {
+    Self::new(IterSource::Filter { source, predicate })
+  }
+
+  /// Creates an iterator over the first `count` items of `source`.
+  pub fn take(source: RantIteratorRef, count: usize) -> Self {
+    Self::new(IterSource::Take { source, remaining: count })
+  }
+
+  /// Creates an iterator that passes pairs of items from two iterators through `func`, ending when either one runs out.
+  pub fn zip(source_a: RantIteratorRef, source_b: RantIteratorRef, func: RantFunctionRef) -> Self {
+    Self::new(IterSource::Zip { source_a, source_b, func })
+  }
+
+  /// Creates an iterator over the items of each iterator in `sources`, in order.
+  pub fn chain(sources: Vec<RantIteratorRef>) -> Self {
+    Self::new(IterSource::Chain { sources: sources.into() })
+  }
+
+  /// Creates a generator, an iterator over the values yielded by `func` when called with `args`.
+  ///
+  /// The function doesn't run until the first item is read, and is suspended at each `@yield` until the next one is.
+  pub fn from_generator(func: RantFunctionRef, args: Vec<RantValue>) -> Self {
+    Self::new(IterSource::Generator { state: Rc::new(RefCell::new(GeneratorState::Ready { func, args })) })
+  }
+
+  /// Creates an iterator over a value.
+  ///
+  /// Lists, sets, ranges, strings (by grapheme), and map keys can be iterated over; iterators are returned as-is.
+  pub fn from_value(value: RantValue) -> Option<RantIteratorRef> {
+    let iter = match value {
+      RantValue::Iterator(iter) => return Some(iter),
+      RantValue::List(list) => Self::from_list(list),
+      RantValue::Set(set) => Self::from_list(Rc::new(RefCell::new(set.borrow().to_list()))),
+      RantValue::Range(range) => Self::from_range(range),
+      RantValue::String(s) => Self::from_list(Rc::new(RefCell::new(s.to_rant_list()))),
+      RantValue::Map(map) => Self::from_list(Rc::new(RefCell::new(map.borrow().raw_keys()))),
+      _ => return None,
+    };
+    Some(Rc::new(RefCell::new(iter)))
+  }
+
+  /// Returns true if the iterator has run out of items.
+  ///
+  /// An iterator is only known to be finished after an attempt to read past its last item,
+  /// except for generators, which are finished as soon as their function returns.
+  #[inline]
+  pub fn is_done(&self) -> bool {
+    self.is_done || matches!(&self.source, IterSource::Generator { state } if matches!(*state.borrow(), GeneratorState::Finished))
+  }
+
+  /// Returns true if the iterator is a generator.
+  #[inline]
+  pub fn is_generator(&self) -> bool {
+    matches!(self.source, IterSource::Generator { .. })
+  }
+
+  /// Reads the next item of an iterator and passes it to `then`.
+  ///
+  /// Items that depend on function calls are read by queueing intents on the current frame, so `then` might run after this returns.
+  pub(crate) fn next(iter: &RantIteratorRef, vm: &mut VM, then: IterNextCallback) -> RuntimeResult<()> {
+    if iter.borrow().is_done {
+      return then(vm, None)
+    }
+
+    // Remember when the iterator runs out so its source isn't read again
+    let iter_ref = Rc::clone(iter);
+    Self::read_source(iter, vm, Box::new(move |vm, item| {
+      if item.is_none() {
+        iter_ref.borrow_mut().is_done = true;
+      }
+      then(vm, item)
+    }))
+  }
+
+  /// Reads the next item from the source of an iterator.
+  fn read_source(iter: &RantIteratorRef, vm: &mut VM, then: IterNextCallback) -> RuntimeResult<()> {
+    let mut iter_mut = iter.borrow_mut();
+    match &mut iter_mut.source {
+      IterSource::List { list, index } => {
+        let item = list.borrow().get(*index).cloned();
+        *index += 1;
+        drop(iter_mut);
+        then(vm, item)
+      },
+      IterSource::Range { range, index } => {
+        let item = range.get(*index).map(RantValue::Int);
+        *index += 1;
+        drop(iter_mut);
+        then(vm, item)
+      },
+      IterSource::Func { func } => {
+        let func = Rc::clone(func);
+        drop(iter_mut);
+        call_then(vm, func, vec![], Box::new(move |vm, item| {
+          then(vm, (!item.is_empty()).then_some(item))
+        }))
+      },
+      IterSource::Map { source, func } => {
+        let (source, func) = (Rc::clone(source), Rc::clone(func));
+        drop(iter_mut);
+        Self::next(&source, vm, Box::new(move |vm, item| match item {
+          Some(item) => call_then(vm, func, vec![item], Box::new(move |vm, mapped| then(vm, Some(mapped)))),
+          None => then(vm, None),
+        }))
+      },
+      IterSource::Filter { source, predicate } => {
+        let (source, predicate) = (Rc::clone(source), Rc::clone(predicate));
+        drop(iter_mut);
+        let iter = Rc::clone(iter);
+        Self::next(&source, vm, Box::new(move |vm, item| match item {
+          Some(item) => call_then(vm, predicate, vec![item.clone()], Box::new(move |vm, passed| match passed {
+            RantValue::Boolean(true) => then(vm, Some(item)),
+            // Skip to the next item that passes
+            RantValue::Boolean(false) => Self::read_source(&iter, vm, then),
+            other => runtime_error!(RuntimeErrorType::TypeError, "filter callback expected to return 'bool' value, but returned '{}' instead", other.type_name()),
+          })),
+          None => then(vm, None),
+        }))
+      },
+      IterSource::Take { source, remaining } => {
+        if *remaining == 0 {
+          drop(iter_mut);
+          return then(vm, None)
+        }
+        *remaining -= 1;
+        let source = Rc::clone(source);
+        drop(iter_mut);
+        Self::next(&source, vm, then)
+      },
+      IterSource::Zip { source_a, source_b, func } => {
+        let (source_a, source_b, func) = (Rc::clone(source_a), Rc::clone(source_b), Rc::clone(func));
+        drop(iter_mut);
+        Self::next(&source_a, vm, Box::new(move |vm, item_a| match item_a {
+          Some(item_a) => Self::next(&source_b, vm, Box::new(move |vm, item_b| match item_b {
+            Some(item_b) => call_then(vm, func, vec![item_a, item_b], Box::new(move |vm, zipped| then(vm, Some(zipped)))),
+            None => then(vm, None),
+          })),
+          None => then(vm, None),
+        }))
+      },
+      IterSource::Chain { sources } => {
+        let source = match sources.front() {
+          Some(source) => Rc::clone(source),
+          None => {
+            drop(iter_mut);
+            return then(vm, None)
+          },
+        };
+        drop(iter_mut);
+        let iter = Rc::clone(iter);
+        Self::next(&source, vm, Box::new(move |vm, item| match item {
+          Some(item) => then(vm, Some(item)),
+          // Move on to the next source
+          None => {
+            if let IterSource::Chain { sources } = &mut iter.borrow_mut().source {
+              sources.pop_front();
+            }
+            Self::read_source(&iter, vm, then)
+          },
+        }))
+      },
+      IterSource::Generator { state } => {
+        let state = Rc::clone(state);
+        drop(iter_mut);
+        vm.resume_generator(state, then)
+      },
+    }
+  }
+
+  /// Reads the remaining items of an iterator into a list and passes it to `then`.
+  ///
+  /// Each item is read in its own intent, so long iterators don't grow the native call stack.
+  pub(crate) fn collect(iter: RantIteratorRef, vm: &mut VM, mut list: RantList, then: IterCollectCallback) -> RuntimeResult<()> {
+    let next_iter = Rc::clone(&iter);
+    Self::next(&next_iter, vm, Box::new(move |vm, item| match item {
+      Some(item) => {
+        list.push(item);
+        vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall {
+          function: Box::new(move |vm| Self::collect(iter, vm, list, then)),
+          interrupt: false,
+        });
+        Ok(())
+      },
+      None => then(vm, list),
+    }))
+  }
+}
+
+impl Debug for RantIterator {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let kind = match &self.source {
+      IterSource::List { .. } => "list",
+      IterSource::Range { .. } => "range",
+      IterSource::Func { .. } => "func",
+      IterSource::Map { .. } => "map",
+      IterSource::Filter { .. } => "filter",
+      IterSource::Take { .. } => "take",
+      IterSource::Zip { .. } => "zip",
+      IterSource::Chain { .. } => "chain",
+      IterSource::Generator { .. } => "generator",
+    };
+    write!(f, "[iterator({})]", kind)
+  }
+}
+
+/// Calls a function with the specified arguments and passes its return value to `then`.
+fn call_then(vm: &mut VM, func: RantFunctionRef, args: Vec<RantValue>, then: CallCallback) -> RuntimeResult<()> {
+  vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall {
+    function: Box::new(move |vm| {
+      let val = vm.pop_val()?;
+      then(vm, val)
+    }),
+    interrupt: false,
+  });
+
+  let argc = args.len();
+  vm.push_val(RantValue::Function(func))?;
+  for arg in args.into_iter().rev() {
+    vm.push_val(arg)?;
+  }
+  vm.cur_frame_mut().push_intent_front(Intent::Call {
+    argc,
+    flag: PrintFlag::None,
+    override_print: true,
+  });
+  Ok(())
+}
@@ -0,0 +1,326 @@
+//! Text representations of Rant syntax trees for inspection by external tools.
+
+use super::*;
+use std::fmt::Write;
+
+/// Output formats for syntax tree dumps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AstDumpFormat {
+  /// An indented S-expression, e.g. `(call (path "upper") ...)`.
+  SExpr,
+  /// A JSON object per node, with `kind` and `children` fields alongside any node attributes.
+  Json,
+}
+
+/// Generates a dump of a syntax tree in the specified format.
+///
+/// Debug cursors are omitted from the output.
+pub fn dump_ast(seq: &Sequence, format: AstDumpFormat) -> String {
+  let mut dumper = AstDumper {
+    stack: vec![DumpNode::new("program")],
+  };
+  walk_sequence(&mut dumper, seq);
+  let root = dumper.stack.pop().unwrap();
+
+  let mut output = String::new();
+  match format {
+    AstDumpFormat::SExpr => root.write_sexpr(&mut output, 0),
+    AstDumpFormat::Json => root.write_json(&mut output),
+  }
+  output
+}
+
+/// An attribute value of a dumped node.
+enum DumpValue {
+  Str(String),
+  Int(i64),
+  Float(f64),
+  Bool(bool),
+}
+
+/// A node in the dumped tree.
+struct DumpNode {
+  kind: &'static str,
+  attrs: Vec<(&'static str, DumpValue)>,
+  children: Vec<DumpNode>,
+}
+
+impl DumpNode {
+  fn new(kind: &'static str) -> Self {
+    Self {
+      kind,
+      attrs: vec![],
+      children: vec![],
+    }
+  }
+
+  fn with(mut self, name: &'static str, value: DumpValue) -> Self {
+    self.attrs.push((name, value));
+    self
+  }
+
+  fn with_str(self, name: &'static str, value: impl ToString) -> Self {
+    self.with(name, DumpValue::Str(value.to_string()))
+  }
+
+  fn write_sexpr(&self, buf: &mut String, depth: usize) {
+    write!(buf, "({}", self.kind).unwrap();
+    for (name, value) in self.attrs.iter() {
+      write!(buf, " :{} ", name).unwrap();
+      value.write(buf);
+    }
+    for child in self.children.iter() {
+      write!(buf, "\n{}", "  ".repeat(depth + 1)).unwrap();
+      child.write_sexpr(buf, depth + 1);
+    }
+    buf.push(')');
+  }
+
+  fn write_json(&self, buf: &mut String) {
+    buf.push_str("{\"kind\":");
+    write_json_string(buf, self.kind).unwrap();
+    for (name, value) in self.attrs.iter() {
+      write!(buf, ",\"{}\":", name).unwrap();
+      value.write(buf);
+    }
+    buf.push_str(",\"children\":[");
+    for (i, child) in self.children.iter().enumerate() {
+      if i > 0 { buf.push(','); }
+      child.write_json(buf);
+    }
+    buf.push_str("]}");
+  }
+}
+
+impl DumpValue {
+  /// Writes the value in a form that is valid in both JSON and S-expressions.
+  fn write(&self, buf: &mut String) {
+    match self {
+      DumpValue::Str(s) => write_json_string(buf, s).unwrap(),
+      DumpValue::Int(n) => write!(buf, "{}", n).unwrap(),
+      DumpValue::Float(n) if n.is_finite() => write!(buf, "{:?}", n).unwrap(),
+      DumpValue::Float(n) => write_json_string(buf, &n.to_string()).unwrap(),
+      DumpValue::Bool(b) => write!(buf, "{}", b).unwrap(),
+    }
+  }
+}
+
+/// Writes `s` as a quoted JSON string literal, which is also a valid S-expression string.
+pub fn write_json_string<W: Write>(w: &mut W, s: &str) -> std::fmt::Result {
+  w.write_char('"')?;
+  for c in s.chars() {
+    match c {
+      '"' => w.write_str("\\\"")?,
+      '\\' => w.write_str("\\\\")?,
+      '\n' => w.write_str("\\n")?,
+      '\r' => w.write_str("\\r")?,
+      '\t' => w.write_str("\\t")?,
+      c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+      c => w.write_char(c)?,
+    }
+  }
+  w.write_char('"')
+}
+
+/// Builds a tree of dump nodes by visiting a syntax tree.
+struct AstDumper {
+  /// The nodes currently being visited, with the root at the bottom.
+  stack: Vec<DumpNode>,
+}
+
+impl AstDumper {
+  /// Adds a node to the tree and visits its children with `walk`.
+  fn node<F: FnOnce(&mut Self)>(&mut self, node: DumpNode, walk: F) {
+    self.stack.push(node);
+    walk(self);
+    let node = self.stack.pop().unwrap();
+    self.stack.last_mut().unwrap().children.push(node);
+  }
+}
+
+fn flag_name(flag: PrintFlag) -> &'static str {
+  match flag {
+    PrintFlag::None => "none",
+    PrintFlag::Hint => "hint",
+    PrintFlag::Sink => "sink",
+  }
+}
+
+fn path_kind_name(kind: AccessPathKind) -> String {
+  match kind {
+    AccessPathKind::Local => "local".to_owned(),
+    AccessPathKind::ExplicitGlobal => "global".to_owned(),
+    AccessPathKind::Descope(n) => format!("descope({})", n),
+  }
+}
+
+impl RstVisitor for AstDumper {
+  fn visit_sequence(&mut self, seq: &Sequence) {
+    let mut node = DumpNode::new("sequence");
+    if let Some(name) = seq.name() {
+      node = node.with_str("name", name);
+    }
+    self.node(node, |this| walk_sequence(this, seq));
+  }
+
+  fn visit_rst(&mut self, rst: &Rst) {
+    let node = match rst {
+      // Nodes with their own visitor methods are added there
+      Rst::Sequence(_)
+      | Rst::Match(_)
+      | Rst::Lambda(_)
+      | Rst::FuncCall(_)
+      | Rst::FuncDef(_)
+      | Rst::DefVar(..)
+      | Rst::DefConst(..)
+      | Rst::Fragment(_)
+      | Rst::Whitespace(_)
+      | Rst::Integer(_)
+      | Rst::Float(_)
+      | Rst::Boolean(_)
+      | Rst::EmptyValue
+      | Rst::ConstCollection(_) => return walk_rst(self, rst),
+      Rst::DebugCursor(_) => return,
+      Rst::Block(block) => DumpNode::new("block")
+        .with_str("flag", flag_name(block.flag))
+        .with("weighted", DumpValue::Bool(block.is_weighted)),
+      Rst::ListInit(_) => DumpNode::new("list"),
+      Rst::SetInit(_) => DumpNode::new("set-literal"),
+      Rst::MapInit(entries) => {
+        // Static keys have no child node, so list them as an attribute
+        let keys = entries.iter().map(|(key, _)| match key {
+          MapKeyExpr::Static(key) => key.to_string(),
+          MapKeyExpr::Dynamic(_) => "{...}".to_owned(),
+        }).collect::<Vec<_>>().join(";");
+        DumpNode::new("map").with_str("keys", keys)
+      },
+      Rst::InterpolatedString(parts) => {
+        // Embedded expressions are child nodes, so the attribute only shows where they go
+        let template = parts.iter().map(|part| match part {
+          StringPart::Text(text) => text.as_str(),
+          StringPart::Expr(_) => "{...}",
+        }).collect::<String>();
+        DumpNode::new("interpolated-string").with_str("template", template)
+      },
+      Rst::PipedCall(piped) => DumpNode::new("piped-call")
+        .with_str("flag", flag_name(piped.flag))
+        .with("temporal", DumpValue::Bool(piped.is_temporal)),
+      Rst::Depth(name, kind, _) => DumpNode::new("depth")
+        .with_str("name", name)
+        .with_str("scope", path_kind_name(*kind)),
+      Rst::Get(..) => DumpNode::new("get"),
+      Rst::Set(..) => DumpNode::new("set"),
+      Rst::PipeValue => DumpNode::new("pipe-value"),
+      Rst::Return(_) => DumpNode::new("return"),
+      Rst::Continue(_) => DumpNode::new("continue"),
+      Rst::Break(_) => DumpNode::new("break"),
+      Rst::Yield(_) => DumpNode::new("yield"),
+      Rst::Nop => DumpNode::new("nop"),
+    };
+    self.node(node, |this| walk_rst(this, rst));
+  }
+
+  fn visit_block_element(&mut self, elem: &BlockElement) {
+    let mut node = DumpNode::new("element");
+    if let Some(BlockWeight::Constant(weight)) = &elem.weight {
+      node = node.with("weight", DumpValue::Float(*weight));
+    }
+    self.node(node, |this| walk_block_element(this, elem));
+  }
+
+  fn visit_match(&mut self, expr: &MatchExpr) {
+    self.node(DumpNode::new("match"), |this| walk_match(this, expr));
+  }
+
+  fn visit_match_arm(&mut self, arm: &MatchArm) {
+    let node = DumpNode::new("arm").with_str("pattern", &arm.pattern);
+    self.node(node, |this| walk_match_arm(this, arm));
+  }
+
+  fn visit_func_call(&mut self, call: &FunctionCall) {
+    let node = DumpNode::new("call")
+      .with_str("flag", flag_name(call.flag))
+      .with("temporal", DumpValue::Bool(call.is_temporal));
+    self.node(node, |this| walk_func_call(this, call));
+  }
+
+  fn visit_argument(&mut self, arg: &ArgumentExpr) {
+    let spread = match arg.spread_mode {
+      ArgumentSpreadMode::NoSpread => "none".to_owned(),
+      ArgumentSpreadMode::Parametric => "parametric".to_owned(),
+      ArgumentSpreadMode::Temporal { label } => format!("temporal({})", label),
+    };
+    let mut node = DumpNode::new("arg").with_str("spread", spread);
+    if let Some(name) = &arg.name {
+      node = node.with_str("name", name);
+    }
+    self.node(node, |this| walk_argument(this, arg));
+  }
+
+  fn visit_func_def(&mut self, def: &FunctionDef) {
+    let mut node = DumpNode::new("func-def")
+      .with("const", DumpValue::Bool(def.is_const))
+      .with_str("captures", def.capture_vars.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(";"));
+    if let Some(return_type) = &def.return_type {
+      node = node.with_str("return-type", return_type);
+    }
+    self.node(node, |this| walk_func_def(this, def));
+  }
+
+  fn visit_lambda(&mut self, lambda: &LambdaExpr) {
+    let mut node = DumpNode::new("lambda")
+      .with_str("captures", lambda.capture_vars.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(";"));
+    if let Some(return_type) = &lambda.return_type {
+      node = node.with_str("return-type", return_type);
+    }
+    self.node(node, |this| walk_lambda(this, lambda));
+  }
+
+  fn visit_parameter(&mut self, param: &Parameter) {
+    let varity = match param.varity {
+      Varity::Required => "required",
+      Varity::Optional => "optional",
+      Varity::VariadicStar => "variadic-star",
+      Varity::VariadicPlus => "variadic-plus",
+    };
+    let mut node = DumpNode::new("param")
+      .with_str("name", &param.name)
+      .with_str("varity", varity);
+    if let Some(pattern) = &param.pattern {
+      node = node.with_str("pattern", pattern);
+    }
+    if let Some(type_annotation) = &param.type_annotation {
+      node = node.with_str("type", type_annotation);
+    }
+    self.node(node, |this| walk_parameter(this, param));
+  }
+
+  fn visit_access_path(&mut self, path: &AccessPath) {
+    let node = DumpNode::new("path")
+      .with_str("path", path)
+      .with_str("scope", path_kind_name(path.kind()));
+    self.node(node, |this| walk_access_path(this, path));
+  }
+
+  fn visit_var_def(&mut self, name: &Identifier, kind: AccessPathKind, is_const: bool, value: Option<&Sequence>) {
+    let node = DumpNode::new(if is_const { "def-const" } else { "def-var" })
+      .with_str("name", name)
+      .with_str("scope", path_kind_name(kind));
+    self.node(node, |this| walk_var_def(this, name, kind, is_const, value));
+  }
+
+  fn visit_literal(&mut self, literal: &Rst) {
+    let node = match literal {
+      Rst::Fragment(s) => DumpNode::new("fragment").with_str("value", s),
+      Rst::Whitespace(s) => DumpNode::new("whitespace").with_str("value", s),
+      Rst::Integer(n) => DumpNode::new("integer").with("value", DumpValue::Int(*n)),
+      Rst::Float(n) => DumpNode::new("float").with("value", DumpValue::Float(*n)),
+      Rst::Boolean(b) => DumpNode::new("boolean").with("value", DumpValue::Bool(*b)),
+      Rst::ConstCollection(value) => DumpNode::new("const-collection")
+        .with_str("type", value.get_type())
+        .with_str("value", value),
+      _ => DumpNode::new("empty"),
+    };
+    self.node(node, |_| {});
+  }
+}
@@ -1,9 +1,19 @@
 //! Contains Rant's syntax tree implementation and supporting data structures.
 
 use std::{collections::HashMap, fmt::Display, ops::{Deref, DerefMut, Range}, rc::Rc};
-use crate::{RantProgramInfo, InternalString, RantValue, RantValueType};
+use crate::{RantProgramInfo, InternalString, RantValue, RantValueType, compiler::Position};
+
+mod dump;
+mod pattern;
+mod visit;
+
+pub use dump::*;
+pub use pattern::*;
+pub use visit::*;
 
 pub(crate) const PIPE_VALUE_NAME: &str = "~PIPE";
+pub(crate) const DESTRUCTURE_VALUE_NAME: &str = "~DESTRUCTURE";
+pub(crate) const DESTRUCTURE_PARAM_PREFIX: &str = "~PARAM";
 
 /// Printflags indicate to the compiler whether a given program element is likely to print something or not.
 #[repr(u8)]
@@ -399,16 +409,22 @@ pub struct Block {
   /// Determines whether the block uses weights.
   pub is_weighted: bool,
   /// The elements associated with the block.
-  pub elements: Rc<Vec<BlockElement>>
+  pub elements: Rc<Vec<BlockElement>>,
+  /// The location of the block in its source.
+  pub pos: Position,
+  /// The locations of elements that the optimizer removed from the block, kept for coverage reports.
+  pub removed_elements: Vec<Position>,
 }
 
 impl Block {
   /// Creates a new block.
-  pub fn new(flag: PrintFlag, is_weighted: bool, elements: Vec<BlockElement>) -> Self {
+  pub fn new(flag: PrintFlag, is_weighted: bool, elements: Vec<BlockElement>, pos: Position) -> Self {
     Block {
       flag,
       is_weighted,
-      elements: Rc::new(elements)
+      elements: Rc::new(elements),
+      pos,
+      removed_elements: vec![],
     }
   }
 
@@ -417,6 +433,8 @@ impl Block {
   pub fn reversed(&self) -> Self {
     Self {
       elements: Rc::new(self.elements.iter().rev().cloned().collect()),
+      pos: self.pos.clone(),
+      removed_elements: self.removed_elements.clone(),
       .. *self
     }
   }
@@ -426,6 +444,12 @@ impl Block {
   pub fn len(&self) -> usize {
     self.elements.len()
   }
+
+  /// Determines whether the block has no elements.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.elements.is_empty()
+  }
 }
 
 /// A single element of a regular block.
@@ -435,6 +459,8 @@ pub struct BlockElement {
   pub main: Rc<Sequence>,
   /// The weight of the element.
   pub weight: Option<BlockWeight>,
+  /// The location of the element in its source.
+  pub pos: Position,
 }
 
 impl Clone for BlockElement {
@@ -442,7 +468,8 @@ impl Clone for BlockElement {
   fn clone(&self) -> Self {
     Self {
       main: Rc::clone(&self.main),
-      weight: self.weight.clone()
+      weight: self.weight.clone(),
+      pos: self.pos.clone(),
     }
   }
 }
@@ -466,6 +493,28 @@ impl Clone for BlockWeight {
   }
 }
 
+/// A `@match` expression, which runs the body of the first arm whose pattern matches a value.
+#[derive(Debug)]
+pub struct MatchExpr {
+  /// The expression that produces the value to match.
+  pub value: Rc<Sequence>,
+  /// The arms to test the value against, in order.
+  pub arms: Rc<Vec<MatchArm>>,
+  /// The location of the match expression in its source.
+  pub pos: Position,
+}
+
+/// A single arm of a `@match` expression.
+#[derive(Debug)]
+pub struct MatchArm {
+  /// The pattern that selects the arm.
+  pub pattern: Pattern,
+  /// The body that runs when the arm is selected.
+  pub body: Rc<Sequence>,
+  /// The location of the arm in its source.
+  pub pos: Position,
+}
+
 /// Describes the arity requirements of a function parameter.
 #[derive(Debug, Copy, Clone)]
 pub enum Varity {
@@ -534,6 +583,8 @@ pub struct ArgumentExpr {
   pub expr: Rc<Sequence>,
   /// The spread mode for the argument.
   pub spread_mode: ArgumentSpreadMode,
+  /// The name of the parameter the argument is passed to, if it's a named argument (`@name = value`).
+  pub name: Option<Identifier>,
 }
 
 /// Describes what to call for a function call.
@@ -665,6 +716,8 @@ pub struct FunctionDef {
   pub capture_vars: Rc<Vec<Identifier>>,
   /// The body of the function being defined.
   pub body: Rc<Sequence>,
+  /// The type the function's return value is checked against, if annotated.
+  pub return_type: Option<Rc<TypeAnnotation>>,
 }
 
 /// Describes a Rant lambda.
@@ -676,6 +729,8 @@ pub struct LambdaExpr {
   pub params: Rc<Vec<Parameter>>,
   /// The variables to capture into the lambda.
   pub capture_vars: Rc<Vec<Identifier>>,
+  /// The type the lambda's return value is checked against, if annotated.
+  pub return_type: Option<Rc<TypeAnnotation>>,
 }
 
 /// Describes a function parameter.
@@ -687,6 +742,14 @@ pub struct Parameter {
   pub varity: Varity,
   /// The default value of the parameter.
   pub default_value_expr: Option<Rc<Sequence>>,
+  /// The destructuring pattern that splits the argument into variables, if any.
+  ///
+  /// The parser adds definitions for the pattern's variables to the start of the function body.
+  pub pattern: Option<Rc<DestructurePattern>>,
+  /// The type that arguments passed to the parameter are checked against, if annotated.
+  ///
+  /// On variadic parameters, each argument is checked.
+  pub type_annotation: Option<TypeAnnotation>,
 }
 
 impl Parameter {
@@ -704,6 +767,35 @@ impl Parameter {
   }
 }
 
+/// A type annotation on a function parameter or return value, e.g. `(int | float)`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeAnnotation {
+  /// Accepts any value: `(any)`
+  Any,
+  /// Accepts values of any of the listed types.
+  Union(Vec<RantValueType>),
+}
+
+impl TypeAnnotation {
+  /// Determines whether the annotation accepts values of the specified type.
+  #[inline]
+  pub fn accepts(&self, value_type: RantValueType) -> bool {
+    match self {
+      TypeAnnotation::Any => true,
+      TypeAnnotation::Union(types) => types.contains(&value_type),
+    }
+  }
+}
+
+impl Display for TypeAnnotation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      TypeAnnotation::Any => write!(f, "any"),
+      TypeAnnotation::Union(types) => write!(f, "{}", types.iter().map(|t| t.name()).collect::<Vec<_>>().join(" | ")),
+    }
+  }
+}
+
 /// Key creation methods for map initializer entries.
 #[derive(Debug)]
 pub enum MapKeyExpr {
@@ -713,6 +805,15 @@ pub enum MapKeyExpr {
   Static(InternalString),
 }
 
+/// A part of an interpolated string literal.
+#[derive(Debug)]
+pub enum StringPart {
+  /// Literal text.
+  Text(InternalString),
+  /// An accessor or function call whose output is formatted and inserted into the string.
+  Expr(Rc<Sequence>),
+}
+
 pub trait RstTrace {
   fn span(&self) -> Range<usize>;
   fn find(&self, range: &Range<usize>) -> Option<RstLeaf>;
@@ -740,10 +841,16 @@ pub enum Rst {
   Sequence(Rc<Sequence>),
   /// Rant block containing zero or more sequences
   Block(Rc<Block>),
+  /// Match expression
+  Match(Rc<MatchExpr>),
   /// List initializer
   ListInit(Rc<Vec<Rc<Sequence>>>),
   /// Map initializer
   MapInit(Rc<Vec<(MapKeyExpr, Rc<Sequence>)>>),
+  /// Set initializer
+  SetInit(Rc<Vec<Rc<Sequence>>>),
+  /// Interpolated string literal
+  InterpolatedString(Rc<Vec<StringPart>>),
   /// Lambda expression
   Lambda(LambdaExpr),
   /// Single function call
@@ -776,12 +883,18 @@ pub enum Rst {
   Boolean(bool),
   /// Empty value
   EmptyValue,
+  /// List or map of constant values built at compile time.
+  ///
+  /// Collections are mutable, so each evaluation gets its own handle to the value, whose contents are copied on the first modification.
+  ConstCollection(RantValue),
   /// Return
   Return(Option<Rc<Sequence>>),
   /// Continue
   Continue(Option<Rc<Sequence>>),
   /// Break
   Break(Option<Rc<Sequence>>),
+  /// Yield
+  Yield(Option<Rc<Sequence>>),
   /// Provides debug information about the next sequence element
   DebugCursor(DebugInfo),
 }
@@ -792,8 +905,11 @@ impl Rst {
     match self {
       Rst::Sequence(_) =>                     "sequence",
       Rst::Block(..) =>                       "block",
+      Rst::Match(_) =>                        "match",
       Rst::ListInit(_) =>                     "list",
       Rst::MapInit(_) =>                      "map",
+      Rst::SetInit(_) =>                      "set",
+      Rst::InterpolatedString(_) =>           "interpolated string",
       Rst::Lambda(_) =>                       "lambda",
       Rst::FuncCall(_) =>                     "function call",
       Rst::FuncDef(_) =>                      "function definition",
@@ -803,6 +919,7 @@ impl Rst {
       Rst::Float(_) =>                        "float",
       Rst::Boolean(_) =>                      "boolean",
       Rst::EmptyValue =>                      "empty",
+      Rst::ConstCollection(_) =>              "constant collection",
       Rst::Nop =>                             "no-op",
       Rst::DefVar(..) =>                      "variable definition",
       Rst::DefConst(..) =>                    "constant definition",
@@ -814,6 +931,7 @@ impl Rst {
       Rst::Return(_) =>                       "return",
       Rst::Continue(_) =>                     "continue",
       Rst::Break(_) =>                        "break",
+      Rst::Yield(_) =>                        "yield",
       Rst::DebugCursor(_) =>                  "debug cursor",
     }
   }
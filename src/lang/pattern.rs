@@ -0,0 +1,213 @@
+//! Patterns for `@match` expressions and destructuring definitions.
+
+use super::*;
+use crate::{RantList, RantRange};
+use std::cell::RefCell;
+
+/// A pattern that a value can be tested against in a `@match` arm.
+#[derive(Debug)]
+pub enum Pattern {
+  /// Matches any value: `_`
+  Wildcard,
+  /// Matches values of the same type that are equal to a constant, e.g. `1`, `"foo"`, `@true`, or `<>`
+  Literal(RantValue),
+  /// Matches values of a type, e.g. `@int`
+  Type(RantValueType),
+  /// Matches integers produced by a range, e.g. `1..10`
+  Range(RantRange),
+  /// Matches lists by their items, e.g. `(a; _; *rest)`
+  List {
+    /// The patterns for the leading items of the list.
+    items: Vec<Pattern>,
+    /// The pattern for a list of the remaining items; if `None`, the list can't have any other items.
+    rest: Option<Box<Pattern>>,
+  },
+  /// Matches maps that have the specified keys, e.g. `@(name; age = @int)`
+  Map(Vec<(InternalString, Pattern)>),
+  /// Matches the inner pattern and binds the value to a local variable, e.g. `$x` or `@int $x`
+  Bind(Box<Pattern>, Identifier),
+}
+
+impl Pattern {
+  /// Tests a value against the pattern.
+  ///
+  /// Values bound by the pattern are appended to `bindings`, which should be discarded if the match fails.
+  pub fn matches(&self, value: &RantValue, bindings: &mut Vec<(Identifier, RantValue)>) -> bool {
+    match (self, value) {
+      (Pattern::Wildcard, _) => true,
+      (Pattern::Literal(expected), value) => expected.get_type() == value.get_type() && expected == value,
+      (Pattern::Type(expected), value) => value.get_type() == *expected,
+      (Pattern::Range(range), RantValue::Int(n)) => range.contains(*n),
+      (Pattern::List { items, rest }, RantValue::List(list)) => {
+        let list = list.borrow();
+        if list.len() < items.len() || (rest.is_none() && list.len() > items.len()) {
+          return false
+        }
+        if !items.iter().zip(list.iter()).all(|(item, value)| item.matches(value, bindings)) {
+          return false
+        }
+        rest.as_ref().is_none_or(|rest| {
+          let rest_list = list.iter().skip(items.len()).cloned().collect::<RantList>();
+          rest.matches(&RantValue::List(Rc::new(RefCell::new(rest_list))), bindings)
+        })
+      },
+      (Pattern::Map(entries), RantValue::Map(map)) => {
+        let map = map.borrow();
+        entries.iter().all(|(key, pattern)| map.get(key.as_str()).is_some_and(|value| pattern.matches(&value, bindings)))
+      },
+      (Pattern::Bind(pattern, name), value) => {
+        if !pattern.matches(value, bindings) {
+          return false
+        }
+        bindings.push((name.clone(), value.clone()));
+        true
+      },
+      _ => false,
+    }
+  }
+
+  /// Indicates whether the pattern matches every value.
+  pub fn is_irrefutable(&self) -> bool {
+    match self {
+      Pattern::Wildcard => true,
+      Pattern::Bind(pattern, _) => pattern.is_irrefutable(),
+      _ => false,
+    }
+  }
+
+  /// Indicates whether the pattern matches every value that `other` matches.
+  ///
+  /// This check is conservative: it can return `false` for some patterns that do cover `other`, but never returns `true` for ones that don't.
+  pub fn covers(&self, other: &Pattern) -> bool {
+    match (self, other) {
+      (a, _) if a.is_irrefutable() => true,
+      (Pattern::Bind(a, _), b) => a.covers(b),
+      (a, Pattern::Bind(b, _)) => a.covers(b),
+      (Pattern::Literal(a), Pattern::Literal(b)) => a.get_type() == b.get_type() && a == b,
+      (Pattern::Type(a), Pattern::Type(b)) => a == b,
+      (Pattern::Type(t), Pattern::Literal(value)) => value.get_type() == *t,
+      (Pattern::Type(RantValueType::Int), Pattern::Range(_)) => true,
+      (Pattern::Type(RantValueType::List), Pattern::List { .. }) => true,
+      (Pattern::Type(RantValueType::Map), Pattern::Map(_)) => true,
+      (Pattern::Range(range), Pattern::Literal(RantValue::Int(n))) => range.contains(*n),
+      (Pattern::Range(a), Pattern::Range(b)) => {
+        b.is_empty() || (a.abs_step() == 1 && a.contains(b.start()) && b.get(b.len() - 1).is_some_and(|last| a.contains(last)))
+      },
+      (Pattern::List { items: a_items, rest: a_rest }, Pattern::List { items: b_items, rest: b_rest }) => {
+        let are_lengths_covered = match a_rest {
+          None => b_rest.is_none() && a_items.len() == b_items.len(),
+          Some(a_rest) => a_rest.is_irrefutable() && b_items.len() >= a_items.len(),
+        };
+        are_lengths_covered && a_items.iter().zip(b_items.iter()).all(|(a, b)| a.covers(b))
+      },
+      (Pattern::Map(a_entries), Pattern::Map(b_entries)) => {
+        a_entries.iter().all(|(a_key, a)| b_entries.iter().any(|(b_key, b)| a_key == b_key && a.covers(b)))
+      },
+      _ => false,
+    }
+  }
+}
+
+impl Display for Pattern {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Pattern::Wildcard => write!(f, "_"),
+      Pattern::Literal(RantValue::String(s)) => write!(f, "\"{}\"", s.as_str().replace('"', "\"\"")),
+      Pattern::Literal(RantValue::Boolean(b)) => write!(f, "@{}", b),
+      Pattern::Literal(RantValue::Empty) => write!(f, "<>"),
+      Pattern::Literal(value) => write!(f, "{}", value),
+      Pattern::Type(t) => write!(f, "@{}", t),
+      Pattern::Range(range) => write!(f, "{}..{}", range.start(), range.end()),
+      Pattern::List { items, rest } => {
+        let mut parts = items.iter().map(|item| item.to_string()).collect::<Vec<_>>();
+        match rest.as_deref() {
+          Some(Pattern::Wildcard) => parts.push("*".to_owned()),
+          Some(rest) => parts.push(format!("*{}", rest)),
+          None => {},
+        }
+        write!(f, "({})", parts.join("; "))
+      },
+      Pattern::Map(entries) => {
+        let parts = entries.iter().map(|(key, pattern)| match pattern {
+          Pattern::Wildcard => key.to_string(),
+          pattern => format!("{} = {}", key, pattern),
+        }).collect::<Vec<_>>();
+        write!(f, "@({})", parts.join("; "))
+      },
+      Pattern::Bind(pattern, name) => match pattern.as_ref() {
+        Pattern::Wildcard => write!(f, "${}", name),
+        pattern => write!(f, "{} ${}", pattern, name),
+      },
+    }
+  }
+}
+
+/// A pattern that splits a list or map into variables in a definition or function parameter, e.g. `(first; *rest)` or `@(name; age ? 0)`.
+#[derive(Debug)]
+pub enum DestructurePattern {
+  /// Binds a value to a variable, using the fallback expression (if any) when the value is missing.
+  Var {
+    /// The name of the variable.
+    name: Identifier,
+    /// The value to use when the list index or map key doesn't exist.
+    fallback: Option<Rc<Sequence>>,
+  },
+  /// Binds list items by index.
+  List {
+    /// The patterns for the leading items of the list.
+    items: Vec<DestructurePattern>,
+    /// The variable that receives a list of the remaining items, if any.
+    rest: Option<Identifier>,
+  },
+  /// Binds map values by key.
+  Map(Vec<(Identifier, DestructurePattern)>),
+}
+
+impl DestructurePattern {
+  /// Gets the names of the variables bound by the pattern, in the order they appear.
+  pub fn var_names(&self) -> Vec<&Identifier> {
+    let mut names = vec![];
+    self.collect_var_names(&mut names);
+    names
+  }
+
+  fn collect_var_names<'a>(&'a self, names: &mut Vec<&'a Identifier>) {
+    match self {
+      DestructurePattern::Var { name, .. } => names.push(name),
+      DestructurePattern::List { items, rest } => {
+        for item in items {
+          item.collect_var_names(names);
+        }
+        names.extend(rest);
+      },
+      DestructurePattern::Map(entries) => {
+        for (_, item) in entries {
+          item.collect_var_names(names);
+        }
+      },
+    }
+  }
+}
+
+impl Display for DestructurePattern {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      DestructurePattern::Var { name, fallback: None } => write!(f, "{}", name),
+      DestructurePattern::Var { name, fallback: Some(_) } => write!(f, "{} ? ...", name),
+      DestructurePattern::List { items, rest } => {
+        let mut parts = items.iter().map(|item| item.to_string()).collect::<Vec<_>>();
+        if let Some(rest) = rest {
+          parts.push(format!("*{}", rest));
+        }
+        write!(f, "({})", parts.join("; "))
+      },
+      DestructurePattern::Map(entries) => {
+        let parts = entries.iter().map(|(key, pattern)| match pattern {
+          DestructurePattern::Var { name, .. } if name == key => pattern.to_string(),
+          pattern => format!("{} = {}", key, pattern),
+        }).collect::<Vec<_>>();
+        write!(f, "@({})", parts.join("; "))
+      },
+    }
+  }
+}
@@ -0,0 +1,256 @@
+//! Read-only traversal of Rant syntax trees.
+
+use super::*;
+
+/// Visits the nodes of a Rant syntax tree.
+///
+/// Each method has a default implementation that visits the children of the node through the matching `walk_*` function.
+/// Override the methods for the nodes you're interested in, and call the `walk_*` function from the override
+/// to keep visiting their children.
+///
+/// ```
+/// use rant::Rant;
+/// use rant::lang::{RstVisitor, FunctionCall, FunctionCallTarget, walk_func_call};
+///
+/// #[derive(Default)]
+/// struct CallCounter(usize);
+///
+/// impl RstVisitor for CallCounter {
+///   fn visit_func_call(&mut self, call: &FunctionCall) {
+///     self.0 += 1;
+///     walk_func_call(self, call);
+///   }
+/// }
+///
+/// let program = Rant::new().compile_quiet("[upper: [rep: 2][sel: once]{a}]").unwrap();
+/// let mut counter = CallCounter::default();
+/// counter.visit_sequence(program.root());
+/// assert_eq!(counter.0, 3);
+/// ```
+pub trait RstVisitor {
+  /// Visits a sequence of program elements.
+  fn visit_sequence(&mut self, seq: &Sequence) {
+    walk_sequence(self, seq)
+  }
+
+  /// Visits a single program element.
+  fn visit_rst(&mut self, rst: &Rst) {
+    walk_rst(self, rst)
+  }
+
+  /// Visits a block.
+  fn visit_block(&mut self, block: &Block) {
+    walk_block(self, block)
+  }
+
+  /// Visits a single element of a block.
+  fn visit_block_element(&mut self, elem: &BlockElement) {
+    walk_block_element(self, elem)
+  }
+
+  /// Visits a match expression.
+  fn visit_match(&mut self, expr: &MatchExpr) {
+    walk_match(self, expr)
+  }
+
+  /// Visits a single arm of a match expression.
+  fn visit_match_arm(&mut self, arm: &MatchArm) {
+    walk_match_arm(self, arm)
+  }
+
+  /// Visits a function call, including each step of a piped call.
+  fn visit_func_call(&mut self, call: &FunctionCall) {
+    walk_func_call(self, call)
+  }
+
+  /// Visits an argument of a function call.
+  fn visit_argument(&mut self, arg: &ArgumentExpr) {
+    walk_argument(self, arg)
+  }
+
+  /// Visits a function definition.
+  fn visit_func_def(&mut self, def: &FunctionDef) {
+    walk_func_def(self, def)
+  }
+
+  /// Visits a lambda expression.
+  fn visit_lambda(&mut self, lambda: &LambdaExpr) {
+    walk_lambda(self, lambda)
+  }
+
+  /// Visits a parameter of a function definition or lambda.
+  fn visit_parameter(&mut self, param: &Parameter) {
+    walk_parameter(self, param)
+  }
+
+  /// Visits the access path of a getter, setter, function call, or function definition.
+  fn visit_access_path(&mut self, path: &AccessPath) {
+    walk_access_path(self, path)
+  }
+
+  /// Visits a variable or constant definition.
+  fn visit_var_def(&mut self, name: &Identifier, kind: AccessPathKind, is_const: bool, value: Option<&Sequence>) {
+    walk_var_def(self, name, kind, is_const, value)
+  }
+
+  /// Visits a literal: a fragment, whitespace, number, boolean, empty value, or constant collection.
+  fn visit_literal(&mut self, _literal: &Rst) {}
+}
+
+/// Visits each element of a sequence.
+pub fn walk_sequence<V: RstVisitor + ?Sized>(visitor: &mut V, seq: &Sequence) {
+  for rst in seq.iter() {
+    visitor.visit_rst(rst);
+  }
+}
+
+/// Visits the children of a program element.
+pub fn walk_rst<V: RstVisitor + ?Sized>(visitor: &mut V, rst: &Rst) {
+  match rst {
+    Rst::Sequence(seq) => visitor.visit_sequence(seq),
+    Rst::Block(block) => visitor.visit_block(block),
+    Rst::Match(expr) => visitor.visit_match(expr),
+    Rst::ListInit(items) | Rst::SetInit(items) => {
+      for item in items.iter() {
+        visitor.visit_sequence(item);
+      }
+    },
+    Rst::MapInit(entries) => {
+      for (key, value) in entries.iter() {
+        if let MapKeyExpr::Dynamic(key_expr) = key {
+          visitor.visit_sequence(key_expr);
+        }
+        visitor.visit_sequence(value);
+      }
+    },
+    Rst::InterpolatedString(parts) => {
+      for part in parts.iter() {
+        if let StringPart::Expr(expr) = part {
+          visitor.visit_sequence(expr);
+        }
+      }
+    },
+    Rst::Lambda(lambda) => visitor.visit_lambda(lambda),
+    Rst::FuncCall(call) => visitor.visit_func_call(call),
+    Rst::PipedCall(piped) => {
+      for step in piped.steps.iter() {
+        visitor.visit_func_call(step);
+      }
+    },
+    Rst::FuncDef(def) => visitor.visit_func_def(def),
+    Rst::DefVar(name, kind, value) => visitor.visit_var_def(name, *kind, false, value.as_deref()),
+    Rst::DefConst(name, kind, value) => visitor.visit_var_def(name, *kind, true, value.as_deref()),
+    Rst::Get(path, fallback) => {
+      visitor.visit_access_path(path);
+      if let Some(fallback) = fallback {
+        visitor.visit_sequence(fallback);
+      }
+    },
+    Rst::Set(path, value) => {
+      visitor.visit_access_path(path);
+      visitor.visit_sequence(value);
+    },
+    Rst::Depth(.., Some(expr))
+    | Rst::Return(Some(expr))
+    | Rst::Continue(Some(expr))
+    | Rst::Break(Some(expr))
+    | Rst::Yield(Some(expr)) => visitor.visit_sequence(expr),
+    Rst::Fragment(_)
+    | Rst::Whitespace(_)
+    | Rst::Integer(_)
+    | Rst::Float(_)
+    | Rst::Boolean(_)
+    | Rst::EmptyValue
+    | Rst::ConstCollection(_) => visitor.visit_literal(rst),
+    Rst::Depth(..)
+    | Rst::Return(None)
+    | Rst::Continue(None)
+    | Rst::Break(None)
+    | Rst::Yield(None)
+    | Rst::Nop
+    | Rst::PipeValue
+    | Rst::DebugCursor(_) => {},
+  }
+}
+
+/// Visits each element of a block.
+pub fn walk_block<V: RstVisitor + ?Sized>(visitor: &mut V, block: &Block) {
+  for elem in block.elements.iter() {
+    visitor.visit_block_element(elem);
+  }
+}
+
+/// Visits the weight expression (if any) and body of a block element.
+pub fn walk_block_element<V: RstVisitor + ?Sized>(visitor: &mut V, elem: &BlockElement) {
+  if let Some(BlockWeight::Dynamic(weight)) = &elem.weight {
+    visitor.visit_sequence(weight);
+  }
+  visitor.visit_sequence(&elem.main);
+}
+
+/// Visits the value expression and each arm of a match expression.
+pub fn walk_match<V: RstVisitor + ?Sized>(visitor: &mut V, expr: &MatchExpr) {
+  visitor.visit_sequence(&expr.value);
+  for arm in expr.arms.iter() {
+    visitor.visit_match_arm(arm);
+  }
+}
+
+/// Visits the body of a match arm.
+pub fn walk_match_arm<V: RstVisitor + ?Sized>(visitor: &mut V, arm: &MatchArm) {
+  visitor.visit_sequence(&arm.body);
+}
+
+/// Visits the target and arguments of a function call.
+pub fn walk_func_call<V: RstVisitor + ?Sized>(visitor: &mut V, call: &FunctionCall) {
+  match &call.target {
+    FunctionCallTarget::Path(path) => visitor.visit_access_path(path),
+    FunctionCallTarget::Expression(expr) => visitor.visit_sequence(expr),
+  }
+  for arg in call.arguments.iter() {
+    visitor.visit_argument(arg);
+  }
+}
+
+/// Visits the expression of a function argument.
+pub fn walk_argument<V: RstVisitor + ?Sized>(visitor: &mut V, arg: &ArgumentExpr) {
+  visitor.visit_sequence(&arg.expr);
+}
+
+/// Visits the path, parameters, and body of a function definition.
+pub fn walk_func_def<V: RstVisitor + ?Sized>(visitor: &mut V, def: &FunctionDef) {
+  visitor.visit_access_path(&def.path);
+  for param in def.params.iter() {
+    visitor.visit_parameter(param);
+  }
+  visitor.visit_sequence(&def.body);
+}
+
+/// Visits the parameters and body of a lambda.
+pub fn walk_lambda<V: RstVisitor + ?Sized>(visitor: &mut V, lambda: &LambdaExpr) {
+  for param in lambda.params.iter() {
+    visitor.visit_parameter(param);
+  }
+  visitor.visit_sequence(&lambda.body);
+}
+
+/// Visits the default value expression of a parameter, if any.
+pub fn walk_parameter<V: RstVisitor + ?Sized>(visitor: &mut V, param: &Parameter) {
+  if let Some(default_value) = &param.default_value_expr {
+    visitor.visit_sequence(default_value);
+  }
+}
+
+/// Visits the dynamic keys, anonymous values, and dynamic slice bounds of an access path.
+pub fn walk_access_path<V: RstVisitor + ?Sized>(visitor: &mut V, path: &AccessPath) {
+  for expr in path.dynamic_exprs() {
+    visitor.visit_sequence(&expr);
+  }
+}
+
+/// Visits the value of a variable or constant definition, if any.
+pub fn walk_var_def<V: RstVisitor + ?Sized>(visitor: &mut V, _name: &Identifier, _kind: AccessPathKind, _is_const: bool, value: Option<&Sequence>) {
+  if let Some(value) = value {
+    visitor.visit_sequence(value);
+  }
+}
@@ -34,6 +34,7 @@
 // Public modules
 pub mod data;
 pub mod compiler;
+pub mod lang;
 pub mod runtime;
 
 // Internal modules
@@ -41,7 +42,7 @@ mod collections;
 mod convert;
 mod format;
 mod func;
-mod lang;
+mod iter;
 mod rng;
 mod stdlib;
 mod string;
@@ -55,12 +56,13 @@ pub use crate::convert::*;
 pub use crate::string::*;
 pub use crate::value::*;
 pub use crate::func::*;
+pub use crate::iter::*;
 pub use crate::var::*;
 
 use crate::compiler::*;
 use crate::lang::Sequence;
 use crate::rng::RantRng;
-use crate::runtime::{RuntimeResult, IntoRuntimeResult, RuntimeError, RuntimeErrorType, VM};
+use crate::runtime::{RuntimeResult, IntoRuntimeResult, RuntimeError, RuntimeErrorType, VM, Debugger, Profiler, Coverage};
 
 use std::{path::Path, rc::Rc, cell::RefCell, fmt::Display, path::PathBuf, io::ErrorKind, collections::HashMap};
 use std::env;
@@ -153,19 +155,19 @@ impl Rant {
   /// Compiles a source string using the specified reporter.
   #[must_use = "compiling a program without storing or running it achieves nothing"]
   pub fn compile<R: Reporter>(&self, source: &str, reporter: &mut R) -> Result<RantProgram, CompilerErrorKind> {
-    compiler::compile_string(source, reporter, self.options.debug_mode, RantProgramInfo {
+    compiler::compile_string(source, reporter, self.options.debug_mode, self.options.optimization_level, RantProgramInfo {
       name: None,
       path: None,
-    })
+    }, Some(&self.globals))
   }
 
   /// Compiles a source string using the specified reporter and source name.
   #[must_use = "compiling a program without storing or running it achieves nothing"]
   pub fn compile_named<R: Reporter>(&self, source: &str, reporter: &mut R, name: &str) -> Result<RantProgram, CompilerErrorKind> {
-    compiler::compile_string(source, reporter, self.options.debug_mode, RantProgramInfo {
+    compiler::compile_string(source, reporter, self.options.debug_mode, self.options.optimization_level, RantProgramInfo {
       name: Some(name.to_owned()),
       path: None,
-    })
+    }, Some(&self.globals))
   }
 
   /// Compiles a source string without reporting problems.
@@ -177,10 +179,10 @@ impl Rant {
   /// If you require this information, use the `compile()` method instead.
   #[must_use = "compiling a program without storing or running it achieves nothing"]
   pub fn compile_quiet(&self, source: &str) -> Result<RantProgram, CompilerErrorKind> {
-    compiler::compile_string(source, &mut (), self.options.debug_mode, RantProgramInfo {
+    compiler::compile_string(source, &mut (), self.options.debug_mode, self.options.optimization_level, RantProgramInfo {
       name: None,
       path: None,
-    })
+    }, Some(&self.globals))
   }
 
   /// Compiles a source string without reporting problems and assigns it the specified name.
@@ -192,16 +194,16 @@ impl Rant {
   /// If you require this information, use the `compile()` method instead.
   #[must_use = "compiling a program without storing or running it achieves nothing"]
   pub fn compile_quiet_named(&self, source: &str, name: &str) -> Result<RantProgram, CompilerErrorKind> {
-    compiler::compile_string(source, &mut (), self.options.debug_mode, RantProgramInfo {
+    compiler::compile_string(source, &mut (), self.options.debug_mode, self.options.optimization_level, RantProgramInfo {
       name: Some(name.to_owned()),
       path: None,
-    })
+    }, Some(&self.globals))
   }
   
   /// Compiles a source file using the specified reporter.
   #[must_use = "compiling a program without storing or running it achieves nothing"]
   pub fn compile_file<P: AsRef<Path>, R: Reporter>(&self, path: P, reporter: &mut R) -> Result<RantProgram, CompilerErrorKind> {
-    compiler::compile_file(path, reporter, self.options.debug_mode)
+    compiler::compile_file(path, reporter, self.options.debug_mode, self.options.optimization_level, Some(&self.globals))
   }
 
   /// Compiles a source file without reporting problems.
@@ -213,7 +215,7 @@ impl Rant {
   /// If you require this information, use the `compile_file()` method instead.
   #[must_use = "compiling a program without storing or running it achieves nothing"]
   pub fn compile_file_quiet<P: AsRef<Path>>(&self, path: P) -> Result<RantProgram, CompilerErrorKind> {
-    compiler::compile_file(path, &mut (), self.options.debug_mode)
+    compiler::compile_file(path, &mut (), self.options.debug_mode, self.options.optimization_level, Some(&self.globals))
   }
 
   /// Sets a global variable. This will auto-define the global if it doesn't exist. 
@@ -359,6 +361,26 @@ impl Rant {
     VM::new(self.rng.clone(), self, program).run_with(args)
   }
 
+  /// Runs a program under the control of a debugger and returns the output value.
+  ///
+  /// The program must be compiled with `debug_mode` enabled for the debugger to be able to pause it.
+  pub fn run_debug(&mut self, program: &RantProgram, debugger: &mut dyn Debugger) -> RuntimeResult<RantValue> {
+    VM::new(self.rng.clone(), self, program).with_debugger(debugger).run()
+  }
+
+  /// Runs a program with a profiler attached and returns the output value.
+  pub fn run_profiled(&mut self, program: &RantProgram, profiler: &mut Profiler) -> RuntimeResult<RantValue> {
+    VM::new(self.rng.clone(), self, program).with_profiler(profiler).run()
+  }
+
+  /// Runs a program with a coverage collector attached and returns the output value.
+  ///
+  /// The collector can be reused across runs to accumulate coverage data.
+  /// Blocks inlined by [`OptimizationLevel::Full`] are left out of the collected data.
+  pub fn run_with_coverage(&mut self, program: &RantProgram, coverage: &mut Coverage) -> RuntimeResult<RantValue> {
+    VM::new(self.rng.clone(), self, program).with_coverage(coverage).run()
+  }
+
   /// Attempts to load and compile a module with the specified name.
   pub(crate) fn try_read_module(&mut self, module_path: &str, caller_origin: Rc<RantProgramInfo>) -> ModuleLoadResult {
     if !self.options.enable_require {
@@ -404,8 +426,11 @@ impl Rant {
     }
   }
 
+  /// Finds the file that `[require]` would load for the specified module path.
+  ///
+  /// `dependant_path` is the path of the program requiring the module, if any; its directory is searched first.
   #[inline]
-  fn find_module_path(&self, module_path: &str, dependant_path: Option<&str>) -> Option<PathBuf> {
+  pub fn find_module_path(&self, module_path: &str, dependant_path: Option<&str>) -> Option<PathBuf> {
     let module_path = PathBuf::from(
         module_path.replace("/", &String::from(std::path::MAIN_SEPARATOR))
       )
@@ -484,6 +509,11 @@ pub struct RantOptions {
   /// Specifies a preferred module loading path with higher precedence than the global module path.
   /// If not specified, looks in the current working directory.
   pub local_modules_path: Option<String>,
+  /// Specifies how much compiled programs are optimized. Defaults to `OptimizationLevel::Basic`.
+  pub optimization_level: OptimizationLevel,
+  /// Promotes integers to arbitrary-precision integers when arithmetic on them overflows.
+  /// If disabled, integer overflow raises an `ARITHMETIC_ERROR` instead. Defaults to `false`.
+  pub enable_big_ints: bool,
 }
 
 impl Default for RantOptions {
@@ -495,6 +525,8 @@ impl Default for RantOptions {
       enable_require: true,
       enable_global_modules: true,
       local_modules_path: None,
+      optimization_level: Default::default(),
+      enable_big_ints: false,
     }
   }
 }
@@ -531,10 +563,18 @@ impl RantProgram {
   pub fn info(&self) -> &RantProgramInfo {
     self.info.as_ref()
   }
+
+  /// Gets the root sequence of the program's syntax tree.
+  ///
+  /// Use an [`RstVisitor`](lang/trait.RstVisitor.html) to traverse it.
+  #[inline]
+  pub fn root(&self) -> &Sequence {
+    self.root.as_ref()
+  }
 }
 
 /// Contains metadata used to identify a loaded program.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RantProgramInfo {
   path: Option<String>,
   name: Option<String>,
@@ -555,7 +595,7 @@ impl RantProgramInfo {
 }
 
 /// Represents an error that occurred when attempting to load a Rant module.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ModuleLoadError {
   name: String,
   reason: ModuleLoadErrorReason,
@@ -576,7 +616,7 @@ impl ModuleLoadError {
 }
 
 /// Represents the reason for which a Rant module failed to load.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ModuleLoadErrorReason {
   /// The module could not load because the calling context has module loading disabled.
   NotAllowed,
@@ -610,6 +650,7 @@ impl IntoRuntimeResult<RantProgram> for ModuleLoadResult {
       error_type: RuntimeErrorType::ModuleLoadError(err),
       description: None,
       stack_trace: None,
+      stack_frames: None,
     })
   }
 }
\ No newline at end of file
@@ -0,0 +1,327 @@
+use std::{collections::{BTreeMap, HashMap}, fmt::Write};
+use crate::{compiler::Position, lang::*};
+use super::*;
+use super::profiler::origin_name;
+
+/// Coverage data collected for a single block element.
+#[derive(Debug, Clone)]
+pub struct ElementCoverage {
+  /// The location of the element in its source.
+  pub pos: Position,
+  /// The number of times the element was selected.
+  pub hits: u64,
+}
+
+/// Coverage data collected for a single block.
+#[derive(Debug, Clone)]
+pub struct BlockCoverage {
+  /// The origin name (path or program name) of the block.
+  pub origin: String,
+  /// The location of the block in its source.
+  pub pos: Position,
+  /// The number of times the block was reached.
+  pub entries: u64,
+  /// The elements of the block, in source order.
+  pub elements: Vec<ElementCoverage>,
+}
+
+impl BlockCoverage {
+  /// Returns `true` if every element of the block has been selected at least once.
+  #[inline]
+  pub fn is_fully_covered(&self) -> bool {
+    self.elements.iter().all(|elem| elem.hits > 0)
+  }
+
+  /// Iterates over the indices and data of the elements that have never been selected.
+  pub fn uncovered_elements(&self) -> impl Iterator<Item = (usize, &ElementCoverage)> {
+    self.elements.iter().enumerate().filter(|(_, elem)| elem.hits == 0)
+  }
+}
+
+/// Records how often each block element is selected across one or more program runs.
+///
+/// Programs passed to `Coverage::add_program()` (and modules they require) have all of their blocks registered up front,
+/// so blocks that are never reached still show up in reports.
+#[derive(Debug, Default)]
+pub struct Coverage {
+  blocks: Vec<BlockCoverage>,
+  /// Maps origin names and block start offsets to indices in `blocks`.
+  block_indices: HashMap<String, HashMap<usize, usize>>,
+}
+
+impl Coverage {
+  /// Creates a new, empty coverage collector.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Registers every block in a program so that it is included in reports even if it never runs.
+  pub fn add_program(&mut self, program: &RantProgram) {
+    self.add_sequence(&program.root);
+  }
+
+  pub(crate) fn add_sequence(&mut self, seq: &Sequence) {
+    let origin = origin_name(&seq.origin);
+    for rst in seq.iter() {
+      self.add_rst(origin, rst);
+    }
+  }
+
+  fn add_access_path(&mut self, path: &AccessPath) {
+    for expr in path.dynamic_exprs() {
+      self.add_sequence(&expr);
+    }
+  }
+
+  fn add_params(&mut self, params: &[Parameter]) {
+    for default_expr in params.iter().filter_map(|param| param.default_value_expr.as_ref()) {
+      self.add_sequence(default_expr);
+    }
+  }
+
+  fn add_call(&mut self, call: &FunctionCall) {
+    match &call.target {
+      FunctionCallTarget::Path(path) => self.add_access_path(path),
+      FunctionCallTarget::Expression(expr) => self.add_sequence(expr),
+    }
+    for arg in call.arguments.iter() {
+      self.add_sequence(&arg.expr);
+    }
+  }
+
+  fn add_rst(&mut self, origin: &str, rst: &Rst) {
+    match rst {
+      Rst::Sequence(seq) => self.add_sequence(seq),
+      Rst::Block(block) => {
+        self.block_index(origin, block);
+        for elem in block.elements.iter() {
+          if let Some(BlockWeight::Dynamic(weight_expr)) = &elem.weight {
+            self.add_sequence(weight_expr);
+          }
+          self.add_sequence(&elem.main);
+        }
+      },
+      Rst::ListInit(items) | Rst::SetInit(items) => {
+        for item in items.iter() {
+          self.add_sequence(item);
+        }
+      },
+      Rst::MapInit(entries) => {
+        for (key, value) in entries.iter() {
+          if let MapKeyExpr::Dynamic(key_expr) = key {
+            self.add_sequence(key_expr);
+          }
+          self.add_sequence(value);
+        }
+      },
+      Rst::InterpolatedString(parts) => {
+        for part in parts.iter() {
+          if let StringPart::Expr(expr) = part {
+            self.add_sequence(expr);
+          }
+        }
+      },
+      Rst::Match(expr) => {
+        self.add_sequence(&expr.value);
+        for arm in expr.arms.iter() {
+          self.add_sequence(&arm.body);
+        }
+      },
+      Rst::Lambda(lambda) => {
+        self.add_params(&lambda.params);
+        self.add_sequence(&lambda.body);
+      },
+      Rst::FuncCall(call) => self.add_call(call),
+      Rst::PipedCall(piped_call) => {
+        for call in piped_call.steps.iter() {
+          self.add_call(call);
+        }
+      },
+      Rst::FuncDef(def) => {
+        self.add_access_path(&def.path);
+        self.add_params(&def.params);
+        self.add_sequence(&def.body);
+      },
+      Rst::Get(path, fallback) => {
+        self.add_access_path(path);
+        if let Some(fallback) = fallback {
+          self.add_sequence(fallback);
+        }
+      },
+      Rst::Set(path, value) => {
+        self.add_access_path(path);
+        self.add_sequence(value);
+      },
+      Rst::DefVar(_, _, Some(expr))
+      | Rst::DefConst(_, _, Some(expr))
+      | Rst::Depth(_, _, Some(expr))
+      | Rst::Return(Some(expr))
+      | Rst::Continue(Some(expr))
+      | Rst::Break(Some(expr))
+      | Rst::Yield(Some(expr)) => self.add_sequence(expr),
+      _ => {}
+    }
+  }
+
+  /// Gets the index of a block's coverage data, registering the block if needed.
+  fn block_index(&mut self, origin: &str, block: &Block) -> usize {
+    let block_start = block.pos.span().start;
+    if let Some(index) = self.block_indices.get(origin).and_then(|blocks| blocks.get(&block_start)) {
+      return *index
+    }
+
+    let index = self.blocks.len();
+    // Elements removed by the optimizer are never selected, but are still part of the block
+    let mut elements: Vec<ElementCoverage> = block.elements
+      .iter()
+      .map(|elem| &elem.pos)
+      .chain(block.removed_elements.iter())
+      .map(|pos| ElementCoverage {
+        pos: pos.clone(),
+        hits: 0,
+      })
+      .collect();
+    elements.sort_by_key(|elem| elem.pos.span().start);
+
+    self.blocks.push(BlockCoverage {
+      origin: origin.to_owned(),
+      pos: block.pos.clone(),
+      entries: 0,
+      elements,
+    });
+    self.block_indices.entry(origin.to_owned()).or_default().insert(block_start, index);
+    index
+  }
+
+  /// Records that a block was reached.
+  pub(crate) fn enter_block(&mut self, origin: &str, block: &Block) {
+    let index = self.block_index(origin, block);
+    self.blocks[index].entries += 1;
+  }
+
+  /// Records that a block element was selected.
+  pub(crate) fn hit_element(&mut self, origin: &str, block_pos: &Position, elem_pos: &Position) {
+    let elem_start = elem_pos.span().start;
+    let block_index = self.block_indices
+      .get(origin)
+      .and_then(|blocks| blocks.get(&block_pos.span().start))
+      .copied();
+
+    if let Some(elem) = block_index.and_then(|index| self.blocks[index].elements.iter_mut().find(|elem| elem.pos.span().start == elem_start)) {
+      elem.hits += 1;
+    }
+  }
+
+  /// Gets the collected data for each block, sorted by origin and position.
+  pub fn blocks(&self) -> Vec<&BlockCoverage> {
+    let mut blocks: Vec<&BlockCoverage> = self.blocks.iter().collect();
+    blocks.sort_by(|a, b| a.origin.cmp(&b.origin).then_with(|| a.pos.span().start.cmp(&b.pos.span().start)));
+    blocks
+  }
+
+  /// Gets the total number of registered block elements.
+  pub fn element_count(&self) -> usize {
+    self.blocks.iter().map(|block| block.elements.len()).sum()
+  }
+
+  /// Gets the number of registered block elements that have been selected at least once.
+  pub fn covered_element_count(&self) -> usize {
+    self.blocks.iter().map(|block| block.elements.iter().filter(|elem| elem.hits > 0).count()).sum()
+  }
+
+  /// Groups the sorted blocks by origin.
+  fn blocks_by_origin(&self) -> Vec<(&str, Vec<&BlockCoverage>)> {
+    let mut files: Vec<(&str, Vec<&BlockCoverage>)> = vec![];
+    for block in self.blocks() {
+      match files.last_mut() {
+        Some((origin, blocks)) if *origin == block.origin.as_str() => blocks.push(block),
+        _ => files.push((block.origin.as_str(), vec![block])),
+      }
+    }
+    files
+  }
+
+  /// Generates an lcov tracefile.
+  ///
+  /// Each block is reported as a branch point with one branch per element; element lines are reported as line data.
+  pub fn lcov_report(&self) -> String {
+    let mut report = String::new();
+    for (origin, blocks) in self.blocks_by_origin() {
+      writeln!(report, "TN:").unwrap();
+      writeln!(report, "SF:{}", origin).unwrap();
+
+      let mut branches_hit = 0;
+      let mut line_hits: BTreeMap<usize, u64> = BTreeMap::new();
+      for (block_id, block) in blocks.iter().enumerate() {
+        for (elem_index, elem) in block.elements.iter().enumerate() {
+          if block.entries == 0 {
+            writeln!(report, "BRDA:{},{},{},-", block.pos.line(), block_id, elem_index).unwrap();
+          } else {
+            writeln!(report, "BRDA:{},{},{},{}", block.pos.line(), block_id, elem_index, elem.hits).unwrap();
+          }
+          if elem.hits > 0 {
+            branches_hit += 1;
+          }
+          *line_hits.entry(elem.pos.line()).or_default() += elem.hits;
+        }
+      }
+      writeln!(report, "BRF:{}", blocks.iter().map(|block| block.elements.len()).sum::<usize>()).unwrap();
+      writeln!(report, "BRH:{}", branches_hit).unwrap();
+
+      for (line, hits) in line_hits.iter() {
+        writeln!(report, "DA:{},{}", line, hits).unwrap();
+      }
+      writeln!(report, "LF:{}", line_hits.len()).unwrap();
+      writeln!(report, "LH:{}", line_hits.values().filter(|hits| **hits > 0).count()).unwrap();
+      writeln!(report, "end_of_record").unwrap();
+    }
+    report
+  }
+
+  /// Generates a JSON report listing every block and element with its source location and hit count.
+  pub fn json_report(&self) -> String {
+    let mut report = String::new();
+    report.push_str("{\"files\":[");
+    for (file_index, (origin, blocks)) in self.blocks_by_origin().iter().enumerate() {
+      if file_index > 0 { report.push(','); }
+      report.push_str("{\"path\":");
+      write_json_string(&mut report, origin).unwrap();
+      report.push_str(",\"blocks\":[");
+      for (block_index, block) in blocks.iter().enumerate() {
+        if block_index > 0 { report.push(','); }
+        write!(report, "{{{},\"entries\":{},\"elements\":[", json_pos_fields(&block.pos), block.entries).unwrap();
+        for (elem_index, elem) in block.elements.iter().enumerate() {
+          if elem_index > 0 { report.push(','); }
+          write!(report, "{{\"index\":{},{},\"hits\":{}}}", elem_index, json_pos_fields(&elem.pos), elem.hits).unwrap();
+        }
+        report.push_str("]}");
+      }
+      report.push_str("]}");
+    }
+    report.push_str("]}");
+    report
+  }
+}
+
+fn json_pos_fields(pos: &Position) -> String {
+  let span = pos.span();
+  format!("\"line\":{},\"col\":{},\"start\":{},\"end\":{}", pos.line(), pos.col(), span.start, span.end)
+}
+
+impl<'rant> VM<'rant> {
+  /// Attaches a coverage collector to the VM.
+  #[inline]
+  pub(crate) fn with_coverage(mut self, coverage: &'rant mut Coverage) -> Self {
+    coverage.add_program(self.program);
+    self.coverage = Some(coverage);
+    self
+  }
+
+  /// Registers the blocks of a newly loaded module with the coverage collector, if any.
+  #[inline]
+  pub(crate) fn cover_module(&mut self, root: &Sequence) {
+    if let Some(coverage) = self.coverage.as_mut() {
+      coverage.add_sequence(root);
+    }
+  }
+}
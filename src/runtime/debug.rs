@@ -0,0 +1,255 @@
+use crate::lang::DebugInfo;
+use super::*;
+
+/// Describes why the VM paused execution and handed control to a debugger.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PauseReason {
+  /// The program is about to run its first line.
+  Entry,
+  /// The VM reached a line with a breakpoint on it.
+  Breakpoint,
+  /// A step requested by the debugger has completed.
+  Step,
+  /// The debugger asked the running program to pause.
+  Pause,
+}
+
+/// Tells the VM how to resume execution after a debugger pause.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DebugAction {
+  /// Run until the next breakpoint.
+  Continue,
+  /// Pause on the next line, entering any frames pushed along the way.
+  StepIn,
+  /// Pause on the next line in the current frame or one of its callers.
+  StepOver,
+  /// Pause on the next line after the current frame returns.
+  StepOut,
+}
+
+/// A source line on which the VM pauses.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Breakpoint {
+  /// The origin name (path or program name) the breakpoint applies to.
+  /// If `None`, the breakpoint applies to every program.
+  pub source: Option<String>,
+  /// The line number of the breakpoint.
+  pub line: usize,
+}
+
+impl Breakpoint {
+  /// Creates a breakpoint on a line in any program.
+  #[inline]
+  pub fn new(line: usize) -> Self {
+    Self {
+      source: None,
+      line,
+    }
+  }
+
+  /// Creates a breakpoint on a line in the program with the specified origin name.
+  #[inline]
+  pub fn with_source(source: impl Into<String>, line: usize) -> Self {
+    Self {
+      source: Some(source.into()),
+      line,
+    }
+  }
+
+  #[inline]
+  fn matches(&self, origin_name: &str, line: usize) -> bool {
+    self.line == line && !matches!(self.source.as_deref(), Some(source) if source != origin_name)
+  }
+}
+
+/// Implemented by debugger front ends to control a program run by the VM.
+///
+/// Programs must be compiled with `debug_mode` enabled; the VM only stops on the debug cursors emitted by the compiler.
+pub trait Debugger {
+  /// Called once before the program starts running.
+  /// The returned action determines where the VM first pauses; return `DebugAction::StepIn` to stop on entry.
+  fn on_start(&mut self, vm: &mut VM) -> DebugAction {
+    let _ = vm;
+    DebugAction::Continue
+  }
+
+  /// Called whenever the VM pauses. The returned action determines where the VM pauses next.
+  fn on_pause(&mut self, vm: &mut VM, reason: PauseReason) -> DebugAction;
+
+  /// Called on every debug cursor the VM passes while running. Return `true` to pause right away.
+  fn poll_pause(&mut self, vm: &mut VM) -> bool {
+    let _ = vm;
+    false
+  }
+}
+
+/// Stores the breakpoints and stepping state of a debugger attached to the VM.
+#[derive(Default)]
+pub(crate) struct DebugState {
+  breakpoints: Vec<Breakpoint>,
+  /// The pending step action and the call stack size when it was requested.
+  step: Option<(DebugAction, usize)>,
+  /// Indicates whether the VM has paused at least once.
+  has_paused: bool,
+}
+
+impl DebugState {
+  #[inline]
+  fn set_action(&mut self, action: DebugAction, call_stack_size: usize) {
+    self.step = match action {
+      DebugAction::Continue => None,
+      action => Some((action, call_stack_size)),
+    };
+  }
+
+  /// Determines whether the VM should pause on a line at the specified call stack size.
+  #[inline]
+  fn check(&self, origin_name: &str, line: usize, call_stack_size: usize) -> Option<PauseReason> {
+    let step_done = match self.step {
+      Some((DebugAction::StepIn, _)) => true,
+      Some((DebugAction::StepOver, requested_size)) => call_stack_size <= requested_size,
+      Some((DebugAction::StepOut, requested_size)) => call_stack_size < requested_size,
+      Some((DebugAction::Continue, _)) | None => false,
+    };
+
+    if step_done {
+      return Some(if self.has_paused { PauseReason::Step } else { PauseReason::Entry })
+    }
+
+    if self.breakpoints.iter().any(|bp| bp.matches(origin_name, line)) {
+      return Some(PauseReason::Breakpoint)
+    }
+
+    None
+  }
+}
+
+impl<'rant> VM<'rant> {
+  /// Attaches a debugger to the VM.
+  #[inline]
+  pub(crate) fn with_debugger(mut self, debugger: &'rant mut dyn Debugger) -> Self {
+    self.debugger = Some(debugger);
+    self
+  }
+
+  /// Notifies the attached debugger that the program is starting.
+  pub(crate) fn debug_start(&mut self) {
+    if let Some(debugger) = self.debugger.take() {
+      let action = debugger.on_start(self);
+      self.debug_state.set_action(action, self.call_stack.len());
+      self.debugger = Some(debugger);
+    }
+  }
+
+  /// Moves the current frame to a new debug cursor and pauses if a breakpoint or step is hit.
+  #[inline]
+  pub(crate) fn debug_cursor(&mut self, info: &DebugInfo) {
+    let prev_line = self.cur_frame().debug_pos().0;
+    self.cur_frame_mut().set_debug_info(info);
+
+    if self.debugger.is_none() {
+      return
+    }
+
+    // Pause requests can arrive in the middle of a line, so check for them on every cursor
+    if self.debug_poll() {
+      self.debug_pause(PauseReason::Pause);
+      return
+    }
+
+    let DebugInfo::Location { line, .. } = *info;
+
+    // Only stop once per line in each frame
+    if line == prev_line {
+      return
+    }
+
+    // Frames that start on the line their caller is already on continue that line, unless they run a new function invocation
+    let frame = self.cur_frame();
+    if prev_line == 0 && frame.flavor() != StackFrameFlavor::FunctionBody {
+      let caller_location = self.call_stack.frames().skip(1).find(|parent| parent.debug_pos().0 > 0);
+      if caller_location.is_some_and(|parent| parent.debug_pos().0 == line && Rc::ptr_eq(parent.origin(), frame.origin())) {
+        return
+      }
+    }
+
+    let call_stack_size = self.call_stack.len();
+    if let Some(reason) = self.debug_state.check(self.cur_frame().origin_name(), line, call_stack_size) {
+      self.debug_pause(reason);
+    }
+  }
+
+  #[inline]
+  fn debug_poll(&mut self) -> bool {
+    match self.debugger.take() {
+      Some(debugger) => {
+        let pause = debugger.poll_pause(self);
+        self.debugger = Some(debugger);
+        pause
+      },
+      None => false,
+    }
+  }
+
+  #[inline]
+  fn debug_pause(&mut self, reason: PauseReason) {
+    if let Some(debugger) = self.debugger.take() {
+      self.debug_state.has_paused = true;
+      let action = debugger.on_pause(self, reason);
+      self.debug_state.set_action(action, self.call_stack.len());
+      self.debugger = Some(debugger);
+    }
+  }
+
+  /// Returns `true` if a debugger is attached to the VM.
+  #[inline]
+  pub fn is_debugging(&self) -> bool {
+    self.debugger.is_some()
+  }
+
+  /// Adds a breakpoint. Returns `false` if an identical breakpoint already exists.
+  pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+    if self.debug_state.breakpoints.contains(&breakpoint) {
+      return false
+    }
+    self.debug_state.breakpoints.push(breakpoint);
+    true
+  }
+
+  /// Removes a breakpoint. Returns `false` if no such breakpoint exists.
+  pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+    let prev_len = self.debug_state.breakpoints.len();
+    self.debug_state.breakpoints.retain(|bp| bp != breakpoint);
+    prev_len != self.debug_state.breakpoints.len()
+  }
+
+  /// Removes all breakpoints.
+  #[inline]
+  pub fn clear_breakpoints(&mut self) {
+    self.debug_state.breakpoints.clear();
+  }
+
+  /// Gets the active breakpoints.
+  #[inline]
+  pub fn breakpoints(&self) -> &[Breakpoint] {
+    &self.debug_state.breakpoints
+  }
+
+  /// Gets a reference to the call stack.
+  #[inline(always)]
+  pub fn call_stack(&self) -> &CallStack<Intent> {
+    &self.call_stack
+  }
+
+  /// Gets the contents of the value stack, from bottom to top.
+  #[inline(always)]
+  pub fn value_stack(&self) -> &[RantValue] {
+    &self.val_stack
+  }
+
+  /// Looks up the value of a variable as it is currently visible to the topmost frame.
+  #[inline]
+  pub fn inspect_var(&self, varname: &str) -> Option<RantValue> {
+    self.get_var_value(varname, AccessPathKind::Local, false).ok()
+  }
+}
@@ -1,13 +1,13 @@
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, rc::Rc};
 
-use crate::{IndexError, KeyError, ModuleLoadError, SliceError, ValueError, data::DataSourceError};
+use crate::{DEFAULT_PROGRAM_NAME, IndexError, KeyError, ModuleLoadError, RantMap, RantProgramInfo, RantSpecial, RantValue, SliceError, ValueError, data::DataSourceError};
 
-use super::{resolver::SelectorError};
+use super::{StackFrameFlavor, resolver::SelectorError};
 
 /// Type alias for `Result<T, RuntimeError>`
 pub type RuntimeResult<T> = Result<T, RuntimeError>;
 /// A runtime error raised by a Rant program.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RuntimeError {
   /// The type of runtime error.
   pub error_type: RuntimeErrorType,
@@ -15,6 +15,88 @@ pub struct RuntimeError {
   pub description: Option<String>,
   /// A stack trace describing the location of the error.
   pub stack_trace: Option<String>,
+  /// The frames of the stack trace, from the innermost frame to the outermost one.
+  pub stack_frames: Option<Box<[StackTraceFrame]>>,
+}
+
+/// A frame (or run of identical consecutive frames) in the stack trace of a runtime error.
+#[derive(Debug, Clone)]
+pub struct StackTraceFrame {
+  /// The name of the function the frame belongs to, or a description of the frame's flavor if it is unnamed.
+  pub name: String,
+  /// The kind of program element the frame represents.
+  pub flavor: StackFrameFlavor,
+  /// Information about the program the frame's code came from, shared with the program itself.
+  pub origin: Rc<RantProgramInfo>,
+  /// The last line/col reached in the frame, if the program was compiled with debug symbols.
+  pub pos: Option<(usize, usize)>,
+  /// The number of consecutive identical frames represented by this frame.
+  pub count: usize,
+}
+
+impl StackTraceFrame {
+  /// Gets the path or name of the program the frame's code came from.
+  #[inline]
+  pub fn origin_name(&self) -> &str {
+    self.origin.path()
+      .or_else(|| self.origin.name())
+      .unwrap_or(DEFAULT_PROGRAM_NAME)
+  }
+
+  /// Determines whether two frames would appear the same in a stack trace.
+  #[inline]
+  pub(crate) fn is_same_location(&self, other: &Self) -> bool {
+    self.name == other.name && self.pos == other.pos && self.origin_name() == other.origin_name()
+  }
+}
+
+impl Display for StackTraceFrame {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.pos {
+      Some((line, col)) => write!(f, "[{}:{}:{}] in {}", self.origin_name(), line, col, self.name)?,
+      None => write!(f, "[{}] in {}", self.origin_name(), self.name)?,
+    }
+    if self.count > 1 {
+      write!(f, " ({} frames)", self.count)?;
+    }
+    Ok(())
+  }
+}
+
+/// Renders stack trace frames in the same format as `RuntimeError::stack_trace`.
+pub fn format_stack_trace(frames: &[StackTraceFrame]) -> String {
+  frames.iter().map(|frame| format!("-> {}", frame)).collect::<Vec<String>>().join("\n")
+}
+
+impl RuntimeError {
+  /// Converts the error into the map that `[try]` passes to its handler.
+  ///
+  /// The map has the error's `id` and `message`, plus its `payload` and `trace` if it has them.
+  /// The error itself is kept under `source` so that `[rethrow]` can raise it again unchanged.
+  pub fn to_rant_map(&self) -> RantMap {
+    let mut map = RantMap::new();
+    map.raw_set("source", RantValue::Special(RantSpecial::Error(Rc::new(self.clone()))));
+    map.raw_set("id", RantValue::String(self.error_type.id().into()));
+    map.raw_set("message", RantValue::String(self.description.clone().unwrap_or_else(|| self.error_type.to_string()).into()));
+    if let RuntimeErrorType::UserError(err) = &self.error_type {
+      if let Some(payload) = &err.payload {
+        map.raw_set("payload", payload.clone());
+      }
+    }
+    if let Some(trace) = &self.stack_trace {
+      map.raw_set("trace", RantValue::String(trace.as_str().into()));
+    }
+    map
+  }
+}
+
+/// Details of an error raised by a program with `[error]` or `[rethrow]`.
+#[derive(Debug, Default, Clone)]
+pub struct UserError {
+  /// The ID given to the error in place of `USER_ERROR`.
+  pub id: Option<String>,
+  /// The value the error was raised with.
+  pub payload: Option<RantValue>,
 }
 
 impl Error for RuntimeError {
@@ -45,7 +127,7 @@ impl Display for RuntimeError {
 }
 
 /// Provides general categories of runtime errors encountered in Rant.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum RuntimeErrorType {
   /// Stack has overflowed.
   ///
@@ -91,6 +173,10 @@ pub enum RuntimeErrorType {
   ///
   /// Rant error ID: `VALUE_ERROR`
   ValueError(ValueError),
+  /// Integer arithmetic overflowed
+  ///
+  /// Rant error ID: `ARITHMETIC_ERROR`
+  ArithmeticError,
   /// Error occurred while indexing value
   ///
   /// Rant error ID: `INDEX_ERROR`
@@ -113,8 +199,8 @@ pub enum RuntimeErrorType {
   ModuleLoadError(ModuleLoadError),
   /// Error manually triggered by program
   ///
-  /// Rant error ID: `USER_ERROR`
-  UserError,
+  /// Rant error ID: `USER_ERROR`, unless the program gave it another ID
+  UserError(Box<UserError>),
   /// Error during control flow operation (e.g. return or break)
   ///
   /// Rant error ID: `CONTROL_FLOW_ERROR`
@@ -123,10 +209,15 @@ pub enum RuntimeErrorType {
   ///
   /// Rant error ID: `DATA_SOURCE_ERROR`
   DataSourceError(DataSourceError),
+  /// `[retry]` ran out of attempts before its predicate accepted a generated value.
+  /// Contains the number of attempts made.
+  ///
+  /// Rant error ID: `RETRY_ERROR`
+  RetryError(usize),
 }
 
 impl RuntimeErrorType {
-  pub fn id(&self) -> &'static str {
+  pub fn id(&self) -> &str {
     match self {
       Self::StackOverflow => "STACK_OVERFLOW_ERROR",
       Self::StackUnderflow => "STACK_UNDERFLOW_ERROR",
@@ -136,10 +227,11 @@ impl RuntimeErrorType {
       Self::ArgumentMismatch => "ARG_MISMATCH_ERROR",
       Self::ArgumentError => "ARG_ERROR",
       Self::CannotInvokeValue => "INVOKE_ERROR",
-      Self::UserError => "USER_ERROR",
+      Self::UserError(err) => err.id.as_deref().unwrap_or("USER_ERROR"),
       Self::AssertError => "ASSERT_ERROR",
       Self::TypeError => "TYPE_ERROR",
       Self::ValueError(_) => "VALUE_ERROR",
+      Self::ArithmeticError => "ARITHMETIC_ERROR",
       Self::IndexError(_) => "INDEX_ERROR",
       Self::KeyError(_) => "KEY_ERROR",
       Self::SliceError(_) => "SLICE_ERROR",
@@ -147,6 +239,7 @@ impl RuntimeErrorType {
       Self::ModuleLoadError(_) => "MODULE_ERROR",
       Self::ControlFlowError => "CONTROL_FLOW_ERROR",
       Self::DataSourceError(_) => "DATA_SOURCE_ERROR",
+      Self::RetryError(_) => "RETRY_ERROR",
     }
   }
 }
@@ -161,6 +254,7 @@ impl Display for RuntimeErrorType {
       Self::SelectorError(e) => write!(f, "{}", e),
       Self::ModuleLoadError(e) => write!(f, "{}", e),
       Self::DataSourceError(e) => write!(f, "{}", e),
+      Self::RetryError(attempts) => write!(f, "no acceptable value was generated after {} {}", attempts, if *attempts == 1 { "attempt" } else { "attempts" }),
       _ => write!(f, "{}", self.id()),
     }
   }
@@ -0,0 +1,134 @@
+use std::mem;
+use super::*;
+
+/// Type alias for `Rc<RefCell<GeneratorState>>`
+pub(crate) type GeneratorStateRef = Rc<RefCell<GeneratorState>>;
+
+/// Tracks how far a generator's function has run.
+pub(crate) enum GeneratorState {
+  /// The function hasn't been called yet.
+  Ready { func: RantFunctionRef, args: Vec<RantValue> },
+  /// The function is paused at a `@yield`.
+  Suspended(SuspendedGenerator),
+  /// The function is on the call stack.
+  Running,
+  /// The function has returned.
+  Finished,
+}
+
+/// The state of a generator set aside by `@yield`.
+pub(crate) struct SuspendedGenerator {
+  frames: SuspendedFrames<Intent>,
+  blocks: Vec<BlockState>,
+  values: Vec<RantValue>,
+}
+
+/// A generator whose function is on the call stack.
+pub(crate) struct ActiveGenerator {
+  state: GeneratorStateRef,
+  /// Index of the generator function's body frame in the call stack
+  body_frame_index: usize,
+  value_stack_size: usize,
+  block_stack_size: usize,
+}
+
+impl<'rant> VM<'rant> {
+  /// Runs a generator's function until it yields or returns, then passes the yielded value (or `None` if it returned) to `then`.
+  ///
+  /// The function is called the first time the generator is resumed; after that, it continues from the `@yield` it stopped at.
+  pub(crate) fn resume_generator(&mut self, state: GeneratorStateRef, then: IterNextCallback) -> RuntimeResult<()> {
+    if matches!(*state.borrow(), GeneratorState::Running) {
+      runtime_error!(RuntimeErrorType::ControlFlowError, "generator is already running");
+    }
+    if matches!(*state.borrow(), GeneratorState::Finished) {
+      return then(self, None)
+    }
+    let prev_state = mem::replace(&mut *state.borrow_mut(), GeneratorState::Running);
+
+    // Find out what the generator did once control comes back to the caller
+    self.cur_frame_mut().push_intent_front(Intent::RuntimeCall {
+      function: Box::new(move |vm| vm.finish_generator_step(then)),
+      interrupt: false,
+    });
+
+    self.generators.push(ActiveGenerator {
+      state,
+      body_frame_index: self.call_stack.len(),
+      value_stack_size: self.val_stack.len(),
+      block_stack_size: self.resolver.block_stack_len(),
+    });
+
+    match prev_state {
+      // Generators only produce values through `@yield`, so the function's output is discarded
+      GeneratorState::Ready { func, args } => self.call_func(func, args, PrintFlag::Sink, false)?,
+      GeneratorState::Suspended(SuspendedGenerator { frames, blocks, values }) => {
+        if self.call_stack.len() + frames.len() > MAX_STACK_SIZE {
+          runtime_error!(RuntimeErrorType::StackOverflow, "call stack has overflowed");
+        }
+        for block in blocks {
+          self.resolver.restore_block(block);
+        }
+        self.val_stack.extend(values);
+        self.call_stack.resume_frames(frames);
+      },
+      GeneratorState::Running | GeneratorState::Finished => unreachable!(),
+    }
+
+    Ok(())
+  }
+
+  /// Passes the value yielded by the generator that just ran to `then`, or `None` if its function returned.
+  fn finish_generator_step(&mut self, then: IterNextCallback) -> RuntimeResult<()> {
+    let active = match self.generators.pop() {
+      Some(active) => active,
+      None => runtime_error!(RuntimeErrorType::InternalError, "generator stack has underflowed"),
+    };
+
+    if matches!(*active.state.borrow(), GeneratorState::Suspended(_)) {
+      let val = self.pop_val()?;
+      return then(self, Some(val))
+    }
+
+    // Drop anything the function returned
+    self.val_stack.truncate(active.value_stack_size);
+    *active.state.borrow_mut() = GeneratorState::Finished;
+    then(self, None)
+  }
+
+  /// Suspends the running generator and passes a value back to the code that resumed it.
+  pub fn yield_value(&mut self, val: RantValue) -> RuntimeResult<()> {
+    let body_depth = self.call_stack.taste_for_first(StackFrameFlavor::FunctionBody);
+    let (state, frame_count, value_stack_size, block_stack_size) = match (self.generators.last(), body_depth) {
+      // Only the generator's own function body can yield, not functions it calls
+      (Some(active), Some(depth)) if self.call_stack.len() - depth - 1 == active.body_frame_index => {
+        (Rc::clone(&active.state), depth + 1, active.value_stack_size, active.block_stack_size)
+      },
+      _ => runtime_error!(RuntimeErrorType::ControlFlowError, "no reachable generator to yield from"),
+    };
+
+    let mut blocks = vec![];
+    while self.resolver.block_stack_len() > block_stack_size {
+      blocks.extend(self.resolver.pop_block());
+    }
+    blocks.reverse();
+
+    let values = self.val_stack.drain(value_stack_size..).collect();
+    let frames = self.call_stack.suspend_frames(frame_count);
+    if let Some(profiler) = self.profiler.as_mut() {
+      profiler.exit_to(self.call_stack.len());
+    }
+
+    *state.borrow_mut() = GeneratorState::Suspended(SuspendedGenerator { frames, blocks, values });
+    self.push_val(val)?;
+    Ok(())
+  }
+
+  /// Stops any generators whose functions were removed from the call stack by an unwind.
+  pub(crate) fn unwind_generators(&mut self) {
+    while self.generators.last().is_some_and(|active| active.body_frame_index >= self.call_stack.len()) {
+      if let Some(active) = self.generators.pop() {
+        *active.state.borrow_mut() = GeneratorState::Finished;
+      }
+    }
+  }
+}
@@ -1,9 +1,9 @@
 use std::rc::Rc;
 
 use crate::lang::*;
-use crate::{RantFunctionRef, RantList, RantMap, RantValue};
+use crate::{InternalString, RantFunctionRef, RantList, RantMap, RantSet, RantValue};
 
-use super::{RuntimeResult, SetterValueSource, VM, VarWriteMode, resolver::Weights};
+use super::{NamedArgs, RuntimeResult, SetterValueSource, VM, VarWriteMode, resolver::Weights};
 
 // Intents are actions queued on a stack frame that are performed before the frame runs.
 ///
@@ -20,6 +20,8 @@ pub enum Intent {
   ContinueLast,
   /// Pops a value off the value stack and breaks from the current repeater with it.
   BreakLast,
+  /// Pops a value off the value stack and yields it from the current generator.
+  YieldLast,
   /// Pops a map off the stack and loads it as a module with the specified name.
   ImportLastAsModule { module_name: String, descope: usize },
   /// Check if the active block is finished and either continue the block or pop the state from the stack
@@ -63,12 +65,20 @@ pub enum Intent {
   CreateDefaultArgs { context: RantFunctionRef, default_arg_exprs: Vec<(Rc<Sequence>, usize)>, eval_index: usize, },
   /// Pop `argc` args off the stack, then pop a function off the stack and call it with the args.
   Call { argc: usize, flag: PrintFlag, override_print: bool },
+  /// Pop a value off the stack, raise a type error if the return type doesn't accept it, and push it back.
+  CheckReturnType { return_type: Rc<TypeAnnotation> },
   /// Call a function for every variant of a temporal argument set and increment the provided temporal state.
-  CallTemporal { func: RantFunctionRef, args: Rc<Vec<RantValue>>, temporal_state: TemporalSpreadState, flag: PrintFlag, },
+  CallTemporal { func: RantFunctionRef, args: Rc<Vec<RantValue>>, named_args: Rc<NamedArgs>, temporal_state: TemporalSpreadState, flag: PrintFlag, },
   /// Pop value from stack and add it to a list. If `index` is out of range, print the list.
   BuildList { init: Rc<Vec<Rc<Sequence>>>, index: usize, list: RantList },
   /// Pop value and optional key from stack and add them to a map. If `pair_index` is out of range, print the map.
   BuildMap { init: Rc<Vec<(MapKeyExpr, Rc<Sequence>)>>, pair_index: usize, map: RantMap },
+  /// Pop value from stack and add it to a set. If `index` is out of range, print the set.
+  BuildSet { init: Rc<Vec<Rc<Sequence>>>, index: usize, set: RantSet },
+  /// Pop value from stack, format it, and append it to a string. If `index` is out of range, print the string.
+  BuildString { parts: Rc<Vec<StringPart>>, index: usize, string: InternalString },
+  /// Pop a value off the stack and run the body of the first arm in `expr` that matches it.
+  SelectMatchArm { expr: Rc<MatchExpr> },
   /// Evaluate block weights and then run the block
   BuildWeightedBlock { block: Rc<Block>, weights: Weights, pop_next_weight: bool, },
   /// Calls a function that accepts a mutable reference to the current runtime. Optionally interrupts the intent loop to force another tick.
@@ -92,15 +102,20 @@ impl Intent {
       Intent::InvokePipeStep { .. } => "invoke_pipe_step",
       Intent::Call { .. } => "call",
       Intent::CallTemporal { .. } => "call_temporal",
+      Intent::CheckReturnType { .. } => "check_return_type",
       Intent::BuildList { .. } => "build_list",
       Intent::BuildMap { .. } => "build_map",
+      Intent::BuildSet { .. } => "build_set",
+      Intent::BuildString { .. } => "build_string",
       Intent::ImportLastAsModule { .. } => "load_module",
       Intent::RuntimeCall { .. } => "runtime_call",
       Intent::DropStaleUnwinds => "drop_stale_unwinds",
       Intent::ReturnLast => "return_last",
       Intent::ContinueLast => "continue_last",
       Intent::BreakLast => "break_last",
+      Intent::YieldLast => "yield_last",
       Intent::BuildWeightedBlock { .. } => "build_weighted_block",
+      Intent::SelectMatchArm { .. } => "select_match_arm",
       Intent::CreateDefaultArgs { .. } => "create_default_args",
     }
   }
@@ -128,6 +143,7 @@ pub enum InvokePipeStepState {
     step_function: RantFunctionRef,
     temporal_state: TemporalSpreadState,
     args: Vec<RantValue>,
+    named_args: NamedArgs,
   },
   /// Step function is ready to call.
   ///
@@ -135,6 +151,7 @@ pub enum InvokePipeStepState {
   PreCall { 
     step_function: RantFunctionRef,
     args: Vec<RantValue>,
+    named_args: NamedArgs,
   },
   /// Step function has returned and output can be used.
   PostCall,
@@ -145,5 +162,6 @@ pub enum InvokePipeStepState {
     step_function: RantFunctionRef,
     temporal_state: TemporalSpreadState,
     args: Vec<RantValue>,
+    named_args: NamedArgs,
   }
 }
\ No newline at end of file
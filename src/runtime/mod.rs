@@ -1,7 +1,11 @@
 pub(crate) mod resolver;
+mod coverage;
+mod debug;
 mod error;
+mod generator;
 mod intent;
 mod output;
+mod profiler;
 mod stack;
 
 use crate::*;
@@ -9,9 +13,14 @@ use crate::lang::*;
 use crate::util::*;
 use self::resolver::*;
 
+pub use self::coverage::*;
+pub use self::debug::*;
 pub use self::intent::*;
+pub use self::profiler::*;
 pub use self::stack::*;
 pub use self::error::*;
+pub(crate) use self::generator::*;
+pub use self::resolver::SELECTOR_MODE_NAMES;
 
 use std::{cell::RefCell, fmt::{Debug, Display}, ops::Deref, rc::Rc};
 use smallvec::{SmallVec, smallvec};
@@ -22,6 +31,9 @@ pub const MAX_STACK_SIZE: usize = 20000;
 pub(crate) const CALL_STACK_INLINE_COUNT: usize = 4;
 pub(crate) const VALUE_STACK_INLINE_COUNT: usize = 4;
 
+/// Arguments passed to a function by parameter name, in the order they were passed.
+pub type NamedArgs = Vec<(Identifier, RantValue)>;
+
 /// The Rant Virtual Machine.
 pub struct VM<'rant> {
   rng_stack: SmallVec<[Rc<RantRng>; 1]>,
@@ -31,6 +43,11 @@ pub struct VM<'rant> {
   call_stack: CallStack<Intent>,
   resolver: Resolver,
   unwinds: SmallVec<[UnwindState; 1]>,
+  generators: Vec<ActiveGenerator>,
+  debugger: Option<&'rant mut dyn Debugger>,
+  debug_state: DebugState,
+  profiler: Option<&'rant mut Profiler>,
+  coverage: Option<&'rant mut Coverage>,
 }
 
 impl<'rant> VM<'rant> {
@@ -44,6 +61,11 @@ impl<'rant> VM<'rant> {
       val_stack: Default::default(),
       call_stack: Default::default(),
       unwinds: Default::default(),
+      generators: Default::default(),
+      debugger: None,
+      debug_state: Default::default(),
+      profiler: None,
+      coverage: None,
     }
   }
 }
@@ -68,6 +90,7 @@ macro_rules! runtime_error {
       error_type: e,
       description: None,
       stack_trace: None,
+      stack_frames: None,
     })
   }};
   ($err_type:expr, $desc:expr) => {
@@ -75,6 +98,7 @@ macro_rules! runtime_error {
       error_type: $err_type,
       description: Some($desc.to_string()),
       stack_trace: None,
+      stack_frames: None,
     })
   };
 }
@@ -112,10 +136,21 @@ pub enum SetterValueSource {
 
 pub struct UnwindState {
   pub handler: Option<RantFunctionRef>,
+  /// IDs of the errors to catch, or `None` to catch all errors.
+  pub error_ids: Option<Vec<String>>,
   pub value_stack_size: usize,
   pub block_stack_size: usize,
   pub attr_stack_size: usize,
   pub call_stack_size: usize,
+  pub rng_stack_size: usize,
+}
+
+impl UnwindState {
+  /// Returns true if the safe point catches errors with the specified ID.
+  #[inline]
+  pub fn catches(&self, error_id: &str) -> bool {
+    self.error_ids.as_ref().is_none_or(|ids| ids.iter().any(|id| id == error_id))
+  }
 }
 
 impl<'rant> VM<'rant> {
@@ -124,7 +159,11 @@ impl<'rant> VM<'rant> {
     let mut result = self.run_inner();
     // On error, generate stack trace
     if let Err(err) = result.as_mut() {
-      err.stack_trace = Some(self.call_stack.gen_stack_trace());
+      self.record_stack_trace(err);
+    }
+    // Finish any calls left unfinished by an error
+    if let Some(profiler) = self.profiler.as_mut() {
+      profiler.exit_to(0);
     }
     result
   }
@@ -142,16 +181,46 @@ impl<'rant> VM<'rant> {
     let mut result = self.run_inner();
     // On error, generate stack trace
     if let Err(err) = result.as_mut() {
-      err.stack_trace = Some(self.call_stack.gen_stack_trace());
+      self.record_stack_trace(err);
+    }
+    // Finish any calls left unfinished by an error
+    if let Some(profiler) = self.profiler.as_mut() {
+      profiler.exit_to(0);
     }
     result
   }
   
+  /// Records the current call stack as the location of an error, unless the error already has one.
+  ///
+  /// Errors raised again by `[rethrow]` keep the location they were first raised at.
+  fn record_stack_trace(&self, err: &mut RuntimeError) {
+    if err.stack_frames.is_some() {
+      return
+    }
+    let frames = self.call_stack.gen_stack_frames();
+    err.stack_trace = Some(format_stack_trace(&frames));
+    err.stack_frames = Some(frames.into_boxed_slice());
+  }
+
   #[inline]
   fn run_inner(&mut self) -> RuntimeResult<RantValue> {
     // Push the program's root sequence onto the call stack
     // This doesn't need an overflow check because it will *always* succeed
     self.push_frame_unchecked(self.program.root.clone(), true, StackFrameFlavor::FunctionBody);
+
+    // Let the debugger (if any) decide where to pause first
+    self.debug_start();
+
+    // Profile the root sequence as if it were a function
+    if self.profiler.is_some() {
+      let root_key = FunctionKey {
+        name: self.program.root.name().map_or(DEFAULT_PROGRAM_NAME, |name| name.as_str()).to_owned(),
+        origin: Some(self.cur_frame().origin_name().to_owned()),
+      };
+      if let Some(profiler) = self.profiler.as_mut() {
+        profiler.enter(root_key, 1);
+      }
+    }
     
     while !self.is_stack_empty() {
       // Tick VM
@@ -163,12 +232,18 @@ impl<'rant> VM<'rant> {
         Ok(false) => {
           runtime_trace!("tick done (stack @ {})", self.call_stack.len());
         },
-        Err(err) => {
+        Err(mut err) => {
+          // Record where the error happened before the stack is unwound
+          if !self.unwinds.is_empty() {
+            self.record_stack_trace(&mut err);
+          }
+
           // Try to unwind to last safe point
-          if let Some(unwind) = self.unwind() {
+          if let Some(unwind) = self.unwind(&err) {
             // Fire off handler if available
             if let Some(handler) = unwind.handler {
-              self.call_func(handler, vec![RantValue::String(err.to_string().into())], PrintFlag::None, false)?;
+              let err_map = RantValue::Map(Rc::new(RefCell::new(err.to_rant_map())));
+              self.call_func(handler, vec![err_map], PrintFlag::None, false)?;
               continue;
             }
           } else {
@@ -211,6 +286,11 @@ impl<'rant> VM<'rant> {
           self.interrupt_repeater(Some(val), false)?;
           return Ok(true)
         },
+        Intent::YieldLast => {
+          let val = self.pop_val()?;
+          self.yield_value(val)?;
+          return Ok(true)
+        },
         Intent::CheckBlock => {            
           self.check_block()?;
         },
@@ -305,9 +385,12 @@ impl<'rant> VM<'rant> {
             // Pop it off the value stack
             let default_arg = self.pop_val()?;
             // Store it as a local
-            let (_, var_name_index) = default_arg_exprs[eval_index - 1];
-            let var_name = &context.params[var_name_index].name;
-            self.def_var_value(var_name.as_str(), AccessPathKind::Local, default_arg, true)?;
+            let (_, param_index) = default_arg_exprs[eval_index - 1];
+            let param = &context.params[param_index];
+            if let Some(expected) = &param.type_annotation {
+              check_arg_type(param, expected, &default_arg)?;
+            }
+            self.def_var_value(param.name.as_str(), AccessPathKind::Local, default_arg, true)?;
           }
 
           // Check if there's another arg expression to evaluate
@@ -354,29 +437,8 @@ impl<'rant> VM<'rant> {
             self.push_frame_flavored(arg_seq, true, StackFrameFlavor::ArgumentExpression)?;
             return Ok(true)
           } else {
-            // Pop the evaluated args off the stack
-            let mut args = vec![];
-            for arg_expr in arg_exprs.iter() {
-              let arg = self.pop_val()?;
-              // When parametric spread is used and the argument is a list, expand its values into individual args
-              if matches!(arg_expr.spread_mode, ArgumentSpreadMode::Parametric) {
-                if let RantValue::List(list_ref) = &arg {
-                  for spread_arg in list_ref.borrow().iter() {
-                    args.push(spread_arg.clone());
-                  }
-                  continue
-                }
-              }
-              args.push(arg);
-            }
-
-            // Pop the function and make sure it's callable
-            let func = match self.pop_val()? {
-              RantValue::Function(func) => {
-                func
-              },
-              other => runtime_error!(RuntimeErrorType::CannotInvokeValue, format!("cannot call '{}' value", other.type_name()))
-            };
+            // Pop the function and its evaluated args off the stack
+            let (func, args, named_args) = self.pop_call(arg_exprs.as_slice())?;
 
             // Call the function
             if is_temporal {
@@ -388,11 +450,12 @@ impl<'rant> VM<'rant> {
                   func,
                   temporal_state, 
                   args: Rc::new(args), 
+                  named_args: Rc::new(named_args),
                   flag
                 });
               }
             } else {
-              self.call_func(func, args, flag, false)?;
+              self.call_func_with_named_args(func, args, named_args, flag, false)?;
             }
             
             return Ok(true)
@@ -459,30 +522,8 @@ impl<'rant> VM<'rant> {
                   self.def_pipeval(pipeval)?;
                 }
               } else {
-                // If all args are evaluated, pop them off the stack
-                let mut args = vec![];
-                for arg_expr in arg_exprs.iter() {
-                  let arg = self.pop_val()?;
-                  // When parametric spread is used and the argument is a list, expand its values into individual args
-                  if matches!(arg_expr.spread_mode, ArgumentSpreadMode::Parametric) {
-                    if let RantValue::List(list_ref) = &arg {
-                      for spread_arg in list_ref.borrow().iter() {
-                        args.push(spread_arg.clone());
-                      }
-                      continue
-                    }
-                  }
-                  args.push(arg);
-                }
-
-                // Pop the function and make sure it's callable
-                let step_function = match self.pop_val()? {
-                  RantValue::Function(func) => {
-                    func
-                  },
-                  // What are you doing, step function?
-                  other => runtime_error!(RuntimeErrorType::CannotInvokeValue, format!("cannot call '{}' value", other.type_name()))
-                };
+                // If all args are evaluated, pop them and the step function off the stack
+                let (step_function, args, named_args) = self.pop_call(arg_exprs.as_slice())?;
                 
                 // Transition to pre-call for next step
                 self.cur_frame_mut().push_intent_front(Intent::InvokePipeStep {
@@ -491,9 +532,10 @@ impl<'rant> VM<'rant> {
                       step_function,
                       temporal_state: TemporalSpreadState::new(arg_exprs.as_slice(), args.as_slice()),
                       args,
+                      named_args,
                     }
                   } else {
-                    InvokePipeStepState::PreCall { step_function, args }
+                    InvokePipeStepState::PreCall { step_function, args, named_args }
                   },
                   steps,
                   step_index,
@@ -503,7 +545,7 @@ impl<'rant> VM<'rant> {
               }
               return Ok(true)
             },
-            InvokePipeStepState::PreCall { step_function, args } => {
+            InvokePipeStepState::PreCall { step_function, args, named_args } => {
               // Transition intent to PostCall after function returns
               self.cur_frame_mut().push_intent_front(Intent::InvokePipeStep {
                 steps,
@@ -514,7 +556,7 @@ impl<'rant> VM<'rant> {
               });
 
               // Call it and interrupt
-              self.call_func(step_function, args, PrintFlag::None, true)?;
+              self.call_func_with_named_args(step_function, args, named_args, PrintFlag::None, true)?;
               return Ok(true)
             },
             InvokePipeStepState::PostCall => {
@@ -536,7 +578,7 @@ impl<'rant> VM<'rant> {
                 self.cur_frame_mut().write_value(next_pipeval);
               }
             },
-            InvokePipeStepState::PreTemporalCall { step_function, args, temporal_state } => {
+            InvokePipeStepState::PreTemporalCall { step_function, args, named_args, temporal_state } => {
               let targs = args.iter().enumerate().map(|(arg_index, arg)| {
                 // Check if this is a temporally spread argument
                 if let Some(tindex) = temporal_state.get(arg_index) {
@@ -557,15 +599,16 @@ impl<'rant> VM<'rant> {
                   step_function: Rc::clone(&step_function),
                   temporal_state,
                   args,
+                  named_args: named_args.clone(),
                 },
                 pipeval,
                 flag,
               });
 
-              self.call_func(step_function, targs, PrintFlag::None, true)?;
+              self.call_func_with_named_args(step_function, targs, named_args, PrintFlag::None, true)?;
               return Ok(true)
             },
-            InvokePipeStepState::PostTemporalCall { step_function, args, mut temporal_state } => {
+            InvokePipeStepState::PostTemporalCall { step_function, args, named_args, mut temporal_state } => {
               let next_piprval = self.pop_val()?;
               let next_step_index = step_index + 1;
               let step_count = steps.len();
@@ -579,6 +622,7 @@ impl<'rant> VM<'rant> {
                     step_function,
                     temporal_state,
                     args,
+                    named_args,
                   },
                   pipeval,
                   flag,
@@ -603,7 +647,7 @@ impl<'rant> VM<'rant> {
             },
           }
         },
-        Intent::CallTemporal { func, args, mut temporal_state, flag } => {
+        Intent::CallTemporal { func, args, named_args, mut temporal_state, flag } => {
           let targs = args.iter().enumerate().map(|(arg_index, arg)| {
             // Check if this is a temporally spread argument
             if let Some(tindex) = temporal_state.get(arg_index) {
@@ -617,12 +661,19 @@ impl<'rant> VM<'rant> {
           }).collect::<Vec<RantValue>>();
 
           if temporal_state.increment() {
-            self.cur_frame_mut().push_intent_front(Intent::CallTemporal { func: Rc::clone(&func), args, temporal_state, flag });
+            self.cur_frame_mut().push_intent_front(Intent::CallTemporal { func: Rc::clone(&func), args, named_args: Rc::clone(&named_args), temporal_state, flag });
           }
 
-          self.call_func(func, targs, flag, false)?;
+          self.call_func_with_named_args(func, targs, named_args.as_ref().clone(), flag, false)?;
           return Ok(true)
         },
+        Intent::CheckReturnType { return_type } => {
+          let value = self.pop_val()?;
+          if !return_type.accepts(value.get_type()) {
+            runtime_error!(RuntimeErrorType::TypeError, format!("function should return {}, but returned {}", return_type, value.type_name()));
+          }
+          self.push_val(value)?;
+        },
         Intent::Call { argc, flag, override_print } => {
           // Pop the evaluated args off the stack
           let mut args = vec![];
@@ -695,6 +746,62 @@ impl<'rant> VM<'rant> {
             return Ok(true)
           }
         },
+        Intent::BuildSet { init, index, mut set } => {
+          // Add latest evaluated value to set
+          if index > 0 {
+            set.insert(self.pop_val()?).into_runtime_result()?;
+          }
+
+          // Check if the set is complete
+          if index >= init.len() {
+            self.cur_frame_mut().write_value(RantValue::Set(Rc::new(RefCell::new(set))))
+          } else {
+            // Continue set creation
+            self.cur_frame_mut().push_intent_front(Intent::BuildSet { init: Rc::clone(&init), index: index + 1, set });
+            let val_expr = &init[index];
+            self.push_frame(Rc::clone(val_expr), true)?;
+            return Ok(true)
+          }
+        },
+        Intent::BuildString { parts, mut index, mut string } => {
+          // Add latest evaluated expression to string, formatting numbers like printed output
+          if let Some(StringPart::Expr(_)) = index.checked_sub(1).and_then(|i| parts.get(i)) {
+            match self.pop_val()? {
+              RantValue::Empty => {},
+              RantValue::Int(n) => {
+                let num_format = self.cur_frame().use_output(|output| output.format().num_format.clone()).unwrap_or_default();
+                string.push_str(&num_format.format_integer(n));
+              },
+              RantValue::BigInt(n) => {
+                let num_format = self.cur_frame().use_output(|output| output.format().num_format.clone()).unwrap_or_default();
+                string.push_str(&num_format.format_big_integer(&n));
+              },
+              RantValue::Float(n) => {
+                let num_format = self.cur_frame().use_output(|output| output.format().num_format.clone()).unwrap_or_default();
+                string.push_str(&num_format.format_float(n));
+              },
+              value => string.push_str(&value.to_string()),
+            }
+          }
+
+          // Add text up to the next expression
+          while let Some(StringPart::Text(text)) = parts.get(index) {
+            string.push_str(text);
+            index += 1;
+          }
+
+          // Check if the string is complete
+          match parts.get(index) {
+            Some(StringPart::Expr(expr)) => {
+              // Continue string creation
+              let expr = Rc::clone(expr);
+              self.cur_frame_mut().push_intent_front(Intent::BuildString { parts, index: index + 1, string });
+              self.push_frame(expr, true)?;
+              return Ok(true)
+            },
+            _ => self.cur_frame_mut().write_value(RantValue::String(string.as_str().into())),
+          }
+        },
         Intent::BuildMap { init, pair_index, mut map } => {
           // Add latest evaluated pair to map
           if pair_index > 0 {
@@ -724,6 +831,24 @@ impl<'rant> VM<'rant> {
             return Ok(true)
           }
         },
+        Intent::SelectMatchArm { expr } => {
+          let value = self.pop_val()?;
+          let mut bindings = vec![];
+          let selected_arm = expr.arms.iter().find(|arm| {
+            bindings.clear();
+            arm.pattern.matches(&value, &mut bindings)
+          });
+
+          // If no arm matches, the match prints nothing
+          if let Some(arm) = selected_arm {
+            self.cur_frame_mut().push_intent_front(Intent::PrintLast);
+            self.push_frame(Rc::clone(&arm.body), true)?;
+            for (name, value) in bindings {
+              self.def_var_value(name.as_str(), AccessPathKind::Local, value, false)?;
+            }
+            return Ok(true)
+          }
+        },
         Intent::ImportLastAsModule { module_name, descope } => {
           let module = self.pop_val()?;
 
@@ -768,10 +893,23 @@ impl<'rant> VM<'rant> {
           self.cur_frame_mut().push_intent_front(Intent::BuildMap { init: Rc::clone(elements), pair_index: 0, map: RantMap::new() });
           return Ok(true)
         },
+        Rst::SetInit(elements) => {
+          self.cur_frame_mut().push_intent_front(Intent::BuildSet { init: Rc::clone(elements), index: 0, set: RantSet::new() });
+          return Ok(true)
+        },
+        Rst::InterpolatedString(parts) => {
+          self.cur_frame_mut().push_intent_front(Intent::BuildString { parts: Rc::clone(parts), index: 0, string: InternalString::new() });
+          return Ok(true)
+        },
         Rst::Block(block) => {
           self.pre_push_block(&block, block.flag)?;
           return Ok(true)
         },
+        Rst::Match(expr) => {
+          self.cur_frame_mut().push_intent_front(Intent::SelectMatchArm { expr: Rc::clone(expr) });
+          self.push_frame(Rc::clone(&expr.value), true)?;
+          return Ok(true)
+        },
         Rst::DefVar(vname, access_kind, val_expr) => {
           if let Some(val_expr) = val_expr {
             // If a value is present, it needs to be evaluated first
@@ -835,6 +973,7 @@ impl<'rant> VM<'rant> {
           is_const,
           params,
           path,
+          return_type,
         }) => {
           // Capture variables
           let mut captured_vars = vec![];
@@ -855,6 +994,7 @@ impl<'rant> VM<'rant> {
             .unwrap_or_else(|| params.len()),
             params: Rc::clone(params),
             flavor: None,
+            return_type: return_type.clone(),
           }));
 
           // Evaluate setter path
@@ -872,7 +1012,8 @@ impl<'rant> VM<'rant> {
         Rst::Lambda(LambdaExpr { 
           params, 
           body, 
-          capture_vars: to_capture 
+          capture_vars: to_capture,
+          return_type,
         }) => {
           // Capture variables
           let mut captured_vars = vec![];
@@ -892,6 +1033,7 @@ impl<'rant> VM<'rant> {
             .unwrap_or_else(|| params.len()),
             params: Rc::clone(params),
             flavor: None,
+            return_type: return_type.clone(),
           }));
 
           self.cur_frame_mut().write_value(func);
@@ -949,7 +1091,7 @@ impl<'rant> VM<'rant> {
           self.cur_frame_mut().write_value(pipeval);
         },
         Rst::DebugCursor(info) => {
-          self.cur_frame_mut().set_debug_info(info);
+          self.debug_cursor(info);
         },
         Rst::Fragment(frag) => self.cur_frame_mut().write_frag(frag),
         Rst::Whitespace(ws) => self.cur_frame_mut().write_ws(ws),
@@ -957,6 +1099,7 @@ impl<'rant> VM<'rant> {
         Rst::Float(n) => self.cur_frame_mut().write_value(RantValue::Float(*n)),
         Rst::EmptyValue => self.cur_frame_mut().write_value(RantValue::Empty),
         Rst::Boolean(b) => self.cur_frame_mut().write_value(RantValue::Boolean(*b)),
+        Rst::ConstCollection(collection) => self.cur_frame_mut().write_value(collection.cow_copy()),
         Rst::Nop => {},
         Rst::Return(expr) => {
           if let Some(expr) = expr {
@@ -988,6 +1131,16 @@ impl<'rant> VM<'rant> {
             return Ok(true)
           }
         },
+        Rst::Yield(expr) => {
+          if let Some(expr) = expr {
+            self.cur_frame_mut().push_intent_front(Intent::YieldLast);
+            self.push_frame(Rc::clone(expr), true)?;
+            continue
+          } else {
+            self.yield_value(RantValue::Empty)?;
+            return Ok(true)
+          }
+        },
         rst => {
           runtime_error!(RuntimeErrorType::InternalError, format!("unsupported node type: '{}'", rst.display_name()));
         },
@@ -998,7 +1151,7 @@ impl<'rant> VM<'rant> {
     
     // Pop frame once its sequence is finished
     let last_frame = self.pop_frame()?;
-    if let Some(output) = last_frame.into_output() {
+    if let Some(output) = last_frame.into_output(self.context().options().enable_big_ints)? {
       self.push_val(output)?;
     }
     
@@ -1032,14 +1185,72 @@ impl<'rant> VM<'rant> {
     });
   }
 
+  /// Pops a function and the values of its evaluated argument expressions off the stack.
+  ///
+  /// Parametrically spread lists are expanded into individual arguments, and named arguments are returned separately.
+  fn pop_call(&mut self, arg_exprs: &[ArgumentExpr]) -> RuntimeResult<(RantFunctionRef, Vec<RantValue>, NamedArgs)> {
+    let arg_values = arg_exprs.iter().map(|_| self.pop_val()).collect::<RuntimeResult<Vec<_>>>()?;
+
+    // Pop the function and make sure it's callable
+    let func = match self.pop_val()? {
+      RantValue::Function(func) => func,
+      other => runtime_error!(RuntimeErrorType::CannotInvokeValue, format!("cannot call '{}' value", other.type_name()))
+    };
+
+    let mut args = vec![];
+    let mut named_args = vec![];
+    for (arg_expr, arg) in arg_exprs.iter().zip(arg_values) {
+      if let Some(name) = &arg_expr.name {
+        named_args.push((name.clone(), arg));
+        continue
+      }
+      // When parametric spread is used and the argument is a list, expand its values into individual args
+      if matches!(arg_expr.spread_mode, ArgumentSpreadMode::Parametric) {
+        if let RantValue::List(list_ref) = &arg {
+          for spread_arg in list_ref.borrow().iter() {
+            args.push(spread_arg.clone());
+          }
+          continue
+        }
+      }
+      args.push(arg);
+    }
+    Ok((func, args, named_args))
+  }
+
   /// Prepares a call to a function with the specified arguments.
   #[inline]
-  pub fn call_func(&mut self, func: RantFunctionRef, mut args: Vec<RantValue>, flag: PrintFlag, override_print: bool) -> RuntimeResult<()> {
+  pub fn call_func(&mut self, func: RantFunctionRef, args: Vec<RantValue>, flag: PrintFlag, override_print: bool) -> RuntimeResult<()> {
+    self.call_func_with_named_args(func, args, vec![], flag, override_print)
+  }
+
+  /// Prepares a call to a function with the specified positional and named arguments.
+  ///
+  /// Named arguments are bound to the parameters with matching names; optional parameters skipped over by them are treated as if they weren't passed.
+  pub fn call_func_with_named_args(&mut self, func: RantFunctionRef, mut args: Vec<RantValue>, named_args: NamedArgs, flag: PrintFlag, override_print: bool) -> RuntimeResult<()> {
+    let skipped_args = if named_args.is_empty() {
+      vec![]
+    } else {
+      bind_named_args(&func, &mut args, named_args)?
+    };
     let argc = args.len();
     let is_printing = !flag.is_sink();
 
+    // A printing call at the end of a function body can reuse the caller's frame
+    // Functions with a return type need their own frame so the return value can be checked
+    let tail_call_depth = if is_printing 
+      && !override_print 
+      && matches!(func.body, RantFunctionInterface::User(_))
+      && func.flavor.is_none_or(|flavor| flavor == StackFrameFlavor::FunctionBody) 
+      && func.return_type.is_none()
+    {
+      self.find_tail_call_frame()
+    } else {
+      None
+    };
+
     // Tell frame to print output if it's available
-    if is_printing && !override_print {
+    if is_printing && !override_print && tail_call_depth.is_none() {
       self.cur_frame_mut().push_intent_front(Intent::PrintLast);
     }
 
@@ -1052,6 +1263,15 @@ impl<'rant> VM<'rant> {
       runtime_error!(RuntimeErrorType::ArgumentMismatch, format!("arguments don't match; expected {}, found {}", func.min_arg_count, argc))
     }
 
+    check_arg_types(&func, &args, &skipped_args)?;
+
+    // Check the return value before it's used
+    if is_printing {
+      if let Some(return_type) = &func.return_type {
+        self.cur_frame_mut().push_intent_front(Intent::CheckReturnType { return_type: Rc::clone(return_type) });
+      }
+    }
+
     // Call the function
     match &func.body {
       RantFunctionInterface::Foreign(foreign_func) => {
@@ -1075,7 +1295,18 @@ impl<'rant> VM<'rant> {
         let mut vararg = func.is_variadic().then(|| RantValue::List(Rc::new(RefCell::new(args_iter.collect::<RantList>()))));
 
         // Push the function onto the call stack
-        self.push_frame_flavored(Rc::clone(user_func), is_printing, func.flavor.unwrap_or(StackFrameFlavor::FunctionBody))?;
+        if let Some(depth) = tail_call_depth {
+          // The blocks being exited have no elements left to run
+          for _ in 0..depth {
+            self.resolver.pop_block();
+          }
+          self.call_stack.tail_call(Rc::clone(user_func), depth);
+          if let Some(profiler) = self.profiler.as_mut() {
+            profiler.exit_to(self.call_stack.len() - 1);
+          }
+        } else {
+          self.push_frame_flavored(Rc::clone(user_func), is_printing, func.flavor.unwrap_or(StackFrameFlavor::FunctionBody))?;
+        }
 
         // Pass captured vars to the function scope
         for (capture_name, capture_var) in func.captured_vars.iter() {
@@ -1095,7 +1326,7 @@ impl<'rant> VM<'rant> {
           let user_arg = if p.varity.is_variadic() {
             vararg.take()
           } else {
-            let user_arg = args_nonvariadic.next();
+            let user_arg = args_nonvariadic.next().filter(|_| !skipped_args.contains(&i));
             if p.is_optional() && user_arg.is_none() {
               if let Some(default_arg_expr) = &p.default_value_expr {
                 default_arg_exprs.push((Rc::clone(&default_arg_expr), i));
@@ -1125,9 +1356,71 @@ impl<'rant> VM<'rant> {
         }
       },
     }
+
+    if let Some(profiler) = self.profiler.as_mut() {
+      profiler.enter_func(&func, self.call_stack.len());
+    }
+    
     Ok(())
   }
 
+  /// Finds the function body frame that a call from the current frame can reuse as a tail call.
+  /// Returns how many frames below the top of the stack it is, or `None` if the call isn't in tail position.
+  ///
+  /// A call is in tail position when nothing else in the function body runs after it returns:
+  /// it must be the last node of the body, or the last node of a block element that is itself in tail position.
+  /// Blocks may be followed by `[else]` clauses, since those are always skipped after a block runs.
+  fn find_tail_call_frame(&self) -> Option<usize> {
+    for (depth, frame) in self.call_stack.frames().enumerate() {
+      let is_at_tail = if depth == 0 {
+        frame.intents().next().is_none() 
+        && frame.remaining_nodes().iter().all(|rst| matches!(rst.as_ref(), Rst::Nop | Rst::DebugCursor(_)))
+      } else {
+        // The frame must be waiting on nothing but the block element above it
+        let mut intents = frame.intents();
+        matches!((intents.next(), intents.next(), intents.next()), (Some(Intent::PrintLast), Some(Intent::CheckBlock), None)) 
+        && self.is_skipped_after_block(frame.remaining_nodes())
+      };
+
+      if !is_at_tail || frame.output().is_none() {
+        return None
+      }
+
+      match frame.flavor() {
+        // The main scope is never replaced
+        StackFrameFlavor::FunctionBody => return (depth + 1 < self.call_stack.len()).then_some(depth),
+        StackFrameFlavor::BlockElement if self.resolver.parent_block(depth).is_some_and(|block| block.is_done()) => {},
+        _ => return None,
+      }
+    }
+    None
+  }
+
+  /// Returns `true` if the specified nodes do nothing when they directly follow a block that has run.
+  fn is_skipped_after_block(&self, nodes: &[Rc<Rst>]) -> bool {
+    let mut is_else_block_next = false;
+    for rst in nodes {
+      match rst.as_ref() {
+        Rst::Nop | Rst::DebugCursor(_) => {},
+        Rst::FuncCall(fcall) if !is_else_block_next && self.is_else_call(fcall) => is_else_block_next = true,
+        // Dynamic weights are evaluated even if the block is skipped
+        Rst::Block(block) if is_else_block_next && block.elements.iter().all(|elem| !matches!(elem.weight, Some(BlockWeight::Dynamic(_)))) => {
+          is_else_block_next = false;
+        },
+        _ => return false,
+      }
+    }
+    !is_else_block_next
+  }
+
+  /// Returns `true` if the specified call is to the native `[else]` function.
+  fn is_else_call(&self, fcall: &FunctionCall) -> bool {
+    !fcall.is_temporal 
+    && fcall.arguments.is_empty() 
+    && matches!(&fcall.target, FunctionCallTarget::Path(path) if path.kind().is_local() && path.static_variable() == Some("else"))
+    && matches!(self.get_var_value("else", AccessPathKind::Local, true), Ok(RantValue::Function(func)) if func.is_native())
+  }
+
   /// Runs a setter.
   #[inline]
   fn set_value(&mut self, path: Rc<AccessPath>, write_mode: VarWriteMode, dynamic_value_count: usize) -> RuntimeResult<()> {
@@ -1339,6 +1632,13 @@ impl<'rant> VM<'rant> {
       if let Some(element) = state.next_element(rng.as_ref()).into_runtime_result()? {
         // Figure out if the block is supposed to print anything
         is_printing = !state.flag().is_sink();
+
+        // Record the selected element with the coverage collector
+        if let (Some(coverage), Some(frame)) = (self.coverage.as_mut(), self.call_stack.top()) {
+          if let (BlockAction::Element(_) | BlockAction::PipedElement { .. }, Some(elem)) = (&element, state.selected_element()) {
+            coverage.hit_element(frame.origin_name(), state.pos(), &elem.pos);
+          }
+        }
         Some(element)
       } else {
         // If the block is done, pop the state from the block stack
@@ -1430,6 +1730,10 @@ impl<'rant> VM<'rant> {
   /// Consumes attributes and pushes a block onto the resolver stack.
   #[inline]
   pub fn push_block(&mut self, block: &Block, weights: Option<Weights>, flag: PrintFlag) -> RuntimeResult<()> {
+    if let (Some(coverage), Some(frame)) = (self.coverage.as_mut(), self.call_stack.top()) {
+      coverage.enter_block(frame.origin_name(), block);
+    }
+
     // Push a new state onto the block stack
     self.resolver.push_block(block, weights, flag);
 
@@ -1500,6 +1804,9 @@ impl<'rant> VM<'rant> {
   pub fn pop_frame(&mut self) -> RuntimeResult<StackFrame<Intent>> {
     runtime_trace!("pop_frame: {} -> {}", self.call_stack.len(), self.call_stack.len() - 1);
     if let Some(frame) = self.call_stack.pop_frame() {
+      if let Some(profiler) = self.profiler.as_mut() {
+        profiler.exit_to(self.call_stack.len());
+      }
       Ok(frame)
     } else {
       runtime_error!(RuntimeErrorType::StackUnderflow, "call stack has underflowed");
@@ -1603,7 +1910,7 @@ impl<'rant> VM<'rant> {
       } else {
         for i in 0..=block_depth {
           let old_frame = self.pop_frame()?;
-          if let Some(output) = old_frame.into_output() {
+          if let Some(output) = old_frame.into_output(self.context().options().enable_big_ints)? {
             if i < block_depth {
               self.cur_frame_mut().write_value(output);
             } else {
@@ -1630,16 +1937,21 @@ impl<'rant> VM<'rant> {
     runtime_trace!("func_return");
     if let Some(block_depth) = self.call_stack.taste_for_first(StackFrameFlavor::FunctionBody) {
       // Pop down to owning scope of function
-      if let Some(break_val) = ret_val {
-        for _ in 0..=block_depth {
-          self.pop_frame()?;
+      if let Some(mut break_val) = ret_val {
+        for i in 0..=block_depth {
+          let mut old_frame = self.pop_frame()?;
+          
+          // Functions replaced by tail calls still need to print their output
+          if i == block_depth {
+            break_val = old_frame.finish_tail_calls(break_val, self.context().options().enable_big_ints)?;
+          }
         }
         self.push_val(break_val)?;
       } else {
         for i in 0..=block_depth {
           let old_frame = self.pop_frame()?;
           let old_frame_flavor = old_frame.flavor();
-          let old_frame_value = old_frame.into_output();
+          let old_frame_value = old_frame.into_output(self.context().options().enable_big_ints)?;
 
           // If a block state is associated with the popped frame, pop that too
           match old_frame_flavor {
@@ -1754,19 +2066,28 @@ impl<'rant> VM<'rant> {
     self.program
   }
 
+  /// Adds a safe point that errors are unwound to, optionally only catching errors with the specified IDs.
   #[inline]
-  pub fn push_unwind_state(&mut self, handler: Option<RantFunctionRef>) {
+  pub fn push_unwind_state(&mut self, handler: Option<RantFunctionRef>, error_ids: Option<Vec<String>>) {
     self.unwinds.push(UnwindState {
       handler,
+      error_ids,
       call_stack_size: self.call_stack.len(),
       value_stack_size: self.val_stack.len(),
       block_stack_size: self.resolver.block_stack_len(),
       attr_stack_size: self.resolver.count_attrs(),
+      rng_stack_size: self.rng_stack.len(),
     });
   }
 
+  /// Unwinds the VM to the most recent safe point that catches the specified error.
   #[inline]
-  pub fn unwind(&mut self) -> Option<UnwindState> {
+  pub fn unwind(&mut self, err: &RuntimeError) -> Option<UnwindState> {
+    // Safe points that don't catch the error are skipped
+    let id = err.error_type.id();
+    while self.unwinds.last().is_some_and(|state| !state.catches(id)) {
+      self.unwinds.pop();
+    }
     let state = self.unwinds.pop();
 
     if let Some(state) = &state {
@@ -1775,6 +2096,10 @@ impl<'rant> VM<'rant> {
         self.call_stack.pop_frame();
       }
 
+      if let Some(profiler) = self.profiler.as_mut() {
+        profiler.exit_to(self.call_stack.len());
+      }
+
       // Unwind value stack
       while self.val_stack.len() > state.value_stack_size {
         self.val_stack.pop();
@@ -1789,8 +2114,83 @@ impl<'rant> VM<'rant> {
       while self.resolver.count_attrs() > state.attr_stack_size {
         self.resolver.pop_attrs();
       }
+
+      // Unwind RNG stack
+      self.rng_stack.truncate(state.rng_stack_size);
+
+      self.unwind_generators();
     }
 
     state
   }
-}
\ No newline at end of file
+}
+
+/// Raises an argument error if an argument doesn't have the type its parameter is annotated with.
+///
+/// Each argument passed to a variadic parameter is checked separately.
+fn check_arg_types(func: &RantFunction, args: &[RantValue], skipped_args: &[usize]) -> RuntimeResult<()> {
+  for (index, param) in func.params.iter().enumerate() {
+    let expected = match &param.type_annotation {
+      Some(expected) => expected,
+      None => continue,
+    };
+    let values = match args.get(index..) {
+      Some(rest) if param.varity.is_variadic() => rest,
+      Some([value, ..]) if !skipped_args.contains(&index) => std::slice::from_ref(value),
+      _ => continue,
+    };
+    for value in values {
+      check_arg_type(param, expected, value)?;
+    }
+  }
+  Ok(())
+}
+
+/// Checks an argument or default value against the type annotation of its parameter.
+#[inline]
+fn check_arg_type(param: &Parameter, expected: &TypeAnnotation, value: &RantValue) -> RuntimeResult<()> {
+  if !expected.accepts(value.get_type()) {
+    runtime_error!(RuntimeErrorType::ArgumentError, format!("argument '{}' should be {}, but found {}", param.name, expected, value.type_name()));
+  }
+  Ok(())
+}
+
+/// Moves named arguments into the positions of their parameters in `args`.
+///
+/// Returns the indices of the optional parameters that were skipped over.
+fn bind_named_args(func: &RantFunction, args: &mut Vec<RantValue>, named_args: NamedArgs) -> RuntimeResult<Vec<usize>> {
+  let positional_count = args.len();
+  let mut slots = args.drain(..).map(Some).collect::<Vec<_>>();
+  for (name, value) in named_args {
+    let index = match func.params.iter().position(|param| param.name == name) {
+      Some(index) => index,
+      None => runtime_error!(RuntimeErrorType::ArgumentMismatch, format!("function has no parameter named '{}'", name)),
+    };
+    if func.params[index].varity.is_variadic() {
+      runtime_error!(RuntimeErrorType::ArgumentMismatch, format!("variadic parameter '{}' can't be passed by name", name));
+    }
+    if index < positional_count {
+      runtime_error!(RuntimeErrorType::ArgumentMismatch, format!("argument '{}' is already passed by position", name));
+    }
+    if index >= slots.len() {
+      slots.resize(index + 1, None);
+    }
+    if slots[index].is_some() {
+      runtime_error!(RuntimeErrorType::ArgumentMismatch, format!("argument '{}' is passed more than once", name));
+    }
+    slots[index] = Some(value);
+  }
+
+  let mut skipped_args = vec![];
+  for (index, slot) in slots.into_iter().enumerate() {
+    match slot {
+      Some(value) => args.push(value),
+      None if func.params[index].is_required() => runtime_error!(RuntimeErrorType::ArgumentMismatch, format!("missing argument '{}'", func.params[index].name)),
+      None => {
+        skipped_args.push(index);
+        args.push(RantValue::Empty);
+      },
+    }
+  }
+  Ok(skipped_args)
+}
@@ -1,4 +1,5 @@
-use crate::{InternalString, RantList, RantMap, RantValue, format::{NumberFormat, OutputFormat}};
+use crate::{InternalString, RantList, RantMap, RantValue, format::{NumberFormat, OutputFormat}, value::promote_on_overflow};
+use super::{IntoRuntimeResult, RuntimeResult};
 use super::format::{WhitespaceNormalizationMode};
 use std::{cell::RefCell, rc::Rc};
 
@@ -22,6 +23,12 @@ impl OutputWriter {
     }
   }
 
+  /// Returns `true` if nothing has been written to the output.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.buffers.is_empty()
+  }
+
   /// Gets a reference to the current output format.
   #[inline]
   pub fn format(&self) -> &OutputFormat {
@@ -132,9 +139,10 @@ impl OutputWriter {
 
 impl OutputWriter {
   /// Consumes the output and returns the final value.
+  /// If concatenating integers overflows and `big_ints` is set, the result is promoted to a big integer.
   #[inline]
-  pub fn render_value(mut self) -> RantValue {    
-    match self.buffers.len() {
+  pub fn render_value(mut self, big_ints: bool) -> RuntimeResult<RantValue> {
+    Ok(match self.buffers.len() {
       // An empty output always returns an empty value
       0 => RantValue::Empty,
       // Single buffer is always returned unchanged
@@ -188,14 +196,14 @@ impl OutputWriter {
             let mut val = RantValue::Empty;
             for buf in self.buffers {
               if let OutputBuffer::Value(bufval) = buf {
-                val = val.concat(bufval);
+                val = promote_on_overflow(big_ints, [val, bufval], |[lhs, rhs]| lhs.concat(rhs)).into_runtime_result()?;
               }
             }
             val
           }
         }
       }
-    }
+    })
   }
 }
 
@@ -232,6 +240,7 @@ impl OutputBuffer {
       Self::Whitespace(s) => s,
       Self::Value(RantValue::Empty) => return None,
       Self::Value(RantValue::Int(n)) => format.num_format.format_integer(n),
+      Self::Value(RantValue::BigInt(n)) => format.num_format.format_big_integer(&n),
       Self::Value(RantValue::Float(n)) => format.num_format.format_float(n),
       Self::Value(v) => InternalString::from(v.to_string()),
       Self::NumberFormatUpdate(fmt) => {
@@ -0,0 +1,260 @@
+use std::{collections::HashMap, fmt::Write, path::Path, time::{Duration, Instant}};
+use crate::{RantFunctionInterface, RantProgramInfo};
+use super::*;
+
+/// Identifies a profiled function by name and origin.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionKey {
+  /// The display name of the function.
+  pub name: String,
+  /// The origin name (path or program name) of the function, or `None` for native functions.
+  pub origin: Option<String>,
+}
+
+impl FunctionKey {
+  /// Formats the key for use in folded stack output.
+  fn folded_name(&self) -> String {
+    let name = match &self.origin {
+      Some(origin) => format!("{} ({})", self.name, Path::new(origin).file_name().map_or_else(|| origin.clone(), |name| name.to_string_lossy().into_owned())),
+      None => self.name.clone(),
+    };
+    // Semicolons separate frames in folded stacks
+    name.replace(';', ":")
+  }
+}
+
+/// Profiling data collected for a single function.
+#[derive(Debug, Clone)]
+pub struct FunctionProfile {
+  /// The function the data belongs to.
+  pub key: FunctionKey,
+  /// The number of times the function was called.
+  pub calls: u64,
+  /// Total time spent in the function, including calls it made.
+  /// Time spent in recursive calls is only counted once.
+  pub inclusive_time: Duration,
+  /// Total time spent in the function itself, excluding calls it made.
+  pub exclusive_time: Duration,
+  /// Total heap allocations made by the function, including calls it made.
+  pub inclusive_allocations: u64,
+  /// Total heap allocations made by the function itself, excluding calls it made.
+  pub exclusive_allocations: u64,
+}
+
+/// A function call that hasn't returned yet.
+struct ActiveCall {
+  /// Index of the called function in `Profiler::functions`.
+  func_index: usize,
+  /// Size of the call stack after the function's frame was pushed.
+  call_stack_size: usize,
+  start_time: Instant,
+  start_allocations: u64,
+  child_time: Duration,
+  child_allocations: u64,
+}
+
+/// Records call counts, timings, and allocations for the functions called by a program.
+///
+/// Allocations are only counted if an allocation counter is provided via `Profiler::with_allocation_counter()`,
+/// which usually reads a count kept by a global allocator that the host application installs.
+pub struct Profiler {
+  functions: Vec<FunctionProfile>,
+  function_indices: HashMap<FunctionKey, usize>,
+  /// Names of native functions, keyed by function address.
+  native_names: HashMap<*const (), String>,
+  call_stack: Vec<ActiveCall>,
+  /// Exclusive time spent in each unique stack of function indices.
+  folded_stacks: HashMap<Vec<usize>, Duration>,
+  allocation_counter: Option<fn() -> u64>,
+}
+
+impl Default for Profiler {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Profiler {
+  /// Creates a new profiler that doesn't count allocations.
+  pub fn new() -> Self {
+    Self {
+      functions: Default::default(),
+      function_indices: Default::default(),
+      native_names: Default::default(),
+      call_stack: Default::default(),
+      folded_stacks: Default::default(),
+      allocation_counter: None,
+    }
+  }
+
+  /// Creates a new profiler that reads the total number of allocations made so far from `counter`.
+  pub fn with_allocation_counter(counter: fn() -> u64) -> Self {
+    Self {
+      allocation_counter: Some(counter),
+      .. Self::new()
+    }
+  }
+
+  #[inline]
+  fn allocations(&self) -> u64 {
+    self.allocation_counter.map_or(0, |counter| counter())
+  }
+
+  /// Learns the names of native functions stored in globals so that calls to them can be identified.
+  pub(crate) fn index_native_names(&mut self, context: &Rant) {
+    for name in context.global_names() {
+      if let Some(RantValue::Function(func)) = context.get_global(name) {
+        if let RantFunctionInterface::Foreign(foreign_func) = &func.body {
+          self.native_names.insert(Rc::as_ptr(foreign_func) as *const (), format!("[{}]", name));
+        }
+      }
+    }
+  }
+
+  /// Starts recording a call to the function with the specified key.
+  /// `call_stack_size` is the size of the call stack after the function's frame was pushed.
+  pub(crate) fn enter(&mut self, key: FunctionKey, call_stack_size: usize) {
+    let func_index = match self.function_indices.get(&key) {
+      Some(index) => *index,
+      None => {
+        let index = self.functions.len();
+        self.function_indices.insert(key.clone(), index);
+        self.functions.push(FunctionProfile {
+          key,
+          calls: 0,
+          inclusive_time: Duration::default(),
+          exclusive_time: Duration::default(),
+          inclusive_allocations: 0,
+          exclusive_allocations: 0,
+        });
+        index
+      }
+    };
+
+    self.functions[func_index].calls += 1;
+    let start_allocations = self.allocations();
+    self.call_stack.push(ActiveCall {
+      func_index,
+      call_stack_size,
+      start_time: Instant::now(),
+      start_allocations,
+      child_time: Duration::default(),
+      child_allocations: 0,
+    });
+  }
+
+  /// Starts recording a call to a function value.
+  pub(crate) fn enter_func(&mut self, func: &RantFunction, call_stack_size: usize) {
+    let key = match &func.body {
+      RantFunctionInterface::Foreign(foreign_func) => FunctionKey {
+        name: self.native_names
+          .get(&(Rc::as_ptr(foreign_func) as *const ()))
+          .cloned()
+          .unwrap_or_else(|| "(native function)".to_owned()),
+        origin: None,
+      },
+      RantFunctionInterface::User(body) => FunctionKey {
+        name: body.name().map_or("(anonymous function)", |name| name.as_str()).to_owned(),
+        origin: Some(origin_name(&body.origin).to_owned()),
+      },
+    };
+    self.enter(key, call_stack_size);
+  }
+
+  /// Finishes recording all calls whose frames are no longer on a call stack of the specified size.
+  pub(crate) fn exit_to(&mut self, call_stack_size: usize) {
+    while matches!(self.call_stack.last(), Some(call) if call.call_stack_size > call_stack_size) {
+      let call = self.call_stack.pop().unwrap();
+      let elapsed = call.start_time.elapsed();
+      let allocations = self.allocations().saturating_sub(call.start_allocations);
+      let exclusive_time = elapsed.saturating_sub(call.child_time);
+
+      // Recursive calls are already covered by the outermost call's inclusive stats
+      let is_recursive = self.call_stack.iter().any(|active| active.func_index == call.func_index);
+
+      let profile = &mut self.functions[call.func_index];
+      if !is_recursive {
+        profile.inclusive_time += elapsed;
+        profile.inclusive_allocations += allocations;
+      }
+      profile.exclusive_time += exclusive_time;
+      profile.exclusive_allocations += allocations.saturating_sub(call.child_allocations);
+
+      let mut stack: Vec<usize> = self.call_stack.iter().map(|active| active.func_index).collect();
+      stack.push(call.func_index);
+      *self.folded_stacks.entry(stack).or_default() += exclusive_time;
+
+      if let Some(parent) = self.call_stack.last_mut() {
+        parent.child_time += elapsed;
+        parent.child_allocations += allocations;
+      }
+    }
+  }
+
+  /// Gets the collected data for each function, sorted by descending exclusive time.
+  pub fn functions(&self) -> Vec<&FunctionProfile> {
+    let mut functions: Vec<&FunctionProfile> = self.functions.iter().collect();
+    functions.sort_by(|a, b| b.exclusive_time.cmp(&a.exclusive_time).then_with(|| a.key.name.cmp(&b.key.name)));
+    functions
+  }
+
+  /// Generates a flat, human-readable report table sorted by descending exclusive time.
+  pub fn flat_report(&self) -> String {
+    let mut report = String::new();
+    let counts_allocations = self.allocation_counter.is_some();
+    write!(report, "{:>10} {:>12} {:>12}", "calls", "incl. (ms)", "excl. (ms)").unwrap();
+    if counts_allocations {
+      write!(report, " {:>12} {:>12}", "incl. allocs", "excl. allocs").unwrap();
+    }
+    writeln!(report, "  function").unwrap();
+
+    for profile in self.functions() {
+      write!(report, "{:>10} {:>12.3} {:>12.3}",
+        profile.calls,
+        profile.inclusive_time.as_secs_f64() * 1000.0,
+        profile.exclusive_time.as_secs_f64() * 1000.0
+      ).unwrap();
+      if counts_allocations {
+        write!(report, " {:>12} {:>12}", profile.inclusive_allocations, profile.exclusive_allocations).unwrap();
+      }
+      match &profile.key.origin {
+        Some(origin) => writeln!(report, "  {} in {}", profile.key.name, origin).unwrap(),
+        None => writeln!(report, "  {} (native)", profile.key.name).unwrap(),
+      }
+    }
+
+    report
+  }
+
+  /// Generates folded stacks (one `frame;frame;frame microseconds` line per unique stack) for use with flamegraph tools.
+  pub fn folded_stacks(&self) -> String {
+    let mut lines: Vec<String> = self.folded_stacks
+      .iter()
+      .map(|(stack, time)| {
+        let frames: Vec<String> = stack.iter().map(|i| self.functions[*i].key.folded_name()).collect();
+        format!("{} {}", frames.join(";"), time.as_micros())
+      })
+      .collect();
+    lines.sort();
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output
+  }
+}
+
+#[inline]
+pub(super) fn origin_name(info: &RantProgramInfo) -> &str {
+  info.path()
+    .or_else(|| info.name())
+    .unwrap_or(DEFAULT_PROGRAM_NAME)
+}
+
+impl<'rant> VM<'rant> {
+  /// Attaches a profiler to the VM.
+  #[inline]
+  pub(crate) fn with_profiler(mut self, profiler: &'rant mut Profiler) -> Self {
+    profiler.index_native_names(self.engine);
+    self.profiler = Some(profiler);
+    self
+  }
+}
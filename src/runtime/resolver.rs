@@ -1,5 +1,5 @@
 use std::{cell::RefCell, error::Error, fmt::Display, mem, ops::Index, rc::Rc};
-use crate::{FromRant, RantFunction, RantFunctionInterface, RantFunctionRef, RantValue, ValueError, lang::{Block, BlockElement, PrintFlag, Sequence}, rng::RantRng, runtime_error};
+use crate::{FromRant, RantFunction, RantFunctionInterface, RantFunctionRef, RantValue, ValueError, compiler::Position, lang::{Block, BlockElement, PrintFlag, Sequence}, rng::RantRng, runtime_error};
 use smallvec::SmallVec;
 use super::{IntoRuntimeResult, RuntimeError, RuntimeErrorType, RuntimeResult, StackFrameFlavor};
 
@@ -93,6 +93,10 @@ impl Index<usize> for Weights {
 pub struct BlockState {
   /// The elements of the block.
   elements: Rc<Vec<BlockElement>>,
+  /// The location of the block in its source.
+  pos: Position,
+  /// The index of the most recently selected element.
+  selected_index: Option<usize>,
   /// Element weights associated with the block
   weights: Option<Weights>,
   /// Flag to short-circuit the block
@@ -135,6 +139,7 @@ impl BlockState {
         |sel| sel.borrow_mut().select(self.elements.len(), rng)
       )?;
 
+      self.selected_index = Some(next_index);
       let next_elem = Rc::clone(&self.elements[next_index].main);
 
       // If the pipe function is set, generate piped elements
@@ -149,7 +154,8 @@ impl BlockState {
             StackFrameFlavor::RepeaterElement 
           } else { 
             StackFrameFlavor::BlockElement 
-          })
+          }),
+          return_type: None,
         };
         return Ok(Some(BlockAction::PipedElement {
           pipe_func: Rc::clone(pipe_func),
@@ -179,6 +185,23 @@ impl BlockState {
     self.cur_steps
   }
 
+  /// Gets the most recently selected element, if any.
+  #[inline]
+  pub fn selected_element(&self) -> Option<&BlockElement> {
+    self.selected_index.map(|index| &self.elements[index])
+  }
+
+  /// Gets the location of the block in its source.
+  #[inline]
+  pub fn pos(&self) -> &Position {
+    &self.pos
+  }
+
+  #[inline]
+  pub fn element_count(&self) -> usize {
+    self.elements.len()
+  }
+
   #[inline]
   pub fn step_count(&self) -> usize {
     self.total_steps
@@ -263,6 +286,8 @@ impl Resolver {
     let attrs = self.take_attrs();
     let state = BlockState {
       elements: Rc::clone(&block.elements),
+      pos: block.pos.clone(),
+      selected_index: None,
       weights,
       flag: PrintFlag::prioritize(block.flag, flag),
       cur_steps: 0,
@@ -287,12 +312,24 @@ impl Resolver {
     self.block_stack.pop()
   }
 
+  /// Puts a block state that was previously removed with `pop_block` back on top of the block stack.
+  #[inline]
+  pub(crate) fn restore_block(&mut self, state: BlockState) {
+    self.block_stack.push(state);
+  }
+
   /// Gets a reference to the active block state.
   #[inline]
   pub fn active_block(&self) -> Option<&BlockState> {
     self.block_stack.last()
   }
 
+  /// Gets a reference to the block state `depth` blocks below the active one.
+  #[inline]
+  pub fn parent_block(&self, depth: usize) -> Option<&BlockState> {
+    self.block_stack.iter().rev().nth(depth)
+  }
+
   /// Gets a mutable reference to the active block state.
   #[inline]
   pub fn active_block_mut(&mut self) -> Option<&mut BlockState> {
@@ -651,7 +688,7 @@ impl Selector {
   }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SelectorError {
   ElementCountMismatch { expected: usize, found: usize },
   InvalidElementCount(usize),
@@ -682,10 +719,17 @@ impl<T> IntoRuntimeResult<T> for Result<T, SelectorError> {
       error_type: super::RuntimeErrorType::SelectorError(err),
       description: None,
       stack_trace: None,
+      stack_frames: None,
     })
   }
 }
 
+/// The names of all selector modes accepted by `[mksel]`.
+pub const SELECTOR_MODE_NAMES: &[&str] = &[
+  "random", "one", "forward", "forward-clamp", "forward-mirror", "reverse", "reverse-clamp", "reverse-mirror",
+  "deck", "deck-loop", "deck-clamp", "deck-mirror", "ping", "pong", "no-double",
+];
+
 #[derive(Debug)]
 #[repr(u8)]
 pub enum SelectorMode {
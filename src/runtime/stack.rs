@@ -2,7 +2,7 @@ use std::{rc::Rc};
 use std::{collections::VecDeque};
 use fnv::{FnvBuildHasher};
 use quickscope::ScopeMap;
-use crate::{lang::{Sequence, Rst}, RantValue, Rant};
+use crate::{lang::{Sequence, Rst}, RantValue, Rant, RantProgramInfo};
 use crate::runtime::*;
 use super::{output::OutputWriter};
 
@@ -59,6 +59,53 @@ impl<I> CallStack<I> {
     self.frames.push(frame);
   }
 
+  /// Reuses the function body frame `depth` frames below the top of the stack to run a function called in tail position.
+  /// The frames above it are removed from the stack.
+  ///
+  /// The locals of the reused frame and the removed frames are dropped and replaced with a fresh layer for the callee,
+  /// so a chain of tail calls runs in constant stack space.
+  #[inline]
+  pub(crate) fn tail_call(&mut self, callee: Rc<Sequence>, depth: usize) {
+    let inner_frames = self.frames.drain(self.frames.len() - depth ..).collect::<Vec<_>>();
+    if let Some(frame) = self.frames.last_mut() {
+      frame.reuse_for_tail_call(callee, inner_frames);
+      for _ in 0..=depth {
+        self.locals.pop_layer();
+      }
+      self.locals.push_layer();
+    }
+  }
+
+  /// Gets the number of locals layers currently on the stack.
+  #[inline]
+  pub fn locals_depth(&self) -> usize {
+    self.locals.depth()
+  }
+
+  /// Removes the topmost `count` frames from the stack along with their locals so they can be resumed later with `resume_frames`.
+  pub(crate) fn suspend_frames(&mut self, count: usize) -> SuspendedFrames<I> {
+    let mut frames = vec![];
+    for _ in 0..count.min(self.frames.len()) {
+      let frame = self.frames.pop().unwrap();
+      let locals = self.locals.iter_top().map(|(id, var)| (id.clone(), var.clone())).collect();
+      self.locals.pop_layer();
+      frames.push(SuspendedFrame { frame, locals });
+    }
+    frames.reverse();
+    SuspendedFrames { frames }
+  }
+
+  /// Puts frames removed by `suspend_frames` back on top of the stack.
+  pub(crate) fn resume_frames(&mut self, suspended: SuspendedFrames<I>) {
+    for SuspendedFrame { frame, locals } in suspended.frames {
+      self.locals.push_layer();
+      for (id, var) in locals {
+        self.locals.define(id, var);
+      }
+      self.frames.push(frame);
+    }
+  }
+
   /// Returns a mutable reference to the topmost frame in the stack.
   #[inline]
   pub fn top_mut(&mut self) -> Option<&mut StackFrame<I>> {
@@ -84,37 +131,46 @@ impl<I> CallStack<I> {
     self.frames.last()
   }
 
+  /// Iterates over the frames in the stack, from top to bottom.
+  #[inline]
+  pub fn frames(&self) -> impl Iterator<Item = &StackFrame<I>> {
+    self.frames.iter().rev()
+  }
+
+  /// Iterates over the locals defined by the frame `depth` frames below the top of the stack.
+  pub fn locals_at(&self, depth: usize) -> impl Iterator<Item = (&str, &RantVar)> {
+    self.locals
+      .keys()
+      .filter_map(move |id| self.locals
+        .get_parent_depth(id, depth)
+        .filter(|(_, var_depth)| *var_depth == depth)
+        .map(|(var, _)| (id.as_str(), var))
+      )
+  }
+
   /// Generates a stack trace string from the current state of the stack.
   pub fn gen_stack_trace(&self) -> String {
-    let mut trace = String::new();
-    let mut last_frame_info: Option<(String, usize)> = None;
-    for frame in self.frames.iter().rev() {
-      let current_frame_string = frame.to_string();
-
-      if let Some((last_frame_string, count)) = last_frame_info.take() {
-        if current_frame_string == last_frame_string {
-          last_frame_info = Some((last_frame_string, count + 1));
-        } else {
-          // spit out last repeated frame
-          match count {
-            1 => trace.push_str(&format!("-> {}\n", last_frame_string)),
-            _ => trace.push_str(&format!("-> {} ({} frames)\n", last_frame_string, count)),
-          }
-          last_frame_info = Some((current_frame_string, 1));
-        }
-      } else {
-        last_frame_info = Some((current_frame_string, 1));
-      }
-    }
+    format_stack_trace(&self.gen_stack_frames())
+  }
 
-    // emit bottom frame
-    if let Some((last_frame_string, count)) = last_frame_info.take() {
-      match count {
-        1 => trace.push_str(&format!("-> {}", last_frame_string)),
-        _ => trace.push_str(&format!("-> {} ({} frames)", last_frame_string, count)),
+  /// Generates structured stack trace frames from the top of the stack down, collapsing consecutive identical frames.
+  pub fn gen_stack_frames(&self) -> Vec<StackTraceFrame> {
+    let mut trace: Vec<StackTraceFrame> = vec![];
+    for frame in self.frames.iter().rev() {
+      let (line, col) = frame.debug_pos();
+      let current_frame = StackTraceFrame {
+        name: frame.name().to_owned(),
+        flavor: frame.flavor(),
+        origin: Rc::clone(frame.origin()),
+        pos: if line > 0 { Some((line, col)) } else { None },
+        count: 1,
+      };
+
+      match trace.last_mut() {
+        Some(last_frame) if last_frame.is_same_location(&current_frame) => last_frame.count += 1,
+        _ => trace.push(current_frame),
       }
     }
-
     trace
   }
 
@@ -229,6 +285,7 @@ impl<I> CallStack<I> {
       error_type: RuntimeErrorType::InvalidAccess,
       description: Some(format!("{} '{}' not found", if prefer_function { "function" } else { "variable" }, id)),
       stack_trace: None,
+      stack_frames: None,
     })
   }
 
@@ -257,6 +314,7 @@ impl<I> CallStack<I> {
       error_type: RuntimeErrorType::InvalidAccess,
       description: Some(format!("variable '{}' not found", id)),
       stack_trace: None,
+      stack_frames: None,
     })
   }
 
@@ -340,6 +398,26 @@ impl<I> CallStack<I> {
   }
 }
 
+/// Frames taken off the top of a call stack, along with the locals they own.
+pub(crate) struct SuspendedFrames<I> {
+  /// Frames from the bottom of the stack up
+  frames: Vec<SuspendedFrame<I>>,
+}
+
+impl<I> SuspendedFrames<I> {
+  /// Gets the number of suspended frames.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.frames.len()
+  }
+}
+
+struct SuspendedFrame<I> {
+  frame: StackFrame<I>,
+  /// Locals defined in the frame
+  locals: Vec<(InternalString, RantVar)>,
+}
+
 /// Represents a call stack frame.
 pub struct StackFrame<I> {
   /// Node sequence being executed by the frame
@@ -358,6 +436,8 @@ pub struct StackFrame<I> {
   origin: Rc<RantProgramInfo>,
   /// A usage hint provided by the program element that created the frame.
   flavor: StackFrameFlavor,
+  /// Outputs set aside by tail calls, from outermost to innermost
+  tail_outputs: Vec<OutputWriter>,
 }
 
 impl<I> StackFrame<I> {
@@ -372,6 +452,7 @@ impl<I> StackFrame<I> {
       intents: Default::default(),
       debug_pos: (0, 0),
       flavor: Default::default(),
+      tail_outputs: Default::default(),
     }
   }
 
@@ -394,6 +475,7 @@ impl<I> StackFrame<I> {
       intents: Default::default(),
       debug_pos,
       flavor,
+      tail_outputs: Default::default(),
     }
   }
 
@@ -422,6 +504,41 @@ impl<I> StackFrame<I> {
     self.sequence.as_ref().and_then(|seq| seq.get(self.pc).map(Rc::clone))
   }
   
+  /// Gets the nodes that have yet to run in the frame's sequence, not counting the current node.
+  #[inline]
+  pub(crate) fn remaining_nodes(&self) -> &[Rc<Rst>] {
+    self.sequence.as_ref().and_then(|seq| seq.get(self.pc + 1 ..)).unwrap_or_default()
+  }
+
+  /// Iterates over the frame's pending intents in the order they will be handled.
+  #[inline]
+  pub(crate) fn intents(&self) -> impl Iterator<Item = &I> {
+    self.intents.iter()
+  }
+
+  /// Replaces the frame's sequence with the body of a function called in tail position, absorbing the frames above it.
+  ///
+  /// Anything already printed by the frame and the absorbed frames is set aside and wrapped around the callee's output when the frame finishes.
+  #[inline]
+  fn reuse_for_tail_call(&mut self, sequence: Rc<Sequence>, inner_frames: Vec<StackFrame<I>>) {
+    // Outputs are collected from the outermost frame inward
+    let mut replaced_outputs = vec![self.output.take()];
+    for frame in inner_frames {
+      replaced_outputs.extend(frame.tail_outputs.into_iter().map(Some));
+      replaced_outputs.push(frame.output);
+    }
+
+    self.output = replaced_outputs.iter().rev().flatten().next().map(|output| OutputWriter::new(Some(output)));
+    self.tail_outputs.extend(replaced_outputs.into_iter().flatten().filter(|output| !output.is_empty()));
+    self.origin = Rc::clone(&sequence.origin);
+    self.sequence = Some(sequence);
+    self.pc = 0;
+    self.started = false;
+    self.intents.clear();
+    self.debug_pos = (0, 0);
+    self.flavor = StackFrameFlavor::FunctionBody;
+  }
+
   /// Gets the Program Counter (PC) for the frame.
   #[inline(always)]
   pub fn pc(&self) -> usize {
@@ -449,6 +566,14 @@ impl<I> StackFrame<I> {
     self.debug_pos
   }
 
+  /// Gets the name of the function the frame belongs to, or a description of the frame's flavor if it is unnamed.
+  #[inline]
+  pub fn name(&self) -> &str {
+    self.sequence.as_ref()
+      .and_then(|seq| seq.name().map(|name| name.as_str()))
+      .unwrap_or_else(|| self.flavor.name())
+  }
+
   #[inline]
   pub fn origin_name(&self) -> &str {
     self.origin.path
@@ -533,8 +658,23 @@ impl<I> StackFrame<I> {
 
   /// Consumes the frame's output and returns the final value generated by it.
   #[inline]
-  pub fn into_output(mut self) -> Option<RantValue> {
-    self.output.take().map(|o| o.render_value())
+  pub fn into_output(mut self, big_ints: bool) -> RuntimeResult<Option<RantValue>> {
+    let value = match self.output.take() {
+      Some(output) => output.render_value(big_ints)?,
+      None => return Ok(None),
+    };
+    self.finish_tail_calls(value, big_ints).map(Some)
+  }
+
+  /// Prints a value returned by the frame's current function to the outputs of any functions it replaced through tail calls.
+  /// Returns the value the outermost function would have returned.
+  #[inline]
+  pub(crate) fn finish_tail_calls(&mut self, mut value: RantValue, big_ints: bool) -> RuntimeResult<RantValue> {
+    while let Some(mut output) = self.tail_outputs.pop() {
+      output.write_value(value);
+      value = output.render_value(big_ints)?;
+    }
+    Ok(value)
   }
 }
 
@@ -544,9 +684,7 @@ impl<I> Display for StackFrame<I> {
       self.origin_name(), 
       self.debug_pos.0, 
       self.debug_pos.1,
-      self.sequence.as_ref()
-        .and_then(|seq| seq.name().map(|name| name.as_str()))
-        .unwrap_or_else(|| self.flavor.name()), 
+      self.name(),
     )
   }
 }
@@ -12,12 +12,14 @@ pub(crate) fn rep(vm: &mut VM, reps: RantValue) -> RantStdResult {
         error_type: RuntimeErrorType::ArgumentError,
         description: Some(format!("unknown repetition mode: '{}'", s)),
         stack_trace: None,
+        stack_frames: None,
       })
     },
     _ => return Err(RuntimeError {
       error_type: RuntimeErrorType::ArgumentError,
       description: Some(format!("value of type '{}' cannot be used as repetition value", reps.type_name())),
       stack_trace: None,
+      stack_frames: None,
     })
   };
   Ok(())
@@ -77,6 +79,7 @@ pub(crate) fn sel(vm: &mut VM, selector: Option<RantValue>) -> RantStdResult {
         }),
         description: Some("value is not a selector".to_owned()),
         stack_trace: None,
+        stack_frames: None,
       })
     },
     None => None,
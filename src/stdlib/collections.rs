@@ -37,7 +37,7 @@ pub(crate) fn squish(vm: &mut VM, (list, target_size): (RantListRef, usize)) ->
     let right_index = left_index + 1;
     let left_val = list.get(left_index).unwrap().clone();
     let right_val = list.remove(right_index);
-    list[left_index] = left_val + right_val;
+    list[left_index] = checked_arith(vm, [left_val, right_val], |[lhs, rhs]| lhs + rhs)?;
   }
 
   Ok(())
@@ -57,7 +57,7 @@ pub(crate) fn squished(vm: &mut VM, (list, target_size): (RantListRef, usize)) -
     let right_index = (left_index + 1) % n;
     let left_val = list.get(left_index).unwrap().clone();
     let right_val = list.remove(right_index);
-    list[left_index] = left_val + right_val;
+    list[left_index] = checked_arith(vm, [left_val, right_val], |[lhs, rhs]| lhs + rhs)?;
   }
 
   vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list))));
@@ -65,7 +65,17 @@ pub(crate) fn squished(vm: &mut VM, (list, target_size): (RantListRef, usize)) -
   Ok(())
 }
 
-pub(crate) fn filter(vm: &mut VM, (list, predicate): (RantListRef, RantFunctionRef)) -> RantStdResult {
+pub(crate) fn filter(vm: &mut VM, (collection, predicate): (RantValue, RantFunctionRef)) -> RantStdResult {
+  // Other iterables are filtered lazily
+  let list = match collection {
+    RantValue::List(list) => list,
+    other => {
+      let iter = RantIterator::filter(iter_arg("filter", other)?, predicate);
+      vm.cur_frame_mut().write_value(RantValue::Iterator(Rc::new(RefCell::new(iter))));
+      return Ok(())
+    }
+  };
+
   let list_ref = list.borrow();
   if list_ref.is_empty() {
     vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list_ref.clone()))));
@@ -129,7 +139,17 @@ pub(crate) fn filter(vm: &mut VM, (list, predicate): (RantListRef, RantFunctionR
   Ok(())
 }
 
-pub(crate) fn map(vm: &mut VM, (list, map_func): (RantListRef, RantFunctionRef)) -> RantStdResult {
+pub(crate) fn map(vm: &mut VM, (collection, map_func): (RantValue, RantFunctionRef)) -> RantStdResult {
+  // Other iterables are mapped lazily
+  let list = match collection {
+    RantValue::List(list) => list,
+    other => {
+      let iter = RantIterator::map(iter_arg("map", other)?, map_func);
+      vm.cur_frame_mut().write_value(RantValue::Iterator(Rc::new(RefCell::new(iter))));
+      return Ok(())
+    }
+  };
+
   let list_ref = list.borrow();
   if list_ref.is_empty() {
     vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list_ref.clone()))));
@@ -186,7 +206,16 @@ pub(crate) fn map(vm: &mut VM, (list, map_func): (RantListRef, RantFunctionRef))
   Ok(())
 }
 
-pub(crate) fn zip(vm: &mut VM, (list_a, list_b, zip_func): (RantListRef, RantListRef, RantFunctionRef)) -> RantStdResult {
+pub(crate) fn zip(vm: &mut VM, (collection_a, collection_b, zip_func): (RantValue, RantValue, RantFunctionRef)) -> RantStdResult {
+  // Zipping anything other than two lists is lazy
+  let (list_a, list_b) = match (collection_a, collection_b) {
+    (RantValue::List(list_a), RantValue::List(list_b)) => (list_a, list_b),
+    (a, b) => {
+      let iter = RantIterator::zip(iter_arg("zip", a)?, iter_arg("zip", b)?, zip_func);
+      vm.cur_frame_mut().write_value(RantValue::Iterator(Rc::new(RefCell::new(iter))));
+      return Ok(())
+    }
+  };
   let (list_a_ref, list_b_ref) = (list_a.borrow(), list_b.borrow());
   let max_len = list_a_ref.len().max(list_b_ref.len());
 
@@ -279,17 +308,16 @@ pub(crate) fn oxford_join(vm: &mut VM, (comma, conj, comma_conj, list): (RantVal
   Ok(())
 }
 
-pub(crate) fn sum(vm: &mut VM, list: RantListRef) -> RantStdResult {
-  let list = list.borrow();
+pub(crate) fn sum(vm: &mut VM, list: Vec<RantValue>) -> RantStdResult {
   if list.is_empty() {
     return Ok(())
   }
 
-  let mut iter = list.iter().cloned();
+  let mut iter = list.into_iter();
   let mut sum = iter.next().unwrap();
   
   for val in iter {
-    sum = sum + val;
+    sum = checked_arith(vm, [sum, val], |[lhs, rhs]| lhs + rhs)?;
   }
 
   vm.cur_frame_mut().write_value(sum);
@@ -320,6 +348,7 @@ pub(crate) fn clear(vm: &mut VM, collection: RantValue) -> RantStdResult {
   match collection {
     RantValue::List(list) => list.borrow_mut().clear(),
     RantValue::Map(map) => map.borrow_mut().clear(),
+    RantValue::Set(set) => set.borrow_mut().clear(),
     _ => {
       runtime_error!(RuntimeErrorType::ArgumentError, "value passed to [clear] must be a collection type");
     }
@@ -368,8 +397,17 @@ pub(crate) fn translate(vm: &mut VM, (list, map): (RantListRef, RantMapRef)) ->
   Ok(())
 }
 
-pub(crate) fn list_push(vm: &mut VM, (list, value): (RantListRef, RantValue)) -> RantStdResult {
-  list.borrow_mut().push(value);
+pub(crate) fn list_push(vm: &mut VM, (collection, value): (RantValue, RantValue)) -> RantStdResult {
+  match collection {
+    RantValue::List(list) => list.borrow_mut().push(value),
+    // Sets ignore values they already have
+    RantValue::Set(set) => {
+      set.borrow_mut().insert(value).into_runtime_result()?;
+    },
+    other => {
+      runtime_error!(RuntimeErrorType::ArgumentError, "cannot push to '{}' value", other.type_name());
+    }
+  }
   Ok(())
 }
 
@@ -485,6 +523,10 @@ pub(crate) fn remove(vm: &mut VM, (collection, pos): (RantValue, RantValue)) ->
       // TODO: Replace with prototype key-remove function
       map.raw_remove(key.as_str());
     },
+    // Remove from set by value
+    (RantValue::Set(set), value) => {
+      set.borrow_mut().remove(&value);
+    },
     _ => {
       runtime_error!(RuntimeErrorType::ArgumentError, "cannot remove from a non-collection");
     }
@@ -494,6 +536,14 @@ pub(crate) fn remove(vm: &mut VM, (collection, pos): (RantValue, RantValue)) ->
 
 pub(crate) fn take(vm: &mut VM, (collection, pos): (RantValue, RantValue)) -> RantStdResult {
   match (collection, pos) {
+    // Lazily take items from iterator
+    (RantValue::Iterator(iter), RantValue::Int(count)) => {
+      let iter = RantIterator::take(iter, count.max(0) as usize);
+      vm.cur_frame_mut().write_value(RantValue::Iterator(Rc::new(RefCell::new(iter))));
+    },
+    (RantValue::Iterator(_), non_count) => {
+      runtime_error!(RuntimeErrorType::ArgumentError, "cannot take '{}' items from iterator", non_count.type_name());
+    },
     // Take from list by index
     (RantValue::List(list), RantValue::Int(index)) => {
       let mut list = list.borrow_mut();
@@ -561,6 +611,9 @@ pub(crate) fn has(vm: &mut VM, (value, key): (RantValue, RantValue)) -> RantStdR
     (RantValue::List(list), element) => {
       list.borrow().contains(&element)
     },
+    (RantValue::Set(set), element) => {
+      set.borrow().contains(&element)
+    },
     (value, key) => {
       runtime_error!(RuntimeErrorType::ArgumentError, "unable to check if value of type '{}' contains element of type '{}'", value.type_name(), key.type_name())
     }
@@ -570,6 +623,24 @@ pub(crate) fn has(vm: &mut VM, (value, key): (RantValue, RantValue)) -> RantStdR
   Ok(())
 }
 
+pub(crate) fn union(vm: &mut VM, (a, b): (RantSetRef, RantSetRef)) -> RantStdResult {
+  let set = a.borrow().union(&b.borrow());
+  vm.cur_frame_mut().write_value(RantValue::Set(Rc::new(RefCell::new(set))));
+  Ok(())
+}
+
+pub(crate) fn intersection(vm: &mut VM, (a, b): (RantSetRef, RantSetRef)) -> RantStdResult {
+  let set = a.borrow().intersection(&b.borrow());
+  vm.cur_frame_mut().write_value(RantValue::Set(Rc::new(RefCell::new(set))));
+  Ok(())
+}
+
+pub(crate) fn difference(vm: &mut VM, (a, b): (RantSetRef, RantSetRef)) -> RantStdResult {
+  let set = a.borrow().difference(&b.borrow());
+  vm.cur_frame_mut().write_value(RantValue::Set(Rc::new(RefCell::new(set))));
+  Ok(())
+}
+
 pub(crate) fn seg(vm: &mut VM, (collection, seg_size): (RantValue, usize)) -> RantStdResult {
   if !collection.is_indexable() {
     runtime_error!(RuntimeErrorType::ArgumentError, "seg: type '{}' cannot be segmented", collection.type_name())
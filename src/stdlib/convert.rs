@@ -21,6 +21,32 @@ pub(crate) fn to_bool(vm: &mut VM, value: RantValue) -> RantStdResult {
 }
 
 pub(crate) fn to_list(vm: &mut VM, collection: RantValue) -> RantStdResult {
+  // Iterators are read to the end
+  if let RantValue::Iterator(iter) = collection {
+    return RantIterator::collect(iter, vm, RantList::new(), Box::new(|vm, list| {
+      vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list))));
+      Ok(())
+    }))
+  }
+
   vm.cur_frame_mut().write_value(collection.into_rant_list());
   Ok(())
+}
+
+pub(crate) fn to_set(vm: &mut VM, collection: RantValue) -> RantStdResult {
+  // Iterators are read to the end
+  if let RantValue::Iterator(iter) = collection {
+    return RantIterator::collect(iter, vm, RantList::new(), Box::new(|vm, list| {
+      let set = RantSet::from_values(list).into_runtime_result()?;
+      vm.cur_frame_mut().write_value(RantValue::Set(Rc::new(RefCell::new(set))));
+      Ok(())
+    }))
+  }
+
+  let set = match collection.into_rant_list() {
+    RantValue::List(list) => RantValue::Set(Rc::new(RefCell::new(RantSet::from_values(list.borrow().iter().cloned()).into_runtime_result()?))),
+    _ => RantValue::Empty,
+  };
+  vm.cur_frame_mut().write_value(set);
+  Ok(())
 }
\ No newline at end of file
@@ -129,17 +129,42 @@ pub(crate) fn len(vm: &mut VM, val: RantValue) -> RantStdResult {
   Ok(())
 }
 
-pub(crate) fn error(vm: &mut VM, msg: Option<String>) -> RantStdResult {
+pub(crate) fn error(vm: &mut VM, (value, id): (Option<RantValue>, Option<String>)) -> RantStdResult {
   const DEFAULT_ERROR_MESSAGE: &str = "user error";
   Err(RuntimeError {
-    error_type: RuntimeErrorType::UserError,
-    description: msg,
+    description: Some(value.as_ref().map_or_else(|| DEFAULT_ERROR_MESSAGE.to_owned(), |value| value.to_string())),
+    error_type: RuntimeErrorType::UserError(Box::new(UserError {
+      id,
+      payload: value,
+    })),
     stack_trace: None,
+    stack_frames: None,
   })
 }
 
-pub(crate) fn range(vm: &mut VM, (a, b, step): (i64, Option<i64>, Option<u64>)) -> RantStdResult {
-  let step = step.unwrap_or(1);
+pub(crate) fn rethrow(vm: &mut VM, err: RantMapRef) -> RantStdResult {
+  let err = err.borrow();
+  // Maps from `[try]` carry the error they were made from
+  if let Some(RantValue::Special(RantSpecial::Error(source))) = err.raw_get("source") {
+    return Err(RuntimeError::clone(source))
+  }
+  let id = match err.raw_get("id") {
+    Some(RantValue::String(id)) => id.to_string(),
+    _ => runtime_error!(RuntimeErrorType::ArgumentError, "rethrow: error map has no 'id' string"),
+  };
+  Err(RuntimeError {
+    description: err.raw_get("message").map(|message| message.to_string()),
+    error_type: RuntimeErrorType::UserError(Box::new(UserError {
+      id: Some(id),
+      payload: err.raw_get("payload").cloned(),
+    })),
+    stack_trace: None,
+    stack_frames: None,
+  })
+}
+
+pub(crate) fn range(vm: &mut VM, (a, b, step): (RantValue, Option<RantValue>, Option<RantValue>)) -> RantStdResult {
+  let (a, b, step) = range_args("range", a, b, step)?;
   
   let range = if let Some(b) = b {
     RantRange::new(a, b, step)
@@ -151,8 +176,8 @@ pub(crate) fn range(vm: &mut VM, (a, b, step): (i64, Option<i64>, Option<u64>))
   Ok(())
 }
 
-pub(crate) fn irange(vm: &mut VM, (a, b, step): (i64, Option<i64>, Option<u64>)) -> RantStdResult {
-  let step = step.unwrap_or(1);
+pub(crate) fn irange(vm: &mut VM, (a, b, step): (RantValue, Option<RantValue>, Option<RantValue>)) -> RantStdResult {
+  let (a, b, step) = range_args("irange", a, b, step)?;
   
   let range = if let Some(b) = b {
     RantRange::new(a, b + if a <= b { 1 } else { -1 }, step)
@@ -164,6 +189,14 @@ pub(crate) fn irange(vm: &mut VM, (a, b, step): (i64, Option<i64>, Option<u64>))
   Ok(())
 }
 
+/// Converts the bounds and step of a range, which can't be big integers.
+fn range_args(fname: &str, a: RantValue, b: Option<RantValue>, step: Option<RantValue>) -> RuntimeResult<(i64, Option<i64>, u64)> {
+  let a = small_int_arg(fname, a)?;
+  let b = b.map(|b| small_int_arg(fname, b)).transpose()?;
+  let step = step.map(|step| small_int_arg(fname, step)).transpose()?.unwrap_or(1);
+  Ok((a, b, step))
+}
+
 pub(crate) fn require(vm: &mut VM, module_path: String) -> RantStdResult {
   // Get name of module from path
   if let Some(module_name) = 
@@ -185,6 +218,7 @@ pub(crate) fn require(vm: &mut VM, module_path: String) -> RantStdResult {
     // If not cached, attempt to load it from file and run its root sequence
     let caller_origin = Rc::clone(&vm.cur_frame().origin());
     let module_pgm = vm.context_mut().try_read_module(&module_path, caller_origin).into_runtime_result()?;
+    vm.cover_module(&module_pgm.root);
     vm.cur_frame_mut().push_intent_front(Intent::ImportLastAsModule { module_name, descope: 1 });
     vm.push_frame_flavored(Rc::clone(&module_pgm.root), true, StackFrameFlavor::FunctionBody)?;
     Ok(())
@@ -193,8 +227,14 @@ pub(crate) fn require(vm: &mut VM, module_path: String) -> RantStdResult {
   }
 }
 
-pub(crate) fn try_(vm: &mut VM, (context, handler): (RantValue, Option<RantFunctionRef>)) -> RantStdResult {
-  vm.push_unwind_state(handler);
+pub(crate) fn try_(vm: &mut VM, (context, handler, catch): (RantValue, Option<RantFunctionRef>, Option<RantValue>)) -> RantStdResult {
+  let error_ids = match catch {
+    None | Some(RantValue::Empty) => None,
+    Some(RantValue::String(id)) => Some(vec![id.to_string()]),
+    Some(RantValue::List(ids)) => Some(ids.borrow().iter().map(|id| id.to_string()).collect()),
+    Some(other) => runtime_error!(RuntimeErrorType::ArgumentError, "try: expected error ID string or list, but found '{}'", other.type_name()),
+  };
+  vm.push_unwind_state(handler, error_ids);
   vm.cur_frame_mut().push_intent_front(Intent::DropStaleUnwinds);
   match context {
     RantValue::Function(func) => {
@@ -216,4 +256,83 @@ pub(crate) fn data(vm: &mut VM, (data_source_name, data_source_args): (InternalS
     }
   }
   Ok(())
-}
\ No newline at end of file
+}
+
+/// `[$retry: generator (function); predicate (function); max-tries (int)]`
+///
+/// Calls `generator` until `predicate` accepts its output, then prints the accepted value.
+/// Each attempt runs on its own RNG fork, so the result is deterministic for a given seed.
+pub(crate) fn retry(vm: &mut VM, (generator, predicate, max_tries): (RantFunctionRef, RantFunctionRef, usize)) -> RantStdResult {
+  if max_tries == 0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "retry: max-tries must be greater than 0");
+  }
+
+  fn _iterate_retry(vm: &mut VM, base_rng: Rc<RantRng>, generator: RantFunctionRef, predicate: RantFunctionRef, attempt: usize, max_tries: usize) -> RuntimeResult<()> {
+    if attempt >= max_tries {
+      runtime_error!(RuntimeErrorType::RetryError(attempt));
+    }
+
+    // Run the generator on a dedicated fork for this attempt
+    vm.push_rng(Rc::new(base_rng.fork_u64(attempt as u64)));
+
+    let generator_clone = Rc::clone(&generator);
+
+    // Check the candidate once the generator returns
+    vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall {
+      function: Box::new(move |vm| {
+        vm.pop_rng();
+        let candidate = vm.pop_val()?;
+        let predicate_clone = Rc::clone(&predicate);
+        let candidate_clone = candidate.clone();
+
+        // Accept or reject the candidate once the predicate returns
+        vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall {
+          function: Box::new(move |vm| {
+            match vm.pop_val()? {
+              RantValue::Boolean(true) => {
+                vm.cur_frame_mut().write_value(candidate);
+                Ok(())
+              },
+              RantValue::Boolean(false) => _iterate_retry(vm, base_rng, generator, predicate, attempt + 1, max_tries),
+              other => runtime_error!(RuntimeErrorType::TypeError, "retry predicate expected to return 'bool' value, but returned '{}' instead", other.type_name())
+            }
+          }),
+          interrupt: false,
+        });
+
+        // Prepare predicate call for the candidate
+        vm.push_val(RantValue::Function(predicate_clone))?;
+        vm.push_val(candidate_clone)?;
+        vm.cur_frame_mut().push_intent_front(Intent::Call {
+          argc: 1,
+          flag: PrintFlag::None,
+          override_print: true,
+        });
+
+        Ok(())
+      }),
+      interrupt: false,
+    });
+
+    // Prepare generator call for the current attempt
+    vm.push_val(RantValue::Function(generator_clone))?;
+    vm.cur_frame_mut().push_intent_front(Intent::Call {
+      argc: 0,
+      flag: PrintFlag::None,
+      override_print: true,
+    });
+
+    Ok(())
+  }
+
+  // Derive the attempt forks from a single fork of the current RNG so that repeated calls don't share attempts
+  let base_rng = Rc::new(vm.rng().fork_random());
+  vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall {
+    function: Box::new(move |vm| {
+      _iterate_retry(vm, base_rng, generator, predicate, 0, max_tries)
+    }),
+    interrupt: false,
+  });
+
+  Ok(())
+}
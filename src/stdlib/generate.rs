@@ -1,6 +1,7 @@
 use super::*;
 
-pub(crate) fn rand(vm: &mut VM, (a, b): (i64, i64)) -> RantStdResult {
+pub(crate) fn rand(vm: &mut VM, (a, b): (RantValue, RantValue)) -> RantStdResult {
+  let (a, b) = (small_int_arg("rand", a)?, small_int_arg("rand", b)?);
   let n = vm.rng().next_i64(a, b);
   vm.cur_frame_mut().write_value(RantValue::Int(n));
   Ok(())
@@ -12,7 +13,8 @@ pub(crate) fn randf(vm: &mut VM, (a, b): (f64, f64)) -> RantStdResult {
   Ok(())
 }
 
-pub(crate) fn rand_list(vm: &mut VM, (a, b, n): (i64, i64, usize)) -> RantStdResult {
+pub(crate) fn rand_list(vm: &mut VM, (a, b, n): (RantValue, RantValue, usize)) -> RantStdResult {
+  let (a, b) = (small_int_arg("rand-list", a)?, small_int_arg("rand-list", b)?);
   let mut list = RantList::new();
   let rng = vm.rng();
   for _ in 0..n {
@@ -90,6 +92,7 @@ pub(crate) fn shred(vm: &mut VM, (value, n, variance): (RantValue, i64, Option<f
       error_type: RuntimeErrorType::ArgumentError,
       description: Some("shred count must be greater than zero".to_owned()),
       stack_trace: None,
+      stack_frames: None,
     })
   }
 
@@ -150,6 +153,7 @@ pub(crate) fn shred(vm: &mut VM, (value, n, variance): (RantValue, i64, Option<f
         error_type: RuntimeErrorType::ArgumentError,
         description: Some(format!("cannot shred '{}' value", other.type_name())),
         stack_trace: None,
+        stack_frames: None,
       })
     }
   }
@@ -0,0 +1,86 @@
+use super::*;
+
+/// Gets an iterator over a value passed to a stdlib function, or raises an argument error if it can't be iterated over.
+pub(crate) fn iter_arg(fname: &str, value: RantValue) -> RuntimeResult<RantIteratorRef> {
+  let type_name = value.type_name();
+  match RantIterator::from_value(value) {
+    Some(iter) => Ok(iter),
+    None => runtime_error!(RuntimeErrorType::ArgumentError, "{}: cannot iterate over '{}' value", fname, type_name),
+  }
+}
+
+/// `[$iter: value (any)]`
+///
+/// Gets an iterator over the items of a list, set, range, or string, or the keys of a map. Iterators are returned unchanged.
+pub(crate) fn iter(vm: &mut VM, value: RantValue) -> RantStdResult {
+  let iter = iter_arg("iter", value)?;
+  vm.cur_frame_mut().write_value(RantValue::Iterator(iter));
+  Ok(())
+}
+
+/// `[$iter-func: func (function)]`
+///
+/// Gets an iterator that calls `func` for each item and ends when it returns `empty`.
+pub(crate) fn iter_func(vm: &mut VM, func: RantFunctionRef) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Iterator(Rc::new(RefCell::new(RantIterator::from_func(func)))));
+  Ok(())
+}
+
+/// `[$iter-next: iter (iterator); fallback? (any)]`
+///
+/// Advances an iterator and returns its next item, or `fallback` if the iterator is done.
+pub(crate) fn iter_next(vm: &mut VM, (iter, fallback): (RantValue, Option<RantValue>)) -> RantStdResult {
+  let iter = iter_arg("iter-next", iter)?;
+  RantIterator::next(&iter, vm, Box::new(move |vm, item| {
+    if let Some(item) = item.or(fallback) {
+      vm.cur_frame_mut().write_value(item);
+    }
+    Ok(())
+  }))
+}
+
+/// `[$chain: values* (any)]`
+///
+/// Gets an iterator over the items of each value in turn.
+pub(crate) fn chain(vm: &mut VM, values: VarArgs<RantValue>) -> RantStdResult {
+  let sources = values.iter().cloned().map(|value| iter_arg("chain", value)).collect::<RuntimeResult<Vec<_>>>()?;
+  vm.cur_frame_mut().write_value(RantValue::Iterator(Rc::new(RefCell::new(RantIterator::chain(sources)))));
+  Ok(())
+}
+
+/// `[$gen: func (function); args* (any)]`
+///
+/// Creates a generator, an iterator over the values yielded by `func` when called with `args`.
+/// The function doesn't run until the first item is read.
+pub(crate) fn gen(vm: &mut VM, (func, args): (RantFunctionRef, VarArgs<RantValue>)) -> RantStdResult {
+  let gen = RantIterator::from_generator(func, args.iter().cloned().collect());
+  vm.cur_frame_mut().write_value(RantValue::Iterator(Rc::new(RefCell::new(gen))));
+  Ok(())
+}
+
+/// `[$resume: gen (iterator)]`
+///
+/// Runs a generator until its next `@yield` and returns the yielded value.
+pub(crate) fn resume(vm: &mut VM, gen: RantValue) -> RantStdResult {
+  let gen = iter_arg("resume", gen)?;
+  if !gen.borrow().is_generator() {
+    runtime_error!(RuntimeErrorType::ArgumentError, "resume: iterator is not a generator");
+  }
+  RantIterator::next(&gen, vm, Box::new(|vm, item| {
+    if let Some(item) = item {
+      vm.cur_frame_mut().write_value(item);
+    }
+    Ok(())
+  }))
+}
+
+/// `[$is-done: iter (iterator)]`
+///
+/// Returns `true` if an iterator has run out of items.
+/// Iterators are only known to be done after reading past their last item, but generators are done as soon as their function returns.
+pub(crate) fn is_done(vm: &mut VM, iter: RantValue) -> RantStdResult {
+  let iter = iter_arg("is-done", iter)?;
+  let is_done = iter.borrow().is_done();
+  vm.cur_frame_mut().write_value(RantValue::Boolean(is_done));
+  Ok(())
+}
@@ -1,12 +1,29 @@
 use cervine::Cow;
+use num_traits::ToPrimitive;
 
 use super::*;
 
+/// Applies an arithmetic operation to some operands.
+/// If integer arithmetic overflows and big integers are enabled, the operation is retried with big integer operands.
+pub(crate) fn checked_arith<const N: usize>(vm: &VM, operands: [RantValue; N], op: impl Fn([RantValue; N]) -> ValueResult<RantValue>) -> RuntimeResult<RantValue> {
+  promote_on_overflow(vm.context().options().enable_big_ints, operands, op).into_runtime_result()
+}
+
+/// Converts an integer argument of a function that only works with 64-bit integers.
+/// Big integers raise an arithmetic error naming the function instead of a conversion error.
+pub(crate) fn small_int_arg<T: FromRant>(fname: &str, value: RantValue) -> RuntimeResult<T> {
+  if let RantValue::BigInt(n) = &value {
+    runtime_error!(RuntimeErrorType::ArithmeticError, "{}: {} is too large; this function only supports 64-bit integers", fname, n);
+  }
+  T::from_rant(value).into_runtime_result()
+}
+
 /// `[$add: lhs (any); rhs (any)]`
 ///
 /// Adds two values.
 pub(crate) fn add(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdResult {
-  vm.cur_frame_mut().write_value(lhs + rhs);
+  let sum = checked_arith(vm, [lhs, rhs], |[lhs, rhs]| lhs + rhs)?;
+  vm.cur_frame_mut().write_value(sum);
   Ok(())
 }
 
@@ -19,7 +36,8 @@ pub(crate) fn clamp(vm: &mut VM, (value, a, b): (RantValue, RantValue, RantValue
 ///
 /// Multiplies two values.
 pub(crate) fn mul(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdResult {
-  vm.cur_frame_mut().write_value(lhs * rhs);
+  let product = checked_arith(vm, [lhs, rhs], |[lhs, rhs]| lhs * rhs)?;
+  vm.cur_frame_mut().write_value(product);
   Ok(())
 }
 
@@ -27,7 +45,8 @@ pub(crate) fn mul(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdRes
 ///
 /// Multiplies two values, then adds a third value to the result.
 pub(crate) fn mul_add(vm: &mut VM, (lhs, mhs, rhs): (RantValue, RantValue, RantValue)) -> RantStdResult {
-  vm.cur_frame_mut().write_value(lhs * mhs + rhs);
+  let result = checked_arith(vm, [lhs, mhs, rhs], |[lhs, mhs, rhs]| (lhs * mhs)? + rhs)?;
+  vm.cur_frame_mut().write_value(result);
   Ok(())
 }
 
@@ -35,7 +54,8 @@ pub(crate) fn mul_add(vm: &mut VM, (lhs, mhs, rhs): (RantValue, RantValue, RantV
 ///
 /// Subtracts one value from another.
 pub(crate) fn sub(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdResult {
-  vm.cur_frame_mut().write_value(lhs - rhs);
+  let difference = checked_arith(vm, [lhs, rhs], |[lhs, rhs]| lhs - rhs)?;
+  vm.cur_frame_mut().write_value(difference);
   Ok(())
 }
 
@@ -43,7 +63,8 @@ pub(crate) fn sub(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdRes
 ///
 /// Divides one number by another.
 pub(crate) fn div(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdResult {
-  vm.cur_frame_mut().write_value((lhs / rhs).into_runtime_result()?);
+  let quotient = checked_arith(vm, [lhs, rhs], |[lhs, rhs]| lhs / rhs)?;
+  vm.cur_frame_mut().write_value(quotient);
   Ok(())
 }
 
@@ -51,7 +72,8 @@ pub(crate) fn div(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdRes
 ///
 /// Gets the modulus of two values.
 pub(crate) fn mod_(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdResult {
-  vm.cur_frame_mut().write_value((lhs % rhs).into_runtime_result()?);
+  let remainder = checked_arith(vm, [lhs, rhs], |[lhs, rhs]| lhs % rhs)?;
+  vm.cur_frame_mut().write_value(remainder);
   Ok(())
 }
 
@@ -59,7 +81,8 @@ pub(crate) fn mod_(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdRe
 ///
 /// Negates a value.
 pub(crate) fn neg(vm: &mut VM, val: RantValue) -> RantStdResult {
-  vm.cur_frame_mut().write_value(-val);
+  let negated = checked_arith(vm, [val], |[val]| -val)?;
+  vm.cur_frame_mut().write_value(negated);
   Ok(())
 }
 
@@ -77,7 +100,7 @@ pub(crate) fn recip(vm: &mut VM, val: RantValue) -> RantStdResult {
 pub(crate) fn floor(vm: &mut VM, val: RantValue) -> RantStdResult {
   let val_result = match val {
     RantValue::Float(f) => RantValue::Float(f.floor()),
-    RantValue::Int(i) => RantValue::Int(i),
+    RantValue::Int(_) | RantValue::BigInt(_) => val,
     other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use floor function on '{}' value")
   };
   vm.cur_frame_mut().write_value(val_result);
@@ -90,7 +113,7 @@ pub(crate) fn floor(vm: &mut VM, val: RantValue) -> RantStdResult {
 pub(crate) fn ceil(vm: &mut VM, val: RantValue) -> RantStdResult {
   let val_result = match val {
     RantValue::Float(f) => RantValue::Float(f.ceil()),
-    RantValue::Int(i) => RantValue::Int(i),
+    RantValue::Int(_) | RantValue::BigInt(_) => val,
     other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use ceil function on '{}' value")
   };
   vm.cur_frame_mut().write_value(val_result);
@@ -113,7 +136,8 @@ pub(crate) fn frac(vm: &mut VM, val: RantValue) -> RantStdResult {
 ///
 /// Calculates the absolute value of `num`.
 pub(crate) fn abs(vm: &mut VM, num: RantValue) -> RantStdResult {
-  vm.cur_frame_mut().write_value(num.abs().into_runtime_result()?);
+  let abs = checked_arith(vm, [num], |[num]| num.abs())?;
+  vm.cur_frame_mut().write_value(abs);
   Ok(())
 }
 
@@ -121,7 +145,8 @@ pub(crate) fn abs(vm: &mut VM, num: RantValue) -> RantStdResult {
 ///
 /// Raises `lhs` to the `rhs` power.
 pub(crate) fn pow(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdResult {
-  vm.cur_frame_mut().write_value(lhs.pow(rhs).into_runtime_result()?);
+  let power = checked_arith(vm, [lhs, rhs], |[lhs, rhs]| lhs.pow(rhs))?;
+  vm.cur_frame_mut().write_value(power);
   Ok(())
 }
 
@@ -187,6 +212,7 @@ pub(crate) fn atan2(vm: &mut VM, (y, x): (f64, f64)) -> RantStdResult {
 pub(crate) fn sqrt(vm: &mut VM, num: RantValue) -> RantStdResult {
   let result = match num {
     RantValue::Int(i) => RantValue::Float((i as f64).sqrt()),
+    RantValue::BigInt(i) => RantValue::Float(i.to_f64().unwrap_or(f64::NAN).sqrt()),
     RantValue::Float(f) => RantValue::Float(f.sqrt()),
     other => runtime_error!(RuntimeErrorType::ArgumentError, "can't take square root of type '{}'", other.type_name())
   };
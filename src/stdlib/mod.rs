@@ -19,6 +19,7 @@ mod convert;
 mod format;
 mod general;
 mod generate;
+mod iter;
 mod math;
 mod proto;
 mod strings;
@@ -27,7 +28,7 @@ mod verify;
 use self::{
   assertion::*, block::*, boolean::*, collections::*, 
   compare::*, control::*, convert::*, format::*, 
-  general::*, generate::*, math::*, proto::*, 
+  general::*, generate::*, iter::*, math::*, proto::*, 
   strings::*, verify::*
 };
 
@@ -40,6 +41,7 @@ macro_rules! runtime_error {
       error_type: $err_type,
       description: None,
       stack_trace: None,
+      stack_frames: None,
     })
   }};
   ($err_type:expr, $msg:literal) => {
@@ -47,6 +49,7 @@ macro_rules! runtime_error {
       error_type: $err_type,
       description: Some($msg.to_owned()),
       stack_trace: None,
+      stack_frames: None,
     })
   };
   ($err_type:expr, $msg_fmt:literal, $($msg_fmt_args:expr),+) => {
@@ -54,6 +57,7 @@ macro_rules! runtime_error {
       error_type: $err_type,
       description: Some(format!($msg_fmt, $($msg_fmt_args),+)),
       stack_trace: None,
+      stack_frames: None,
     })
   };
 }
@@ -61,32 +65,33 @@ macro_rules! runtime_error {
 pub(crate) fn load_stdlib(context: &mut Rant)
 {
   macro_rules! load_func {
-    ($fname:ident) => {{
-      let func = $fname.as_rant_func();
+    ($fname:ident $(, [$($param:literal),+])?) => {{
+      let func = $fname.as_rant_func()$(.with_param_names(&[$($param),+]))?;
       context.set_global_force(stringify!($fname), RantValue::Function(Rc::new(func)), true);
     }};
-    ($fname:ident, $id:literal) => {{
-      let func = $fname.as_rant_func();
+    ($fname:ident, $id:literal $(, [$($param:literal),+])?) => {{
+      let func = $fname.as_rant_func()$(.with_param_names(&[$($param),+]))?;
       context.set_global_force($id, RantValue::Function(Rc::new(func)), true);
     }};
   }
 
+  // Parameter names in brackets allow the function to take named arguments
   macro_rules! load_funcs {
-    ($($fname:ident $(as $id:expr)?),+) => {
-      $(load_func!($fname$(, $id)?);)+
+    ($($fname:ident $(as $id:literal)? $([$($param:literal),+])?),+) => {
+      $(load_func!($fname$(, $id)?$(, [$($param),+])?);)+
     };
   }
 
   load_funcs!(
     // General functions
-    alt, call, cat, data, either, len, get_type as "type", seed, nop, print, range, irange, fork, unfork, try_ as "try",
+    alt, call, cat, data, either ["cond", "a", "b"], len, get_type as "type", seed, nop, print, range ["a", "b", "step"], irange ["a", "b", "step"], fork, unfork, try_ as "try" ["context", "handler", "catch"], retry,
 
     // Assertion functions
     assert as "assert", assert_eq as "assert-eq", assert_neq as "assert-neq",
 
     // Formatting functions
-    whitespace_fmt as "whitespace-fmt", 
-    num_fmt as "num-fmt",
+    whitespace_fmt as "whitespace-fmt" ["mode", "custom-value"], 
+    num_fmt as "num-fmt" ["options", "depth"],
     num_fmt_system as "num-fmt-system", 
     num_fmt_alt as "num-fmt-alt",
     num_fmt_padding as "num-fmt-padding",
@@ -125,7 +130,7 @@ pub(crate) fn load_stdlib(context: &mut Rant)
     asin, sin, acos, cos, atan, atan2, tan, sqrt, 
 
     // Conversion functions
-    to_int as "to-int", to_float as "to-float", to_string as "to-string", to_bool as "to-bool", to_list as "to-list",
+    to_int as "to-int", to_float as "to-float", to_string as "to-string", to_bool as "to-bool", to_list as "to-list", to_set as "to-set",
 
     // Generator functions
     alpha, dig, digh, dignz, maybe, rand, randf, rand_list as "rand-list", randf_list as "randf-list", shred,
@@ -137,14 +142,21 @@ pub(crate) fn load_stdlib(context: &mut Rant)
     assoc, chunks, clear, collect, has, keys, index_of as "index-of", insert, last_index_of as "last-index-of", nlist, remove, rev, sift, sifted, squish, squished, take, translate, values,
 
     // List functions
-    pick, filter, join, map, sort, sorted, shuffle, shuffled, sum,
-    list_push as "push", list_pop as "pop", oxford_join as "oxford-join", zip,
+    pick, filter, join ["list", "sep"], map, sort, sorted, shuffle, shuffled, sum,
+    list_push as "push", list_pop as "pop", oxford_join as "oxford-join" ["comma", "conj", "comma-conj", "list"], zip ["list-a", "list-b", "zip-func"],
+
+    // Set functions
+    union, intersection, difference,
+
+    // Iterator functions
+    iter, iter_func as "iter-func", iter_next as "iter-next" ["iter", "fallback"], chain,
+    gen, resume, is_done as "is-done",
 
     // String functions
-    lower, upper, seg, split, lines, indent,
+    lower, upper, seg, split ["s", "at"], lines, indent ["text", "indent"],
 
     // Error functions
-    error
+    error ["value", "id"], rethrow
   );
 
   // Load [require] function if requested
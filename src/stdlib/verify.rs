@@ -1,3 +1,6 @@
+use num_bigint::BigInt;
+use num_traits::Zero;
+
 use super::*;
 use crate::util;
 
@@ -5,27 +8,43 @@ use crate::util;
 /// `[$is-odd: val (int)]`
 ///
 /// Returns true if `val` is odd.
-pub(crate) fn is_odd(vm: &mut VM, val: i64) -> RantStdResult {
-  vm.cur_frame_mut().write_value(RantValue::Boolean(val % 2 != 0));
+pub(crate) fn is_odd(vm: &mut VM, val: RantValue) -> RantStdResult {
+  let is_odd = !is_multiple(val, RantValue::Int(2))?;
+  vm.cur_frame_mut().write_value(RantValue::Boolean(is_odd));
   Ok(())
 }
 
 /// `[$is-even: val (int)]`
 ///
 /// Returns true if `val` is even.
-pub(crate) fn is_even(vm: &mut VM, val: i64) -> RantStdResult {
-  vm.cur_frame_mut().write_value(RantValue::Boolean(val % 2 == 0));
+pub(crate) fn is_even(vm: &mut VM, val: RantValue) -> RantStdResult {
+  let is_even = is_multiple(val, RantValue::Int(2))?;
+  vm.cur_frame_mut().write_value(RantValue::Boolean(is_even));
   Ok(())
 }
 
 /// `[$is-factor: value (int); factor (int)]`
 ///
 /// Returns true if `value` is divisible by `factor`.
-pub(crate) fn is_factor(vm: &mut VM, (value, factor): (i64, i64)) -> RantStdResult {
-  vm.cur_frame_mut().write_value(RantValue::Boolean(factor != 0 && value % factor == 0));
+pub(crate) fn is_factor(vm: &mut VM, (value, factor): (RantValue, RantValue)) -> RantStdResult {
+  let is_factor = is_multiple(value, factor)?;
+  vm.cur_frame_mut().write_value(RantValue::Boolean(is_factor));
   Ok(())
 }
 
+/// Determines whether `value` is divisible by `factor`, switching to big integer arithmetic if either one is a big integer.
+fn is_multiple(value: RantValue, factor: RantValue) -> RuntimeResult<bool> {
+  if matches!(value, RantValue::BigInt(_)) || matches!(factor, RantValue::BigInt(_)) {
+    let value = BigInt::from_rant(value).into_runtime_result()?;
+    let factor = BigInt::from_rant(factor).into_runtime_result()?;
+    return Ok(!factor.is_zero() && (value % factor).is_zero())
+  }
+
+  let value = i64::from_rant(value).into_runtime_result()?;
+  let factor = i64::from_rant(factor).into_runtime_result()?;
+  Ok(factor != 0 && value.wrapping_rem(factor) == 0)
+}
+
 pub(crate) fn is_string(vm: &mut VM, value: RantValue) -> RantStdResult {
   vm.cur_frame_mut().write_value(RantValue::Boolean(value.get_type() == RantValueType::String));
   Ok(())
@@ -0,0 +1,48 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Wraps the system allocator and counts allocations so that `--profile` can report them.
+///
+/// Nothing is counted until `enable()` is called, so runs without `--profile` only pay for a flag check.
+pub struct CountingAllocator;
+
+static IS_ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+#[inline]
+fn count_allocation() {
+  if IS_ENABLED.load(Ordering::Relaxed) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+  }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    count_allocation();
+    System.alloc(layout)
+  }
+
+  unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+    count_allocation();
+    System.alloc_zeroed(layout)
+  }
+
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    count_allocation();
+    System.realloc(ptr, layout, new_size)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+}
+
+/// Starts counting allocations.
+pub fn enable() {
+  IS_ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Gets the number of allocations made since counting was enabled.
+pub fn allocation_count() -> u64 {
+  ALLOCATIONS.load(Ordering::Relaxed)
+}
@@ -0,0 +1,279 @@
+use super::*;
+use rant::runtime::*;
+use std::collections::HashMap;
+use std::fs;
+
+const DEBUGGER_HELP: &str = "\
+commands:
+  c, continue         run until the next breakpoint
+  s, step             step to the next line, entering calls and blocks
+  n, next             step to the next line in the current frame
+  o, out              step out of the current frame
+  b, break [LOC]      set a breakpoint at LINE or FILE:LINE (lists breakpoints if omitted)
+  d, delete LOC       remove a breakpoint
+  bt, backtrace       print the call stack
+  l, locals [N]       print the locals of frame N (default 0, the top frame)
+  p, print NAME       print the value of a variable
+  vs, values          print the value stack
+  block               print the active block state
+  attrs               print the active attribute frame
+  h, help             print this message
+  q, quit             exit the debugger";
+
+/// Interactive command-line front end for the VM debugger.
+struct CliDebugger {
+  /// Path of the program being debugged.
+  main_path: String,
+  /// Breakpoints requested on the command line.
+  initial_breakpoints: Vec<Breakpoint>,
+  /// Source lines of each file seen so far, keyed by origin name.
+  sources: HashMap<String, Vec<String>>,
+  /// The last command entered, repeated on empty input.
+  last_command: Option<String>,
+}
+
+impl CliDebugger {
+  fn parse_location(&self, loc: &str) -> Option<Breakpoint> {
+    match loc.rsplit_once(':') {
+      Some((path, line)) => {
+        let path = Path::new(path).canonicalize().ok()?;
+        Some(Breakpoint::with_source(path.to_string_lossy(), line.parse().ok()?))
+      },
+      None => Some(Breakpoint::with_source(self.main_path.as_str(), loc.parse().ok()?)),
+    }
+  }
+
+  fn source_line(&mut self, origin_name: &str, line: usize) -> Option<&str> {
+    let lines = self.sources.entry(origin_name.to_owned()).or_insert_with(|| {
+      fs::read_to_string(origin_name)
+        .map(|src| src.lines().map(|l| l.to_owned()).collect())
+        .unwrap_or_default()
+    });
+    lines.get(line.checked_sub(1)?).map(|l| l.as_str())
+  }
+
+  fn print_location(&mut self, vm: &VM, reason: PauseReason) {
+    let frame = vm.cur_frame();
+    let (line, col) = frame.debug_pos();
+    let origin_name = frame.origin_name().to_owned();
+    let reason = match reason {
+      PauseReason::Entry => "entry",
+      PauseReason::Breakpoint => "breakpoint",
+      PauseReason::Step => "step",
+      PauseReason::Pause => "pause",
+    };
+    eprintln!("{} ({}) at {}:{}:{}", "Paused".bright_yellow().bold(), reason, origin_name, line, col);
+    if let Some(src_line) = self.source_line(&origin_name, line) {
+      eprintln!("{:>5} | {}", line.to_string().cyan(), src_line);
+    }
+  }
+
+  fn print_backtrace(&self, vm: &VM) {
+    for (i, frame) in vm.call_stack().frames().enumerate() {
+      eprintln!("#{:<3} {}", i, frame);
+    }
+  }
+
+  fn print_locals(&self, vm: &VM, depth: usize) {
+    if depth >= vm.call_stack().len() {
+      log_error!("no frame at depth {}", depth);
+      return
+    }
+
+    let mut locals: Vec<(&str, &RantVar)> = vm.call_stack().locals_at(depth).collect();
+    if locals.is_empty() {
+      eprintln!("(no locals)");
+      return
+    }
+
+    locals.sort_by_key(|(name, _)| *name);
+    for (name, var) in locals {
+      let value = var.value_ref();
+      eprintln!("{}{} = {:?} ({})", if var.is_const() { "const " } else { "" }, name.bright_white(), *value, value.type_name());
+    }
+  }
+
+  fn print_value_stack(&self, vm: &VM) {
+    let values = vm.value_stack();
+    if values.is_empty() {
+      eprintln!("(value stack is empty)");
+      return
+    }
+
+    for (i, value) in values.iter().enumerate().rev() {
+      eprintln!("{:<4} {:?} ({})", i, value, value.type_name());
+    }
+  }
+
+  fn print_block(&self, vm: &VM) {
+    match vm.resolver().active_block() {
+      Some(block) => {
+        eprintln!("elements: {}", block.element_count());
+        eprintln!("step: {} of {}", block.step(), block.step_count());
+        eprintln!("repeater: {}", block.is_repeater());
+        eprintln!("piped: {}", block.is_piped());
+        eprintln!("flag: {:?}", block.flag());
+      },
+      None => eprintln!("(no active block)"),
+    }
+  }
+
+  fn print_attrs(&self, vm: &VM) {
+    let attrs = vm.resolver().attrs();
+    eprintln!("reps: {:?}", attrs.reps);
+    eprintln!("separator: {:?}", attrs.separator);
+    eprintln!("condval: {:?}", attrs.condval);
+    eprintln!("prev condval: {:?}", attrs.prev_condval);
+    eprintln!("selector: {}", if attrs.selector.is_some() { "set" } else { "none" });
+    eprintln!("pipe: {}", if attrs.pipe.is_some() { "set" } else { "none" });
+  }
+
+  fn read_command(&mut self) -> Option<String> {
+    eprint!("{} ", "(rdb)".cyan());
+    io::stderr().flush().unwrap();
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+      Ok(0) | Err(_) => None,
+      Ok(_) => {
+        let input = input.trim();
+        if input.is_empty() {
+          self.last_command.clone()
+        } else {
+          self.last_command = Some(input.to_owned());
+          Some(input.to_owned())
+        }
+      }
+    }
+  }
+}
+
+impl Debugger for CliDebugger {
+  fn on_start(&mut self, vm: &mut VM) -> DebugAction {
+    for bp in self.initial_breakpoints.drain(..) {
+      vm.add_breakpoint(bp);
+    }
+    eprintln!("Debugging {} (type 'help' for a list of commands)", self.main_path);
+    DebugAction::StepIn
+  }
+
+  fn on_pause(&mut self, vm: &mut VM, reason: PauseReason) -> DebugAction {
+    self.print_location(vm, reason);
+
+    loop {
+      let input = match self.read_command() {
+        Some(input) => input,
+        None => process::exit(exitcode::OK),
+      };
+      let mut parts = input.split_whitespace();
+      let command = parts.next().unwrap_or_default();
+      let arg = parts.next();
+
+      match command {
+        "c" | "continue" => return DebugAction::Continue,
+        "s" | "step" => return DebugAction::StepIn,
+        "n" | "next" => return DebugAction::StepOver,
+        "o" | "out" => return DebugAction::StepOut,
+        "b" | "break" => match arg {
+          Some(loc) => match self.parse_location(loc) {
+            Some(bp) => {
+              eprintln!("breakpoint set at {}", format_breakpoint(&bp));
+              vm.add_breakpoint(bp);
+            },
+            None => log_error!("invalid breakpoint location: {}", loc),
+          },
+          None => {
+            if vm.breakpoints().is_empty() {
+              eprintln!("(no breakpoints)");
+            }
+            for bp in vm.breakpoints() {
+              eprintln!("{}", format_breakpoint(bp));
+            }
+          }
+        },
+        "d" | "delete" => match arg.and_then(|loc| self.parse_location(loc)) {
+          Some(bp) => {
+            if !vm.remove_breakpoint(&bp) {
+              log_error!("no breakpoint at {}", format_breakpoint(&bp));
+            }
+          },
+          None => log_error!("expected a breakpoint location"),
+        },
+        "bt" | "backtrace" => self.print_backtrace(vm),
+        "l" | "locals" => match arg.map(|n| n.parse::<usize>()) {
+          Some(Ok(depth)) => self.print_locals(vm, depth),
+          Some(Err(_)) => log_error!("invalid frame number"),
+          None => self.print_locals(vm, 0),
+        },
+        "p" | "print" => match arg {
+          Some(name) => match vm.inspect_var(name) {
+            Some(value) => eprintln!("{:?} ({})", value, value.type_name()),
+            None => log_error!("variable '{}' not found", name),
+          },
+          None => log_error!("expected a variable name"),
+        },
+        "vs" | "values" => self.print_value_stack(vm),
+        "block" => self.print_block(vm),
+        "attrs" => self.print_attrs(vm),
+        "h" | "help" => eprintln!("{}", DEBUGGER_HELP),
+        "q" | "quit" => process::exit(exitcode::OK),
+        other => log_error!("unknown command: '{}' (type 'help' for a list of commands)", other),
+      }
+    }
+  }
+}
+
+fn format_breakpoint(bp: &Breakpoint) -> String {
+  format!("{}:{}", bp.source.as_deref().unwrap_or("*"), bp.line)
+}
+
+/// Compiles and runs a Rant file under the interactive debugger.
+pub fn run_debugger(path: &str, breakpoints: Vec<&str>, opts: &RantCliOptions) -> ExitCode {
+  if !Path::new(path).exists() {
+    log_error!("file not found: {}", path);
+    return exitcode::NOINPUT
+  }
+
+  let mut rant = Rant::with_options(RantOptions {
+    use_stdlib: true,
+    debug_mode: true,
+    seed: opts.seed.unwrap_or_else(|| rand::thread_rng().gen()),
+    .. Default::default()
+  });
+
+  let program = match compile_rant(&mut rant, &ProgramSource::FilePath(path.to_owned()), opts) {
+    Ok(program) => program,
+    Err(code) => return code,
+  };
+
+  let mut debugger = CliDebugger {
+    main_path: program.info().path().unwrap_or(path).to_owned(),
+    initial_breakpoints: vec![],
+    sources: Default::default(),
+    last_command: None,
+  };
+
+  for loc in breakpoints {
+    match debugger.parse_location(loc) {
+      Some(bp) => debugger.initial_breakpoints.push(bp),
+      None => {
+        log_error!("invalid breakpoint location: {}", loc);
+        return exitcode::USAGE
+      }
+    }
+  }
+
+  match rant.run_debug(&program, &mut debugger) {
+    Ok(output) => {
+      let output = output.to_string();
+      if !output.is_empty() {
+        println!("{}", output);
+      }
+      eprintln!("{}", "Program finished".bright_green().bold());
+      exitcode::OK
+    },
+    Err(err) => {
+      print_runtime_error(&err, None);
+      exitcode::SOFTWARE
+    }
+  }
+}
@@ -0,0 +1,91 @@
+use super::*;
+use std::fs;
+use std::io::Read;
+
+/// Exit code for `rant fmt --check` when a file isn't formatted.
+const CHECK_FAILED: ExitCode = 1;
+
+/// Formats files in place, or with `check`, reports the files that aren't formatted without changing them.
+///
+/// If no paths are given, formats stdin to stdout.
+pub fn run_fmt(paths: Vec<&str>, check: bool) -> ExitCode {
+  if paths.is_empty() {
+    let mut source = String::new();
+    if io::stdin().read_to_string(&mut source).is_err() {
+      log_error!("failed to read input");
+      return exitcode::IOERR
+    }
+
+    return match format_or_report("(stdin)", &source) {
+      Some(formatted) if check => {
+        if formatted == source {
+          exitcode::OK
+        } else {
+          report_unformatted("(stdin)", &source, &formatted);
+          CHECK_FAILED
+        }
+      },
+      Some(formatted) => {
+        print!("{}", formatted);
+        exitcode::OK
+      },
+      None => exitcode::DATAERR,
+    }
+  }
+
+  let mut code = exitcode::OK;
+  for path in paths {
+    let source = match fs::read_to_string(path) {
+      Ok(source) => source,
+      Err(err) => {
+        log_error!("failed to read {}: {}", path, err);
+        code = exitcode::NOINPUT;
+        continue
+      }
+    };
+
+    let formatted = match format_or_report(path, &source) {
+      Some(formatted) => formatted,
+      None => {
+        code = exitcode::DATAERR;
+        continue
+      }
+    };
+
+    if formatted == source {
+      continue
+    }
+
+    if check {
+      report_unformatted(path, &source, &formatted);
+      if code == exitcode::OK {
+        code = CHECK_FAILED;
+      }
+    } else if let Err(err) = fs::write(path, formatted) {
+      log_error!("failed to write {}: {}", path, err);
+      code = exitcode::IOERR;
+    }
+  }
+  code
+}
+
+/// Formats source code, printing any syntax errors if it can't be formatted.
+fn format_or_report(file_name: &str, source: &str) -> Option<String> {
+  let mut problems: Vec<CompilerMessage> = vec![];
+  match compiler::format_source(source, &mut problems) {
+    Ok(formatted) => Some(formatted),
+    Err(_) => {
+      print_compiler_messages(file_name, source.to_owned(), &problems, true);
+      None
+    }
+  }
+}
+
+/// Prints the first line that formatting would change.
+fn report_unformatted(file_name: &str, source: &str, formatted: &str) {
+  let line = source.lines()
+    .zip(formatted.lines())
+    .position(|(a, b)| a != b)
+    .unwrap_or_else(|| source.lines().count().min(formatted.lines().count()));
+  eprintln!("{} {}:{}", "Not formatted:".bright_yellow().bold(), file_name, line + 1);
+}
@@ -0,0 +1,54 @@
+//! Machine-readable output of compiler messages and runtime errors, enabled with `--message-format json`.
+//!
+//! Each message is printed to stderr as a JSON object on its own line.
+
+use super::*;
+use json::Json;
+
+/// Prints compiler errors and warnings as JSON lines.
+///
+/// `file_name` should be the origin name of the program so that it matches the files named in runtime error frames.
+pub fn print_compiler_messages_json(file_name: &str, problems: &[CompilerMessage], no_warn: bool) {
+  for msg in problems.iter() {
+    if no_warn && msg.is_warning() { continue }
+
+    let pos = msg.pos();
+    eprintln!("{}", Json::object(vec![
+      ("type", "compiler-message".into()),
+      ("file", file_name.into()),
+      ("code", msg.code().into()),
+      ("severity", msg.severity().to_string().into()),
+      ("message", msg.message().into()),
+      ("inline_message", msg.inline_message().into()),
+      ("hint", msg.hint().into()),
+      ("line", pos.as_ref().map(|pos| pos.line()).into()),
+      ("col", pos.as_ref().map(|pos| pos.col()).into()),
+      ("span", pos.map(|pos| {
+        let span = pos.span();
+        Json::object(vec![
+          ("start", span.start.into()),
+          ("end", span.end.into()),
+        ])
+      }).unwrap_or(Json::Null)),
+    ]));
+  }
+}
+
+/// Prints a runtime error as a JSON line, including its stack trace as text and as structured frames.
+pub fn print_runtime_error_json(err: &RuntimeError) {
+  let frames = err.stack_frames.iter().flatten().map(|frame| Json::object(vec![
+    ("name", frame.name.as_str().into()),
+    ("file", frame.origin.path().unwrap_or_else(|| frame.origin_name()).into()),
+    ("line", frame.pos.map(|(line, _)| line).into()),
+    ("col", frame.pos.map(|(_, col)| col).into()),
+    ("count", frame.count.into()),
+  ])).collect::<Vec<_>>();
+
+  eprintln!("{}", Json::object(vec![
+    ("type", "runtime-error".into()),
+    ("id", err.error_type.id().into()),
+    ("description", err.description.clone().unwrap_or_else(|| err.error_type.to_string()).into()),
+    ("trace", err.stack_trace.clone().into()),
+    ("frames", frames.into()),
+  ]));
+}
@@ -1,6 +1,6 @@
 #![allow(clippy::single_component_path_imports)]
 
-use clap::{App, Arg};
+use clap::{App, Arg, SubCommand};
 use codemap_diagnostic::{ColorConfig, Emitter, SpanLabel, SpanStyle, Diagnostic, Level};
 use codemap::CodeMap;
 use colored::*;
@@ -9,16 +9,29 @@ use ctrlc;
 use exitcode::{self, ExitCode};
 use rand::Rng;
 use rant::*;
-use rant::compiler::CompilerMessage;
+use rant::compiler::{CompilerMessage, OptimizationLevel, WarningConfig, WarningLevel};
+use rant::lang::{AstDumpFormat, dump_ast};
+use rant::runtime::{Coverage, Profiler, RuntimeError};
 use std::{path::Path, time::Instant};
 use std::io::{self, Write};
 use std::process;
 use std::sync::mpsc;
 
+#[global_allocator]
+static GLOBAL_ALLOCATOR: alloc_counter::CountingAllocator = alloc_counter::CountingAllocator;
+
 struct RantCliOptions {
   no_debug: bool,
   no_warn: bool,
   bench_mode: bool,
+  profile: bool,
+  profile_out: Option<String>,
+  coverage_out: Option<String>,
+  coverage_json: bool,
+  dump_ast: Option<AstDumpFormat>,
+  json_messages: bool,
+  warning_config: WarningConfig,
+  opt_level: OptimizationLevel,
   seed: Option<u64>,
 }
 
@@ -28,12 +41,30 @@ enum ProgramSource {
   FilePath(String)
 }
 
+impl ProgramSource {
+  /// Gets the origin name the compiler assigns to the program, which runtime stack frames also refer to it by.
+  fn origin_name(&self) -> String {
+    match self {
+      Self::Inline(_) => "cmdline".to_owned(),
+      Self::Stdin(_) => "stdin".to_owned(),
+      Self::FilePath(path) => Path::new(path).canonicalize().map(|path| path.to_string_lossy().into_owned()).unwrap_or_else(|_| path.to_owned()),
+    }
+  }
+}
+
 macro_rules! log_error {
-  ($fmt:expr $(, $arg:expr),*) => {
+  ($fmt:expr $(, $arg:expr)*) => {
     eprintln!("{}: {}", "error".bright_red().bold(), format!($fmt $(, $arg)*))
   }
 }
 
+#[path = "../common/json.rs"]
+mod json;
+mod alloc_counter;
+mod debug;
+mod fmt;
+mod json_messages;
+
 fn main() {
   let version_long = format!("{} [{}]", BUILD_VERSION, embedded_triple::get());
 
@@ -57,6 +88,69 @@ fn main() {
       .short("b")
       .long("bench-mode")
     )
+    .arg(Arg::with_name("profile")
+      .help("Profiles function calls and prints a report after running")
+      .short("p")
+      .long("profile")
+    )
+    .arg(Arg::with_name("profile-out")
+      .help("Writes profiling data to a file as folded stacks for flamegraph tools (implies --profile)")
+      .long("profile-out")
+      .value_name("FILE")
+    )
+    .arg(Arg::with_name("coverage-out")
+      .help("Writes block element coverage data to a file after running; blocks inlined by --opt-level full are not included")
+      .long("coverage-out")
+      .value_name("FILE")
+      .conflicts_with_all(&["profile", "profile-out"])
+    )
+    .arg(Arg::with_name("coverage-format")
+      .help("Specifies the format of the coverage data")
+      .long("coverage-format")
+      .value_name("FORMAT")
+      .possible_values(&["lcov", "json"])
+      .default_value("lcov")
+    )
+    .arg(Arg::with_name("dump-ast")
+      .help("Prints the syntax tree of the program instead of running it")
+      .long("dump-ast")
+    )
+    .arg(Arg::with_name("ast-format")
+      .help("Specifies the format of the syntax tree printed by --dump-ast")
+      .long("ast-format")
+      .value_name("FORMAT")
+      .possible_values(&["sexpr", "json"])
+      .default_value("sexpr")
+    )
+    .arg(Arg::with_name("message-format")
+      .help("Specifies the format of compiler messages and runtime errors")
+      .long("message-format")
+      .value_name("FORMAT")
+      .possible_values(&["human", "json"])
+      .default_value("human")
+    )
+    .arg(Arg::with_name("opt-level")
+      .help("Specifies how much programs are optimized before running")
+      .long("opt-level")
+      .short("O")
+      .value_name("LEVEL")
+      .possible_values(&["none", "basic", "full"])
+      .default_value("basic")
+    )
+    .arg(Arg::with_name("allow")
+      .help("Silences a compiler warning by code or name (e.g. `R1002` or `unused-function`), or all warnings with `warnings`")
+      .long("allow")
+      .value_name("PROBLEM")
+      .multiple(true)
+      .number_of_values(1)
+    )
+    .arg(Arg::with_name("deny")
+      .help("Reports a compiler warning as an error by code or name, or all warnings with `warnings`")
+      .long("deny")
+      .value_name("PROBLEM")
+      .multiple(true)
+      .number_of_values(1)
+    )
     .arg(Arg::with_name("no-warnings")
       .help("Disables compiler warnings")
       .short("W")
@@ -71,6 +165,34 @@ fn main() {
       .help("Specifies a Rant file to run")
       .index(1)
     )
+    .subcommand(SubCommand::with_name("debug")
+      .about("Runs a Rant file in the interactive debugger")
+      .arg(Arg::with_name("break")
+        .help("Sets a breakpoint on a line, optionally prefixed with a file path (e.g. `12` or `lib.rant:12`)")
+        .short("b")
+        .long("break")
+        .value_name("LOCATION")
+        .multiple(true)
+        .number_of_values(1)
+      )
+      .arg(Arg::with_name("FILE")
+        .help("Specifies a Rant file to debug")
+        .required(true)
+        .index(1)
+      )
+    )
+    .subcommand(SubCommand::with_name("fmt")
+      .about("Formats Rant source files")
+      .arg(Arg::with_name("check")
+        .help("Checks that the files are formatted without changing them")
+        .long("check")
+      )
+      .arg(Arg::with_name("FILE")
+        .help("Specifies the files to format (formats stdin to stdout if omitted)")
+        .multiple(true)
+        .index(1)
+      )
+    )
     .get_matches();
 
   // Signal handling
@@ -85,13 +207,53 @@ fn main() {
     }
   });
 
+  let mut warning_config = WarningConfig::new();
+  for (arg_name, level) in [("allow", WarningLevel::Allow), ("deny", WarningLevel::Deny)] {
+    for problem in arg_matches.values_of(arg_name).into_iter().flatten() {
+      if !warning_config.set(problem, level) {
+        log_error!("--{}: '{}' is not the code or name of any compiler problem", arg_name, problem);
+        process::exit(exitcode::USAGE);
+      }
+    }
+  }
+
   let opts = RantCliOptions {
     bench_mode: arg_matches.is_present("bench-mode"),
+    profile: arg_matches.is_present("profile") || arg_matches.is_present("profile-out"),
+    profile_out: arg_matches.value_of("profile-out").map(|path| path.to_owned()),
+    coverage_out: arg_matches.value_of("coverage-out").map(|path| path.to_owned()),
+    coverage_json: arg_matches.value_of("coverage-format") == Some("json"),
+    dump_ast: arg_matches.is_present("dump-ast").then(|| match arg_matches.value_of("ast-format") {
+      Some("json") => AstDumpFormat::Json,
+      _ => AstDumpFormat::SExpr,
+    }),
+    json_messages: arg_matches.value_of("message-format") == Some("json"),
+    warning_config,
+    opt_level: match arg_matches.value_of("opt-level") {
+      Some("none") => OptimizationLevel::None,
+      Some("full") => OptimizationLevel::Full,
+      _ => OptimizationLevel::Basic,
+    },
     no_debug: arg_matches.is_present("no-debug"),
     no_warn: arg_matches.is_present("no-warnings"),
     seed: arg_matches.value_of("seed").map(|seed_str| u64::from_str_radix(seed_str, 16).ok()).flatten(),
   };
   
+  // Run the debugger if requested
+  if let Some(debug_matches) = arg_matches.subcommand_matches("debug") {
+    let path = debug_matches.value_of("FILE").unwrap();
+    let breakpoints = debug_matches.values_of("break").map(|locs| locs.collect()).unwrap_or_default();
+    let code = debug::run_debugger(path, breakpoints, &opts);
+    process::exit(code);
+  }
+
+  // Run the formatter if requested
+  if let Some(fmt_matches) = arg_matches.subcommand_matches("fmt") {
+    let paths = fmt_matches.values_of("FILE").map(|paths| paths.collect()).unwrap_or_default();
+    let code = fmt::run_fmt(paths, fmt_matches.is_present("check"));
+    process::exit(code);
+  }
+  
   let in_str = arg_matches.value_of("eval");
   let in_file = arg_matches.value_of("FILE");
   
@@ -99,6 +261,7 @@ fn main() {
     use_stdlib: true,
     debug_mode: !opts.no_debug,
     seed: opts.seed.unwrap_or_else(|| rand::thread_rng().gen()),
+    optimization_level: opts.opt_level,
     .. Default::default()
   });
   
@@ -137,37 +300,186 @@ fn repl(rant: &mut Rant, opts: &RantCliOptions) {
 }
 
 fn run_rant(ctx: &mut Rant, source: ProgramSource, opts: &RantCliOptions) -> ExitCode {
+  let program = match compile_rant(ctx, &source, opts) {
+    Ok(program) => program,
+    Err(code) => return code,
+  };
+
+  if let Some(format) = opts.dump_ast {
+    println!("{}", dump_ast(program.root(), format));
+    return exitcode::OK
+  }
+  
+  // Run it
+  let show_stats = opts.bench_mode;
+  let seed = opts.seed.unwrap_or_else(|| rand::thread_rng().gen());
+  ctx.set_seed(seed);
+  let mut profiler = opts.profile.then(|| {
+    alloc_counter::enable();
+    Profiler::with_allocation_counter(alloc_counter::allocation_count)
+  });
+  let mut coverage = opts.coverage_out.is_some().then(Coverage::new);
+  let start_time = Instant::now();
+  let run_result = match (profiler.as_mut(), coverage.as_mut()) {
+    (Some(profiler), _) => ctx.run_profiled(&program, profiler),
+    (None, Some(coverage)) => ctx.run_with_coverage(&program, coverage),
+    (None, None) => ctx.run(&program),
+  }.map(|output| output.to_string());
+  let run_time = start_time.elapsed();
+  
+  // Display results
+  let code = match run_result {
+    Ok(output) => {
+      if !output.is_empty() {
+        println!("{}", output);
+      }
+      if show_stats {
+        eprintln!("{} in {:?} (seed = {:016x})", "Executed".bright_green().bold(), run_time, seed);
+      }
+      exitcode::OK
+    },
+    Err(err) => {
+      if opts.json_messages {
+        json_messages::print_runtime_error_json(&err);
+      } else {
+        print_runtime_error(&err, match &source {
+          ProgramSource::Inline(code) | ProgramSource::Stdin(code) => Some(code.as_str()),
+          ProgramSource::FilePath(_) => None,
+        });
+      }
+      if show_stats {
+        eprintln!("{} in {:?} (seed = {:016x})", "Crashed".bright_red().bold(), run_time, seed);
+      }
+      exitcode::SOFTWARE
+    }
+  };
+
+  // Report profiling results
+  if let Some(profiler) = profiler {
+    eprintln!("{}\n{}", "Profile".bright_green().bold(), profiler.flat_report());
+    if let Some(path) = &opts.profile_out {
+      if let Err(err) = std::fs::write(path, profiler.folded_stacks()) {
+        log_error!("failed to write profile to {}: {}", path, err);
+      }
+    }
+  }
+
+  // Write coverage report
+  if let (Some(coverage), Some(path)) = (coverage, &opts.coverage_out) {
+    let report = if opts.coverage_json {
+      coverage.json_report()
+    } else {
+      coverage.lcov_report()
+    };
+    if let Err(err) = std::fs::write(path, report) {
+      log_error!("failed to write coverage data to {}: {}", path, err);
+    }
+    eprintln!("{} {}/{} block elements selected", "Coverage:".bright_green().bold(), coverage.covered_element_count(), coverage.element_count());
+  }
+
+  code
+}
+
+/// Prints a runtime error with a source snippet of the innermost frame that has a known location, followed by the stack trace.
+///
+/// `inline_source` provides the code for programs that weren't loaded from a file.
+fn print_runtime_error(err: &RuntimeError, inline_source: Option<&str>) {
+  let mut codemap = CodeMap::new();
+
+  let label = err.stack_frames.iter().flatten().find_map(|frame| {
+    let (line, col) = frame.pos?;
+    let (file_name, code) = match frame.origin.path() {
+      Some(path) => (path.to_owned(), std::fs::read_to_string(path).ok()?),
+      None => (format!("({})", frame.origin_name()), inline_source?.to_owned()),
+    };
+    let file = codemap.add_file(file_name, code);
+    if line == 0 || line > file.num_lines() {
+      return None
+    }
+
+    // Highlight the character at the frame's position
+    let line_text = file.source_line(line - 1);
+    let start = (col.max(1) - 1).min(line_text.len());
+    if !line_text.is_char_boundary(start) {
+      return None
+    }
+    let end = line_text[start..].chars().next().map_or(start, |c| start + c.len_utf8());
+    Some(SpanLabel {
+      span: file.line_span(line - 1).subspan(start as u64, end as u64),
+      label: Some(format!("in {}", frame.name)),
+      style: SpanStyle::Primary,
+    })
+  });
+
+  let d = Diagnostic {
+    level: Level::Error,
+    message: err.description.clone().unwrap_or_else(|| err.error_type.to_string()),
+    code: Some(err.error_type.id().to_owned()),
+    spans: label.into_iter().collect(),
+  };
+  Emitter::stderr(ColorConfig::Always, Some(&codemap)).emit(&[d]);
+  eprintln!("stack trace:\n{}\n", err.stack_trace.as_deref().unwrap_or("(no trace available)"));
+}
+
+/// Compiles a program source and prints any compiler messages it produces.
+fn compile_rant(ctx: &mut Rant, source: &ProgramSource, opts: &RantCliOptions) -> Result<RantProgram, ExitCode> {
   let show_stats = opts.bench_mode;
   let start_time = Instant::now();
   let mut problems: Vec<CompilerMessage> = vec![];
+  let mut reporter = opts.warning_config.reporter(&mut problems);
 
-  let compile_result = match &source {
-    ProgramSource::Inline(source) => ctx.compile_named(source, &mut problems, "cmdline"),
-    ProgramSource::Stdin(source) => ctx.compile_named(source, &mut problems, "stdin"),
-    ProgramSource::FilePath(path) => ctx.compile_file(path, &mut problems)
+  let compile_result = match source {
+    ProgramSource::Inline(code) | ProgramSource::Stdin(code) => ctx.compile_named(code, &mut reporter, &source.origin_name()),
+    ProgramSource::FilePath(path) => ctx.compile_file(path, &mut reporter)
   };
   
   let parse_time = start_time.elapsed();
 
-  let code = match &source {
+  let code = match source {
     ProgramSource::Inline(s) => s.to_owned(),
     ProgramSource::Stdin(s) => s.to_owned(),
     ProgramSource::FilePath(path) => std::fs::read_to_string(path).expect("can't open file for error reporting")
   };     
 
-  let mut codemap = CodeMap::new();
-
-  let file_span = codemap.add_file(match &source {
+  let file_name = match source {
     ProgramSource::Inline(_) => "(cmdline)",
     ProgramSource::Stdin(_) => "(stdin)",
     ProgramSource::FilePath(path) => path
-  }.to_owned(), code).span;
+  };
+
+  if opts.json_messages {
+    json_messages::print_compiler_messages_json(&source.origin_name(), &problems, opts.no_warn);
+  } else {
+    print_compiler_messages(file_name, code, &problems, opts.no_warn);
+  }
 
+  let errc = problems.iter().filter(|msg| msg.is_error()).count();
+  
+  // Make sure it compiled successfully
+  match compile_result {
+    Ok(program) => {
+      if show_stats {
+        eprintln!("{} in {:?}", "Compiled".bright_green().bold(), parse_time) 
+      }
+      Ok(program)
+    },
+    Err(_) if opts.json_messages => Err(exitcode::DATAERR),
+    Err(_) => {
+      eprintln!("\n{}\n", format!("{} ({} {} found)", "Compile failed".bright_red(), errc, if errc == 1 { "error" } else { "errors" }).bold());
+      Err(exitcode::DATAERR)
+    }
+  }
+}
+
+/// Prints compiler errors and warnings with source snippets.
+fn print_compiler_messages(file_name: &str, code: String, problems: &[CompilerMessage], no_warn: bool) {
+  let mut codemap = CodeMap::new();
+  let file_span = codemap.add_file(file_name.to_owned(), code).span;
   let mut emitter = Emitter::stderr(ColorConfig::Always, Some(&codemap));
   
   // Print errors/warnings
   for msg in problems.iter() {
-    if opts.no_warn && msg.is_warning() { continue }
+    if no_warn && msg.is_warning() { continue }
     
     let d = Diagnostic {
       level: match msg.severity() {
@@ -190,47 +502,4 @@ fn run_rant(ctx: &mut Rant, source: ProgramSource, opts: &RantCliOptions) -> Exi
     };
     emitter.emit(&[d]);
   }
-
-  let errc = problems.iter().filter(|msg| msg.is_error()).count();
-  
-  // Make sure it compiled successfully
-  match &compile_result {
-    Ok(_) => {
-      if show_stats {
-        eprintln!("{} in {:?}", "Compiled".bright_green().bold(), parse_time) 
-      }
-    },
-    Err(_) => {
-      eprintln!("\n{}\n", format!("{} ({} {} found)", "Compile failed".bright_red(), errc, if errc == 1 { "error" } else { "errors" }).bold());
-      return exitcode::DATAERR
-    }
-  }
-  
-  // Run it
-  let program = compile_result.unwrap();
-  let seed = opts.seed.unwrap_or_else(|| rand::thread_rng().gen());
-  ctx.set_seed(seed);
-  let start_time = Instant::now();
-  let run_result = ctx.run(&program).map(|output| output.to_string());
-  let run_time = start_time.elapsed();
-  
-  // Display results
-  match run_result {
-    Ok(output) => {
-      if !output.is_empty() {
-        println!("{}", output);
-      }
-      if show_stats {
-        eprintln!("{} in {:?} (seed = {:016x})", "Executed".bright_green().bold(), run_time, seed);
-      }
-      exitcode::OK
-    },
-    Err(err) => {
-      eprintln!("{}: {}\n\nstack trace:\n{}", "Runtime error".bright_red().bold(), &err, &err.stack_trace.as_deref().unwrap_or("(no trace available)"));
-      if show_stats {
-        eprintln!("{} in {:?} (seed = {:016x})", "Crashed".bright_red().bold(), run_time, seed);
-      }
-      exitcode::SOFTWARE
-    }
-  }
 }
\ No newline at end of file
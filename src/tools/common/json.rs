@@ -0,0 +1,112 @@
+//! Minimal JSON value type shared by the Rant tools that speak JSON-based protocols.
+//!
+//! Tools that also read JSON include `json_parse.rs` alongside this module.
+
+use std::fmt::{self, Display, Write};
+use rant::lang::write_json_string;
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+  Null,
+  Bool(bool),
+  /// A number written without a fraction or exponent, kept exact so that 64-bit IDs and seeds survive a round trip.
+  Integer(i128),
+  Number(f64),
+  String(String),
+  Array(Vec<Json>),
+  /// Object members, in insertion order.
+  Object(Vec<(String, Json)>),
+}
+
+impl Json {
+  /// Creates an object from a list of key-value pairs.
+  pub fn object<K: Into<String>>(members: Vec<(K, Json)>) -> Self {
+    Self::Object(members.into_iter().map(|(k, v)| (k.into(), v)).collect())
+  }
+}
+
+impl From<bool> for Json {
+  fn from(b: bool) -> Self {
+    Self::Bool(b)
+  }
+}
+
+impl From<i64> for Json {
+  fn from(n: i64) -> Self {
+    Self::Integer(n.into())
+  }
+}
+
+impl From<u64> for Json {
+  fn from(n: u64) -> Self {
+    Self::Integer(n.into())
+  }
+}
+
+impl From<f64> for Json {
+  fn from(n: f64) -> Self {
+    Self::Number(n)
+  }
+}
+
+impl From<usize> for Json {
+  fn from(n: usize) -> Self {
+    Self::Integer(n as i128)
+  }
+}
+
+
+impl From<&str> for Json {
+  fn from(s: &str) -> Self {
+    Self::String(s.to_owned())
+  }
+}
+
+impl From<String> for Json {
+  fn from(s: String) -> Self {
+    Self::String(s)
+  }
+}
+
+impl From<Vec<Json>> for Json {
+  fn from(items: Vec<Json>) -> Self {
+    Self::Array(items)
+  }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+  fn from(value: Option<T>) -> Self {
+    value.map_or(Self::Null, Into::into)
+  }
+}
+
+impl Display for Json {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Null => f.write_str("null"),
+      Self::Bool(b) => write!(f, "{}", b),
+      Self::Integer(n) => write!(f, "{}", n),
+      Self::Number(n) if n.is_finite() => write!(f, "{}", n),
+      Self::Number(_) => f.write_str("null"),
+      Self::String(s) => write_json_string(f, s),
+      Self::Array(items) => {
+        f.write_char('[')?;
+        for (i, item) in items.iter().enumerate() {
+          if i > 0 { f.write_char(',')?; }
+          write!(f, "{}", item)?;
+        }
+        f.write_char(']')
+      },
+      Self::Object(members) => {
+        f.write_char('{')?;
+        for (i, (key, value)) in members.iter().enumerate() {
+          if i > 0 { f.write_char(',')?; }
+          write_json_string(f, key)?;
+          write!(f, ":{}", value)?;
+        }
+        f.write_char('}')
+      },
+    }
+  }
+}
@@ -0,0 +1,251 @@
+//! Parsing and inspection of JSON values, for the Rant tools that receive JSON messages.
+//!
+//! Extends the `Json` type from `json.rs`, which must be included as the crate-level module `json`.
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use crate::json::Json;
+
+impl Json {
+  /// Gets the value of an object member, if this is an object and the member exists.
+  pub fn get(&self, key: &str) -> Option<&Json> {
+    match self {
+      Self::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+      _ => None,
+    }
+  }
+
+  /// Adds or replaces an object member. Does nothing if this isn't an object.
+  pub fn set(&mut self, key: &str, value: Json) {
+    if let Self::Object(members) = self {
+      match members.iter_mut().find(|(k, _)| k == key) {
+        Some((_, v)) => *v = value,
+        None => members.push((key.to_owned(), value)),
+      }
+    }
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Self::String(s) => Some(s.as_str()),
+      _ => None,
+    }
+  }
+
+  pub fn as_i64(&self) -> Option<i64> {
+    match self {
+      Self::Integer(n) => i64::try_from(*n).ok(),
+      Self::Number(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => Some(*n as i64),
+      _ => None,
+    }
+  }
+
+  pub fn as_u64(&self) -> Option<u64> {
+    match self {
+      Self::Integer(n) => u64::try_from(*n).ok(),
+      Self::Number(n) if n.fract() == 0.0 && *n >= 0.0 && *n < u64::MAX as f64 => Some(*n as u64),
+      _ => None,
+    }
+  }
+
+  pub fn as_bool(&self) -> Option<bool> {
+    match self {
+      Self::Bool(b) => Some(*b),
+      _ => None,
+    }
+  }
+
+  pub fn as_array(&self) -> Option<&[Json]> {
+    match self {
+      Self::Array(items) => Some(items.as_slice()),
+      _ => None,
+    }
+  }
+
+  /// Parses a JSON document.
+  pub fn parse(src: &str) -> Result<Json, JsonError> {
+    let mut parser = Parser { src: src.as_bytes(), pos: 0 };
+    let value = parser.parse_value()?;
+    parser.skip_ws();
+    if parser.pos < parser.src.len() {
+      return Err(parser.error("trailing characters"))
+    }
+    Ok(value)
+  }
+}
+
+/// Error produced when parsing invalid JSON.
+#[derive(Debug)]
+pub struct JsonError {
+  pub message: String,
+  pub pos: usize,
+}
+
+impl Display for JsonError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at byte {}", self.message, self.pos)
+  }
+}
+
+struct Parser<'a> {
+  src: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Parser<'a> {
+  fn error(&self, message: &str) -> JsonError {
+    JsonError {
+      message: message.to_owned(),
+      pos: self.pos,
+    }
+  }
+
+  fn skip_ws(&mut self) {
+    while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.src.get(self.pos) {
+      self.pos += 1;
+    }
+  }
+
+  fn expect_literal(&mut self, literal: &str, value: Json) -> Result<Json, JsonError> {
+    if self.src[self.pos..].starts_with(literal.as_bytes()) {
+      self.pos += literal.len();
+      Ok(value)
+    } else {
+      Err(self.error("invalid literal"))
+    }
+  }
+
+  fn parse_value(&mut self) -> Result<Json, JsonError> {
+    self.skip_ws();
+    match self.src.get(self.pos) {
+      Some(b'n') => self.expect_literal("null", Json::Null),
+      Some(b't') => self.expect_literal("true", Json::Bool(true)),
+      Some(b'f') => self.expect_literal("false", Json::Bool(false)),
+      Some(b'"') => self.parse_string().map(Json::String),
+      Some(b'[') => {
+        self.pos += 1;
+        let mut items = vec![];
+        self.skip_ws();
+        if self.src.get(self.pos) == Some(&b']') {
+          self.pos += 1;
+          return Ok(Json::Array(items))
+        }
+        loop {
+          items.push(self.parse_value()?);
+          self.skip_ws();
+          match self.src.get(self.pos) {
+            Some(b',') => self.pos += 1,
+            Some(b']') => {
+              self.pos += 1;
+              return Ok(Json::Array(items))
+            },
+            _ => return Err(self.error("expected ',' or ']'")),
+          }
+        }
+      },
+      Some(b'{') => {
+        self.pos += 1;
+        let mut members = vec![];
+        self.skip_ws();
+        if self.src.get(self.pos) == Some(&b'}') {
+          self.pos += 1;
+          return Ok(Json::Object(members))
+        }
+        loop {
+          self.skip_ws();
+          if self.src.get(self.pos) != Some(&b'"') {
+            return Err(self.error("expected object key"))
+          }
+          let key = self.parse_string()?;
+          self.skip_ws();
+          if self.src.get(self.pos) != Some(&b':') {
+            return Err(self.error("expected ':'"))
+          }
+          self.pos += 1;
+          members.push((key, self.parse_value()?));
+          self.skip_ws();
+          match self.src.get(self.pos) {
+            Some(b',') => self.pos += 1,
+            Some(b'}') => {
+              self.pos += 1;
+              return Ok(Json::Object(members))
+            },
+            _ => return Err(self.error("expected ',' or '}'")),
+          }
+        }
+      },
+      Some(b'-' | b'0'..=b'9') => {
+        let start = self.pos;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.src.get(self.pos) {
+          self.pos += 1;
+        }
+        let number = std::str::from_utf8(&self.src[start..self.pos]).map_err(|_| self.error("invalid number"))?;
+        // Integers too large for i128 lose precision either way, so they are stored as floats
+        let is_integer = !number.contains(['.', 'e', 'E']);
+        match number.parse::<i128>() {
+          Ok(n) if is_integer => Ok(Json::Integer(n)),
+          _ => number.parse().map(Json::Number).map_err(|_| self.error("invalid number")),
+        }
+      },
+      Some(_) => Err(self.error("unexpected character")),
+      None => Err(self.error("unexpected end of input")),
+    }
+  }
+
+  fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+    let hex = self.src.get(self.pos..self.pos + 4).ok_or_else(|| self.error("unexpected end of input"))?;
+    let code = std::str::from_utf8(hex)
+      .ok()
+      .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+      .ok_or_else(|| self.error("invalid unicode escape"))?;
+    self.pos += 4;
+    Ok(code)
+  }
+
+  fn parse_string(&mut self) -> Result<String, JsonError> {
+    // Skip opening quote
+    self.pos += 1;
+    let mut buf = Vec::new();
+    loop {
+      match self.src.get(self.pos) {
+        Some(b'"') => {
+          self.pos += 1;
+          return String::from_utf8(buf).map_err(|_| self.error("invalid UTF-8 in string"))
+        },
+        Some(b'\\') => {
+          self.pos += 1;
+          let escaped = *self.src.get(self.pos).ok_or_else(|| self.error("unexpected end of input"))?;
+          self.pos += 1;
+          match escaped {
+            b'"' => buf.push(b'"'),
+            b'\\' => buf.push(b'\\'),
+            b'/' => buf.push(b'/'),
+            b'b' => buf.push(0x08),
+            b'f' => buf.push(0x0c),
+            b'n' => buf.push(b'\n'),
+            b'r' => buf.push(b'\r'),
+            b't' => buf.push(b'\t'),
+            b'u' => {
+              let mut code = self.parse_hex4()?;
+              // Combine surrogate pairs
+              if (0xd800..0xdc00).contains(&code) && self.src[self.pos..].starts_with(b"\\u") {
+                self.pos += 2;
+                let low = self.parse_hex4()?;
+                code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+              }
+              let c = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+              let mut char_buf = [0u8; 4];
+              buf.extend_from_slice(c.encode_utf8(&mut char_buf).as_bytes());
+            },
+            _ => return Err(self.error("invalid escape sequence")),
+          }
+        },
+        Some(b) => {
+          buf.push(*b);
+          self.pos += 1;
+        },
+        None => return Err(self.error("unterminated string")),
+      }
+    }
+  }
+}
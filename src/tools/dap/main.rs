@@ -0,0 +1,341 @@
+//! Debug Adapter Protocol server for Rant.
+//!
+//! Speaks DAP over stdin/stdout so that any DAP-capable editor can launch and debug Rant programs.
+
+#[path = "../common/json.rs"]
+mod json;
+#[path = "../common/json_parse.rs"]
+mod json_parse;
+mod session;
+
+use json::Json;
+use rand::Rng;
+use rant::*;
+use session::DapDebugger;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+
+/// Thread ID reported for the VM; Rant programs are single-threaded.
+const THREAD_ID: i64 = 1;
+
+/// Reads and writes DAP messages over stdio.
+///
+/// Messages are read on a separate thread so that requests such as `pause` can be picked up while the program runs.
+pub struct Transport {
+  input: mpsc::Receiver<Json>,
+  output: io::Stdout,
+  seq: i64,
+}
+
+impl Transport {
+  fn new() -> Self {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+      let mut input = BufReader::new(io::stdin());
+      while let Some(msg) = read_message(&mut input) {
+        if sender.send(msg).is_err() {
+          break
+        }
+      }
+    });
+
+    Self {
+      input: receiver,
+      output: io::stdout(),
+      seq: 0,
+    }
+  }
+
+  /// Waits for the next message. Returns `None` once the client closes the stream.
+  pub fn read_message(&mut self) -> Option<Json> {
+    self.input.recv().ok()
+  }
+
+  /// Returns the next message if one has already arrived.
+  pub fn try_read_message(&mut self) -> Option<Json> {
+    self.input.try_recv().ok()
+  }
+
+  fn send(&mut self, mut msg: Json) {
+    self.seq += 1;
+    msg.set("seq", self.seq.into());
+    let content = msg.to_string();
+    write!(self.output, "Content-Length: {}\r\n\r\n{}", content.len(), content).expect("failed to write to stdout");
+    self.output.flush().expect("failed to flush stdout");
+  }
+
+  /// Sends a successful response to a request.
+  pub fn respond(&mut self, request: &Json, body: Json) {
+    self.send(Json::object(vec![
+      ("type", "response".into()),
+      ("request_seq", request.get("seq").cloned().unwrap_or(Json::Null)),
+      ("success", true.into()),
+      ("command", request.get("command").cloned().unwrap_or(Json::Null)),
+      ("body", body),
+    ]));
+  }
+
+  /// Sends a failed response to a request.
+  pub fn respond_error(&mut self, request: &Json, message: &str) {
+    self.send(Json::object(vec![
+      ("type", "response".into()),
+      ("request_seq", request.get("seq").cloned().unwrap_or(Json::Null)),
+      ("success", false.into()),
+      ("command", request.get("command").cloned().unwrap_or(Json::Null)),
+      ("message", message.into()),
+    ]));
+  }
+
+  /// Sends an event.
+  pub fn event(&mut self, event: &str, body: Json) {
+    self.send(Json::object(vec![
+      ("type", "event".into()),
+      ("event", event.into()),
+      ("body", body),
+    ]));
+  }
+
+  /// Sends program or adapter output to the client.
+  pub fn output(&mut self, category: &str, text: &str) {
+    self.event("output", Json::object(vec![
+      ("category", category.into()),
+      ("output", text.into()),
+    ]));
+  }
+}
+
+/// Reads the next message from a stream. Returns `None` once the stream is closed.
+fn read_message(input: &mut impl BufRead) -> Option<Json> {
+  loop {
+    let mut content_length = None;
+
+    // Read headers
+    loop {
+      let mut line = String::new();
+      if input.read_line(&mut line).ok()? == 0 {
+        return None
+      }
+      let line = line.trim_end();
+      if line.is_empty() {
+        break
+      }
+      if let Some((name, value)) = line.split_once(':') {
+        if name.eq_ignore_ascii_case("Content-Length") {
+          content_length = value.trim().parse::<usize>().ok();
+        }
+      }
+    }
+
+    let content_length = match content_length {
+      Some(len) => len,
+      None => continue,
+    };
+
+    let mut content = vec![0; content_length];
+    input.read_exact(&mut content).ok()?;
+    match std::str::from_utf8(&content).ok().map(Json::parse) {
+      Some(Ok(msg)) => return Some(msg),
+      _ => eprintln!("rant-dap: ignoring malformed message"),
+    }
+  }
+}
+
+/// Arguments of the `launch` request.
+struct LaunchConfig {
+  program: String,
+  stop_on_entry: bool,
+  no_debug: bool,
+  seed: Option<u64>,
+}
+
+impl LaunchConfig {
+  fn from_args(args: &Json) -> Result<Self, String> {
+    let program = args.get("program")
+      .and_then(Json::as_str)
+      .ok_or_else(|| "missing 'program' launch argument".to_owned())?;
+
+    let seed = match args.get("seed") {
+      Some(Json::String(hex)) => Some(u64::from_str_radix(hex, 16).map_err(|_| format!("invalid seed: '{}'", hex))?),
+      Some(seed @ (Json::Integer(_) | Json::Number(_))) => Some(seed.as_u64().ok_or_else(|| "seed must be a non-negative integer".to_owned())?),
+      _ => None,
+    };
+
+    Ok(Self {
+      program: program.to_owned(),
+      stop_on_entry: args.get("stopOnEntry").and_then(Json::as_bool).unwrap_or(false),
+      no_debug: args.get("noDebug").and_then(Json::as_bool).unwrap_or(false),
+      seed,
+    })
+  }
+}
+
+/// Canonicalizes a client-provided source path so that it matches the origin paths assigned by the compiler.
+pub fn canonical_source_path(path: &str) -> String {
+  Path::new(path)
+    .canonicalize()
+    .map(|path| path.to_string_lossy().into_owned())
+    .unwrap_or_else(|_| path.to_owned())
+}
+
+/// Reads the lines from a `setBreakpoints` request and acknowledges them.
+pub fn set_breakpoints(transport: &mut Transport, request: &Json) -> Option<(String, Vec<usize>)> {
+  let args = request.get("arguments");
+  let path = match args.and_then(|args| args.get("source")).and_then(|source| source.get("path")).and_then(Json::as_str) {
+    Some(path) => canonical_source_path(path),
+    None => {
+      transport.respond_error(request, "breakpoint source has no path");
+      return None
+    }
+  };
+
+  let lines: Vec<usize> = args
+    .and_then(|args| args.get("breakpoints"))
+    .and_then(Json::as_array)
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|bp| bp.get("line").and_then(Json::as_u64))
+    .map(|line| line as usize)
+    .collect();
+
+  let verified = lines.iter().map(|line| Json::object(vec![
+    ("verified", true.into()),
+    ("line", (*line).into()),
+  ])).collect::<Vec<_>>();
+
+  transport.respond(request, Json::object(vec![("breakpoints", verified.into())]));
+  Some((path, lines))
+}
+
+fn capabilities() -> Json {
+  Json::object(vec![
+    ("supportsConfigurationDoneRequest", true.into()),
+    ("supportsEvaluateForHovers", true.into()),
+    ("supportsTerminateRequest", true.into()),
+  ])
+}
+
+fn threads_body() -> Json {
+  Json::object(vec![
+    ("threads", vec![Json::object(vec![
+      ("id", THREAD_ID.into()),
+      ("name", "main".into()),
+    ])].into()),
+  ])
+}
+
+/// Compiles and runs the launched program, reporting its output and exit status to the client.
+fn run_program(transport: &mut Transport, config: &LaunchConfig, breakpoints: HashMap<String, Vec<usize>>) {
+  let mut rant = Rant::with_options(RantOptions {
+    use_stdlib: true,
+    debug_mode: true,
+    seed: config.seed.unwrap_or_else(|| rand::thread_rng().gen()),
+    .. Default::default()
+  });
+
+  let mut problems: Vec<compiler::CompilerMessage> = vec![];
+  let exit_code = match rant.compile_file(&config.program, &mut problems) {
+    Ok(program) => {
+      for msg in problems.iter().filter(|msg| msg.is_warning()) {
+        transport.output("console", &format_compiler_message(&config.program, msg));
+      }
+
+      let result = if config.no_debug {
+        rant.run(&program)
+      } else {
+        let mut debugger = DapDebugger::new(transport, breakpoints, config.stop_on_entry);
+        rant.run_debug(&program, &mut debugger)
+      };
+
+      match result {
+        Ok(output) => {
+          let output = output.to_string();
+          if !output.is_empty() {
+            transport.output("stdout", &format!("{}\n", output));
+          }
+          0
+        },
+        Err(err) => {
+          transport.output("stderr", &format!("Runtime error: {}\n\nstack trace:\n{}\n", err, err.stack_trace.as_deref().unwrap_or("(no trace available)")));
+          1
+        }
+      }
+    },
+    Err(_) => {
+      for msg in problems.iter() {
+        transport.output("stderr", &format_compiler_message(&config.program, msg));
+      }
+      1
+    }
+  };
+
+  transport.event("exited", Json::object(vec![("exitCode", (exit_code as i64).into())]));
+  transport.event("terminated", Json::object(Vec::<(&str, Json)>::new()));
+}
+
+fn format_compiler_message(path: &str, msg: &compiler::CompilerMessage) -> String {
+  let severity = if msg.is_error() { "error" } else { "warning" };
+  match msg.pos() {
+    Some(pos) => format!("{}:{}:{}: {}[{}]: {}\n", path, pos.line(), pos.col(), severity, msg.code(), msg.message()),
+    None => format!("{}: {}[{}]: {}\n", path, severity, msg.code(), msg.message()),
+  }
+}
+
+fn main() {
+  let mut transport = Transport::new();
+  let mut launch_config: Option<LaunchConfig> = None;
+  let mut breakpoints: HashMap<String, Vec<usize>> = HashMap::new();
+  let mut configuration_done = false;
+  let mut has_run = false;
+
+  while let Some(request) = transport.read_message() {
+    let command = request.get("command").and_then(Json::as_str).unwrap_or_default().to_owned();
+    match command.as_str() {
+      "initialize" => {
+        transport.respond(&request, capabilities());
+        transport.event("initialized", Json::object(Vec::<(&str, Json)>::new()));
+      },
+      "launch" => {
+        match LaunchConfig::from_args(request.get("arguments").unwrap_or(&Json::Null)) {
+          Ok(config) => {
+            launch_config = Some(config);
+            transport.respond(&request, Json::Null);
+          },
+          Err(msg) => transport.respond_error(&request, &msg),
+        }
+      },
+      "setBreakpoints" => {
+        if let Some((path, lines)) = set_breakpoints(&mut transport, &request) {
+          breakpoints.insert(path, lines);
+        }
+      },
+      "setExceptionBreakpoints" => transport.respond(&request, Json::Null),
+      "configurationDone" => {
+        configuration_done = true;
+        transport.respond(&request, Json::Null);
+      },
+      "threads" => transport.respond(&request, threads_body()),
+      "disconnect" | "terminate" => {
+        transport.respond(&request, Json::Null);
+        process::exit(0);
+      },
+      _ => transport.respond_error(&request, &format!("unsupported request: '{}'", command)),
+    }
+
+    // Start the program once the client has finished configuring it
+    if configuration_done && !has_run {
+      if let Some(config) = launch_config.as_ref() {
+        has_run = true;
+        if !Path::new(&config.program).exists() {
+          transport.output("stderr", &format!("file not found: {}\n", config.program));
+          transport.event("terminated", Json::object(Vec::<(&str, Json)>::new()));
+          continue
+        }
+        run_program(&mut transport, config, std::mem::take(&mut breakpoints));
+      }
+    }
+  }
+}
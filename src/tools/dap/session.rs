@@ -0,0 +1,321 @@
+use super::*;
+use rant::runtime::*;
+
+/// Something the client can expand in the variables view.
+enum VarContainer {
+  /// The locals of the frame at the specified depth.
+  Locals(usize),
+  /// The globals of the Rant context.
+  Globals,
+  /// The contents of a list or map.
+  Value(RantValue),
+}
+
+/// Bridges the VM debugger API to a DAP client.
+pub struct DapDebugger<'a> {
+  transport: &'a mut Transport,
+  /// Breakpoint lines requested before launch, keyed by canonical source path.
+  initial_breakpoints: HashMap<String, Vec<usize>>,
+  stop_on_entry: bool,
+  /// Containers handed out as variable references during the current pause.
+  /// A variable reference is an index into this list plus one.
+  var_refs: Vec<VarContainer>,
+}
+
+impl<'a> DapDebugger<'a> {
+  pub fn new(transport: &'a mut Transport, breakpoints: HashMap<String, Vec<usize>>, stop_on_entry: bool) -> Self {
+    Self {
+      transport,
+      initial_breakpoints: breakpoints,
+      stop_on_entry,
+      var_refs: vec![],
+    }
+  }
+
+  fn add_var_ref(&mut self, container: VarContainer) -> usize {
+    self.var_refs.push(container);
+    self.var_refs.len()
+  }
+
+  /// Creates a DAP `Variable` for a value, making lists and maps expandable.
+  fn make_variable(&mut self, name: &str, value: &RantValue) -> Json {
+    let var_ref = match value {
+      RantValue::List(list) if !list.borrow().is_empty() => self.add_var_ref(VarContainer::Value(value.clone())),
+      RantValue::Map(map) if !map.borrow().is_empty() => self.add_var_ref(VarContainer::Value(value.clone())),
+      _ => 0,
+    };
+
+    Json::object(vec![
+      ("name", name.into()),
+      ("value", display_value(value).into()),
+      ("type", value.type_name().into()),
+      ("variablesReference", var_ref.into()),
+    ])
+  }
+
+  fn replace_breakpoints(&mut self, vm: &mut VM, path: String, lines: Vec<usize>) {
+    let stale: Vec<Breakpoint> = vm.breakpoints()
+      .iter()
+      .filter(|bp| bp.source.as_deref() == Some(path.as_str()))
+      .cloned()
+      .collect();
+    for bp in stale.iter() {
+      vm.remove_breakpoint(bp);
+    }
+    for line in lines {
+      vm.add_breakpoint(Breakpoint::with_source(path.as_str(), line));
+    }
+  }
+
+  fn stack_trace(&mut self, vm: &VM, request: &Json) {
+    let args = request.get("arguments");
+    let start_frame = args.and_then(|args| args.get("startFrame")).and_then(Json::as_u64).unwrap_or(0) as usize;
+    let levels = args.and_then(|args| args.get("levels")).and_then(Json::as_u64).filter(|n| *n > 0).map_or(usize::MAX, |n| n as usize);
+
+    let frames: Vec<Json> = vm.call_stack()
+      .frames()
+      .enumerate()
+      .skip(start_frame)
+      .take(levels)
+      .map(|(depth, frame)| {
+        let (line, col) = frame.debug_pos();
+        let source = match frame.origin().path() {
+          Some(path) => Json::object(vec![
+            ("name", Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| path.to_owned()).into()),
+            ("path", path.into()),
+          ]),
+          None => Json::object(vec![
+            ("name", frame.origin_name().into()),
+          ]),
+        };
+        Json::object(vec![
+          ("id", (depth + 1).into()),
+          ("name", frame.name().into()),
+          ("source", source),
+          ("line", line.max(1).into()),
+          ("column", col.max(1).into()),
+        ])
+      })
+      .collect();
+
+    self.transport.respond(request, Json::object(vec![
+      ("stackFrames", frames.into()),
+      ("totalFrames", vm.call_stack().len().into()),
+    ]));
+  }
+
+  fn scopes(&mut self, vm: &VM, request: &Json) {
+    let frame_id = request.get("arguments").and_then(|args| args.get("frameId")).and_then(Json::as_u64).unwrap_or(0) as usize;
+    if frame_id == 0 || frame_id > vm.call_stack().len() {
+      self.transport.respond_error(request, "invalid frame ID");
+      return
+    }
+
+    let locals_ref = self.add_var_ref(VarContainer::Locals(frame_id - 1));
+    let globals_ref = self.add_var_ref(VarContainer::Globals);
+    self.transport.respond(request, Json::object(vec![
+      ("scopes", vec![
+        Json::object(vec![
+          ("name", "Locals".into()),
+          ("presentationHint", "locals".into()),
+          ("variablesReference", locals_ref.into()),
+          ("expensive", false.into()),
+        ]),
+        Json::object(vec![
+          ("name", "Globals".into()),
+          ("variablesReference", globals_ref.into()),
+          ("expensive", true.into()),
+        ]),
+      ].into()),
+    ]));
+  }
+
+  fn variables(&mut self, vm: &VM, request: &Json) {
+    let var_ref = request.get("arguments").and_then(|args| args.get("variablesReference")).and_then(Json::as_u64).unwrap_or(0) as usize;
+
+    // Collect the (name, value) pairs first so that nested containers can be registered afterwards
+    let mut vars: Vec<(String, RantValue)> = match var_ref.checked_sub(1).and_then(|i| self.var_refs.get(i)) {
+      Some(VarContainer::Locals(depth)) => vm.call_stack()
+        .locals_at(*depth)
+        .map(|(name, var)| (name.to_owned(), var.value_cloned()))
+        .collect(),
+      Some(VarContainer::Globals) => vm.context()
+        .global_names()
+        .filter_map(|name| vm.context().get_global(name).map(|value| (name.to_owned(), value)))
+        .collect(),
+      Some(VarContainer::Value(RantValue::List(list))) => list.borrow()
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (i.to_string(), value.clone()))
+        .collect(),
+      Some(VarContainer::Value(RantValue::Map(map))) => {
+        let map = map.borrow();
+        map.raw_keys()
+          .iter()
+          .filter_map(|key| {
+            let key = key.to_string();
+            map.raw_get(&key).map(|value| (key, value.clone()))
+          })
+          .collect()
+      },
+      Some(VarContainer::Value(_)) => vec![],
+      None => {
+        self.transport.respond_error(request, "invalid variables reference");
+        return
+      }
+    };
+
+    // Lists are already in order; sort everything else by name
+    if !matches!(self.var_refs.get(var_ref - 1), Some(VarContainer::Value(RantValue::List(_)))) {
+      vars.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    let variables: Vec<Json> = vars.iter().map(|(name, value)| self.make_variable(name, value)).collect();
+    self.transport.respond(request, Json::object(vec![("variables", variables.into())]));
+  }
+
+  fn evaluate(&mut self, vm: &VM, request: &Json) {
+    let expr = request.get("arguments").and_then(|args| args.get("expression")).and_then(Json::as_str).unwrap_or_default().trim();
+    let name = expr.trim_start_matches('<').trim_end_matches('>');
+    match vm.inspect_var(name) {
+      Some(value) => {
+        let var = self.make_variable(name, &value);
+        self.transport.respond(request, Json::object(vec![
+          ("result", var.get("value").cloned().unwrap_or(Json::Null)),
+          ("type", value.type_name().into()),
+          ("variablesReference", var.get("variablesReference").cloned().unwrap_or(Json::Null)),
+        ]));
+      },
+      None => self.transport.respond_error(request, &format!("variable '{}' not found", name)),
+    }
+  }
+
+  /// Acknowledges a `disconnect` or `terminate` request and shuts down the adapter.
+  fn terminate(&mut self, request: &Json) -> ! {
+    self.transport.respond(request, Json::Null);
+    self.transport.event("terminated", Json::object(Vec::<(&str, Json)>::new()));
+    process::exit(0);
+  }
+}
+
+impl<'a> Debugger for DapDebugger<'a> {
+  fn on_start(&mut self, vm: &mut VM) -> DebugAction {
+    for (path, lines) in self.initial_breakpoints.drain() {
+      for line in lines {
+        vm.add_breakpoint(Breakpoint::with_source(path.as_str(), line));
+      }
+    }
+
+    if self.stop_on_entry {
+      DebugAction::StepIn
+    } else {
+      DebugAction::Continue
+    }
+  }
+
+  fn poll_pause(&mut self, vm: &mut VM) -> bool {
+    while let Some(request) = self.transport.try_read_message() {
+      match request.get("command").and_then(Json::as_str).unwrap_or_default() {
+        "pause" => {
+          self.transport.respond(&request, Json::Null);
+          return true
+        },
+        "threads" => self.transport.respond(&request, threads_body()),
+        "setBreakpoints" => {
+          if let Some((path, lines)) = set_breakpoints(self.transport, &request) {
+            self.replace_breakpoints(vm, path, lines);
+          }
+        },
+        "setExceptionBreakpoints" => self.transport.respond(&request, Json::Null),
+        "disconnect" | "terminate" => self.terminate(&request),
+        other => {
+          let msg = format!("'{}' is only available while the program is paused", other);
+          self.transport.respond_error(&request, &msg);
+        },
+      }
+    }
+    false
+  }
+
+  fn on_pause(&mut self, vm: &mut VM, reason: PauseReason) -> DebugAction {
+    self.var_refs.clear();
+    self.transport.event("stopped", Json::object(vec![
+      ("reason", match reason {
+        PauseReason::Entry => "entry",
+        PauseReason::Breakpoint => "breakpoint",
+        PauseReason::Step => "step",
+        PauseReason::Pause => "pause",
+      }.into()),
+      ("threadId", THREAD_ID.into()),
+      ("allThreadsStopped", true.into()),
+    ]));
+
+    loop {
+      let request = match self.transport.read_message() {
+        Some(request) => request,
+        None => process::exit(0),
+      };
+
+      let action = match request.get("command").and_then(Json::as_str).unwrap_or_default() {
+        "continue" => Some(DebugAction::Continue),
+        "next" => Some(DebugAction::StepOver),
+        "stepIn" => Some(DebugAction::StepIn),
+        "stepOut" => Some(DebugAction::StepOut),
+        "threads" => {
+          self.transport.respond(&request, threads_body());
+          None
+        },
+        "stackTrace" => {
+          self.stack_trace(vm, &request);
+          None
+        },
+        "scopes" => {
+          self.scopes(vm, &request);
+          None
+        },
+        "variables" => {
+          self.variables(vm, &request);
+          None
+        },
+        "evaluate" => {
+          self.evaluate(vm, &request);
+          None
+        },
+        "setBreakpoints" => {
+          if let Some((path, lines)) = set_breakpoints(self.transport, &request) {
+            self.replace_breakpoints(vm, path, lines);
+          }
+          None
+        },
+        "setExceptionBreakpoints" | "pause" => {
+          self.transport.respond(&request, Json::Null);
+          None
+        },
+        "disconnect" | "terminate" => self.terminate(&request),
+        other => {
+          let msg = format!("unsupported request: '{}'", other);
+          self.transport.respond_error(&request, &msg);
+          None
+        },
+      };
+
+      if let Some(action) = action {
+        let body = if action == DebugAction::Continue {
+          Json::object(vec![("allThreadsContinued", true.into())])
+        } else {
+          Json::Null
+        };
+        self.transport.respond(&request, body);
+        return action
+      }
+    }
+  }
+}
+
+/// Formats a value for display in the variables view.
+fn display_value(value: &RantValue) -> String {
+  match value {
+    RantValue::String(s) => format!("\"{}\"", s),
+    other => format!("{:?}", other),
+  }
+}
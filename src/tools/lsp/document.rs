@@ -0,0 +1,133 @@
+use crate::json::Json;
+use rant::compiler::{self, CompilerMessage, SymbolTable};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// An open text document and the results of its latest analysis.
+pub struct Document {
+  text: String,
+  /// Byte offsets of the start of each line.
+  line_starts: Vec<usize>,
+  symbols: SymbolTable,
+  messages: Vec<CompilerMessage>,
+}
+
+impl Document {
+  /// Creates a document and analyzes its contents.
+  pub fn new(text: String) -> Self {
+    let line_starts = std::iter::once(0)
+      .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+      .collect();
+    let mut messages = vec![];
+    let symbols = compiler::analyze(&text, &mut messages);
+    Self {
+      text,
+      line_starts,
+      symbols,
+      messages,
+    }
+  }
+
+  #[inline]
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  #[inline]
+  pub fn symbols(&self) -> &SymbolTable {
+    &self.symbols
+  }
+
+  #[inline]
+  pub fn messages(&self) -> &[CompilerMessage] {
+    &self.messages
+  }
+
+  /// Converts a byte offset to an LSP position (zero-based line and UTF-16 column).
+  pub fn position(&self, offset: usize) -> Json {
+    let offset = offset.min(self.text.len());
+    let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+    let line_start = self.line_starts[line];
+    let character: usize = self.text
+      .get(line_start..offset)
+      .map_or(0, |prefix| prefix.chars().map(char::len_utf16).sum());
+    Json::object(vec![
+      ("line", line.into()),
+      ("character", character.into()),
+    ])
+  }
+
+  /// Converts a byte span to an LSP range.
+  pub fn range(&self, span: &Range<usize>) -> Json {
+    Json::object(vec![
+      ("start", self.position(span.start)),
+      ("end", self.position(span.end.max(span.start))),
+    ])
+  }
+
+  /// Converts an LSP position to a byte offset, clamping it to the document.
+  pub fn offset(&self, position: &Json) -> usize {
+    let line = position.get("line").and_then(Json::as_u64).unwrap_or(0) as usize;
+    let character = position.get("character").and_then(Json::as_u64).unwrap_or(0) as usize;
+    let line_start = match self.line_starts.get(line) {
+      Some(start) => *start,
+      None => return self.text.len(),
+    };
+    let line_end = self.line_starts.get(line + 1).map_or(self.text.len(), |next| next - 1);
+
+    let mut units = 0;
+    for (i, c) in self.text[line_start..line_end].char_indices() {
+      if units >= character {
+        return line_start + i
+      }
+      units += c.len_utf16();
+    }
+    line_end
+  }
+
+  /// Gets the text of the line containing the offset, up to the offset.
+  pub fn line_prefix(&self, offset: usize) -> &str {
+    let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+    &self.text[self.line_starts[line]..offset]
+  }
+}
+
+/// Converts a `file://` URI to a local path.
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+  let path = uri.strip_prefix("file://")?;
+  let bytes = path.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    if bytes[i] == b'%' {
+      if let Some(byte) = path.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+        decoded.push(byte);
+        i += 3;
+        continue
+      }
+    }
+    decoded.push(bytes[i]);
+    i += 1;
+  }
+  let path = String::from_utf8(decoded).ok()?;
+
+  // Windows paths look like "/C:/..."
+  let path = match path.strip_prefix('/') {
+    Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest.to_owned(),
+    _ => path,
+  };
+  Some(PathBuf::from(path))
+}
+
+/// Converts a local path to a `file://` URI.
+pub fn path_to_uri(path: &Path) -> String {
+  let path = path.to_string_lossy().replace('\\', "/");
+  let mut uri = String::from(if path.starts_with('/') { "file://" } else { "file:///" });
+  for byte in path.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => uri.push(byte as char),
+      _ => uri.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  uri
+}
@@ -0,0 +1,399 @@
+//! Language Server Protocol server for Rant.
+//!
+//! Speaks LSP over stdin/stdout, providing diagnostics, document symbols, go-to-definition, hover docs, and completion.
+
+#[path = "../common/json.rs"]
+mod json;
+#[path = "../common/json_parse.rs"]
+#[allow(dead_code)]
+mod json_parse;
+mod document;
+mod stdlib_docs;
+
+use document::*;
+use json::Json;
+use rant::compiler::{SymbolDefinition, SymbolKind};
+use rant::runtime::SELECTOR_MODE_NAMES;
+use rant::*;
+use stdlib_docs::StdlibDocs;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process;
+
+/// JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// LSP `CompletionItemKind` values.
+mod completion_kind {
+  pub const FUNCTION: i64 = 3;
+  pub const VARIABLE: i64 = 6;
+  pub const ENUM_MEMBER: i64 = 20;
+  pub const CONSTANT: i64 = 21;
+}
+
+/// LSP `SymbolKind` value for functions.
+const SYMBOL_KIND_FUNCTION: i64 = 12;
+
+/// Reads and writes JSON-RPC messages over stdio.
+struct Transport {
+  input: BufReader<io::Stdin>,
+  output: io::Stdout,
+}
+
+impl Transport {
+  fn new() -> Self {
+    Self {
+      input: BufReader::new(io::stdin()),
+      output: io::stdout(),
+    }
+  }
+
+  /// Reads the next message. Returns `None` once the client closes the stream.
+  fn read_message(&mut self) -> Option<Json> {
+    loop {
+      let mut content_length = None;
+
+      // Read headers
+      loop {
+        let mut line = String::new();
+        if self.input.read_line(&mut line).ok()? == 0 {
+          return None
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+          break
+        }
+        if let Some((name, value)) = line.split_once(':') {
+          if name.eq_ignore_ascii_case("Content-Length") {
+            content_length = value.trim().parse::<usize>().ok();
+          }
+        }
+      }
+
+      let content_length = match content_length {
+        Some(len) => len,
+        None => continue,
+      };
+
+      let mut content = vec![0; content_length];
+      self.input.read_exact(&mut content).ok()?;
+      match std::str::from_utf8(&content).ok().map(Json::parse) {
+        Some(Ok(msg)) => return Some(msg),
+        _ => eprintln!("rant-lsp: ignoring malformed message"),
+      }
+    }
+  }
+
+  fn send(&mut self, msg: Json) {
+    let content = msg.to_string();
+    write!(self.output, "Content-Length: {}\r\n\r\n{}", content.len(), content).expect("failed to write to stdout");
+    self.output.flush().expect("failed to flush stdout");
+  }
+
+  /// Sends the result of a request.
+  fn respond(&mut self, request: &Json, result: Json) {
+    self.send(Json::object(vec![
+      ("jsonrpc", "2.0".into()),
+      ("id", request.get("id").cloned().unwrap_or(Json::Null)),
+      ("result", result),
+    ]));
+  }
+
+  /// Sends an error response to a request.
+  fn respond_error(&mut self, request: &Json, code: i64, message: &str) {
+    self.send(Json::object(vec![
+      ("jsonrpc", "2.0".into()),
+      ("id", request.get("id").cloned().unwrap_or(Json::Null)),
+      ("error", Json::object(vec![
+        ("code", code.into()),
+        ("message", message.into()),
+      ])),
+    ]));
+  }
+
+  /// Sends a notification.
+  fn notify(&mut self, method: &str, params: Json) {
+    self.send(Json::object(vec![
+      ("jsonrpc", "2.0".into()),
+      ("method", method.into()),
+      ("params", params),
+    ]));
+  }
+}
+
+/// Holds the open documents and the stdlib information shared between requests.
+struct Server {
+  rant: Rant,
+  docs: StdlibDocs,
+  documents: HashMap<String, Document>,
+}
+
+impl Server {
+  fn new() -> Self {
+    let rant = Rant::new();
+    let docs = StdlibDocs::new(&rant);
+    Self {
+      rant,
+      docs,
+      documents: Default::default(),
+    }
+  }
+
+  /// Re-analyzes a document and publishes its diagnostics.
+  fn update_document(&mut self, transport: &mut Transport, uri: &str, text: String) {
+    let doc = Document::new(text);
+    let diagnostics = doc.messages().iter().map(|msg| {
+      let range = match msg.pos() {
+        Some(pos) => doc.range(&pos.span()),
+        None => doc.range(&(0..0)),
+      };
+      Json::object(vec![
+        ("range", range),
+        ("severity", (if msg.is_error() { 1i64 } else { 2 }).into()),
+        ("code", msg.code().into()),
+        ("source", "rant".into()),
+        ("message", msg.message().into()),
+      ])
+    }).collect::<Vec<_>>();
+
+    transport.notify("textDocument/publishDiagnostics", Json::object(vec![
+      ("uri", uri.into()),
+      ("diagnostics", diagnostics.into()),
+    ]));
+    self.documents.insert(uri.to_owned(), doc);
+  }
+
+  fn document_symbols(&self, doc: &Document) -> Json {
+    doc.symbols()
+      .definitions()
+      .iter()
+      .filter(|def| def.kind == SymbolKind::Function)
+      .map(|def| Json::object(vec![
+        ("name", def.name.as_str().into()),
+        ("kind", SYMBOL_KIND_FUNCTION.into()),
+        ("range", doc.range(&def.span)),
+        ("selectionRange", doc.range(&def.name_span)),
+      ]))
+      .collect::<Vec<_>>()
+      .into()
+  }
+
+  fn definition(&self, uri: &str, doc: &Document, offset: usize) -> Json {
+    let symbols = doc.symbols();
+
+    // Variables defined in the same document
+    let local_def = symbols.reference_at(offset)
+      .and_then(|reference| symbols.definition_of(reference))
+      .or_else(|| symbols.definition_at(offset));
+    if let Some(def) = local_def {
+      return Json::object(vec![
+        ("uri", uri.into()),
+        ("range", doc.range(&def.name_span)),
+      ])
+    }
+
+    // Required modules
+    if let Some(module) = symbols.module_at(offset) {
+      let doc_path = uri_to_path(uri).map(|path| path.to_string_lossy().into_owned());
+      if let Some(module_path) = self.rant.find_module_path(&module.path, doc_path.as_deref()) {
+        let start = Json::object(vec![
+          ("line", 0usize.into()),
+          ("character", 0usize.into()),
+        ]);
+        return Json::object(vec![
+          ("uri", path_to_uri(&module_path).into()),
+          ("range", Json::object(vec![
+            ("start", start.clone()),
+            ("end", start),
+          ])),
+        ])
+      }
+    }
+
+    Json::Null
+  }
+
+  fn hover(&self, doc: &Document, offset: usize) -> Json {
+    let symbols = doc.symbols();
+    let (contents, span) = if let Some(reference) = symbols.reference_at(offset) {
+      match symbols.definition_of(reference) {
+        Some(def) => (describe_definition(doc, def), reference.span.clone()),
+        None => match self.docs.get(&reference.name) {
+          Some(func_doc) => (func_doc.to_markdown(), reference.span.clone()),
+          None => return Json::Null,
+        },
+      }
+    } else if let Some(def) = symbols.definition_at(offset) {
+      (describe_definition(doc, def), def.name_span.clone())
+    } else {
+      return Json::Null
+    };
+
+    Json::object(vec![
+      ("contents", Json::object(vec![
+        ("kind", "markdown".into()),
+        ("value", contents.into()),
+      ])),
+      ("range", doc.range(&span)),
+    ])
+  }
+
+  fn completion(&self, doc: &Document, offset: usize) -> Json {
+    // Selector modes are offered inside [mksel] calls
+    if is_selector_mode_position(doc.line_prefix(offset)) {
+      return SELECTOR_MODE_NAMES
+        .iter()
+        .map(|mode| Json::object(vec![
+          ("label", (*mode).into()),
+          ("kind", completion_kind::ENUM_MEMBER.into()),
+        ]))
+        .collect::<Vec<_>>()
+        .into()
+    }
+
+    let mut items = vec![];
+    let mut seen = std::collections::HashSet::new();
+    for def in doc.symbols().definitions().iter().filter(|def| def.span.start < offset) {
+      if seen.insert(def.name.as_str()) {
+        items.push(Json::object(vec![
+          ("label", def.name.as_str().into()),
+          ("kind", match def.kind {
+            SymbolKind::Function => completion_kind::FUNCTION,
+            SymbolKind::Constant => completion_kind::CONSTANT,
+            SymbolKind::Variable | SymbolKind::Parameter => completion_kind::VARIABLE,
+          }.into()),
+          ("detail", doc.text()[def.span.clone()].into()),
+        ]));
+      }
+    }
+
+    let mut stdlib_names = self.docs.names().collect::<Vec<_>>();
+    stdlib_names.sort_unstable();
+    for name in stdlib_names {
+      if !seen.insert(name) {
+        continue
+      }
+      let func_doc = self.docs.get(name).unwrap();
+      items.push(Json::object(vec![
+        ("label", name.into()),
+        ("kind", completion_kind::FUNCTION.into()),
+        ("detail", func_doc.signature.as_str().into()),
+        ("documentation", Json::object(vec![
+          ("kind", "markdown".into()),
+          ("value", func_doc.to_markdown().into()),
+        ])),
+      ]));
+    }
+
+    items.into()
+  }
+}
+
+/// Formats hover contents for a symbol defined in the document.
+fn describe_definition(doc: &Document, def: &SymbolDefinition) -> String {
+  let kind = match def.kind {
+    SymbolKind::Variable => "variable",
+    SymbolKind::Constant => "constant",
+    SymbolKind::Function => "function",
+    SymbolKind::Parameter => "parameter",
+  };
+  let source = doc.text()[def.span.clone()].trim();
+  format!("({}) `{}`\n\n```rant\n{}\n```", kind, def.name, source)
+}
+
+/// Checks if the text before the cursor is the first argument of a `[mksel]` call.
+fn is_selector_mode_position(line_prefix: &str) -> bool {
+  match line_prefix.rfind('[') {
+    Some(start) => {
+      let call = &line_prefix[start + 1..];
+      match call.strip_prefix("mksel:") {
+        Some(arg) => !arg.contains(&[';', ']', '|'][..]),
+        None => false,
+      }
+    },
+    None => false,
+  }
+}
+
+fn capabilities() -> Json {
+  Json::object(vec![
+    ("capabilities", Json::object(vec![
+      // Full document sync
+      ("textDocumentSync", 1i64.into()),
+      ("hoverProvider", true.into()),
+      ("definitionProvider", true.into()),
+      ("documentSymbolProvider", true.into()),
+      ("completionProvider", Json::object(vec![
+        ("triggerCharacters", vec![Json::from("["), Json::from("\""), Json::from(":")].into()),
+      ])),
+    ])),
+    ("serverInfo", Json::object(vec![
+      ("name", "rant-lsp".into()),
+      ("version", BUILD_VERSION.into()),
+    ])),
+  ])
+}
+
+fn main() {
+  let mut transport = Transport::new();
+  let mut server = Server::new();
+  let mut is_shutdown = false;
+
+  while let Some(msg) = transport.read_message() {
+    let method = msg.get("method").and_then(Json::as_str).unwrap_or_default().to_owned();
+    let params = msg.get("params").cloned().unwrap_or(Json::Null);
+    let uri = params.get("textDocument").and_then(|doc| doc.get("uri")).and_then(Json::as_str).unwrap_or_default().to_owned();
+    let is_request = msg.get("id").is_some();
+
+    match method.as_str() {
+      "initialize" => transport.respond(&msg, capabilities()),
+      "shutdown" => {
+        is_shutdown = true;
+        transport.respond(&msg, Json::Null);
+      },
+      "exit" => process::exit(if is_shutdown { 0 } else { 1 }),
+      "textDocument/didOpen" => {
+        let text = params.get("textDocument").and_then(|doc| doc.get("text")).and_then(Json::as_str).unwrap_or_default();
+        server.update_document(&mut transport, &uri, text.to_owned());
+      },
+      "textDocument/didChange" => {
+        // With full sync, the last change holds the entire document
+        let text = params.get("contentChanges")
+          .and_then(Json::as_array)
+          .and_then(|changes| changes.last())
+          .and_then(|change| change.get("text"))
+          .and_then(Json::as_str);
+        if let Some(text) = text {
+          server.update_document(&mut transport, &uri, text.to_owned());
+        }
+      },
+      "textDocument/didClose" => {
+        server.documents.remove(&uri);
+        transport.notify("textDocument/publishDiagnostics", Json::object(vec![
+          ("uri", uri.as_str().into()),
+          ("diagnostics", Vec::<Json>::new().into()),
+        ]));
+      },
+      "textDocument/documentSymbol" | "textDocument/definition" | "textDocument/hover" | "textDocument/completion" => {
+        let doc = match server.documents.get(&uri) {
+          Some(doc) => doc,
+          None => {
+            transport.respond(&msg, Json::Null);
+            continue
+          }
+        };
+        let offset = params.get("position").map_or(0, |pos| doc.offset(pos));
+        let result = match method.as_str() {
+          "textDocument/documentSymbol" => server.document_symbols(doc),
+          "textDocument/definition" => server.definition(&uri, doc, offset),
+          "textDocument/hover" => server.hover(doc, offset),
+          _ => server.completion(doc, offset),
+        };
+        transport.respond(&msg, result);
+      },
+      // Other notifications can be safely ignored
+      _ if !is_request => {},
+      _ => transport.respond_error(&msg, METHOD_NOT_FOUND, &format!("unsupported method: '{}'", method)),
+    }
+  }
+}
@@ -0,0 +1,118 @@
+//! Hover documentation for standard library functions, taken from the doc comments in the stdlib sources.
+
+use rant::{Rant, RantValue};
+use std::collections::HashMap;
+
+const STDLIB_SOURCES: &[&str] = &[
+  include_str!("../../stdlib/assertion.rs"),
+  include_str!("../../stdlib/block.rs"),
+  include_str!("../../stdlib/boolean.rs"),
+  include_str!("../../stdlib/collections.rs"),
+  include_str!("../../stdlib/compare.rs"),
+  include_str!("../../stdlib/control.rs"),
+  include_str!("../../stdlib/convert.rs"),
+  include_str!("../../stdlib/format.rs"),
+  include_str!("../../stdlib/general.rs"),
+  include_str!("../../stdlib/generate.rs"),
+  include_str!("../../stdlib/iter.rs"),
+  include_str!("../../stdlib/math.rs"),
+  include_str!("../../stdlib/proto.rs"),
+  include_str!("../../stdlib/strings.rs"),
+  include_str!("../../stdlib/verify.rs"),
+];
+
+/// Documentation for a single stdlib function.
+pub struct FunctionDoc {
+  /// The function signature, e.g. `[$add: lhs (any); rhs (any)]`.
+  pub signature: String,
+  /// The description following the signature, if any.
+  pub description: String,
+}
+
+/// Documentation for every function in the standard library.
+pub struct StdlibDocs {
+  docs: HashMap<String, FunctionDoc>,
+}
+
+impl StdlibDocs {
+  /// Collects documentation for every function global of `rant`.
+  ///
+  /// Functions without a doc comment get a signature generated from their parameters.
+  pub fn new(rant: &Rant) -> Self {
+    let mut docs = HashMap::new();
+    for source in STDLIB_SOURCES {
+      for (name, doc) in parse_doc_comments(source) {
+        docs.entry(name).or_insert(doc);
+      }
+    }
+
+    for name in rant.global_names() {
+      if docs.contains_key(name) {
+        continue
+      }
+      if let Some(RantValue::Function(func)) = rant.get_global(name) {
+        docs.insert(name.to_owned(), FunctionDoc {
+          signature: func.signature(name),
+          description: String::new(),
+        });
+      }
+    }
+
+    docs.retain(|name, _| rant.has_global(name));
+    Self { docs }
+  }
+
+  #[inline]
+  pub fn get(&self, name: &str) -> Option<&FunctionDoc> {
+    self.docs.get(name)
+  }
+
+  /// Iterates over the names of all documented functions.
+  #[inline]
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.docs.keys().map(String::as_str)
+  }
+}
+
+impl FunctionDoc {
+  /// Formats the documentation as Markdown.
+  pub fn to_markdown(&self) -> String {
+    if self.description.is_empty() {
+      format!("```rant\n{}\n```", self.signature)
+    } else {
+      format!("```rant\n{}\n```\n\n{}", self.signature, self.description)
+    }
+  }
+}
+
+/// Finds doc comments that start with a function signature like `` `[$name: ...]` ``.
+fn parse_doc_comments(source: &str) -> Vec<(String, FunctionDoc)> {
+  let mut docs = vec![];
+  let mut lines = source.lines().map(str::trim).peekable();
+  while let Some(line) = lines.next() {
+    let signature = match line.strip_prefix("/// `[").and_then(|sig| sig.strip_suffix("]`")) {
+      Some(sig) => sig.trim_start_matches('$'),
+      None => continue,
+    };
+    let name = signature
+      .split(|c: char| c == ':' || c.is_whitespace())
+      .next()
+      .unwrap_or_default()
+      .to_owned();
+    if name.is_empty() {
+      continue
+    }
+
+    let mut description = vec![];
+    while let Some(doc_line) = lines.peek().and_then(|line| line.strip_prefix("///")) {
+      description.push(doc_line.strip_prefix(' ').unwrap_or(doc_line));
+      lines.next();
+    }
+
+    docs.push((name, FunctionDoc {
+      signature: format!("[${}]", signature),
+      description: description.join("\n").trim().to_owned(),
+    }));
+  }
+  docs
+}
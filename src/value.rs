@@ -1,4 +1,4 @@
-use crate::{RantFunction, RantString, lang::Slice, util};
+use crate::{RantFunction, RantIteratorRef, RantString, lang::Slice, util};
 use crate::collections::*;
 use crate::runtime::resolver::*;
 use crate::runtime::*;
@@ -7,6 +7,8 @@ use std::{cell::RefCell, fmt::{Display, Debug}, ops::{Add, Div, Mul, Neg, Not, R
 use std::error::Error;
 use std::cmp::Ordering;
 use cast::*;
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
 
 const MAX_DISPLAY_STRING_DEPTH: usize = 4;
 
@@ -35,6 +37,7 @@ macro_rules! impl_into_runtime_result {
           error_type: RuntimeErrorType::$err_type_variant(err),
           description: None,
           stack_trace: None,
+          stack_frames: None,
         })
       }
     }
@@ -77,6 +80,10 @@ pub enum RantValue {
   Float(f64),
   /// A Rant value of type `int`. Passed by-value.
   Int(i64),
+  /// A Rant value of type `int` that doesn't fit in an `i64`. Passed by-value.
+  ///
+  /// Only produced when `RantOptions.enable_big_ints` is set and integer arithmetic overflows.
+  BigInt(Rc<BigInt>),
   /// A Rant value of type `bool`. Passed by-value.
   Boolean(bool),
   /// A Rant value of type `function`. Passed by-reference.
@@ -85,8 +92,12 @@ pub enum RantValue {
   List(RantListRef),
   /// A Rant value of type `map`. Passed by-reference.
   Map(RantMapRef),
+  /// A Rant value of type `set`. Passed by-reference.
+  Set(RantSetRef),
   /// A Rant value of type `range`. Passed by-value.
   Range(RantRange),
+  /// A Rant value of type `iterator`. Passed by-reference.
+  Iterator(RantIteratorRef),
   /// A Rant value of type `special`. Passed by-value.
   Special(RantSpecial),
   /// A Rant unit value of type `empty`. Passed by-value.
@@ -121,6 +132,81 @@ impl RantValue {
   pub fn is_callable(&self) -> bool {
     matches!(self, Self::Function(..))
   }
+
+  /// Creates an `int` value from a big integer, which is only stored as a big integer if it doesn't fit in an `i64`.
+  #[inline]
+  pub fn from_big_int(n: BigInt) -> Self {
+    match n.to_i64() {
+      Some(n) => Self::Int(n),
+      None => Self::BigInt(Rc::new(n)),
+    }
+  }
+
+  /// Converts an `int` or `bool` to a big integer so that arithmetic on it can't overflow.
+  /// Other values are returned unchanged.
+  #[inline]
+  pub fn into_big_int(self) -> Self {
+    match self.to_big_int() {
+      Some(n) => Self::BigInt(Rc::new(n)),
+      None => self,
+    }
+  }
+
+  /// Gets the value of an `int` or `bool` as a big integer.
+  #[inline]
+  fn to_big_int(&self) -> Option<BigInt> {
+    match self {
+      Self::Int(n) => Some(BigInt::from(*n)),
+      Self::BigInt(n) => Some(BigInt::clone(n)),
+      Self::Boolean(b) => Some(BigInt::from(bi64(*b))),
+      _ => None,
+    }
+  }
+}
+
+/// Operands of an arithmetic operator, after accounting for big integers.
+enum Operands {
+  /// Both operands are integral and at least one is a big integer.
+  BigInt(BigInt, BigInt),
+  /// Any other operands, with big integers paired with floats converted to floats.
+  Other(RantValue, RantValue),
+}
+
+impl Operands {
+  #[inline]
+  fn new(lhs: RantValue, rhs: RantValue) -> Self {
+    match (&lhs, &rhs) {
+      (RantValue::BigInt(_), _) | (_, RantValue::BigInt(_)) => {},
+      _ => return Self::Other(lhs, rhs),
+    }
+    if let (Some(a), Some(b)) = (lhs.to_big_int(), rhs.to_big_int()) {
+      return Self::BigInt(a, b)
+    }
+    match (lhs, rhs) {
+      (RantValue::BigInt(a), rhs @ RantValue::Float(_)) => Self::Other(RantValue::Float(big_f64(&a)), rhs),
+      (lhs @ RantValue::Float(_), RantValue::BigInt(b)) => Self::Other(lhs, RantValue::Float(big_f64(&b))),
+      (lhs, rhs) => Self::Other(lhs, rhs),
+    }
+  }
+}
+
+/// Applies an arithmetic operation to some operands.
+/// If integer arithmetic overflows and `promote` is set, the operation is retried with big integer operands.
+#[inline]
+pub(crate) fn promote_on_overflow<const N: usize>(promote: bool, operands: [RantValue; N], op: impl Fn([RantValue; N]) -> ValueResult<RantValue>) -> ValueResult<RantValue> {
+  if !promote {
+    return op(operands)
+  }
+  match op(operands.clone()) {
+    Err(ValueError::Overflow) => op(operands.map(RantValue::into_big_int)),
+    result => result,
+  }
+}
+
+/// Converts a big integer to the nearest `f64`.
+#[inline]
+fn big_f64(n: &BigInt) -> f64 {
+  n.to_f64().unwrap_or(f64::NAN)
 }
 
 #[allow(clippy::len_without_is_empty)]
@@ -132,7 +218,7 @@ impl RantValue {
   /// 2. `int` returns `true` for any non-zero value; otherwise, `false`.
   /// 3. `float` returns `true` for any [normal](https://en.wikipedia.org/wiki/Normal_number_(computing)) value; otherwise, `false`.
   /// 4. `empty` returns `false`.
-  /// 5. Collections (`string`, `list`, `map`, `range`, `block`) return `true` if non-empty; otherwise, `false`.
+  /// 5. Collections (`string`, `list`, `map`, `set`, `range`, `block`) return `true` if non-empty; otherwise, `false`.
   /// 6. All other types return `true`.
   #[inline]
   pub fn to_bool(&self) -> bool {
@@ -141,10 +227,13 @@ impl RantValue {
       Self::String(s) => !s.is_empty(),
       Self::Float(n) => n.is_normal(),
       Self::Int(n) => *n != 0,
+      Self::BigInt(n) => !n.is_zero(),
       Self::Function(_) => true,
       Self::List(l) => !l.borrow().is_empty(),
       Self::Map(m) => !m.borrow().is_empty(),
+      Self::Set(set) => !set.borrow().is_empty(),
       Self::Range(r) => !r.is_empty(),
+      Self::Iterator(_) => true,
       Self::Special(_) => true,
       Self::Empty => false,
     }
@@ -160,7 +249,7 @@ impl RantValue {
   #[inline]
   pub fn into_rant_int(self) -> Self {
     match self {
-      Self::Int(_) => self,
+      Self::Int(_) | Self::BigInt(_) => self,
       Self::Float(n) => Self::Int(n as i64),
       Self::String(s) => {
         match s.as_str().parse() {
@@ -179,6 +268,7 @@ impl RantValue {
     match self {
       Self::Float(_) => self,
       Self::Int(n) => Self::Float(n as f64),
+      Self::BigInt(n) => Self::Float(big_f64(&n)),
       Self::String(s) => {
         match s.as_str().parse() {
           Ok(n) => Self::Float(n),
@@ -205,32 +295,38 @@ impl RantValue {
     Self::List(match self {
       Self::String(s) => Rc::new(RefCell::new(s.to_rant_list())),
       Self::List(list) => Rc::new(RefCell::new(list.borrow().clone())),
+      Self::Set(set) => Rc::new(RefCell::new(set.borrow().to_list())),
       Self::Range(range) => Rc::new(RefCell::new(range.to_list())),
       _ => return RantValue::Empty,
     })
   }
 
   /// Concatenates two values.
+  /// Returns `ValueError::Overflow` if integer concatenation overflows.
   #[inline]
-  pub fn concat(self, rhs: Self) -> Self {
-    match (self, rhs) {
+  pub fn concat(self, rhs: Self) -> ValueResult<Self> {
+    let (lhs, rhs) = match Operands::new(self, rhs) {
+      Operands::BigInt(a, b) => return Ok(Self::from_big_int(a + b)),
+      Operands::Other(lhs, rhs) => (lhs, rhs),
+    };
+    Ok(match (lhs, rhs) {
       (Self::Empty, Self::Empty) => Self::Empty,
       (lhs, Self::Empty) => lhs,
       (Self::Empty, rhs) => rhs,
-      (Self::Int(a), Self::Int(b)) => Self::Int(a.saturating_add(b)),
+      (Self::Int(a), Self::Int(b)) => Self::Int(a.checked_add(b).ok_or(ValueError::Overflow)?),
       (Self::Int(a), Self::Float(b)) => Self::Float(f64(a) + b),
-      (Self::Int(a), Self::Boolean(b)) => Self::Int(a.saturating_add(bi64(b))),
+      (Self::Int(a), Self::Boolean(b)) => Self::Int(a.checked_add(bi64(b)).ok_or(ValueError::Overflow)?),
       (Self::Float(a), Self::Float(b)) => Self::Float(a + b),
       (Self::Float(a), Self::Int(b)) => Self::Float(a + f64(b)),
       (Self::Float(a), Self::Boolean(b)) => Self::Float(a + bf64(b)),
       (Self::String(a), Self::String(b)) => Self::String(a + b),
       (Self::String(a), rhs) => Self::String(a + rhs.to_string().into()),
       (Self::Boolean(a), Self::Boolean(b)) => Self::Boolean(a || b),
-      (Self::Boolean(a), Self::Int(b)) => Self::Int(bi64(a).saturating_add(b)),
+      (Self::Boolean(a), Self::Int(b)) => Self::Int(bi64(a).checked_add(b).ok_or(ValueError::Overflow)?),
       (Self::Boolean(a), Self::Float(b)) => Self::Float(bf64(a) + b),
       (Self::List(a), Self::List(b)) => Self::List(Rc::new(RefCell::new(a.borrow().iter().cloned().chain(b.borrow().iter().cloned()).collect()))),
       (lhs, rhs) => Self::String(RantString::from(format!("{}{}", lhs, rhs)))
-    }
+    })
   }
 
   /// Gets the length of the value.
@@ -245,6 +341,8 @@ impl RantValue {
       Self::Range(range) => range.len(),
       // Length of map is element count
       Self::Map(map) => map.borrow().raw_len(),
+      // Length of set is element count
+      Self::Set(set) => set.borrow().len(),
       // Treat everything else as length 1, since all other value types are primitives
       _ => 1
     }
@@ -266,11 +364,47 @@ impl RantValue {
     match self {
       Self::List(list) => RantValue::List(Rc::new(RefCell::new(list.borrow().clone()))),
       Self::Map(map) => RantValue::Map(Rc::new(RefCell::new(map.borrow().clone()))),
+      Self::Set(set) => RantValue::Set(Rc::new(RefCell::new(set.borrow().clone()))),
       Self::Special(special) => RantValue::Special(special.clone()),
       _ => self.clone(),
     }
   }
 
+  /// Returns a copy of the value that shares no list, map, or set handles with the original.
+  ///
+  /// The contents of the collections are still shared, and are only copied once either side modifies them.
+  #[inline]
+  pub(crate) fn cow_copy(&self) -> Self {
+    match self {
+      Self::List(list) => {
+        let list = list.borrow();
+        let copy = if list.iter().any(Self::is_collection) {
+          list.iter().map(Self::cow_copy).collect()
+        } else {
+          list.clone()
+        };
+        RantValue::List(Rc::new(RefCell::new(copy)))
+      },
+      Self::Map(map) => {
+        let map = map.borrow();
+        let mut copy = map.clone();
+        for (key, val) in map.raw_pairs_internal().filter(|(_, val)| val.is_collection()) {
+          copy.raw_set(key, val.cow_copy());
+        }
+        RantValue::Map(Rc::new(RefCell::new(copy)))
+      },
+      // Set items are always passed by-value, so a shallow copy is enough
+      Self::Set(_) => self.shallow_copy(),
+      _ => self.clone(),
+    }
+  }
+
+  /// Returns true if the value is a list, map, or set.
+  #[inline]
+  fn is_collection(&self) -> bool {
+    matches!(self, Self::List(_) | Self::Map(_) | Self::Set(_))
+  }
+
   /// Gets the Rant type associated with the value.
   #[inline]
   pub fn get_type(&self) -> RantValueType {
@@ -278,11 +412,14 @@ impl RantValue {
       Self::String(_) =>     RantValueType::String,
       Self::Float(_) =>      RantValueType::Float,
       Self::Int(_) =>        RantValueType::Int,
+      Self::BigInt(_) =>     RantValueType::Int,
       Self::Boolean(_) =>    RantValueType::Boolean,
       Self::Function(_) =>   RantValueType::Function,
       Self::List(_) =>       RantValueType::List,
       Self::Map(_) =>        RantValueType::Map,
+      Self::Set(_) =>        RantValueType::Set,
       Self::Range(_) =>      RantValueType::Range,
+      Self::Iterator(_) =>   RantValueType::Iterator,
       Self::Special(_) =>    RantValueType::Special,
       Self::Empty =>         RantValueType::Empty,
     }
@@ -504,10 +641,14 @@ pub enum RantValueType {
   List,
   /// The `map` type.
   Map,
+  /// The `set` type.
+  Set,
   /// The `special` type.
   Special,
   /// The `range` type.
   Range,
+  /// The `iterator` type.
+  Iterator,
   /// The `empty` type.
   Empty
 }
@@ -523,8 +664,10 @@ impl RantValueType {
       Self::Function =>    "function",
       Self::List =>        "list",
       Self::Map =>         "map",
+      Self::Set =>         "set",
       Self::Special =>     "special",
       Self::Range =>       "range",
+      Self::Iterator =>    "iterator",
       Self::Empty =>       "empty",
     }
   }
@@ -537,7 +680,7 @@ impl Display for RantValueType {
 }
 
 /// Error produced by a RantValue operator or conversion.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ValueError {
   /// The requested conversion was not valid.
   InvalidConversion {
@@ -549,6 +692,8 @@ pub enum ValueError {
   DivideByZero,
   /// An arithmetic operation overflowed.
   Overflow,
+  /// Values of this type can't be stored in a set.
+  InvalidSetItem(RantValueType),
 }
 
 impl_error_default!(ValueError);
@@ -565,6 +710,7 @@ impl Display for ValueError {
       },
       ValueError::DivideByZero => write!(f, "attempted to divide by zero"),
       ValueError::Overflow => write!(f, "arithmetic overflow"),
+      ValueError::InvalidSetItem(t) => write!(f, "cannot store '{}' value in a set", t),
     }
   }
 }
@@ -572,16 +718,25 @@ impl Display for ValueError {
 impl<T> IntoRuntimeResult<T> for Result<T, ValueError> {
   #[inline]
   fn into_runtime_result(self) -> RuntimeResult<T> {
-    self.map_err(|err| RuntimeError {
-      error_type: RuntimeErrorType::ValueError(err),
-      description: None,
-      stack_trace: None,
+    self.map_err(|err| match err {
+      ValueError::Overflow => RuntimeError {
+        error_type: RuntimeErrorType::ArithmeticError,
+        description: Some(err.to_string()),
+        stack_trace: None,
+        stack_frames: None,
+      },
+      err => RuntimeError {
+        error_type: RuntimeErrorType::ValueError(err),
+        description: None,
+        stack_trace: None,
+        stack_frames: None,
+      },
     })
   }
 }
 
 /// Error produced by indexing a RantValue.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum IndexError {
   /// Index was out of range.
   OutOfRange,
@@ -606,7 +761,7 @@ impl Display for IndexError {
 }
 
 /// Error produced by keying a RantValue.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum KeyError {
   /// The specified key could not be found.
   KeyNotFound(String),
@@ -628,7 +783,7 @@ impl Display for KeyError {
 }
 
 /// Error produced by slicing a RantValue.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SliceError {
   /// Slice is out of range.
   OutOfRange,
@@ -664,12 +819,16 @@ impl Display for SliceError {
 pub enum RantSpecial {
   /// Selector state
   Selector(SelectorRef),
+  /// Error caught by `[try]`
+  Error(Rc<RuntimeError>),
 }
 
 impl PartialEq for RantSpecial {
   fn eq(&self, other: &Self) -> bool {
     match (self, other) {
       (RantSpecial::Selector(a), RantSpecial::Selector(b)) => a.as_ptr() == b.as_ptr(),
+      (RantSpecial::Error(a), RantSpecial::Error(b)) => Rc::ptr_eq(a, b),
+      _ => false,
     }
   }
 }
@@ -687,11 +846,14 @@ impl Debug for RantValue {
       Self::String(s) => write!(f, "{}", s),
       Self::Float(n) => write!(f, "{}", n),
       Self::Int(n) => write!(f, "{}", n),
+      Self::BigInt(n) => write!(f, "{}", n),
       Self::Boolean(b) => write!(f, "{}", if *b { "@true" }  else { "@false" }),
       Self::Function(func) => write!(f, "[function({:?})]", func.body),
       Self::List(l) => write!(f, "[list({})]", l.borrow().len()),
       Self::Map(m) => write!(f, "[map({})]", m.borrow().raw_len()),
+      Self::Set(set) => write!(f, "[set({})]", set.borrow().len()),
       Self::Range(range) => write!(f, "{}", range),
+      Self::Iterator(iter) => write!(f, "{:?}", iter.borrow()),
       Self::Special(special) => write!(f, "[special({:?})]", special),
       Self::Empty => write!(f, "[empty]"),
     }
@@ -703,6 +865,7 @@ fn get_display_string(value: &RantValue, max_depth: usize) -> String {
     RantValue::String(s) => s.to_string(),
     RantValue::Float(f) => format!("{}", f),
     RantValue::Int(i) => format!("{}", i),
+    RantValue::BigInt(i) => format!("{}", i),
     RantValue::Boolean(b) => (if *b { "@true" } else { "@false" }).to_string(),
     RantValue::Function(f) => format!("[function({:?})]", f.body),
     RantValue::List(list) => {
@@ -747,7 +910,12 @@ fn get_display_string(value: &RantValue, max_depth: usize) -> String {
       buf.push(')');
       buf
     },
+    RantValue::Set(set) => {
+      let items = set.borrow().iter().map(|item| get_display_string(item, max_depth.saturating_sub(1))).collect::<Vec<_>>();
+      format!("@{{{}}}", items.join("; "))
+    },
     RantValue::Special(_) => "[special]".to_owned(),
+    RantValue::Iterator(_) => "[iterator]".to_owned(),
     RantValue::Range(range) => range.to_string(),
     RantValue::Empty => (if max_depth < MAX_DISPLAY_STRING_DEPTH { "~" } else { "" }).to_owned(),
   }
@@ -768,10 +936,15 @@ impl PartialEq for RantValue {
       (Self::Int(a), Self::Float(b)) => *a as f64 == *b,
       (Self::Float(a), Self::Float(b)) => a == b,
       (Self::Float(a), Self::Int(b)) => *a == *b as f64,
+      (Self::BigInt(a), Self::BigInt(b)) => a == b,
+      (Self::BigInt(a), Self::Int(b)) | (Self::Int(b), Self::BigInt(a)) => **a == BigInt::from(*b),
+      (Self::BigInt(a), Self::Float(b)) | (Self::Float(b), Self::BigInt(a)) => big_f64(a) == *b,
       (Self::Boolean(a), Self::Boolean(b)) => a == b,
       (Self::Range(ra), Self::Range(rb)) => ra == rb,
       (Self::List(a), Self::List(b)) => a.borrow().eq(&b.borrow()),
       (Self::Map(a), Self::Map(b)) => Rc::as_ptr(a) == Rc::as_ptr(b),
+      (Self::Set(a), Self::Set(b)) => a.borrow().eq(&b.borrow()),
+      (Self::Iterator(a), Self::Iterator(b)) => Rc::ptr_eq(a, b),
       (Self::Special(a), Self::Special(b)) => a == b,
       _ => false
     }
@@ -788,6 +961,11 @@ impl PartialOrd for RantValue {
       (Self::Float(a), Self::Float(b)) => a.partial_cmp(b),
       (Self::Float(a), Self::Int(b)) => a.partial_cmp(&(*b as f64)),
       (Self::Int(a), Self::Float(b)) => (&(*a as f64)).partial_cmp(b),
+      (Self::BigInt(a), Self::BigInt(b)) => a.partial_cmp(b),
+      (Self::BigInt(a), Self::Int(b)) => (**a).partial_cmp(&BigInt::from(*b)),
+      (Self::Int(a), Self::BigInt(b)) => BigInt::from(*a).partial_cmp(&**b),
+      (Self::BigInt(a), Self::Float(b)) => big_f64(a).partial_cmp(b),
+      (Self::Float(a), Self::BigInt(b)) => a.partial_cmp(&big_f64(b)),
       (Self::String(a), Self::String(b)) => a.partial_cmp(b),
       (a, b) => if a == b { Some(Ordering::Equal) } else { None }
     }
@@ -806,68 +984,81 @@ impl Not for RantValue {
 }
 
 impl Neg for RantValue {
-  type Output = Self;
+  type Output = ValueResult<Self>;
   fn neg(self) -> Self::Output {
-    match self {
-      Self::Int(a) => Self::Int(a.saturating_neg()),
+    Ok(match self {
+      Self::Int(a) => Self::Int(a.checked_neg().ok_or(ValueError::Overflow)?),
+      Self::BigInt(a) => Self::from_big_int(-BigInt::clone(&a)),
       Self::Float(a) => Self::Float(-a),
       Self::Boolean(a) => Self::Int(-bi64(a)),
       _ => self
-    }
+    })
   }
 }
 
 impl Add for RantValue {
-  type Output = Self;
+  type Output = ValueResult<Self>;
   fn add(self, rhs: Self) -> Self::Output {
-    match (self, rhs) {
+    let (lhs, rhs) = match Operands::new(self, rhs) {
+      Operands::BigInt(a, b) => return Ok(Self::from_big_int(a + b)),
+      Operands::Other(lhs, rhs) => (lhs, rhs),
+    };
+    Ok(match (lhs, rhs) {
       (Self::Empty, Self::Empty) => Self::Empty,
       (lhs, Self::Empty) => lhs,
       (Self::Empty, rhs) => rhs,
-      (Self::Int(a), Self::Int(b)) => Self::Int(a.saturating_add(b)),
+      (Self::Int(a), Self::Int(b)) => Self::Int(a.checked_add(b).ok_or(ValueError::Overflow)?),
       (Self::Int(a), Self::Float(b)) => Self::Float(f64(a) + b),
-      (Self::Int(a), Self::Boolean(b)) => Self::Int(a.saturating_add(bi64(b))),
+      (Self::Int(a), Self::Boolean(b)) => Self::Int(a.checked_add(bi64(b)).ok_or(ValueError::Overflow)?),
       (Self::Float(a), Self::Float(b)) => Self::Float(a + b),
       (Self::Float(a), Self::Int(b)) => Self::Float(a + f64(b)),
       (Self::Float(a), Self::Boolean(b)) => Self::Float(a + bf64(b)),
       (Self::String(a), Self::String(b)) => Self::String(a + b),
       (Self::String(a), rhs) => Self::String(a + rhs.to_string().into()),
       (Self::Boolean(a), Self::Boolean(b)) => Self::Int(bi64(a) + bi64(b)),
-      (Self::Boolean(a), Self::Int(b)) => Self::Int(bi64(a).saturating_add(b)),
+      (Self::Boolean(a), Self::Int(b)) => Self::Int(bi64(a).checked_add(b).ok_or(ValueError::Overflow)?),
       (Self::Boolean(a), Self::Float(b)) => Self::Float(bf64(a) + b),
       (Self::List(a), Self::List(b)) => Self::List(Rc::new(RefCell::new(a.borrow().iter().cloned().chain(b.borrow().iter().cloned()).collect()))),
       (lhs, rhs) => Self::String(RantString::from(format!("{}{}", lhs, rhs)))
-    }
+    })
   }
 }
 
 impl Sub for RantValue {
-  type Output = Self;
+  type Output = ValueResult<Self>;
   fn sub(self, rhs: Self) -> Self::Output {
-    match (self, rhs) {
+    let (lhs, rhs) = match Operands::new(self, rhs) {
+      Operands::BigInt(a, b) => return Ok(Self::from_big_int(a - b)),
+      Operands::Other(lhs, rhs) => (lhs, rhs),
+    };
+    Ok(match (lhs, rhs) {
       (Self::Empty, Self::Empty) => Self::Empty,
       (lhs, Self::Empty) => lhs,
-      (Self::Empty, rhs) => -rhs,
-      (Self::Int(a), Self::Int(b)) => Self::Int(a.saturating_sub(b)),
+      (Self::Empty, rhs) => return -rhs,
+      (Self::Int(a), Self::Int(b)) => Self::Int(a.checked_sub(b).ok_or(ValueError::Overflow)?),
       (Self::Int(a), Self::Float(b)) => Self::Float((a as f64) - b),
-      (Self::Int(a), Self::Boolean(b)) => Self::Int(a - bi64(b)),
+      (Self::Int(a), Self::Boolean(b)) => Self::Int(a.checked_sub(bi64(b)).ok_or(ValueError::Overflow)?),
       (Self::Float(a), Self::Float(b)) => Self::Float(a - b),
       (Self::Float(a), Self::Int(b)) => Self::Float(a - (b as f64)),
       (Self::Float(a), Self::Boolean(b)) => Self::Float(a - bf64(b)),
       (Self::Boolean(a), Self::Boolean(b)) => Self::Int(bi64(a) - bi64(b)),
-      (Self::Boolean(a), Self::Int(b)) => Self::Int(bi64(a).saturating_sub(b)),
+      (Self::Boolean(a), Self::Int(b)) => Self::Int(bi64(a).checked_sub(b).ok_or(ValueError::Overflow)?),
       (Self::Boolean(a), Self::Float(b)) => Self::Float(bf64(a) - b),
       _ => Self::nan()
-    }
+    })
   }
 }
 
 impl Mul for RantValue {
-  type Output = Self;
+  type Output = ValueResult<Self>;
   fn mul(self, rhs: Self) -> Self::Output {
-    match (self, rhs) {
+    let (lhs, rhs) = match Operands::new(self, rhs) {
+      Operands::BigInt(a, b) => return Ok(Self::from_big_int(a * b)),
+      Operands::Other(lhs, rhs) => (lhs, rhs),
+    };
+    Ok(match (lhs, rhs) {
       (Self::Empty, _) | (_, Self::Empty) => Self::Empty,
-      (Self::Int(a), Self::Int(b)) => Self::Int(a.saturating_mul(b)),
+      (Self::Int(a), Self::Int(b)) => Self::Int(a.checked_mul(b).ok_or(ValueError::Overflow)?),
       (Self::Int(a), Self::Float(b)) => Self::Float((a as f64) * b),
       (Self::Int(a), Self::Boolean(b)) => Self::Int(a * bi64(b)),
       (Self::Float(a), Self::Float(b)) => Self::Float(a * b),
@@ -878,17 +1069,22 @@ impl Mul for RantValue {
       (Self::Boolean(a), Self::Float(b)) => Self::Float(bf64(a) * b),
       (Self::String(a), Self::Int(b)) => Self::String(a.as_str().repeat(clamp(b, 0, i64::MAX) as usize).into()),
       _ => Self::nan()
-    }
+    })
   }
 }
 
 impl Div for RantValue {
   type Output = ValueResult<Self>;
   fn div(self, rhs: Self) -> Self::Output {
-    Ok(match (self, rhs) {
+    let (lhs, rhs) = match Operands::new(self, rhs) {
+      Operands::BigInt(_, b) if b.is_zero() => return Err(ValueError::DivideByZero),
+      Operands::BigInt(a, b) => return Ok(Self::from_big_int(a / b)),
+      Operands::Other(lhs, rhs) => (lhs, rhs),
+    };
+    Ok(match (lhs, rhs) {
       (Self::Empty, _) | (_, Self::Empty) => Self::Empty,
       (_, Self::Int(0)) | (_, Self::Boolean(false)) => return Err(ValueError::DivideByZero),
-      (Self::Int(a), Self::Int(b)) => Self::Int(a / b),
+      (Self::Int(a), Self::Int(b)) => Self::Int(a.checked_div(b).ok_or(ValueError::Overflow)?),
       (Self::Int(a), Self::Float(b)) => Self::Float((a as f64) / b),
       (Self::Int(a), Self::Boolean(b)) => Self::Int(a / bi64(b)),
       (Self::Float(a), Self::Float(b)) => Self::Float(a / b),
@@ -905,10 +1101,15 @@ impl Div for RantValue {
 impl Rem for RantValue {
   type Output = ValueResult<Self>;
   fn rem(self, rhs: Self) -> Self::Output {
-    Ok(match (self, rhs) {
+    let (lhs, rhs) = match Operands::new(self, rhs) {
+      Operands::BigInt(_, b) if b.is_zero() => return Err(ValueError::DivideByZero),
+      Operands::BigInt(a, b) => return Ok(Self::from_big_int(a % b)),
+      Operands::Other(lhs, rhs) => (lhs, rhs),
+    };
+    Ok(match (lhs, rhs) {
       (Self::Empty, _) | (_, Self::Empty) => Self::Empty,
       (_, Self::Int(0)) | (_, Self::Boolean(false)) => return Err(ValueError::DivideByZero),
-      (Self::Int(a), Self::Int(b)) => Self::Int(a % b),
+      (Self::Int(a), Self::Int(b)) => Self::Int(a.checked_rem(b).ok_or(ValueError::Overflow)?),
       (Self::Int(a), Self::Float(b)) => Self::Float((a as f64) % b),
       (Self::Int(a), Self::Boolean(b)) => Self::Int(a % bi64(b)),
       _ => Self::nan()
@@ -920,7 +1121,18 @@ impl RantValue {
   /// Raises `self` to the `exponent` power.
   #[inline]
   pub fn pow(self, exponent: Self) -> ValueResult<Self> {
-    match (self, exponent) {
+    let (base, exponent) = match Operands::new(self, exponent) {
+      Operands::BigInt(base, exponent) => {
+        return match exponent.to_u32() {
+          Some(exponent) => Ok(Self::from_big_int(base.pow(exponent))),
+          // Negative powers of big integers are too small to matter
+          None if exponent < BigInt::zero() => Ok(Self::Float(big_f64(&base).powf(big_f64(&exponent)))),
+          None => Err(ValueError::Overflow),
+        }
+      },
+      Operands::Other(base, exponent) => (base, exponent),
+    };
+    match (base, exponent) {
       (Self::Int(lhs), Self::Int(rhs)) => {
         if rhs >= 0 {
           cast::u32(rhs)
@@ -953,6 +1165,7 @@ impl RantValue {
   pub fn abs(self) -> ValueResult<Self> {
     match self {
       Self::Int(i) => i.checked_abs().map(Self::Int).ok_or(ValueError::Overflow),
+      Self::BigInt(i) => Ok(Self::from_big_int(i.abs())),
       Self::Float(f) => Ok(Self::Float(f.abs())),
       _ => Ok(self)
     }
@@ -0,0 +1,102 @@
+/*
+  Arithmetic Tests
+
+  These are tests that verify integer overflow raises an arithmetic error by default,
+  and that integers are promoted to big integers instead when enabled.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::runtime::*;
+use common::{run, run_error, run_error_with, run_with};
+
+fn big_int_rant() -> Rant {
+  Rant::with_options(RantOptions {
+    enable_big_ints: true,
+    .. Default::default()
+  })
+}
+
+#[test]
+fn overflow_raises_arithmetic_error() {
+  assert!(matches!(run_error("[add: 9223372036854775807; 1]").error_type, RuntimeErrorType::ArithmeticError));
+  assert!(matches!(run_error("[sub: -9223372036854775807; 2]").error_type, RuntimeErrorType::ArithmeticError));
+  assert!(matches!(run_error("[mul: 4611686018427387904; 2]").error_type, RuntimeErrorType::ArithmeticError));
+  assert!(matches!(run_error("[pow: 2; 63]").error_type, RuntimeErrorType::ArithmeticError));
+  assert!(matches!(run_error("[sum: (9223372036854775807; 1)]").error_type, RuntimeErrorType::ArithmeticError));
+}
+
+#[test]
+fn overflow_error_can_be_caught() {
+  assert_eq!(run("[try: [?] {[mul: 9223372036854775807; 9223372036854775807]}; [?: e] {<e/id>}]"), "ARITHMETIC_ERROR");
+}
+
+#[test]
+fn in_range_arithmetic_is_unchanged() {
+  assert_eq!(run("[add: 9223372036854775806; 1]"), "9223372036854775807");
+  assert_eq!(run("[pow: 2; 62]"), "4611686018427387904");
+  assert_eq!(run("[add: 1; 0.5]"), "1.5");
+}
+
+#[test]
+fn big_int_promotion() {
+  assert_eq!(run_with(&mut big_int_rant(), "[add: 9223372036854775807; 1]"), "9223372036854775808");
+  assert_eq!(run_with(&mut big_int_rant(), "[mul: 9223372036854775807; 9223372036854775807]"), "85070591730234615847396907784232501249");
+  assert_eq!(run_with(&mut big_int_rant(), "[pow: 2; 100]"), "1267650600228229401496703205376");
+  assert_eq!(run_with(&mut big_int_rant(), "[sum: (9223372036854775807; 9223372036854775807; 2)]"), "18446744073709551616");
+  assert_eq!(run_with(&mut big_int_rant(), "[type: [pow: 10; 30]]"), "int");
+}
+
+#[test]
+fn big_ints_return_to_int_when_in_range() {
+  assert_eq!(run_with(&mut big_int_rant(), "<$n = [add: 9223372036854775807; 1]>[sub: <n>; 1]"), "9223372036854775807");
+  assert_eq!(run_with(&mut big_int_rant(), "<$n = [pow: 2; 64]>[div: <n>; [pow: 2; 60]]"), "16");
+  assert_eq!(run_with(&mut big_int_rant(), "<$n = [pow: 2; 64]>[eq: <n>; [mul: [pow: 2; 32]; [pow: 2; 32]]]"), "@true");
+}
+
+#[test]
+fn big_int_formatting() {
+  assert_eq!(run_with(&mut big_int_rant(), "[num-fmt: @(group-sep = \",\")]n:[pow: 10; 21]"), "n:1,000,000,000,000,000,000,000");
+  assert_eq!(run_with(&mut big_int_rant(), "[num-fmt: @(system = hex; alt = @true)]n:[pow: 2; 68]"), "n:0x100000000000000000");
+  assert_eq!(run_with(&mut big_int_rant(), "[num-fmt: @(system = hex; padding = 20)]n:[neg: [pow: 2; 64]]"), "n:ffff0000000000000000");
+  assert_eq!(run_with(&mut big_int_rant(), "[num-fmt: @(padding = 22)]n:[pow: 10; 20]"), "n:0100000000000000000000");
+  assert_eq!(run_with(&mut big_int_rant(), "<$n = [pow: 10; 20]>[num-fmt: @(system = east-arabic)]$\"<n>\""), format!("\u{0661}{}", "\u{0660}".repeat(20)));
+}
+
+#[test]
+fn big_int_parity() {
+  assert_eq!(run_with(&mut big_int_rant(), "[is-even: [pow: 2; 64]]"), "@true");
+  assert_eq!(run_with(&mut big_int_rant(), "[is-odd: [pow: 2; 64]]"), "@false");
+  assert_eq!(run_with(&mut big_int_rant(), "[is-odd: [add: [pow: 2; 64]; 1]]"), "@true");
+  assert_eq!(run_with(&mut big_int_rant(), "[is-factor: [pow: 2; 64]; [pow: 2; 63]]"), "@true");
+  assert_eq!(run_with(&mut big_int_rant(), "[is-factor: 8; [pow: 2; 64]]"), "@false");
+  assert_eq!(run("[is-factor: -9223372036854775808; -1]"), "@true");
+}
+
+#[test]
+fn big_int_comparison() {
+  assert_eq!(run_with(&mut big_int_rant(), "[gt: [pow: 2; 64]; 9223372036854775807]"), "@true");
+  assert_eq!(run_with(&mut big_int_rant(), "[lt: [neg: [pow: 2; 64]]; -9223372036854775808]"), "@true");
+  assert_eq!(run_with(&mut big_int_rant(), "[max: [pow: 2; 64]; 3]"), "18446744073709551616");
+  assert_eq!(run_with(&mut big_int_rant(), "[is-between: [pow: 2; 64]; 0; [pow: 2; 65]]"), "@true");
+}
+
+#[test]
+fn big_int_unsupported_args_raise_arithmetic_error() {
+  for (src, fname) in [("[range: 0; [pow: 2; 64]]", "range"), ("[irange: [pow: 2; 64]]", "irange"), ("[range: 0; 10; [pow: 2; 64]]", "range"), ("[rand: 0; [pow: 2; 64]]", "rand")] {
+    let err = run_error_with(&mut big_int_rant(), src);
+    assert!(matches!(err.error_type, RuntimeErrorType::ArithmeticError), "{}: {}", src, err);
+    assert!(err.description.as_deref().unwrap_or_default().starts_with(&format!("{}: ", fname)), "{}: {}", src, err);
+  }
+}
+
+#[test]
+fn output_concat_overflow() {
+  let src = "[$g]{9223372036854775807}[$h]{1}[$f]{[g][h]}[f]";
+  assert!(matches!(run_error(src).error_type, RuntimeErrorType::ArithmeticError));
+  assert!(matches!(run_error("[$g]{@true}[$h]{9223372036854775807}[$f]{[g][h]}[f]").error_type, RuntimeErrorType::ArithmeticError));
+  assert_eq!(run_with(&mut big_int_rant(), src), "9223372036854775808");
+  assert_eq!(run("[$g]{9223372036854775806}[$h]{1}[$f]{[g][h]}[f]"), "9223372036854775807");
+}
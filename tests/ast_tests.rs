@@ -0,0 +1,144 @@
+/*
+  AST Tests
+
+  These are tests that verify traversal and dumping of compiled syntax trees.
+
+*/
+
+mod common;
+
+use rant::{Rant, RantOptions};
+use rant::compiler::OptimizationLevel;
+use rant::lang::*;
+use common::compile_with;
+
+/// Creates a context that doesn't optimize programs, so that their syntax trees match the source.
+fn unoptimized_rant() -> Rant {
+  Rant::with_options(RantOptions { optimization_level: OptimizationLevel::None, .. Default::default() })
+}
+
+/// Creates a context that doesn't emit debug cursors, so that dumped syntax trees stay short.
+fn release_rant(optimization_level: OptimizationLevel) -> Rant {
+  Rant::with_options(RantOptions { debug_mode: false, optimization_level, .. Default::default() })
+}
+
+/// Collects the names of called functions and accessed variables.
+#[derive(Default)]
+struct NameCollector {
+  calls: Vec<String>,
+  accessors: Vec<String>,
+  definitions: Vec<String>,
+}
+
+impl RstVisitor for NameCollector {
+  fn visit_func_call(&mut self, call: &FunctionCall) {
+    if let FunctionCallTarget::Path(path) = &call.target {
+      self.calls.push(path.to_string());
+    }
+    walk_func_call(self, call);
+  }
+
+  fn visit_func_def(&mut self, def: &FunctionDef) {
+    self.definitions.push(def.path.to_string());
+    // Skip the path so it isn't counted as an accessor
+    for param in def.params.iter() {
+      self.visit_parameter(param);
+    }
+    self.visit_sequence(&def.body);
+  }
+
+  fn visit_access_path(&mut self, path: &AccessPath) {
+    self.accessors.push(path.to_string());
+    walk_access_path(self, path);
+  }
+
+  fn visit_var_def(&mut self, name: &Identifier, kind: AccessPathKind, is_const: bool, value: Option<&Sequence>) {
+    self.definitions.push(name.to_string());
+    walk_var_def(self, name, kind, is_const, value);
+  }
+}
+
+#[test]
+fn visit_nested_nodes() {
+  let program = compile_with(&mut unoptimized_rant(), "<$greeting = hello>\n[$shout: msg] { [upper: <msg>] }\n{[shout: <greeting>]|<greeting/0>}");
+  let mut collector = NameCollector::default();
+  collector.visit_sequence(program.root());
+  assert_eq!(collector.definitions, vec!["greeting", "shout"]);
+  assert_eq!(collector.calls, vec!["upper", "shout"]);
+  assert_eq!(collector.accessors, vec!["upper", "msg", "shout", "greeting", "greeting/0"]);
+}
+
+#[test]
+fn visit_literals() {
+  #[derive(Default)]
+  struct LiteralCollector(Vec<String>);
+
+  impl RstVisitor for LiteralCollector {
+    fn visit_literal(&mut self, literal: &Rst) {
+      self.0.push(match literal {
+        Rst::Fragment(s) | Rst::Whitespace(s) => s.to_string(),
+        Rst::Integer(n) => n.to_string(),
+        other => other.display_name().to_owned(),
+      });
+    }
+  }
+
+  let program = compile_with(&mut unoptimized_rant(), "(a; 1; @true) b c");
+  let mut collector = LiteralCollector::default();
+  collector.visit_sequence(program.root());
+  assert_eq!(collector.0, vec!["a", "1", "boolean", "b", " ", "c"]);
+}
+
+#[test]
+fn dump_sexpr() {
+  let program = compile_with(&mut release_rant(OptimizationLevel::default()), "{a|<x>}");
+  assert_eq!(dump_ast(program.root(), AstDumpFormat::SExpr), concat!(
+    "(program\n",
+    "  (block :flag \"hint\" :weighted false\n",
+    "    (element\n",
+    "      (sequence :name \"block element\"\n",
+    "        (fragment :value \"a\")))\n",
+    "    (element\n",
+    "      (sequence :name \"block element\"\n",
+    "        (get\n",
+    "          (path :path \"x\" :scope \"local\"))))))",
+  ));
+}
+
+#[test]
+fn dump_json() {
+  let program = compile_with(&mut release_rant(OptimizationLevel::default()), "[$f: a?] {q\\n}");
+  assert_eq!(dump_ast(program.root(), AstDumpFormat::Json), concat!(
+    r#"{"kind":"program","children":[{"kind":"func-def","const":false,"captures":"","children":["#,
+    r#"{"kind":"path","path":"f","scope":"local","children":[]},"#,
+    r#"{"kind":"param","name":"a","varity":"optional","children":[]},"#,
+    r#"{"kind":"sequence","name":"[f]","children":[{"kind":"fragment","value":"q\n","children":[]}]}]}]}"#,
+  ));
+}
+
+#[test]
+fn json_string_escapes() {
+  let mut buf = String::new();
+  write_json_string(&mut buf, "a\"b\\c\n\r\t\u{1}é").unwrap();
+  assert_eq!(buf, r#""a\"b\\c\n\r\t\u0001é""#);
+}
+
+#[test]
+fn dump_setter_and_set_literal() {
+  let program = compile_with(&mut release_rant(OptimizationLevel::None), "<x = 2>@{a; b}");
+  assert_eq!(dump_ast(program.root(), AstDumpFormat::SExpr), concat!(
+    "(program\n",
+    "  (set\n",
+    "    (path :path \"x\" :scope \"local\")\n",
+    "    (sequence :name \"setter value\"\n",
+    "      (integer :value 2)))\n",
+    "  (set-literal\n",
+    "    (sequence :name \"collection item\"\n",
+    "      (fragment :value \"a\"))\n",
+    "    (sequence\n",
+    "      (fragment :value \"b\"))))",
+  ));
+  let json = dump_ast(program.root(), AstDumpFormat::Json);
+  assert!(json.starts_with(r#"{"kind":"program","children":[{"kind":"set","children":["#));
+  assert!(json.contains(r#"{"kind":"set-literal","children":["#));
+}
@@ -0,0 +1,64 @@
+/*
+  Common Test Helpers
+
+  Functions shared by the integration tests for compiling and running programs,
+  either with the default options or with a context set up by the test.
+
+*/
+
+#![allow(dead_code)]
+
+use rant::*;
+use rant::compiler::*;
+use rant::runtime::*;
+
+/// Name given to programs compiled by the `*_with` helpers, as it appears in stack traces and reports.
+pub const PROGRAM_NAME: &str = "test";
+
+/// Compiles a program with an existing context.
+pub fn compile_with(rant: &mut Rant, src: &str) -> RantProgram {
+  rant.compile_quiet_named(src, PROGRAM_NAME).expect("failed to compile program")
+}
+
+/// Compiles and runs a program with an existing context and returns its output as a string.
+pub fn run_with(rant: &mut Rant, src: &str) -> String {
+  let program = compile_with(rant, src);
+  rant.run(&program).expect("failed to run program").to_string()
+}
+
+/// Compiles and runs a program that is expected to fail with an existing context and returns the runtime error.
+pub fn run_error_with(rant: &mut Rant, src: &str) -> RuntimeError {
+  let program = compile_with(rant, src);
+  rant.run(&program).expect_err("program should fail")
+}
+
+/// Compiles and runs a program and returns its output as a string.
+pub fn run(src: &str) -> String {
+  run_value(src).to_string()
+}
+
+/// Compiles and runs a program and returns its output value.
+pub fn run_value(src: &str) -> RantValue {
+  let mut rant = Rant::new();
+  let program = rant.compile_quiet(src).expect("failed to compile program");
+  rant.run(&program).expect("failed to run program")
+}
+
+/// Compiles and runs a program that is expected to fail and returns the runtime error.
+pub fn run_error(src: &str) -> RuntimeError {
+  let mut rant = Rant::new();
+  let program = rant.compile_quiet(src).expect("failed to compile program");
+  rant.run(&program).expect_err("program should fail")
+}
+
+/// Compiles a program and returns its messages as `(problem, source text)` pairs.
+pub fn compile_messages(src: &str) -> Vec<(Problem, String)> {
+  let mut messages: Vec<CompilerMessage> = vec![];
+  let _ = Rant::new().compile(src, &mut messages);
+  messages.into_iter()
+    .map(|msg| {
+      let text = msg.pos().map(|pos| src[pos.span()].to_owned()).unwrap_or_default();
+      (msg.consume().2, text)
+    })
+    .collect()
+}
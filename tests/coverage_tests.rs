@@ -0,0 +1,114 @@
+/*
+  Coverage Tests
+
+  These are tests that verify block element coverage is recorded and reported correctly.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::runtime::*;
+use rant::compiler::OptimizationLevel;
+use common::compile_with;
+
+#[test]
+fn coverage_counts_selections() {
+  let mut r = Rant::new();
+  let pgm = compile_with(&mut r, "[rep: 10]{a|b|c}");
+  let mut coverage = Coverage::new();
+  r.run_with_coverage(&pgm, &mut coverage).expect("failed to run program");
+  let blocks = coverage.blocks();
+  assert_eq!(blocks.len(), 1);
+  assert_eq!(blocks[0].entries, 1);
+  assert_eq!(blocks[0].elements.iter().map(|elem| elem.hits).sum::<u64>(), 10);
+}
+
+#[test]
+fn coverage_accumulates_across_runs() {
+  let mut r = Rant::new();
+  let pgm = compile_with(&mut r, "{a|b}");
+  let mut coverage = Coverage::new();
+  for _ in 0..5 {
+    r.run_with_coverage(&pgm, &mut coverage).expect("failed to run program");
+  }
+  let blocks = coverage.blocks();
+  assert_eq!(blocks[0].entries, 5);
+  assert_eq!(blocks[0].elements.iter().map(|elem| elem.hits).sum::<u64>(), 5);
+}
+
+#[test]
+fn coverage_includes_unreached_blocks() {
+  let mut r = Rant::new();
+  let pgm = compile_with(&mut r, "[$f] {{x|y}}\n[if: @false]{never|nope}");
+  let mut coverage = Coverage::new();
+  r.run_with_coverage(&pgm, &mut coverage).expect("failed to run program");
+  let blocks = coverage.blocks();
+  assert_eq!(blocks.len(), 2);
+  assert_eq!(blocks[0].entries, 0);
+  assert_eq!(blocks[1].entries, 1);
+  assert_eq!(coverage.covered_element_count(), 0);
+  assert_eq!(coverage.element_count(), 4);
+  assert_eq!(blocks[1].uncovered_elements().count(), 2);
+}
+
+#[test]
+fn coverage_keeps_removed_elements() {
+  let mut r = Rant::with_options(RantOptions {
+    optimization_level: OptimizationLevel::Full,
+    .. Default::default()
+  });
+  let pgm = compile_with(&mut r, "[rep: 10]{a|b @weight 0|c}");
+  let mut coverage = Coverage::new();
+  r.run_with_coverage(&pgm, &mut coverage).expect("failed to run program");
+  let blocks = coverage.blocks();
+  assert_eq!(blocks[0].elements.len(), 3);
+  assert_eq!(blocks[0].elements[1].hits, 0);
+  assert_eq!(blocks[0].elements.iter().map(|elem| elem.hits).sum::<u64>(), 10);
+}
+
+#[test]
+fn coverage_source_positions() {
+  let mut r = Rant::new();
+  let pgm = compile_with(&mut r, "x {\n  one\n| two\n}");
+  let mut coverage = Coverage::new();
+  coverage.add_program(&pgm);
+  let blocks = coverage.blocks();
+  assert_eq!((blocks[0].pos.line(), blocks[0].pos.col()), (1, 3));
+  assert_eq!(blocks[0].pos.span(), 2..17);
+  let elems: Vec<(usize, usize, std::ops::Range<usize>)> = blocks[0].elements.iter().map(|elem| (elem.pos.line(), elem.pos.col(), elem.pos.span())).collect();
+  assert_eq!(elems, vec![(2, 3, 6..9), (3, 3, 12..15)]);
+}
+
+#[test]
+fn coverage_lcov_report() {
+  let mut r = Rant::new();
+  let pgm = compile_with(&mut r, "{a}\n[if: @false]{b}");
+  let mut coverage = Coverage::new();
+  r.run_with_coverage(&pgm, &mut coverage).expect("failed to run program");
+  assert_eq!(coverage.lcov_report(), "\
+TN:
+SF:test
+BRDA:1,0,0,1
+BRDA:2,1,0,0
+BRF:2
+BRH:1
+DA:1,1
+DA:2,0
+LF:2
+LH:1
+end_of_record
+");
+}
+
+#[test]
+fn coverage_json_report() {
+  let mut r = Rant::new();
+  let pgm = compile_with(&mut r, "{a}");
+  let mut coverage = Coverage::new();
+  r.run_with_coverage(&pgm, &mut coverage).expect("failed to run program");
+  assert_eq!(
+    coverage.json_report(), 
+    r#"{"files":[{"path":"test","blocks":[{"line":1,"col":1,"start":0,"end":3,"entries":1,"elements":[{"index":0,"line":1,"col":2,"start":1,"end":2,"hits":1}]}]}]}"#
+  );
+}
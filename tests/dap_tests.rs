@@ -0,0 +1,215 @@
+/*
+  DAP Tests
+
+  These are tests that drive the rant-dap adapter through scripted debug sessions over stdio.
+
+*/
+
+#![cfg(feature = "dap")]
+
+#[path = "../src/tools/common/json.rs"]
+mod json;
+#[allow(dead_code)]
+#[path = "../src/tools/common/json_parse.rs"]
+mod json_parse;
+
+use json::Json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Time to wait for the adapter to send an expected message before failing.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A DAP client talking to a spawned adapter.
+struct Client {
+  child: Child,
+  stdin: ChildStdin,
+  messages: mpsc::Receiver<Json>,
+  seq: i64,
+  /// Text of all `output` events received so far, by category.
+  output: Vec<(String, String)>,
+}
+
+impl Client {
+  fn spawn() -> Self {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rant-dap"))
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()
+      .expect("failed to start rant-dap");
+
+    let stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+      loop {
+        let mut content_length = 0;
+        loop {
+          let mut line = String::new();
+          if stdout.read_line(&mut line).unwrap_or(0) == 0 {
+            return
+          }
+          let line = line.trim_end();
+          if line.is_empty() {
+            break
+          }
+          if let Some(len) = line.strip_prefix("Content-Length:") {
+            content_length = len.trim().parse().expect("invalid Content-Length");
+          }
+        }
+        let mut content = vec![0; content_length];
+        stdout.read_exact(&mut content).expect("truncated message");
+        let msg = Json::parse(std::str::from_utf8(&content).unwrap()).expect("adapter sent invalid JSON");
+        if sender.send(msg).is_err() {
+          return
+        }
+      }
+    });
+
+    Self {
+      child,
+      stdin,
+      messages: receiver,
+      seq: 0,
+      output: vec![],
+    }
+  }
+
+  fn send(&mut self, command: &str, arguments: Json) {
+    self.seq += 1;
+    let content = Json::object(vec![
+      ("seq", self.seq.into()),
+      ("type", "request".into()),
+      ("command", command.into()),
+      ("arguments", arguments),
+    ]).to_string();
+    write!(self.stdin, "Content-Length: {}\r\n\r\n{}", content.len(), content).unwrap();
+    self.stdin.flush().unwrap();
+  }
+
+  /// Reads messages until one matches the predicate, recording any output along the way.
+  fn wait_for(&mut self, what: &str, pred: impl Fn(&Json) -> bool) -> Json {
+    loop {
+      let msg = self.messages.recv_timeout(TIMEOUT).unwrap_or_else(|_| panic!("timed out waiting for {}", what));
+      if msg.get("event").and_then(Json::as_str) == Some("output") {
+        let body = msg.get("body").unwrap();
+        let category = body.get("category").and_then(Json::as_str).unwrap_or_default().to_owned();
+        let text = body.get("output").and_then(Json::as_str).unwrap_or_default().to_owned();
+        self.output.push((category, text));
+      }
+      if pred(&msg) {
+        return msg
+      }
+    }
+  }
+
+  /// Sends a request and returns the body of its successful response.
+  fn request(&mut self, command: &str, arguments: Json) -> Json {
+    self.send(command, arguments);
+    let seq = self.seq;
+    let response = self.wait_for(command, |msg| {
+      msg.get("type").and_then(Json::as_str) == Some("response") && msg.get("request_seq").and_then(Json::as_i64) == Some(seq)
+    });
+    assert_eq!(response.get("success"), Some(&Json::Bool(true)), "'{}' failed: {}", command, response);
+    response.get("body").cloned().unwrap_or(Json::Null)
+  }
+
+  /// Waits for an event and returns its body.
+  fn event(&mut self, event: &str) -> Json {
+    let msg = self.wait_for(event, |msg| msg.get("event").and_then(Json::as_str) == Some(event));
+    msg.get("body").cloned().unwrap_or(Json::Null)
+  }
+
+  fn stdout(&self) -> String {
+    self.output.iter().filter(|(category, _)| category == "stdout").map(|(_, text)| text.as_str()).collect()
+  }
+
+  fn finish(mut self) {
+    drop(self.stdin);
+    let status = self.child.wait().expect("failed to wait for rant-dap");
+    assert!(status.success());
+  }
+}
+
+/// Writes a program to a temporary file and returns its path.
+fn write_program(name: &str, src: &str) -> String {
+  let path: PathBuf = std::env::temp_dir().join(format!("rant-dap-{}-{}.rant", std::process::id(), name));
+  std::fs::write(&path, src).expect("failed to write test program");
+  path.to_string_lossy().into_owned()
+}
+
+fn no_args() -> Json {
+  Json::object(Vec::<(&str, Json)>::new())
+}
+
+#[test]
+fn dap_breakpoint_session() {
+  let program = write_program("breakpoint", "<$x = 1>\n<$y = [add: <x>; 1]>\n[seed]\n");
+  let mut client = Client::spawn();
+
+  let caps = client.request("initialize", Json::object(vec![("adapterID", "rant".into())]));
+  assert_eq!(caps.get("supportsConfigurationDoneRequest"), Some(&Json::Bool(true)));
+  client.event("initialized");
+
+  // Seeds above 2^53 can't be represented exactly as floats
+  client.request("launch", Json::object(vec![
+    ("program", program.as_str().into()),
+    ("seed", 9007199254740993u64.into()),
+  ]));
+  let bps = client.request("setBreakpoints", Json::object(vec![
+    ("source", Json::object(vec![("path", program.as_str().into())])),
+    ("breakpoints", vec![Json::object(vec![("line", 3i64.into())])].into()),
+  ]));
+  assert_eq!(bps.get("breakpoints").and_then(Json::as_array).map(|bps| bps.len()), Some(1));
+  client.request("configurationDone", no_args());
+
+  let stopped = client.event("stopped");
+  assert_eq!(stopped.get("reason").and_then(Json::as_str), Some("breakpoint"));
+
+  let trace = client.request("stackTrace", Json::object(vec![("threadId", 1i64.into())]));
+  let top = &trace.get("stackFrames").and_then(Json::as_array).unwrap()[0];
+  assert_eq!(top.get("line").and_then(Json::as_u64), Some(3));
+
+  let scopes = client.request("scopes", Json::object(vec![("frameId", 1i64.into())]));
+  let locals_ref = scopes.get("scopes").and_then(Json::as_array).unwrap()[0].get("variablesReference").cloned().unwrap();
+  let vars = client.request("variables", Json::object(vec![("variablesReference", locals_ref)]));
+  let names: Vec<&str> = vars.get("variables").and_then(Json::as_array).unwrap()
+    .iter()
+    .filter_map(|var| var.get("name").and_then(Json::as_str))
+    .collect();
+  assert_eq!(names, vec!["x", "y"]);
+
+  client.request("continue", Json::object(vec![("threadId", 1i64.into())]));
+  let exited = client.event("exited");
+  assert_eq!(exited.get("exitCode").and_then(Json::as_i64), Some(0));
+  client.event("terminated");
+  assert_eq!(client.stdout(), "9007199254740993\n");
+  client.finish();
+  let _ = std::fs::remove_file(program);
+}
+
+#[test]
+fn dap_pause_running_program() {
+  let program = write_program("pause", "[rep: forever] {x}\n");
+  let mut client = Client::spawn();
+
+  client.request("initialize", Json::object(vec![("adapterID", "rant".into())]));
+  client.request("launch", Json::object(vec![("program", program.as_str().into())]));
+  client.request("configurationDone", no_args());
+
+  client.request("pause", Json::object(vec![("threadId", 1i64.into())]));
+  let stopped = client.event("stopped");
+  assert_eq!(stopped.get("reason").and_then(Json::as_str), Some("pause"));
+
+  let trace = client.request("stackTrace", Json::object(vec![("threadId", 1i64.into())]));
+  let top = &trace.get("stackFrames").and_then(Json::as_array).unwrap()[0];
+  assert_eq!(top.get("line").and_then(Json::as_u64), Some(1));
+
+  client.request("disconnect", no_args());
+  client.finish();
+  let _ = std::fs::remove_file(program);
+}
@@ -0,0 +1,162 @@
+/*
+  Debugger Tests
+
+  These are tests that verify the VM pauses where a debugger asks it to.
+
+*/
+
+use rant::*;
+use rant::runtime::*;
+
+/// Replays a fixed list of actions and records every pause.
+struct ScriptedDebugger {
+  breakpoints: Vec<usize>,
+  start_action: DebugAction,
+  actions: Vec<DebugAction>,
+  pauses: Vec<(PauseReason, usize)>,
+  locals: Vec<Vec<String>>,
+  /// Number of polls after which the debugger requests a pause.
+  pause_after_polls: Option<usize>,
+}
+
+impl ScriptedDebugger {
+  fn new(start_action: DebugAction, breakpoints: &[usize], actions: &[DebugAction]) -> Self {
+    Self {
+      breakpoints: breakpoints.to_vec(),
+      start_action,
+      actions: actions.iter().rev().copied().collect(),
+      pauses: vec![],
+      locals: vec![],
+      pause_after_polls: None,
+    }
+  }
+}
+
+impl Debugger for ScriptedDebugger {
+  fn on_start(&mut self, vm: &mut VM) -> DebugAction {
+    for line in self.breakpoints.iter() {
+      vm.add_breakpoint(Breakpoint::new(*line));
+    }
+    self.start_action
+  }
+
+  fn on_pause(&mut self, vm: &mut VM, reason: PauseReason) -> DebugAction {
+    self.pauses.push((reason, vm.cur_frame().debug_pos().0));
+    let mut locals: Vec<String> = vm.call_stack().locals_at(0).map(|(name, _)| name.to_owned()).collect();
+    locals.sort();
+    self.locals.push(locals);
+    self.actions.pop().unwrap_or(DebugAction::Continue)
+  }
+
+  fn poll_pause(&mut self, _vm: &mut VM) -> bool {
+    match self.pause_after_polls.as_mut() {
+      Some(0) => {
+        self.pause_after_polls = None;
+        true
+      },
+      Some(n) => {
+        *n -= 1;
+        false
+      },
+      None => false,
+    }
+  }
+}
+
+fn run_debug(src: &str, debugger: &mut ScriptedDebugger) -> String {
+  let mut r = Rant::with_options(RantOptions {
+    debug_mode: true,
+    .. Default::default()
+  });
+  let pgm = r.compile_quiet(src).expect("failed to compile program");
+  r.run_debug(&pgm, debugger).expect("failed to run program").to_string()
+}
+
+const FUNC_SRC: &str = r#"<$x = 1>
+[$f: a] {
+  <$y = <a>>
+  <y>
+}
+[f: 5]
+<x>"#;
+
+#[test]
+fn debug_breakpoints() {
+  let mut dbg = ScriptedDebugger::new(DebugAction::Continue, &[3, 7], &[]);
+  run_debug(FUNC_SRC, &mut dbg);
+  assert_eq!(dbg.pauses, vec![(PauseReason::Breakpoint, 3), (PauseReason::Breakpoint, 7)]);
+}
+
+#[test]
+fn debug_stop_on_entry() {
+  let mut dbg = ScriptedDebugger::new(DebugAction::StepIn, &[], &[]);
+  run_debug(FUNC_SRC, &mut dbg);
+  assert_eq!(dbg.pauses, vec![(PauseReason::Entry, 1)]);
+}
+
+#[test]
+fn debug_step_over() {
+  use DebugAction::*;
+  let mut dbg = ScriptedDebugger::new(StepIn, &[], &[StepOver, StepOver, StepOver, StepOver]);
+  run_debug(FUNC_SRC, &mut dbg);
+  let lines: Vec<usize> = dbg.pauses.iter().map(|(_, line)| *line).collect();
+  assert_eq!(lines, vec![1, 2, 6, 7]);
+}
+
+#[test]
+fn debug_step_in_and_out() {
+  use DebugAction::*;
+  let mut dbg = ScriptedDebugger::new(Continue, &[6], &[StepIn, StepIn, StepOut]);
+  run_debug(FUNC_SRC, &mut dbg);
+  let lines: Vec<usize> = dbg.pauses.iter().map(|(_, line)| *line).collect();
+  assert_eq!(lines, vec![6, 3, 4, 7]);
+}
+
+#[test]
+fn debug_inspect_locals() {
+  let mut dbg = ScriptedDebugger::new(DebugAction::Continue, &[4], &[]);
+  run_debug(FUNC_SRC, &mut dbg);
+  assert_eq!(dbg.locals, vec![vec!["a".to_owned(), "y".to_owned()]]);
+}
+
+const LOOP_SRC: &str = r#"<$n = 0>
+[rep: 3] {
+  <n = [add: <n>; 1]>
+}
+<n>"#;
+
+#[test]
+fn debug_breakpoint_in_loop() {
+  let mut dbg = ScriptedDebugger::new(DebugAction::Continue, &[3], &[]);
+  assert_eq!(run_debug(LOOP_SRC, &mut dbg), "3");
+  assert_eq!(dbg.pauses, vec![(PauseReason::Breakpoint, 3); 3]);
+}
+
+const RECURSION_SRC: &str = r#"[$/f: n] {
+  <n>[if: [gt: <n>; 0]] { [/f: [sub: <n>; 1]] }
+}
+[f: 2]"#;
+
+#[test]
+fn debug_breakpoint_in_recursion() {
+  let mut dbg = ScriptedDebugger::new(DebugAction::Continue, &[2], &[]);
+  assert_eq!(run_debug(RECURSION_SRC, &mut dbg), "3");
+  assert_eq!(dbg.pauses, vec![(PauseReason::Breakpoint, 2); 3]);
+}
+
+#[test]
+fn debug_step_in_recursion() {
+  use DebugAction::*;
+  let mut dbg = ScriptedDebugger::new(Continue, &[4], &[StepIn, StepIn, StepIn, StepIn]);
+  run_debug(RECURSION_SRC, &mut dbg);
+  let lines: Vec<usize> = dbg.pauses.iter().map(|(_, line)| *line).collect();
+  assert_eq!(lines, vec![4, 2, 2, 2]);
+}
+
+#[test]
+fn debug_pause_request_mid_line() {
+  let mut dbg = ScriptedDebugger::new(DebugAction::Continue, &[], &[]);
+  dbg.pause_after_polls = Some(50);
+  assert_eq!(run_debug("[rep: 100] {x}", &mut dbg), "x".repeat(100));
+  assert_eq!(dbg.pauses, vec![(PauseReason::Pause, 1)]);
+}
@@ -0,0 +1,106 @@
+/*
+  Destructure Tests
+
+  These are tests that verify list and map patterns in variable definitions and function parameters.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::compiler::*;
+use rant::runtime::*;
+use common::{run, run_error, compile_messages};
+
+#[test]
+fn destructure_list() {
+  assert_eq!(run("<$(a; b; c) = (1; 2; 3)><a>,<b>,<c>"), "1,2,3");
+  assert_eq!(run("<$(a; *rest) = (1; 2; 3)><a>,[len: <rest>],<rest/0>"), "1,2,2");
+  assert_eq!(run("<$(a; *rest) = (1)><a>,[len: <rest>]"), "1,0");
+}
+
+#[test]
+fn destructure_map() {
+  assert_eq!(run("<$@(name; age) = @(name = Ann; age = 30)><name>,<age>"), "Ann,30");
+  assert_eq!(run("<$@(name = n) = @(name = Ann)><n>"), "Ann");
+}
+
+#[test]
+fn destructure_nested() {
+  let src = "<%@(name; pos = (x; y); tags = (first; *others)) = @(name = Ann; pos = (5; 6); tags = (a; b))><name>,<x>,<y>,<first>,<others/0>";
+  assert_eq!(run(src), "Ann,5,6,a,b");
+  let src = "<$(a; (b; c); @(d)) = (1; (2; 3); @(d = 4))><a>,<b>,<c>,<d>";
+  assert_eq!(run(src), "1,2,3,4");
+}
+
+#[test]
+fn destructure_fallbacks() {
+  assert_eq!(run("<$(a; b ? none) = (1)><a>,<b>"), "1,none");
+  assert_eq!(run("<$@(name; age ? 0) = @(name = Ann)><name>,<age>"), "Ann,0");
+}
+
+#[test]
+fn destructure_const() {
+  let messages = compile_messages("<%(a; b) = (1; 2)><a = 3><a><b>");
+  assert!(matches!(messages.as_slice(), [(Problem::ConstantReassignment(name), _)] if name == "a"));
+}
+
+#[test]
+fn destructure_chained_accessors() {
+  assert_eq!(run("<$(a; b) = (1; 2); $c = 3; a; $@(d) = @(d = 4)>,<b>,<c>,<d>"), "1,2,3,4");
+}
+
+#[test]
+fn destructure_shape_mismatch() {
+  assert!(matches!(run_error("<$(a; b) = (1)>").error_type, RuntimeErrorType::IndexError(_)));
+  assert!(matches!(run_error("<$(a) = 1>").error_type, RuntimeErrorType::IndexError(_)));
+  assert!(matches!(run_error("<$@(a) = @(b = 1)>").error_type, RuntimeErrorType::KeyError(_)));
+  assert!(matches!(run_error("<$@(a) = (1)>").error_type, RuntimeErrorType::KeyError(_)));
+}
+
+#[test]
+fn destructure_params() {
+  let src = "[$f: (a; b); @(x; y ? 1)] {<a>,<b>,<x>,<y>}\n[f: (1; 2); @(x = 3)]";
+  assert_eq!(run(src), "1,2,3,1");
+  let src = "[$g: first; (h; *t)] {<first>,<h>,[len: <t>]}\n[g: 0; (1; 2; 3)]";
+  assert_eq!(run(src), "0,1,2");
+  let src = "<$pairs = ((a; 1); (b; 2))>[cat: *[map: <pairs>; [?: (k; v)] {<k>=<v>}]]";
+  assert_eq!(run(src), "a=1b=2");
+}
+
+#[test]
+fn destructure_param_signature() {
+  let mut rant = Rant::new();
+  let program = rant.compile_quiet("[$f: (a; *rest); @(x; y = (z; w ? 0))] {}\n<f>").unwrap();
+  let func = rant.run(&program).unwrap();
+  let RantValue::Function(func) = func else { panic!("expected function") };
+  assert_eq!(func.signature("f"), "[$f: (a; *rest); @(x; y = (z; w ? ...))]");
+}
+
+#[test]
+fn duplicate_bindings() {
+  let messages = compile_messages("<$(a; (b; a)) = (1; (2; 3))><a><b>");
+  assert!(matches!(messages.as_slice(), [(Problem::DuplicateBinding(name), text)] if name == "a" && text == "a"));
+  let messages = compile_messages("[$f: a; (a)] {<a>}\n[f: 1; (2)]");
+  assert!(matches!(messages.as_slice(), [(Problem::DuplicateParameter(name), _)] if name == "a"));
+}
+
+#[test]
+fn destructure_param_order() {
+  let messages = compile_messages("[$f: x ? 1; (a)] {<x><a>}\n[f: 1; (2)]");
+  assert!(matches!(messages.as_slice(), [(Problem::InvalidParamOrder(..), text)] if text == "(a)"));
+}
+
+#[test]
+fn missing_assignment() {
+  let messages = compile_messages("<$(a; b) (1; 2)>");
+  assert!(matches!(messages.as_slice(), [(Problem::ExpectedToken(token), _)] if token == "="));
+}
+
+#[test]
+fn unused_bindings() {
+  let messages = compile_messages("<$(a; b) = (1; 2)><a>");
+  assert!(matches!(messages.as_slice(), [(Problem::UnusedVariable(name), text)] if name == "b" && text == "b"));
+  let messages = compile_messages("[$f: (a; b)] {<a>}\n[f: (1; 2)]");
+  assert!(matches!(messages.as_slice(), [(Problem::UnusedParameter(name), _)] if name == "b"));
+}
@@ -0,0 +1,89 @@
+/*
+  Error Tests
+
+  These are tests that verify errors can be raised with custom IDs and payloads, caught selectively by `[try]`, and rethrown.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::runtime::*;
+use common::{run, run_error};
+
+#[test]
+fn handler_receives_error_map() {
+  assert_eq!(run("[try: [?] {[error: oops]}; [?: e] {<e/id>,<e/message>,<e/payload>}]"), "USER_ERROR,oops,oops");
+  assert_eq!(run("<$m = @()>[try: [?] {<m/nope>}; [?: e] {<e/id>,[has: <e>; payload]}]"), "KEY_ERROR,@false");
+  assert_eq!(run("[try: [?] {[error]}; [?: e] {<e/message>}]"), "user error");
+}
+
+#[test]
+fn handler_receives_trace() {
+  let src = "[$fail] {[error: oops]}\n[try: [?] {[fail]}; [?: e] {<e/trace>}]";
+  assert!(run(src).contains("in [fail]"));
+}
+
+#[test]
+fn custom_error_id() {
+  let src = "[try: [?] {[error: @(entry = noun); MISSING_ENTRY]}; [?: e] {<e/id>,<e/payload/entry>}]";
+  assert_eq!(run(src), "MISSING_ENTRY,noun");
+  let err = run_error("[error: oops; MISSING_ENTRY]");
+  assert_eq!(err.error_type.id(), "MISSING_ENTRY");
+  assert!(matches!(&err.error_type, RuntimeErrorType::UserError(user_err) if matches!(user_err.payload, Some(RantValue::String(_)))));
+  assert_eq!(err.to_string(), "[MISSING_ENTRY] oops");
+}
+
+#[test]
+fn catch_by_id() {
+  let src = "<$m = @()>[try: [?] {[try: [?] {<m/nope>}; [?: e] {inner}; MISSING_ENTRY]}; [?: e] {outer <e/id>}]";
+  assert_eq!(run(src), "outer KEY_ERROR");
+  let src = "<$m = @()>[try: [?] {<m/nope>}; [?: e] {caught <e/id>}; (INDEX_ERROR; KEY_ERROR)]";
+  assert_eq!(run(src), "caught KEY_ERROR");
+  assert_eq!(run("[try: [?] {[error: oops; MISSING]}; @catch = MISSING]"), "");
+  assert_eq!(run_error("[try: [?] {[error: oops]}; @catch = MISSING]").error_type.id(), "USER_ERROR");
+}
+
+#[test]
+fn rethrow_error() {
+  let src = "[try: [?] {[try: [?] {[error: bad; CUSTOM]}; [?: e] {[rethrow: <e>]}]}; [?: e] {<e/id>,<e/message>,<e/payload>}]";
+  assert_eq!(run(src), "CUSTOM,bad,bad");
+  let err = run_error("<$m = @()>[try: [?] {<m/nope>}; [?: e] {[rethrow: <e>]}]");
+  assert_eq!(err.error_type.id(), "KEY_ERROR");
+  assert_eq!(err.to_string(), "[KEY_ERROR] key not found: 'nope'");
+  assert!(matches!(err.error_type, RuntimeErrorType::KeyError(KeyError::KeyNotFound(_))));
+  assert_eq!(run_error("[rethrow: @(message = x)]").error_type.id(), "ARG_ERROR");
+}
+
+#[test]
+fn rethrow_map_without_source() {
+  let err = run_error("[rethrow: @(id = USER_ERROR; message = x)]");
+  assert_eq!(err.error_type.id(), "USER_ERROR");
+  assert_eq!(err.to_string(), "[USER_ERROR] x");
+  let err = run_error("[try: [?] {[error: oops; CUSTOM]}; [?: e] {[rethrow: @(id = <e/id>; message = <e/message>)]}]");
+  assert_eq!(err.error_type.id(), "CUSTOM");
+}
+
+#[test]
+fn rethrow_keeps_original_trace() {
+  let src = "[$fail] {[error: oops]}\n[$handle: e] {[rethrow: <e>]}\n[try: [?] {[fail]}; [?: e] {[handle: <e>]}]";
+  let err = run_error(src);
+  let frames = err.stack_frames.as_deref().expect("error should have stack frames");
+  assert!(frames.iter().any(|frame| frame.name == "[fail]"));
+  assert!(frames.iter().all(|frame| frame.name != "[handle]"));
+  assert_eq!(err.stack_trace.as_deref(), Some(format_stack_trace(frames).as_str()));
+}
+
+#[test]
+fn uncaught_error_has_stack_frames() {
+  let src = "[$fail] {[error: oops; CUSTOM]}\n[try: [?] {[fail]}; [?: e] {}; OTHER]";
+  let err = run_error(src);
+  let frames = err.stack_frames.as_deref().expect("error should have stack frames");
+  assert!(frames.iter().any(|frame| frame.name == "[fail]"));
+  assert_eq!(err.stack_trace.as_deref(), Some(format_stack_trace(frames).as_str()));
+}
+
+#[test]
+fn invalid_catch_ids() {
+  assert_eq!(run_error("[try: [?] {}; @catch = @(a = 1)]").error_type.id(), "ARG_ERROR");
+}
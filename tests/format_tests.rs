@@ -0,0 +1,93 @@
+/*
+  Format Tests
+
+  These are tests that verify the output of compiler::format_source().
+
+*/
+
+mod common;
+
+use rant::compiler::*;
+use rant::lang::{dump_ast, AstDumpFormat};
+use rant::Rant;
+use common::{compile_with, run};
+
+/// Formats source code, checking that the formatted code compiles to the same syntax tree.
+fn format_quiet(src: &str) -> String {
+  let formatted = format_source(src, &mut ()).expect("failed to format source");
+  assert_eq!(tree(&formatted), tree(src), "formatting changed the syntax tree of {:?}", src);
+  formatted
+}
+
+fn tree(src: &str) -> String {
+  let program = compile_with(&mut Rant::new(), src);
+  dump_ast(program.root(), AstDumpFormat::SExpr)
+}
+
+#[test]
+fn indent_blocks() {
+  let src = "{\nfoo\n      |\n    bar\n        }";
+  assert_eq!(format_quiet(src), "{\n  foo\n  |\n  bar\n}");
+}
+
+#[test]
+fn indent_functions_and_collections() {
+  let src = "[$greet: name] {\nHello, <name>!\n    }\n<$items = (\n1; 2;\n@(\na = 3\n)\n)>";
+  assert_eq!(format_quiet(src), "[$greet: name] {\n  Hello, <name>!\n}\n<$items = (\n  1; 2;\n  @(\n    a = 3\n  )\n)>");
+}
+
+#[test]
+fn trim_trailing_whitespace() {
+  assert_eq!(format_quiet("{\n  x   \n   }\n"), "{\n  x\n}\n");
+}
+
+#[test]
+fn preserve_comments_and_strings() {
+  let src = "# comment\n{\n## block\n     comment ##\n\"line one\n      line two\"\n}";
+  assert_eq!(format_quiet(src), "# comment\n{\n  ## block\n     comment ##\n  \"line one\n      line two\"\n}");
+}
+
+#[test]
+fn preserve_printed_whitespace() {
+  let src = "[whitespace-fmt:verbatim]\n{\n    a   \n    b\n}";
+  let formatted = format_quiet(src);
+  assert_eq!(run(&formatted), run(src));
+}
+
+#[test]
+fn format_idempotent() {
+  let src = "[$f: x] {\n[if: <x>] {\n    yes\n} [else] {\n        no\n  }\n}\n[f: @true]";
+  let once = format_quiet(src);
+  assert_eq!(format_quiet(&once), once);
+  assert_eq!(run(&once), run(src));
+}
+
+#[test]
+fn format_syntax_error() {
+  let mut messages: Vec<CompilerMessage> = vec![];
+  assert!(format_source("{ unclosed", &mut messages).is_err());
+  assert!(messages.iter().any(|msg| msg.is_error()));
+}
+
+#[test]
+fn space_signatures() {
+  assert_eq!(format_quiet("[$f:a;b ?  2 ;c*]{<a>}"), "[$f: a; b ? 2; c*]{<a>}");
+  assert_eq!(format_quiet("[$g:  x ;y]{<x><y>}"), "[$g: x; y]{<x><y>}");
+}
+
+#[test]
+fn space_collections() {
+  assert_eq!(format_quiet("<$l = ( 1 ;2;  3 )>"), "<$l = (1; 2; 3)>");
+  assert_eq!(format_quiet("<$m = @( a=1 ;b  =  x y;)>"), "<$m = @(a = 1; b = x y;)>");
+  assert_eq!(format_quiet("<$s = @{ a;b }>"), "<$s = @{a; b}>");
+  assert_eq!(format_quiet("<$l = (a;;b)>"), "<$l = (a;; b)>");
+}
+
+#[test]
+fn keep_whitespace_after_line_comment() {
+  // Indentation after a line comment is a printed whitespace token, so adding some would change the output
+  let src = "x # comment\ny\n{\nz # comment\n w\n}";
+  let formatted = format_quiet(src);
+  assert_eq!(run(&formatted), run(src));
+  assert_eq!(formatted, "x # comment\ny\n{\n  z # comment\n w\n}");
+}
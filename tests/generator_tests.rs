@@ -0,0 +1,95 @@
+/*
+  Generator Tests
+
+  These are tests that verify generator functions keep their locals and position between yields and can be resumed until they finish.
+
+*/
+
+mod common;
+
+use rant::runtime::*;
+use common::{run, run_error};
+
+const COUNT_UP: &str = "[$count-up: n] {\n  <$i = 0>\n  [rep: <n>] {\n    <i = [add: <i>; 1]>\n    @yield <i>\n  }\n}\n";
+
+#[test]
+fn resume_generator() {
+  let src = format!("{}<$g = [gen: <count-up>; 2]>[resume: <g>],[resume: <g>],[is-done: <g>],[resume: <g>],[is-done: <g>],[resume: <g>]", COUNT_UP);
+  assert_eq!(run(&src), "1,2,@false,,@true,");
+}
+
+#[test]
+fn generator_is_iterator() {
+  assert_eq!(run(&format!("{}[type: [gen: <count-up>; 1]]", COUNT_UP)), "iterator");
+  assert_eq!(run(&format!("{}[join: [to-list: [gen: <count-up>; 4]]; ,]", COUNT_UP)), "1,2,3,4");
+  assert_eq!(run(&format!("{}[join: [to-list: [map: [gen: <count-up>; 3]; [?: x] {{[mul: <x>; 10]}}]]; ,]", COUNT_UP)), "10,20,30");
+}
+
+#[test]
+fn runs_lazily() {
+  let src = "<$calls = 0>\n[$g] {<calls = [add: <calls>; 1]>@yield a}\n<$s = [gen: <g>]><calls>,[resume: <s>],<calls>";
+  assert_eq!(run(src), "0,a,1");
+}
+
+#[test]
+fn code_after_yield_runs_on_resume() {
+  assert_eq!(run("[$g] {@yield a @yield b}\n<$s = [gen: <g>]>[resume: <s>],[resume: <s>],[is-done: <s>],[resume: <s>],[is-done: <s>]"), "a,b,@false,,@true");
+  assert_eq!(run("[$g] {{@yield} @yield x}\n<$s = [gen: <g>]>[type: [resume: <s>]],[resume: <s>]"), "empty,x");
+}
+
+#[test]
+fn return_finishes_generator() {
+  let src = "[$g] {@yield a {@return} @yield c}\n<$s = [gen: <g>]>[join: [to-list: <s>]; ,],[is-done: <s>]";
+  assert_eq!(run(src), "a,@true");
+}
+
+#[test]
+fn stateful_generator() {
+  // Numbers each rhyme in a scheme by how many times it has been used so far
+  let src = "[$rhymes: scheme] {\n  <$counts = @()>\n  [rep: [len: <scheme>]] {\n    <$letter = [cat: <scheme/{[step-index]}>]>\n    <counts/{<letter>} = [add: <counts/{<letter>} ? 0>; 1]>\n    @yield $\"<letter><counts/{<letter>}>\"\n  }\n}\n\
+    <$lines = [gen: <rhymes>; [to-list: abab]]>[resume: <lines>],[resume: <lines>],[resume: <lines>],[join: [to-list: <lines>]; ,]";
+  assert_eq!(run(src), "a1,b1,a2,b2");
+}
+
+#[test]
+fn infinite_generator() {
+  let src = "[$nat] {<$n = 0>[rep: forever] {<n = [add: <n>; 1]>@yield <n>}}\n\
+    <$g = [gen: <nat>]>[join: [to-list: [take: [filter: <g>; [?: x] {[is-even: <x>]}]; 3]]; ,],[resume: <g>]";
+  assert_eq!(run(src), "2,4,6,7");
+}
+
+#[test]
+fn nested_generators() {
+  let src = format!("{}[$pairs] {{\n  <$a = [gen: <count-up>; 2]>\n  <$b = [gen: <count-up>; 5]>\n  [rep: forever] {{\n    <$x = [resume: <a>]>\n    [if: [is-done: <a>]] {{@break}}\n    @yield $\"<x>:[resume: <b>]\"\n  }}\n}}\n[join: [to-list: [gen: <pairs>]]; ,]", COUNT_UP);
+  assert_eq!(run(&src), "1:1,2:2");
+}
+
+#[test]
+fn long_generator() {
+  assert_eq!(run("[$many] {[rep: 30000] {@yield [step]}}\n[len: [to-list: [gen: <many>]]]"), "30000");
+}
+
+#[test]
+fn yield_outside_generator() {
+  assert!(matches!(run_error("@yield 1").error_type, RuntimeErrorType::ControlFlowError));
+  assert!(matches!(run_error("[$f] {@yield 1}\n[f]").error_type, RuntimeErrorType::ControlFlowError));
+  // Functions called by a generator can't yield on its behalf
+  assert!(matches!(run_error("[$g] {[map: (1; 2); [?: x] {@yield <x>}]}\n[resume: [gen: <g>]]").error_type, RuntimeErrorType::ControlFlowError));
+}
+
+#[test]
+fn error_finishes_generator() {
+  let src = "[$g] {@yield a [error: oops] @yield b}\n<$s = [gen: <g>]>[resume: <s>],[try: [?] {[resume: <s>]}],[is-done: <s>],[resume: <s>]";
+  assert_eq!(run(src), "a,,@true,");
+}
+
+#[test]
+fn generator_already_running() {
+  assert!(matches!(run_error("<$s>[$g] {@yield [resume: <s>]}\n<s = [gen: <g>]>[resume: <s>]").error_type, RuntimeErrorType::ControlFlowError));
+}
+
+#[test]
+fn resume_requires_generator() {
+  assert!(matches!(run_error("[resume: [iter: (1)]]").error_type, RuntimeErrorType::ArgumentError));
+  assert_eq!(run("<$it = [iter: (1)]>[is-done: <it>],[iter-next: <it>],[iter-next: <it>],[is-done: <it>]"), "@false,1,,@true");
+}
@@ -0,0 +1,102 @@
+/*
+  Interpolation Tests
+
+  These are tests that verify interpolated string literals evaluate their embedded accessors and function calls into a single string.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::compiler::*;
+use rant::lang::{dump_ast, AstDumpFormat};
+use common::{run_value, compile_messages};
+
+#[test]
+fn interpolate_accessors() {
+  let src = r#"<$name = Ann><$pet = @(kind = cat)>$"<name> has a <pet/kind>.""#;
+  assert_eq!(run_value(src).to_string(), "Ann has a cat.");
+}
+
+#[test]
+fn interpolate_calls() {
+  let src = r#"<$items = (a; b; c)>$"[len: <items>] items: [join: <items>; ,\s]""#;
+  assert_eq!(run_value(src).to_string(), "3 items: a, b, c");
+}
+
+#[test]
+fn interpolate_keeps_whitespace() {
+  let src = r#"<$a = 1>$"  <a>  [add: <a>; 1]  ""#;
+  assert_eq!(run_value(src).to_string(), "  1  2  ");
+}
+
+#[test]
+fn interpolate_escapes() {
+  assert_eq!(run_value(r#"$"""quoted"" \<a> \[b] \\ \s""#).to_string(), r#""quoted" <a> [b] \  "#);
+}
+
+#[test]
+fn interpolate_empty_items() {
+  assert_eq!(run_value(r#"$"a<>b""#).to_string(), "ab");
+}
+
+#[test]
+fn interpolated_value_is_string() {
+  assert!(matches!(run_value(r#"<$n = 5>$"<n>""#), RantValue::String(s) if s.as_str() == "5"));
+  assert!(matches!(run_value(r#"$"plain""#), RantValue::String(s) if s.as_str() == "plain"));
+  assert_eq!(run_value(r#"<$x = (1; 2)>[type: $"<x>"]"#).to_string(), "string");
+}
+
+#[test]
+fn interpolate_uses_number_format() {
+  let src = r#"<$n = 4>[num-fmt: @(system = roman)]$"<n> and [add: <n>; 1]""#;
+  assert_eq!(run_value(src).to_string(), "iv and v");
+}
+
+#[test]
+fn interpolate_in_function() {
+  let src = "[$greet: who] {$\"Hi, <who>!\"}\n[greet: Bo]\\s[greet: Cy]";
+  assert_eq!(run_value(src).to_string(), "Hi, Bo! Hi, Cy!");
+}
+
+#[test]
+fn interpolated_string_dump() {
+  let program = Rant::new().compile_quiet(r#"<$x = 1>$"a <x> b""#).unwrap();
+  let dump = dump_ast(program.root(), AstDumpFormat::SExpr);
+  assert!(dump.contains("(interpolated-string :template \"a {...} b\""), "{}", dump);
+}
+
+#[test]
+fn unclosed_item() {
+  let messages = compile_messages(r#"<$a = 1>$"<a""#);
+  assert!(messages.iter().any(|(problem, _)| matches!(problem, Problem::UnclosedVariableAccess)));
+}
+
+#[test]
+fn unclosed_interpolated_string() {
+  let messages = compile_messages(r#"$"<a>"#);
+  assert!(matches!(messages.as_slice(), [(Problem::UnclosedStringLiteral, _)]));
+}
+
+#[test]
+fn interpolate_nested_strings() {
+  assert_eq!(run_value(r#"$"[cat: "a"; "b"]""#).to_string(), "ab");
+  assert_eq!(run_value(r#"$"[cat: "]"; "x "" y"]""#).to_string(), r#"]x " y"#);
+  assert_eq!(run_value(r#"<$a = 1>$"""q"" [cat: $"<a>!"; ">"]""#).to_string(), r#""q" 1!>"#);
+}
+
+#[test]
+fn unclosed_nested_string() {
+  let messages = compile_messages(r#"$"[cat: "a"#);
+  assert!(matches!(messages.as_slice(), [(Problem::UnclosedFunctionCall, _)]), "{:?}", messages);
+}
+
+#[test]
+fn no_definitions_in_items() {
+  let messages = compile_messages(r#"$"<$y = 3><y>""#);
+  assert!(matches!(messages.as_slice(), [(Problem::DefinitionInInterpolatedString(name), text), ..] if name == "y" && text == "$y = 3"), "{:?}", messages);
+  let messages = compile_messages(r#"$"[$f] {a}""#);
+  assert!(matches!(messages.as_slice(), [(Problem::DefinitionInInterpolatedString(name), _), ..] if name == "f"), "{:?}", messages);
+  assert_eq!(run_value(r#"<$/g = 1>$"<$/g = 2><g>""#).to_string(), "2");
+  assert_eq!(run_value(r#"$"[cat: {<$z = 1><z>}]""#).to_string(), "1");
+}
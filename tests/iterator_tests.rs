@@ -0,0 +1,75 @@
+/*
+  Iterator Tests
+
+  These are tests that verify iterators produce values lazily and that their adapters only read as many items as needed.
+
+*/
+
+mod common;
+
+use rant::runtime::*;
+use common::{run, run_error};
+
+#[test]
+fn iterate_values() {
+  assert_eq!(run("[join: [to-list: [iter: (a; b; c)]]; ,]"), "a,b,c");
+  assert_eq!(run("[join: [to-list: [iter: [range: 3]]]; ,]"), "0,1,2");
+  assert_eq!(run("[join: [to-list: [iter: abc]]; ,]"), "a,b,c");
+  assert_eq!(run("[join: [to-list: [iter: @(k = 1)]]; ,]"), "k");
+  assert!(matches!(run_error("[iter: 1]").error_type, RuntimeErrorType::ArgumentError));
+}
+
+#[test]
+fn iterator_type() {
+  assert_eq!(run("[type: [iter: (1)]]"), "iterator");
+  assert_eq!(run("@match [iter: (1)] { @iterator: yes | _: no }"), "yes");
+}
+
+#[test]
+fn next_item() {
+  assert_eq!(run("<$it = [iter: (a; b)]>[iter-next: <it>],[iter-next: <it>],[iter-next: <it>; done],[iter-next: <it>]"), "a,b,done,");
+}
+
+#[test]
+fn iter_func_is_lazy() {
+  let src = "<$n = 0>\n<$nat = [iter-func: [?] {<n = [add: <n>; 1]><n>}]>\n[join: [to-list: [take: <nat>; 3]]; ,],<n>";
+  assert_eq!(run(src), "1,2,3,3");
+  let src = "<$n = 0>\n<$it = [iter-func: [?] {[if: [lt: <n>; 2]]{<n = [add: <n>; 1]><n>}}]>\n[len: [to-list: <it>]]";
+  assert_eq!(run(src), "2");
+}
+
+#[test]
+fn lazy_adapters() {
+  let src = "<$n = 0>\n<$nat = [iter-func: [?] {<n = [add: <n>; 1]><n>}]>\n\
+    [join: [to-list: [take: [filter: [map: <nat>; [?: x] {[mul: <x>; <x>]}]; [?: x] {[is-odd: <x>]}]; 4]]; ,]";
+  assert_eq!(run(src), "1,9,25,49");
+  assert_eq!(run("<$calls = 0>[len: [to-list: [take: [map: [range: 100]; [?: x] {<calls = [add: <calls>; 1]>}]; 2]]],<calls>"), "2,2");
+}
+
+#[test]
+fn zip_and_chain() {
+  assert_eq!(run("[join: [to-list: [zip: [range: 1; 100]; (a; b; c); [?: n; s] {<s><n>}]]; ,]"), "a1,b2,c3");
+  assert_eq!(run("[join: [to-list: [chain: [range: 2]; (x; y); hi]]; ,]"), "0,1,x,y,h,i");
+}
+
+#[test]
+fn lists_stay_eager() {
+  assert_eq!(run("[type: [map: (1; 2); [?: x] {<x>}]]"), "list");
+  assert_eq!(run("[type: [map: [range: 2]; [?: x] {<x>}]]"), "iterator");
+}
+
+#[test]
+fn iterators_are_shared() {
+  assert_eq!(run("<$it = [iter: (1; 2; 3)]><$doubled = [map: <it>; [?: x] {[mul: <x>; 2]}]>[iter-next: <it>],[join: [to-list: <doubled>]; ,]"), "1,4,6");
+}
+
+#[test]
+fn long_iterators() {
+  assert_eq!(run("[len: [to-list: [filter: [range: 50000]; [?: x] {@false}]]]"), "0");
+  assert_eq!(run("[len: [to-list: [map: [range: 50000]; [?: x] {<x>}]]]"), "50000");
+}
+
+#[test]
+fn filter_requires_bool() {
+  assert!(matches!(run_error("[to-list: [filter: [range: 2]; [?: x] {<x>}]]").error_type, RuntimeErrorType::TypeError));
+}
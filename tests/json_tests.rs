@@ -0,0 +1,85 @@
+/*
+  JSON Tests
+
+  These are tests for the JSON value type shared by the Rant tools.
+
+*/
+
+#[path = "../src/tools/common/json.rs"]
+mod json;
+#[allow(dead_code)]
+#[path = "../src/tools/common/json_parse.rs"]
+mod json_parse;
+
+use json::Json;
+
+fn round_trip(src: &str) -> String {
+  Json::parse(src).expect("failed to parse JSON").to_string()
+}
+
+#[test]
+fn round_trip_literals() {
+  assert_eq!(round_trip("null"), "null");
+  assert_eq!(round_trip(" true "), "true");
+  assert_eq!(round_trip("false"), "false");
+}
+
+#[test]
+fn round_trip_collections() {
+  assert_eq!(round_trip(r#"{ "a": [1, 2, {}], "b": { "c": [] } }"#), r#"{"a":[1,2,{}],"b":{"c":[]}}"#);
+}
+
+#[test]
+fn round_trip_keeps_member_order() {
+  assert_eq!(round_trip(r#"{"z":1,"a":2,"m":3}"#), r#"{"z":1,"a":2,"m":3}"#);
+}
+
+#[test]
+fn round_trip_strings() {
+  assert_eq!(round_trip(r#""tab\there \"quoted\" \\ \u00e9 \ud83d\ude00""#), "\"tab\\there \\\"quoted\\\" \\\\ \u{e9} \u{1f600}\"");
+  assert_eq!(Json::parse(r#""\u0001""#).unwrap(), Json::String("\u{1}".to_owned()));
+  assert_eq!(round_trip(r#""\u0001""#), r#""\u0001""#);
+}
+
+#[test]
+fn round_trip_numbers() {
+  assert_eq!(round_trip("-42"), "-42");
+  assert_eq!(round_trip("1.5"), "1.5");
+  assert_eq!(round_trip("2e3"), "2000");
+}
+
+#[test]
+fn integers_are_exact() {
+  let max = Json::parse("18446744073709551615").unwrap();
+  assert_eq!(max.as_u64(), Some(u64::MAX));
+  assert_eq!(max.as_i64(), None);
+  assert_eq!(max.to_string(), "18446744073709551615");
+
+  let odd = Json::parse("9007199254740993").unwrap();
+  assert_eq!(odd.as_u64(), Some(9007199254740993));
+  assert_eq!(Json::from(u64::MAX).to_string(), "18446744073709551615");
+}
+
+#[test]
+fn float_integer_accessors() {
+  assert_eq!(Json::parse("3.0").unwrap().as_u64(), Some(3));
+  assert_eq!(Json::parse("3.5").unwrap().as_i64(), None);
+  assert_eq!(Json::parse("-3").unwrap().as_u64(), None);
+}
+
+#[test]
+fn object_accessors() {
+  let mut obj = Json::object(vec![("a", 1i64.into())]);
+  obj.set("a", "x".into());
+  obj.set("b", true.into());
+  assert_eq!(obj.get("a").and_then(Json::as_str), Some("x"));
+  assert_eq!(obj.get("b").and_then(Json::as_bool), Some(true));
+  assert_eq!(obj.to_string(), r#"{"a":"x","b":true}"#);
+}
+
+#[test]
+fn reject_invalid_json() {
+  for src in ["", "[1,", "{\"a\" 1}", "tru", "\"unterminated", "[1] 2", "{1: 2}", "\"\\q\""] {
+    assert!(Json::parse(src).is_err(), "accepted invalid JSON: {}", src);
+  }
+}
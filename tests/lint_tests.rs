@@ -0,0 +1,112 @@
+/*
+  Lint Tests
+
+  These are tests that verify warnings can be allowed with `#@allow(...)` comments and configured with `WarningConfig`.
+
+*/
+
+use rant::*;
+use rant::compiler::*;
+
+/// Compiles a program with a warning configuration and returns whether it compiled, along with the codes and lines of its messages.
+fn compile_with_config(src: &str, config: &WarningConfig) -> (bool, Vec<(&'static str, bool, usize)>) {
+  let mut messages: Vec<CompilerMessage> = vec![];
+  let result = Rant::new().compile(src, &mut config.reporter(&mut messages));
+  let messages = messages.iter()
+    .map(|msg| (msg.code(), msg.is_error(), msg.pos().map_or(0, |pos| pos.line())))
+    .collect();
+  (result.is_ok(), messages)
+}
+
+#[test]
+fn allow_next_line() {
+  let src = "#@allow(unused-function)\n[$a] {a}\n[$b] {b}";
+  let (ok, messages) = compile_with_config(src, &WarningConfig::new());
+  assert!(ok);
+  assert_eq!(messages, vec![("R1002", false, 3)]);
+}
+
+#[test]
+fn allow_same_line() {
+  let src = "[$a] {a} #@allow(R1002)\n[$b] {b}";
+  let (_, messages) = compile_with_config(src, &WarningConfig::new());
+  assert_eq!(messages, vec![("R1002", false, 2)]);
+}
+
+#[test]
+fn allow_stacked_comments() {
+  let src = "#@allow(unused-variable)\n# helper state\n#@allow(undefined-variable)\n\n<$a = <missing>>\n<$b = 1>";
+  let (_, messages) = compile_with_config(src, &WarningConfig::new());
+  assert_eq!(messages, vec![("R1000", false, 6)]);
+}
+
+#[test]
+fn allow_multiple_and_all() {
+  let (_, messages) = compile_with_config("<$a = <missing>> #@allow(unused-variable, R1006)", &WarningConfig::new());
+  assert!(messages.is_empty());
+  let (_, messages) = compile_with_config("<$a = <missing>> #@allow(warnings)", &WarningConfig::new());
+  assert!(messages.is_empty());
+}
+
+#[test]
+fn allow_does_not_apply_to_errors() {
+  let (ok, messages) = compile_with_config("<%a = 1> <a = 2> #@allow(warnings, constant-reassignment)", &WarningConfig::new());
+  assert!(!ok);
+  assert_eq!(messages, vec![("R0100", true, 1)]);
+}
+
+#[test]
+fn config_deny() {
+  let mut config = WarningConfig::new();
+  config.set("unused-function", WarningLevel::Deny);
+  let (ok, messages) = compile_with_config("[$a] {a}\n<$b = 1>", &config);
+  assert!(!ok);
+  assert_eq!(messages, vec![("R1002", true, 1), ("R1000", false, 2)]);
+}
+
+#[test]
+fn config_allow() {
+  let mut config = WarningConfig::new();
+  config.set("R1000", WarningLevel::Allow);
+  let (ok, messages) = compile_with_config("[$a] {a}\n<$b = 1>", &config);
+  assert!(ok);
+  assert_eq!(messages, vec![("R1002", false, 1)]);
+}
+
+#[test]
+fn config_specific_overrides_all() {
+  let mut config = WarningConfig::new();
+  config.set("unused-variable", WarningLevel::Warn);
+  config.set("warnings", WarningLevel::Deny);
+  let (ok, messages) = compile_with_config("[$a] {a}\n<$b = 1>", &config);
+  assert!(!ok);
+  assert_eq!(messages, vec![("R1002", true, 1), ("R1000", false, 2)]);
+}
+
+#[test]
+fn allow_comment_overrides_config() {
+  let mut config = WarningConfig::new();
+  config.set("warnings", WarningLevel::Deny);
+  let (ok, messages) = compile_with_config("#@allow(unused-function)\n[$a] {a}", &config);
+  assert!(ok);
+  assert!(messages.is_empty());
+}
+
+#[test]
+fn config_rejects_unknown_problems() {
+  let mut config = WarningConfig::new();
+  assert!(!config.set("unused-fuction", WarningLevel::Deny));
+  assert!(!config.set("R9999", WarningLevel::Allow));
+  assert!(config.set("R1002", WarningLevel::Deny));
+  assert!(config.set("constant-reassignment", WarningLevel::Allow));
+  assert!(config.set("warnings", WarningLevel::Warn));
+}
+
+#[test]
+fn allow_unknown_problem() {
+  let (ok, messages) = compile_with_config("[$a] {a} #@allow(unused-fuction)", &WarningConfig::new());
+  assert!(ok);
+  assert_eq!(messages, vec![("R1002", false, 1), ("R1014", false, 1)]);
+  let (_, messages) = compile_with_config("#@allow(unused-function, R1002, warnings)\n[$a] {a}", &WarningConfig::new());
+  assert!(messages.is_empty());
+}
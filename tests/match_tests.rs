@@ -0,0 +1,131 @@
+/*
+  Match Tests
+
+  These are tests that verify `@match` expressions select the right arm, bind sub-values, and report invalid or unreachable arms.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::compiler::*;
+use rant::lang::{dump_ast, AstDumpFormat};
+use common::{run, compile_messages};
+
+const DESCRIBE: &str = r#"
+[$describe: value] {
+  @match <value> {
+    0: zero
+  | 1..10 $n: small <n>
+  | @int: big
+  | "a b": text
+  | (): empty list
+  | ($first; *$rest): list of `[add: [len: <rest>]; 1] from <first>
+  | @(name = @string $name; age): person <name>
+  | @bool $b: bool
+  | <>: nothing
+  | _: other
+  }
+}
+"#;
+
+fn describe(arg: &str) -> String {
+  run(&format!("{}[describe: {}]", DESCRIBE, arg))
+}
+
+#[test]
+fn match_literals_and_types() {
+  assert_eq!(describe("0"), "zero");
+  assert_eq!(describe("10"), "big");
+  assert_eq!(describe("\"a b\""), "text");
+  assert_eq!(describe("@true"), "bool");
+  assert_eq!(describe("<>"), "nothing");
+  assert_eq!(describe("0.0"), "other");
+}
+
+#[test]
+fn match_ranges() {
+  assert_eq!(describe("1"), "small 1");
+  assert_eq!(describe("9"), "small 9");
+  assert_eq!(run("@match 3 {5..1: down | _: other}"), "down");
+  assert_eq!(run("@match 1 {5..1: down | _: other}"), "other");
+}
+
+#[test]
+fn match_lists() {
+  assert_eq!(describe("()"), "empty list");
+  assert_eq!(describe("(a; b; c)"), "list of 3 from a");
+  assert_eq!(run("@match (1; 2) {(_): one | (_; _): two | (_; _; *): more | _: other}"), "two");
+  assert_eq!(run("@match (1; (2; 3)) {(1; ($a; $b)): <a>,<b> | _: other}"), "2,3");
+}
+
+#[test]
+fn match_maps() {
+  assert_eq!(describe("@(name = Ann; age = 30; city = Paris)"), "person Ann");
+  assert_eq!(describe("@(name = Ann)"), "other");
+  assert_eq!(describe("@(name = 1; age = 30)"), "other");
+}
+
+#[test]
+fn match_first_arm_wins() {
+  assert_eq!(run("@match 5 {@int: int | 5: five | _: other}"), "int");
+}
+
+#[test]
+fn match_bindings_are_local() {
+  assert_eq!(run("<$x = outer>@match 1 {$x: <x>}\\s<x>"), "1 outer");
+}
+
+#[test]
+fn match_in_recursive_function() {
+  let src = "[$/count: n] {@match <n> {0: done | $k: <k>,[/count: [sub: <k>; 1]]}}\n[count: 3]";
+  assert_eq!(run(src), "3,2,1,done");
+}
+
+#[test]
+fn match_dump() {
+  let program = Rant::new().compile_quiet("@match <> {(1; *$r): a | _: b}").unwrap();
+  let dump = dump_ast(program.root(), AstDumpFormat::SExpr);
+  assert!(dump.contains("(arm :pattern \"(1; *$r)\""), "{}", dump);
+  assert!(dump.contains("(arm :pattern \"_\""), "{}", dump);
+}
+
+#[test]
+fn missing_fallback() {
+  let messages = compile_messages("@match 1 {1: a | 2: b}");
+  assert!(matches!(messages.as_slice(), [(Problem::MissingMatchFallback, _)]));
+}
+
+#[test]
+fn duplicate_binding() {
+  let messages = compile_messages("@match (1; 2) {($x; $x): <x> | _: b}");
+  assert!(matches!(messages.as_slice(), [(Problem::DuplicateBinding(name), text)] if name == "x" && text == "$x"));
+}
+
+#[test]
+fn invalid_pattern() {
+  let messages = compile_messages("@match 1 {[f]: a | _: b}");
+  assert!(matches!(messages.as_slice(), [(Problem::InvalidPattern(token), _)] if token == "["));
+}
+
+#[test]
+fn unclosed_match() {
+  let messages = compile_messages("@match 1 {1: a | _: b");
+  assert!(matches!(messages.as_slice(), [(Problem::UnclosedMatch, _)]));
+}
+
+#[test]
+fn unreachable_arms() {
+  let src = "@match 4 {@int: a | 1..5: b | 3: c | (1; *): d | (1; 2): e | \"x\": f | $y: <y> | _: g}";
+  let unreachable = compile_messages(src).into_iter()
+    .filter(|(problem, _)| matches!(problem, Problem::UnreachableMatchArm))
+    .map(|(_, text)| text)
+    .collect::<Vec<_>>();
+  assert_eq!(unreachable, vec!["1..5", "3", "(1; 2)", "_"]);
+}
+
+#[test]
+fn reachable_arms() {
+  let src = "@match 4 {1..3: a | 0..5: b | 2.0: c | 7: d | (1; 2): e | (1; *): f | @(a; b): g | @(a): h | _: i}";
+  assert!(compile_messages(src).is_empty());
+}
@@ -0,0 +1,118 @@
+/*
+  Named Argument Tests
+
+  These are tests that verify named arguments (`@name = value`) bind to the right parameters, leave plain text
+  arguments containing `=` alone, and report invalid names and orderings.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::compiler::*;
+use rant::runtime::*;
+use rant::lang::{dump_ast, AstDumpFormat};
+use common::{run, run_error, compile_messages};
+
+const F: &str = "[$f: a; b ? 2; c ? 3] {<a>,<b>,<c>}\n";
+
+#[test]
+fn named_user_args() {
+  assert_eq!(run(&format!("{}[f: 1; @b = 5; @c = 6]", F)), "1,5,6");
+  assert_eq!(run(&format!("{}[f: @c = 6; @a = 1]", F)), "1,2,6");
+  assert_eq!(run(&format!("{}[f: @a=1]", F)), "1,2,3");
+}
+
+#[test]
+fn skipped_optional_args() {
+  assert_eq!(run(&format!("{}[f: 1; @c = 9]", F)), "1,2,9");
+  assert_eq!(run("[$g: a; b?; c?] {<a>,<b ? none>,<c>}\n[g: 1; @c = 3]"), "1,none,3");
+}
+
+#[test]
+fn named_native_args() {
+  let src = r#"[oxford-join: @list = (a; b; c); @comma = ", "; @conj = " or "; @comma-conj = ", or "]"#;
+  assert_eq!(run(src), "a, b, or c");
+  assert_eq!(run("[join: @sep = -; @list = (x; y)]"), "x-y");
+  assert_eq!(run("[join: [to-list: [range: 0; @step = 2; @b = 7]]; ,]"), "0,2,4,6");
+}
+
+#[test]
+fn named_args_with_spread() {
+  assert_eq!(run(&format!("{}[f: *(1; 2); @c = 4]", F)), "1,2,4");
+  assert_eq!(run(&format!("{}[f: **(1; 2); @c = 4]", F)), "1,2,42,2,4");
+}
+
+#[test]
+fn named_args_in_pipe() {
+  assert_eq!(run("[range: 1; @step = 2; @b = 7 |> to-list |> join: []; @sep = ,]"), "1,3,5");
+}
+
+#[test]
+fn named_arg_errors() {
+  assert!(matches!(run_error(&format!("{}<$h = <f>>[h: 1; @a = 1]", F)).error_type, RuntimeErrorType::ArgumentMismatch));
+  assert!(matches!(run_error(&format!("{}<$h = <f>>[h: @b = 1]", F)).error_type, RuntimeErrorType::ArgumentMismatch));
+  assert!(matches!(run_error(&format!("{}<$h = <f>>[h: @d = 1]", F)).error_type, RuntimeErrorType::ArgumentMismatch));
+  assert!(matches!(run_error("[$v: a; rest*] {<a>}\n<$h = <v>>[h: 1; @rest = 2]").error_type, RuntimeErrorType::ArgumentMismatch));
+}
+
+#[test]
+fn named_arg_dump() {
+  let program = Rant::new().compile_quiet("[join: (1; 2); @sep = -]").unwrap();
+  let dump = dump_ast(program.root(), AstDumpFormat::SExpr);
+  assert!(dump.contains("(arg :spread \"none\" :name \"sep\""), "{}", dump);
+}
+
+#[test]
+fn duplicate_named_arg() {
+  let messages = compile_messages(&format!("{}[f: @a = 1; @a = 2]", F));
+  assert!(matches!(messages.as_slice(), [(Problem::DuplicateNamedArgument(name), text)] if name == "a" && text == "@a"));
+}
+
+#[test]
+fn positional_after_named() {
+  let messages = compile_messages(&format!("{}[f: @a = 1; 2 ]", F));
+  assert!(matches!(messages.as_slice(), [(Problem::PositionalArgumentAfterNamed, text)] if text == "2"));
+}
+
+#[test]
+fn equals_in_text_args() {
+  assert_eq!(run("[$f: x] {<x>}\n[f: x = y = z]"), "x = y = z");
+  assert_eq!(run("[cat: x = y]"), "x = y");
+  assert_eq!(run(&format!("{}[f: 1; d = 4]", F)), "1,d = 4,3");
+  assert_eq!(run("[join: (a; b); x = y]"), "ax = yb");
+}
+
+#[test]
+fn equals_in_text_args_before_positional() {
+  let src = "[$g: a; b; c] {<a>,<b>,<c>}\n[g: a = 1; 2; 3]";
+  assert!(compile_messages(src).is_empty());
+  assert_eq!(run(src), "a = 1,2,3");
+  assert!(compile_messages(&format!("{}[f: b = 1; 2; 3]", F)).is_empty());
+}
+
+#[test]
+fn keywords_are_not_names() {
+  assert!(compile_messages("[cat: @true = 1]").is_empty());
+  assert_eq!(run("[cat: @true = 1]"), "@true= 1");
+}
+
+#[test]
+fn unknown_named_arg() {
+  let messages = compile_messages(&format!("{}[f: 1; @d = 4]", F));
+  assert!(matches!(messages.as_slice(), [(Problem::UnknownNamedArgument(fname, name), text)] if fname == "f" && name == "d" && text == "@d"));
+  let messages = compile_messages("[num-fmt: @system = roman]");
+  assert!(matches!(messages.as_slice(), [(Problem::UnknownNamedArgument(fname, name), _)] if fname == "num-fmt" && name == "system"));
+  let messages = compile_messages("[cat: @x = 1]");
+  assert!(matches!(messages.as_slice(), [(Problem::UnknownNamedArgument(fname, name), _)] if fname == "cat" && name == "x"));
+}
+
+#[test]
+fn invalid_named_args() {
+  let messages = compile_messages(&format!("{}[f: 1; @a = 2]", F));
+  assert!(matches!(messages.as_slice(), [(Problem::NamedArgumentAlreadyPassed(name), _)] if name == "a"));
+  let messages = compile_messages("[$v: a; rest*] {<a><rest>}\n[v: 1; @rest = 2]");
+  assert!(matches!(messages.as_slice(), [(Problem::VariadicNamedArgument(name), _)] if name == "rest"));
+  let messages = compile_messages(&format!("{}[f: @c = 1; @b = 2]", F));
+  assert!(matches!(messages.as_slice(), [(Problem::MissingArgument(fname, name), text)] if fname == "f" && name == "a" && text == "f"));
+}
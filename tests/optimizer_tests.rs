@@ -0,0 +1,171 @@
+/*
+  Optimizer Tests
+
+  These are tests that verify the optimization levels simplify programs without changing their output.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::compiler::*;
+use rant::lang::*;
+use common::{compile_with, run_with};
+
+fn rant_with_level(opt_level: OptimizationLevel) -> Rant {
+  Rant::with_options(RantOptions {
+    optimization_level: opt_level,
+    .. Default::default()
+  })
+}
+
+/// Compiles and runs a program at every optimization level and checks that the outputs match.
+fn run_all_levels(src: &str) -> String {
+  let outputs = [OptimizationLevel::None, OptimizationLevel::Basic, OptimizationLevel::Full].iter()
+    .map(|level| run_with(&mut rant_with_level(*level), src))
+    .collect::<Vec<_>>();
+  assert!(outputs.iter().all(|output| output == &outputs[0]), "outputs differ between levels: {:?}", outputs);
+  outputs[0].clone()
+}
+
+/// Counts the nodes of each kind in an optimized program.
+#[derive(Default)]
+struct NodeCounter {
+  blocks: Vec<usize>,
+  calls: usize,
+  collections: usize,
+  fragments: usize,
+}
+
+impl RstVisitor for NodeCounter {
+  fn visit_block(&mut self, block: &Block) {
+    self.blocks.push(block.len());
+    walk_block(self, block);
+  }
+
+  fn visit_func_call(&mut self, call: &FunctionCall) {
+    self.calls += 1;
+    walk_func_call(self, call);
+  }
+
+  fn visit_literal(&mut self, literal: &Rst) {
+    match literal {
+      Rst::ConstCollection(_) => self.collections += 1,
+      Rst::Fragment(_) => self.fragments += 1,
+      _ => {},
+    }
+  }
+}
+
+fn count_nodes(src: &str, opt_level: OptimizationLevel) -> NodeCounter {
+  let program = compile_with(&mut rant_with_level(opt_level), src);
+  let mut counter = NodeCounter::default();
+  counter.visit_sequence(program.root());
+  counter
+}
+
+#[test]
+fn merge_adjacent_fragments() {
+  let src = r#"foo"bar"baz"#;
+  assert_eq!(count_nodes(src, OptimizationLevel::None).fragments, 3);
+  assert_eq!(count_nodes(src, OptimizationLevel::Basic).fragments, 1);
+  assert_eq!(run_all_levels(src), "foobarbaz");
+}
+
+#[test]
+fn precompute_constant_collections() {
+  let src = "<$list = (1; 2.5; foo; (@true; <>); @(a = 1))><$map = @(name = rant; tags = (a; b))>[len: <list>],[len: <map>]";
+  assert_eq!(count_nodes(src, OptimizationLevel::None).collections, 0);
+  assert_eq!(count_nodes(src, OptimizationLevel::Basic).collections, 2);
+  assert_eq!(run_all_levels(src), "5,2");
+}
+
+#[test]
+fn keep_dynamic_collections() {
+  let src = "<$x = 1><$list = (1; <x>)><$map = @(a = 1; {<x>} = 2)>";
+  assert_eq!(count_nodes(src, OptimizationLevel::Basic).collections, 0);
+}
+
+#[test]
+fn constant_collections_are_copied() {
+  let src = r#"[rep: 3][sep: \s]{<$list = (1; (2))>[push: <list>; 3][push: <list/1>; 4][len: <list>],[len: <list/1>]}"#;
+  assert_eq!(run_all_levels(src), "3,2 3,2 3,2");
+}
+
+#[test]
+fn constant_collection_handles_are_shared() {
+  let src = r#"[rep: 2][sep: \s]{<$list = (1; 2)><$alias = <list>>[push: <list>; 3]<$map = @(a = (1))><$inner = <map/a>>[push: <inner>; 2]<$set = @{1; 2}>[push: <set>; 3][len: <alias>],[len: <map/a>],[len: <set>]}"#;
+  assert_eq!(run_all_levels(src), "3,2,3 3,2,3");
+}
+
+#[test]
+fn fold_constant_arithmetic() {
+  let src = "[add: 1; [mul: 2; [neg: 3]]],[div: 7; 2.0],[mod: 7; 4],[sub: 1; 1]";
+  assert_eq!(count_nodes(src, OptimizationLevel::Basic).calls, 6);
+  assert_eq!(count_nodes(src, OptimizationLevel::Full).calls, 0);
+  assert_eq!(run_all_levels(src), "-5,3.5,3,0");
+}
+
+#[test]
+fn keep_unfoldable_arithmetic() {
+  // Redefined functions, non-constant arguments, and errors are left for the runtime
+  assert_eq!(count_nodes("[$add: a; b] {<a><b>}\n[add: 1; 2]", OptimizationLevel::Full).calls, 1);
+  assert_eq!(count_nodes("<$x = 1>[add: <x>; 2][add: *(1; 2)]", OptimizationLevel::Full).calls, 2);
+  assert_eq!(count_nodes("[div: 1; 0]", OptimizationLevel::Full).calls, 1);
+  assert_eq!(count_nodes("[add: 1; 2]", OptimizationLevel::Full).calls, 0);
+
+  let rant = Rant::with_options(RantOptions {
+    use_stdlib: false,
+    optimization_level: OptimizationLevel::Full,
+    .. Default::default()
+  });
+  let program = rant.compile_quiet("[add: 1; 2]").unwrap();
+  let mut counter = NodeCounter::default();
+  counter.visit_sequence(program.root());
+  assert_eq!(counter.calls, 1);
+}
+
+#[test]
+fn remove_zero_weight_elements() {
+  let src = "{a @weight 0|b|c @weight 0}";
+  assert_eq!(count_nodes(src, OptimizationLevel::Basic).blocks, vec![3]);
+  assert_eq!(count_nodes(src, OptimizationLevel::Full).blocks, vec![1]);
+  assert_eq!(run_all_levels(src), "b");
+
+  // All-zero blocks print nothing, so they're left alone
+  assert_eq!(count_nodes("{a @weight 0|b @weight 0}", OptimizationLevel::Full).blocks, vec![2]);
+}
+
+#[test]
+fn keep_zero_weight_elements_with_attributes() {
+  let src = "[rep: all][sep: ,]{a @weight 0|b}";
+  assert_eq!(count_nodes(src, OptimizationLevel::Full).blocks, vec![2]);
+  assert_eq!(run_all_levels(src), "b,b");
+}
+
+#[test]
+fn inline_non_printing_blocks() {
+  let src = "<$x = 1>{<x = 2>}~{<$/y = 3>}<x>,<y>";
+  assert!(count_nodes(src, OptimizationLevel::Full).blocks.is_empty());
+  assert_eq!(run_all_levels(src), "2,3");
+}
+
+#[test]
+fn keep_blocks_that_need_their_scope() {
+  assert_eq!(count_nodes("{<$x = 1>}", OptimizationLevel::Full).blocks, vec![1]);
+  assert_eq!(count_nodes("<$x = 1>{<^x = 2>}", OptimizationLevel::Full).blocks, vec![1]);
+  assert_eq!(count_nodes("<$x = 1>{<x = [step]>}", OptimizationLevel::Full).blocks, vec![1]);
+  assert_eq!(count_nodes("<$x = 1>{<x = 2>a}", OptimizationLevel::Full).blocks, vec![1]);
+
+  // The block after the call can have attributes
+  let src = "<$x = 0>[rep: 3]{<x = [add: <x>; 1]>}<x>";
+  assert_eq!(count_nodes(src, OptimizationLevel::Full).blocks, vec![1]);
+  assert_eq!(run_all_levels(src), "3");
+}
+
+#[test]
+fn conditionals_unchanged() {
+  let src = "<$x = 0>[if: @true]{<x = 1>}[else]{<x = 2>}<x>";
+  assert_eq!(count_nodes(src, OptimizationLevel::Full).blocks, vec![1, 1]);
+  assert_eq!(run_all_levels(src), "1");
+}
@@ -0,0 +1,74 @@
+/*
+  Profiler Tests
+
+  These are tests that verify the profiler attributes calls to the right functions.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::runtime::*;
+use common::compile_with;
+
+fn run_profiled(src: &str) -> Profiler {
+  let mut r = Rant::new();
+  let pgm = compile_with(&mut r, src);
+  let mut profiler = Profiler::new();
+  r.run_profiled(&pgm, &mut profiler).expect("failed to run program");
+  profiler
+}
+
+fn call_count(profiler: &Profiler, name: &str) -> Option<u64> {
+  profiler.functions().iter().find(|f| f.key.name == name).map(|f| f.calls)
+}
+
+#[test]
+fn profile_call_counts() {
+  let profiler = run_profiled("[$g: n] { [add: <n>; 1] } [$f] { [g: 1][g: 2] } [rep: 3] {[f]}");
+  assert_eq!(call_count(&profiler, "main scope"), Some(1));
+  assert_eq!(call_count(&profiler, "[f]"), Some(3));
+  assert_eq!(call_count(&profiler, "[g]"), Some(6));
+  assert_eq!(call_count(&profiler, "[add]"), Some(6));
+  assert_eq!(call_count(&profiler, "[rep]"), Some(1));
+}
+
+#[test]
+fn profile_origins() {
+  let profiler = run_profiled("[$f] { [add: 1; 2] } [f]");
+  let functions = profiler.functions();
+  let f = functions.iter().find(|f| f.key.name == "[f]").unwrap();
+  assert_eq!(f.key.origin.as_deref(), Some("test"));
+  let add = functions.iter().find(|f| f.key.name == "[add]").unwrap();
+  assert_eq!(add.key.origin, None);
+}
+
+#[test]
+fn profile_inclusive_covers_exclusive() {
+  let profiler = run_profiled("[$f] { [add: 1; 2] } [rep: 10] {[f]}");
+  for f in profiler.functions() {
+    assert!(f.inclusive_time >= f.exclusive_time, "{} has less inclusive than exclusive time", f.key.name);
+  }
+}
+
+#[test]
+fn profile_folded_stacks() {
+  let profiler = run_profiled("[$f] { [add: 1; 2] } [f]");
+  let folded = profiler.folded_stacks();
+  let stacks: Vec<&str> = folded
+    .lines()
+    .map(|line| line.rsplit_once(' ').unwrap().0)
+    .collect();
+  assert_eq!(stacks, vec![
+    "main scope (test)",
+    "main scope (test);[f] (test)",
+    "main scope (test);[f] (test);[add]",
+  ]);
+}
+
+#[test]
+fn profile_unwound_calls() {
+  let profiler = run_profiled("[$f] { [error: oops] } [try: <f>; [?: err] { }]");
+  assert_eq!(call_count(&profiler, "[f]"), Some(1));
+  assert_eq!(call_count(&profiler, "[error]"), Some(1));
+}
@@ -741,4 +741,80 @@ fn range_forward_step_indivisible() {
 #[test]
 fn range_reverse_step_indivisible() {
   test_rant_file!("sources/range/range_reverse_step_indivisible.rant");
-}
\ No newline at end of file
+}
+
+#[test]
+fn retry_accept() {
+  test_rant_file!("sources/retry/retry_accept.rant");
+}
+
+#[test]
+fn retry_deterministic() {
+  test_rant_file!("sources/retry/retry_deterministic.rant");
+}
+
+#[test]
+fn retry_error_restores_rng() {
+  test_rant_file!("sources/retry/retry_error_restores_rng.rant");
+}
+
+#[test]
+fn retry_exhausted() {
+  test_rant_file!("sources/retry/retry_exhausted.rant" raises RetryError(5));
+}
+
+#[test]
+fn tail_call_deep_recursion() {
+  test_rant!(r#"
+    [$/count: n; acc] {
+      [if: [le: <n>; 0]] { <acc> }
+      [else] { [/count: [sub: <n>; 1]; [add: <acc>; 1]] }
+    }
+    [count: 30000; 0]
+  "#, "30000");
+}
+
+#[test]
+fn tail_call_constant_locals() {
+  use rant::runtime::{Breakpoint, Debugger, DebugAction, PauseReason, VM};
+
+  /// Records how many locals layers are on the stack at each pause.
+  struct LocalsDepthProbe(Vec<usize>);
+
+  impl Debugger for LocalsDepthProbe {
+    fn on_start(&mut self, vm: &mut VM) -> DebugAction {
+      vm.add_breakpoint(Breakpoint::new(3));
+      DebugAction::Continue
+    }
+
+    fn on_pause(&mut self, vm: &mut VM, _reason: PauseReason) -> DebugAction {
+      self.0.push(vm.call_stack().locals_depth());
+      DebugAction::Continue
+    }
+  }
+
+  let src = "[$/count: n; acc] {\n  [if: [le: <n>; 0]] {\n    <acc>\n  }\n  [else] { [/count: [sub: <n>; 1]; [add: <acc>; 1]] }\n}\n[count: 5000; 0]";
+  let mut r = Rant::with_options(RantOptions {
+    debug_mode: true,
+    .. Default::default()
+  });
+  let pgm = r.compile_quiet(src).expect("failed to compile program");
+  let mut probe = LocalsDepthProbe(vec![]);
+  assert_eq!(r.run_debug(&pgm, &mut probe).expect("failed to run program").to_string(), "5000");
+  assert!(matches!(probe.0.as_slice(), [depth] if *depth < 10), "locals depth grew with tail calls: {:?}", probe.0);
+}
+
+#[test]
+fn tail_call_output_order() {
+  test_rant!(r#"[$/f: n] { a<n>\s [/g: <n>] } [$/g: n] { b<n> [if: [gt: <n>; 0]] { [/f: [sub: <n>; 1]] } } [f: 2]"#, "a2 b2a1 b1a0 b0");
+}
+
+#[test]
+fn tail_call_output_values() {
+  test_rant!(r#"[$/f: n] { (<n>) [if: [gt: <n>; 0]] { [/f: [sub: <n>; 1]] } } [f: 3]"#, "(3; 2; 1; 0)");
+}
+
+#[test]
+fn tail_call_return_value() {
+  test_rant!(r#"[$/f: n] { <n> [if: [gt: <n>; 0]] { [/f: [sub: <n>; 1]] } [else] { @return end } } [f: 3]"#, "321end");
+}
@@ -0,0 +1,95 @@
+/*
+  Set Tests
+
+  These are tests that verify sets keep one copy of each item in insertion order and support the set operations in the standard library.
+
+*/
+
+mod common;
+
+use std::collections::HashSet;
+use common::{run, run_error, run_value};
+
+use rant::*;
+use rant::runtime::*;
+
+#[test]
+fn set_literal() {
+  assert_eq!(run("[type: @{a; b}]"), "set");
+  assert_eq!(run("@{b; a; b; c; a}"), "@{b; a; c}");
+  assert_eq!(run("[len: @{}],[len: @{ x; y; }]"), "0,2");
+}
+
+#[test]
+fn numbers_compare_by_value() {
+  assert_eq!(run("@{1; 1.0; 2; 2.5; 2.5}"), "@{1; 2; 2.5}");
+  assert_eq!(run("[has: @{1}; 1.0]"), "@true");
+}
+
+#[test]
+fn dynamic_items() {
+  assert_eq!(run("<$x = b>[len: @{a; <x>; [cat: a]}]"), "2");
+}
+
+#[test]
+fn membership() {
+  assert_eq!(run("<$s = @{red; green}>[has: <s>; red],[has: <s>; blue]"), "@true,@false");
+}
+
+#[test]
+fn set_operations() {
+  let src = "<$a = @{a; b; c}><$b = @{d; c; b}>[union: <a>; <b>],[intersection: <a>; <b>],[difference: <a>; <b>]";
+  assert_eq!(run(src), "@{a; b; c; d},@{b; c},@{a}");
+}
+
+#[test]
+fn modify_set() {
+  assert_eq!(run("<$s = @{a; b}>[push: <s>; c][push: <s>; a][remove: <s>; b]<s>"), "@{a; c}");
+  assert_eq!(run("<$s = @{a; b}>[clear: <s>][len: <s>]"), "0");
+}
+
+#[test]
+fn convert_to_and_from_lists() {
+  assert_eq!(run("[to-set: (c; a; c; b; a)]"), "@{c; a; b}");
+  assert_eq!(run("[join: [to-list: @{c; a; c; b}]; ,]"), "c,a,b");
+  assert_eq!(run("[to-set: [iter: (1; 2; 1)]]"), "@{1; 2}");
+}
+
+#[test]
+fn iterate_in_insertion_order() {
+  assert_eq!(run("[join: [to-list: [map: @{z; y; z; x}; [?: c] {[upper: <c>]}]]; ,]"), "Z,Y,X");
+}
+
+#[test]
+fn read_as_lists() {
+  assert_eq!(run("[join: @{1; 2; 3}; ,]"), "1,2,3");
+  assert_eq!(run("[sum: @{1; 2}],[sum: @{}]"), "3,");
+  assert_eq!(Vec::<String>::from_rant(run_value("@{b; a}")).unwrap(), vec!["b", "a"]);
+}
+
+#[test]
+fn sets_are_equal_regardless_of_order() {
+  assert_eq!(run("[eq: @{a; b}; @{b; a}],[eq: @{a; b}; @{a}]"), "@true,@false");
+}
+
+#[test]
+fn invalid_set_items() {
+  assert!(matches!(run_error("@{a; (1; 2)}").error_type, RuntimeErrorType::ValueError(ValueError::InvalidSetItem(RantValueType::List))));
+  assert!(matches!(run_error("[push: @{}; @()]").error_type, RuntimeErrorType::ValueError(ValueError::InvalidSetItem(RantValueType::Map))));
+  assert!(matches!(run_error("[union: @{a}; (b)]").error_type, RuntimeErrorType::ValueError(ValueError::InvalidConversion { to: "set", .. })));
+}
+
+#[test]
+fn unclosed_set() {
+  let rant = Rant::new();
+  assert!(rant.compile_quiet("@{a; b").is_err());
+}
+
+#[test]
+fn hash_set_conversion() {
+  let names: HashSet<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+  let value = names.clone().into_rant().unwrap();
+  assert_eq!(value.type_name(), "set");
+  assert_eq!(HashSet::<String>::from_rant(value).unwrap(), names);
+  assert!(HashSet::<String>::from_rant(RantValue::Int(1)).is_err());
+}
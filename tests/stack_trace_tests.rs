@@ -0,0 +1,60 @@
+/*
+  Stack Trace Tests
+
+  These are tests that verify runtime errors carry accurate stack traces.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::runtime::*;
+use common::run_error_with;
+
+fn debug_rant() -> Rant {
+  Rant::with_options(RantOptions {
+    debug_mode: true,
+    .. Default::default()
+  })
+}
+
+#[test]
+fn stack_frames_have_locations() {
+  let err = run_error_with(&mut debug_rant(), "[$f] {\n  x [error: boom]\n}\n[f]");
+  let frames = err.stack_frames.as_ref().unwrap();
+  let names: Vec<&str> = frames.iter().map(|frame| frame.name.as_str()).collect();
+  let flavors: Vec<StackFrameFlavor> = frames.iter().map(|frame| frame.flavor).collect();
+  let positions: Vec<Option<(usize, usize)>> = frames.iter().map(|frame| frame.pos).collect();
+  assert_eq!(names, vec!["native call", "[f]", "main scope"]);
+  assert_eq!(flavors, vec![StackFrameFlavor::NativeCall, StackFrameFlavor::FunctionBody, StackFrameFlavor::FunctionBody]);
+  assert_eq!(positions, vec![Some((2, 5)), Some((2, 5)), Some((4, 1))]);
+  assert!(frames.iter().all(|frame| frame.origin.name() == Some("test") && frame.origin_name() == "test"));
+}
+
+#[test]
+fn stack_frames_collapse_repeats() {
+  let err = run_error_with(&mut debug_rant(), "[$f] {[^f]x}\n[f]");
+  let frames = err.stack_frames.as_ref().unwrap();
+  assert_eq!(frames.len(), 2);
+  assert_eq!(frames[0].name, "[f]");
+  assert!(frames[0].count > 1);
+  assert_eq!(frames[1].count, 1);
+  assert_eq!(err.stack_trace.as_deref().unwrap().lines().next(), Some(format!("-> [test:1:7] in [f] ({} frames)", frames[0].count).as_str()));
+}
+
+#[test]
+fn stack_trace_string_matches_frames() {
+  let err = run_error_with(&mut debug_rant(), "[$f] {\n  x [error: boom]\n}\n[f]");
+  assert_eq!(err.stack_trace.as_deref(), Some("\
+-> [test:2:5] in native call
+-> [test:2:5] in [f]
+-> [test:4:1] in main scope"));
+  assert_eq!(err.stack_trace.as_deref(), Some(format_stack_trace(err.stack_frames.as_ref().unwrap()).as_str()));
+}
+
+#[test]
+fn stack_frames_without_debug_info() {
+  let err = run_error_with(&mut Rant::new(), "[error: boom]");
+  assert!(err.stack_frames.unwrap().iter().all(|frame| frame.pos.is_none()));
+  assert_eq!(err.stack_trace.as_deref(), Some("-> [test] in native call\n-> [test] in main scope"));
+}
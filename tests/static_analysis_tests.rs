@@ -0,0 +1,92 @@
+/*
+  Static Analysis Tests
+
+  These are tests that verify the compiler warns about undefined variables and argument count mismatches.
+
+*/
+
+use rant::*;
+use rant::compiler::*;
+
+/// Compiles a program and returns its undefined variable and argument count warnings along with the source text they point to.
+fn analysis_warnings(rant: &Rant, src: &str) -> Vec<(Problem, String)> {
+  let mut messages: Vec<CompilerMessage> = vec![];
+  assert!(rant.compile(src, &mut messages).is_ok());
+  messages.into_iter()
+    .filter(|msg| matches!(msg.info(), Problem::UndefinedVariable(_) | Problem::ArgumentCountMismatch(..)))
+    .map(|msg| {
+      let text = msg.pos().map(|pos| src[pos.span()].to_owned()).unwrap_or_default();
+      (msg.consume().2, text)
+    })
+    .collect()
+}
+
+#[test]
+fn undefined_variable() {
+  let warnings = analysis_warnings(&Rant::new(), "<$name = world>\nHello, <nmae>!\n<name>");
+  assert!(matches!(warnings.as_slice(), [
+    (Problem::UndefinedVariable(name), text),
+  ] if name == "nmae" && text == "nmae"));
+}
+
+#[test]
+fn undefined_variable_out_of_scope() {
+  let warnings = analysis_warnings(&Rant::new(), "{<$inner = 1>}\n<inner>");
+  assert!(matches!(warnings.as_slice(), [
+    (Problem::UndefinedVariable(name), _),
+  ] if name == "inner"));
+}
+
+#[test]
+fn defined_variables_not_reported() {
+  let src = r#"
+    <$/total = 0>
+    [$count: n] { <n> </total> [next] }
+    [$next] { <x ? fallback> }
+    [count: 1]
+    [require: "lib/util"] <util/foo ? none>
+  "#;
+  assert!(analysis_warnings(&Rant::new(), src).is_empty());
+}
+
+#[test]
+fn context_globals_not_reported() {
+  let mut rant = Rant::new();
+  assert_eq!(analysis_warnings(&rant, "<host-name> [upper: a]").len(), 1);
+  rant.set_global("host-name", RantValue::String("rant".into()));
+  assert!(analysis_warnings(&rant, "<host-name> [upper: a]").is_empty());
+}
+
+#[test]
+fn stdlib_arg_count() {
+  let warnings = analysis_warnings(&Rant::new(), "[join]\n[upper: a; b]\n[cat: a; b; c]\n[cat: *(1; 2)]");
+  assert!(matches!(warnings.as_slice(), [
+    (Problem::ArgumentCountMismatch(join, join_expected, 0), join_text),
+    (Problem::ArgumentCountMismatch(upper, upper_expected, 2), _),
+  ] if join == "join" && join_expected == "1 to 2" && join_text == "join" && upper == "upper" && upper_expected == "1"));
+}
+
+#[test]
+fn user_function_arg_count() {
+  let warnings = analysis_warnings(&Rant::new(), "[$f: a; b?; c*] {<a>}\n[f]\n[f: 1; 2; 3; 4]\n[$g: x] {<x>}\n[g: 1; 2]\n[cat: 1 |> g]");
+  assert!(matches!(warnings.as_slice(), [
+    (Problem::ArgumentCountMismatch(f, f_expected, 0), _),
+    (Problem::ArgumentCountMismatch(g, g_expected, 2), _),
+  ] if f == "f" && f_expected == "at least 1" && g == "g" && g_expected == "1"));
+}
+
+#[test]
+fn shadowed_function_arg_count() {
+  // Reassigned functions can't be checked
+  let warnings = analysis_warnings(&Rant::new(), "<$upper = [?: a; b] {<a><b>}>\n[upper: a; b]\n[$f: a] {<a>}\n<f = <upper>>\n[f: 1; 2]");
+  assert!(warnings.is_empty());
+}
+
+#[test]
+fn globals_from_required_modules_not_reported() {
+  let src = "[require: tests/sources/modules/global-module] [hello] <greeting>";
+  let mut rant = Rant::new();
+  assert!(analysis_warnings(&rant, src).is_empty());
+  let program = rant.compile_quiet(src).expect("failed to compile program");
+  assert_eq!(rant.run(&program).expect("failed to run program").to_string(), "hellohi");
+}
@@ -0,0 +1,79 @@
+/*
+  Symbol Tests
+
+  These are tests that verify the symbols collected by compiler::analyze().
+
+*/
+
+use rant::compiler::*;
+
+fn analyze_quiet(src: &str) -> SymbolTable {
+  analyze(src, &mut ())
+}
+
+#[test]
+fn definitions_in_source_order() {
+  let src = "<$a = 1>\n[$f: x; y?] { <x> }\n<%c = 2>";
+  let symbols = analyze_quiet(src);
+  let defs: Vec<(&str, SymbolKind, &str)> = symbols.definitions()
+    .iter()
+    .map(|def| (def.name.as_str(), def.kind, &src[def.name_span.clone()]))
+    .collect();
+  assert_eq!(defs, vec![
+    ("a", SymbolKind::Variable, "a"),
+    ("f", SymbolKind::Function, "f"),
+    ("x", SymbolKind::Parameter, "x"),
+    ("y", SymbolKind::Parameter, "y"),
+    ("c", SymbolKind::Constant, "c"),
+  ]);
+}
+
+#[test]
+fn references_resolve_to_definitions() {
+  let src = "<$name = world>\n[$greet: who] { Hello, <who>! }\n[greet: <name>]";
+  let symbols = analyze_quiet(src);
+
+  let greet_call = src.rfind("greet").unwrap();
+  let reference = symbols.reference_at(greet_call + 1).unwrap();
+  assert_eq!(reference.name, "greet");
+  assert_eq!(&src[reference.span.clone()], "greet");
+  assert_eq!(symbols.definition_of(reference).unwrap().name_span.start, src.find("greet").unwrap());
+
+  let name_access = src.rfind("name").unwrap();
+  let def = symbols.definition_of(symbols.reference_at(name_access).unwrap()).unwrap();
+  assert_eq!(def.kind, SymbolKind::Variable);
+  assert_eq!(def.name_span.start, src.find("name").unwrap());
+
+  let who_access = src.rfind("who").unwrap();
+  assert_eq!(symbols.definition_of(symbols.reference_at(who_access).unwrap()).unwrap().kind, SymbolKind::Parameter);
+}
+
+#[test]
+fn unresolved_references() {
+  let src = "[upper: <missing>]";
+  let symbols = analyze_quiet(src);
+  let names: Vec<(&str, bool)> = symbols.references()
+    .iter()
+    .map(|reference| (reference.name.as_str(), reference.definition.is_some()))
+    .collect();
+  assert_eq!(names, vec![("upper", false), ("missing", false)]);
+}
+
+#[test]
+fn require_modules() {
+  let src = "[require: \"lib/util\"]\n[require: <dynamic>]";
+  let symbols = analyze_quiet(src);
+  let modules = symbols.modules();
+  assert_eq!(modules.len(), 1);
+  assert_eq!(modules[0].path, "lib/util");
+  assert_eq!(&src[modules[0].span.clone()], "[require: \"lib/util\"]");
+  assert!(symbols.module_at(5).is_some());
+}
+
+#[test]
+fn partial_symbols_on_error() {
+  let mut messages: Vec<CompilerMessage> = vec![];
+  let symbols = analyze("<$a = 1>\n[$f: x] { <x>", &mut messages);
+  assert!(messages.iter().any(|msg| msg.is_error()));
+  assert_eq!(symbols.definitions().first().map(|def| def.name.as_str()), Some("a"));
+}
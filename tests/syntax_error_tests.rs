@@ -0,0 +1,108 @@
+/*
+  Syntax Error Tests
+
+  These are tests that verify the compiler recovers from syntax errors and reports each of them.
+
+*/
+
+use rant::*;
+use rant::compiler::*;
+
+/// Compiles a program and returns the errors it produced as `(problem, line)` pairs.
+fn compile_errors(src: &str) -> Vec<(Problem, usize)> {
+  let mut messages: Vec<CompilerMessage> = vec![];
+  let result = Rant::new().compile(src, &mut messages);
+  let errors: Vec<(Problem, usize)> = messages.into_iter()
+    .filter(|msg| msg.is_error())
+    .map(|msg| {
+      let line = msg.pos().map_or(0, |pos| pos.line());
+      (msg.consume().2, line)
+    })
+    .collect();
+  assert_eq!(result.is_err(), !errors.is_empty());
+  assert!(matches!(result, Ok(_) | Err(CompilerErrorKind::SyntaxError)));
+  errors
+}
+
+#[test]
+fn recover_in_function_signature() {
+  let errors = compile_errors("[$f x] {a}\n[$g: y z] {b}\n[f][g: 1]");
+  assert!(matches!(errors.as_slice(), [
+    (Problem::UnexpectedToken(x), 1),
+    (Problem::UnexpectedToken(z), 2),
+  ] if x == "x" && z == "z"));
+}
+
+#[test]
+fn recover_in_accessors() {
+  let errors = compile_errors("<$a b>\n<$ {x}>\n<a c>\n<$d = 1>");
+  assert!(matches!(errors.as_slice(), [
+    (Problem::UnexpectedToken(_), 1),
+    (Problem::UnexpectedToken(_), 2),
+    (Problem::UnexpectedToken(_), 3),
+  ]));
+}
+
+#[test]
+fn recover_in_function_calls_and_maps() {
+  let errors = compile_errors("[upper bar]\n@(a 1; b = 2)\n[$f: x]\ntext");
+  assert!(matches!(errors.as_slice(), [
+    (Problem::UnexpectedToken(_), 1),
+    (Problem::ExpectedToken(eq), 2),
+    (Problem::ExpectedToken(brace), 4),
+  ] if eq == "=" && brace == "{"));
+}
+
+#[test]
+fn no_cascade_from_multiline_construct() {
+  // The stray '>' on line 2 belongs to the accessor abandoned on line 1
+  let errors = compile_errors("<a b\n  c>\n[upper: x]\n<d e>");
+  assert!(matches!(errors.as_slice(), [
+    (Problem::UnexpectedToken(b), 1),
+    (Problem::UnexpectedToken(e), 4),
+  ] if b == "b" && e == "e"));
+}
+
+#[test]
+fn skip_nested_brackets() {
+  let errors = compile_errors("[upper x {a|[b]}]\n}");
+  assert!(matches!(errors.as_slice(), [
+    (Problem::UnexpectedToken(x), 1),
+    (Problem::UnexpectedToken(brace), 2),
+  ] if x == "x" && brace == "}"));
+}
+
+#[test]
+fn unclosed_block_reports_once() {
+  let errors = compile_errors("{a|[upper: b]\n[$f x] {c}");
+  assert!(matches!(errors.as_slice(), [
+    (Problem::UnexpectedToken(_), 2),
+    (Problem::UnclosedBlock, 1),
+  ]));
+}
+
+#[test]
+fn error_on_bracket_token() {
+  let errors = compile_errors("<$>\n[upper {a}]\n<$ [b]> c");
+  assert!(matches!(errors.as_slice(), [
+    (Problem::UnexpectedToken(a), 1),
+    (Problem::UnexpectedToken(b), 2),
+    (Problem::UnexpectedToken(c), 3),
+  ] if a == ">" && b == "{" && c == "["));
+}
+
+#[test]
+fn invalid_call_name_skips_call() {
+  let errors = compile_errors("<$x=1>[<x>]");
+  assert!(matches!(errors.as_slice(), [(Problem::InvalidIdentifier(name), 1)] if name == "<"));
+}
+
+#[test]
+fn no_missing_body_after_failed_signature() {
+  let errors = compile_errors("[%] {x}\nhello");
+  assert!(matches!(errors.as_slice(), [(Problem::InvalidIdentifier(name), 1)] if name == "]"));
+  let errors = compile_errors("[$f: a b\n] x\n[$g: c] {<c>}");
+  assert!(matches!(errors.as_slice(), [(Problem::UnexpectedToken(b), 1)] if b == "b"));
+  let errors = compile_errors("[$ ] [cat: a]");
+  assert!(matches!(errors.as_slice(), [(Problem::InvalidIdentifier(name), 1)] if name == "]"));
+}
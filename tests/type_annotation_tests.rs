@@ -0,0 +1,114 @@
+/*
+  Type Annotation Tests
+
+  These are tests that verify parameter and return type annotations are checked when functions are called.
+
+*/
+
+mod common;
+
+use rant::*;
+use rant::compiler::*;
+use rant::runtime::*;
+use rant::lang::{dump_ast, AstDumpFormat};
+use common::{run, run_error, compile_messages};
+
+const ADD: &str = "[$add (int | float): a (int); b (int | float)?] {[add: <a>; <b ? 0>]}\n<$f = <add>>\n";
+
+#[test]
+fn annotated_params() {
+  assert_eq!(run(&format!("{}[f: 1; 2.5],[f: 1; 2],[f: 3]", ADD)), "3.5,3,3");
+}
+
+#[test]
+fn param_type_mismatch() {
+  let err = run_error(&format!("{}[f: 1.5; 2]", ADD));
+  assert!(matches!(err.error_type, RuntimeErrorType::ArgumentError));
+  assert_eq!(err.description.as_deref(), Some("argument 'a' should be int, but found float"));
+  let err = run_error(&format!("{}[f: 1; foo]", ADD));
+  assert_eq!(err.description.as_deref(), Some("argument 'b' should be int | float, but found string"));
+}
+
+#[test]
+fn variadic_param_types() {
+  let src = "[$count: items (int)*] {[len: <items>]}\n<$c = <count>>\n";
+  assert_eq!(run(&format!("{}[c],[c: 1; 2; 3]", src)), "0,3");
+  let err = run_error(&format!("{}[c: 1; 2; x]", src));
+  assert_eq!(err.description.as_deref(), Some("argument 'items' should be int, but found string"));
+}
+
+#[test]
+fn any_type() {
+  let src = "[$id (any): x (any)] {<x>}\n<$i = <id>>\n[i: 1],[i: a],[type: [i: (1)]]";
+  assert_eq!(run(src), "1,a,list");
+}
+
+#[test]
+fn return_type_mismatch() {
+  let err = run_error("[$n (int): x] {<x>}\n<$g = <n>>\n[g: a]");
+  assert!(matches!(err.error_type, RuntimeErrorType::TypeError));
+  assert_eq!(err.description.as_deref(), Some("function should return int, but returned string"));
+  assert!(matches!(run_error("[$r (int): x] {@return <x>}\n<$g = <r>>\n[g: a]").error_type, RuntimeErrorType::TypeError));
+  assert_eq!(run("[$r (int): x] {@return <x>}\n[r: 4]"), "4");
+}
+
+#[test]
+fn lambda_annotations() {
+  assert_eq!(run("<$g = [? (string): x (int)] {x<x>}>[g: 4]"), "x4");
+  let err = run_error("<$g = [? (string): x (int)] {<x>}>[g: 4]");
+  assert!(matches!(err.error_type, RuntimeErrorType::TypeError));
+}
+
+#[test]
+fn annotated_signature() {
+  let mut rant = Rant::new();
+  let program = rant.compile_quiet("[$f (string): a (int); b (int | float)?; c (any)*] {}\n<f>").unwrap();
+  let RantValue::Function(func) = rant.run(&program).unwrap() else { panic!("expected function") };
+  assert_eq!(func.signature("f"), "[$f (string): a (int); b (int | float)?; c (any)*]");
+}
+
+#[test]
+fn annotation_dump() {
+  let program = Rant::new().compile_quiet("[$f (int): a (int | float)] {<a>}").unwrap();
+  let dump = dump_ast(program.root(), AstDumpFormat::SExpr);
+  assert!(dump.contains(":return-type \"int\""), "{}", dump);
+  assert!(dump.contains(":type \"int | float\""), "{}", dump);
+}
+
+#[test]
+fn unknown_type() {
+  let messages = compile_messages("[$f: a (str)] {<a>}\n[f: 1]");
+  assert!(matches!(messages.as_slice(), [(Problem::UnknownType(name), text)] if name == "str" && text == "str"));
+}
+
+#[test]
+fn unclosed_type_annotation() {
+  let messages = compile_messages("<$f = [?: a (int] {<a>}>[f: 1]");
+  assert!(matches!(messages.as_slice(), [(Problem::UnclosedTypeAnnotation, text)] if text == "("));
+}
+
+#[test]
+fn literal_arg_type_mismatch() {
+  let messages = compile_messages("[$f: a (int); b (string)?] {<a><b ? >}\n[f: hi there; (1)]");
+  assert!(matches!(messages.as_slice(), [
+    (Problem::ArgumentTypeMismatch(a, a_expected, a_found), a_text),
+    (Problem::ArgumentTypeMismatch(b, b_expected, b_found), b_text),
+  ] if a == "a" && a_expected == "int" && a_found == "string" && a_text == "hi there"
+    && b == "b" && b_expected == "string" && b_found == "list" && b_text == "(1)"));
+  let messages = compile_messages("[$f: a (int); b (string)?] {<a><b ? >}\n[f: @b = 2; @a = 1.5]");
+  assert_eq!(messages.len(), 2);
+  assert!(compile_messages("[$f: a (int)] {<a>}\n<$x = a>[f: <x>][f: 1]").is_empty());
+}
+
+#[test]
+fn mistyped_default() {
+  let err = run_error("[$f: x (int) ? a] {<x>}\n[f]");
+  assert!(matches!(err.error_type, RuntimeErrorType::ArgumentError), "{}", err);
+  assert_eq!(err.description.as_deref(), Some("argument 'x' should be int, but found string"));
+  assert!(matches!(run_error("<$d = 1.5>[$f: x (int) ? <d>] {<x>}\n[f]").error_type, RuntimeErrorType::ArgumentError));
+  assert_eq!(run("[$f: x (int) ? 3] {<x>}\n[f],[f: 4]"), "3,4");
+  let messages = compile_messages("[$f: x (int) ?  a b ; y (list)?] {<x><y ? >}\n[f: 1]");
+  assert!(matches!(messages.as_slice(), [
+    (Problem::ArgumentTypeMismatch(x, expected, found), text),
+  ] if x == "x" && expected == "int" && found == "string" && text == "a b"));
+}